@@ -1,9 +1,31 @@
 use std::collections::HashMap;
 
-use clap::Parser;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory as _, FromArgMatches as _, Parser};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::config::validation::validate_path_safety;
+use crate::error::GraftError;
+use crate::operations::post_commands::DEFAULT_OUTPUT_LIMIT;
+use crate::operations::stdout::is_stdout_target;
+use crate::operations::structured::ReplacementKind;
+use crate::operations::transform::Transform;
+use crate::utils::encoding::TextEncoding;
+use crate::utils::path::normalize_separators;
+use os_shim::System;
+
+/// Environment variable naming the config file used when `--config` is not
+/// given on the command line.
+pub const CONFIG_ENV_VAR: &str = "TIXGRAFT_CONFIG";
+
+/// Environment variable naming the temporary directory used when
+/// `--temp-dir` is not given on the command line.
+pub const TEMP_DIR_ENV_VAR: &str = "TIXGRAFT_TMPDIR";
+
+/// Pull type that copies `source` and then each of `sources` into one target.
+pub const OVERLAY_PULL_TYPE: &str = "overlay";
+
 /// Command-line arguments for tixgraft.
 #[derive(Parser, Debug, Clone)]
 #[command(name = "tixgraft")]
@@ -28,14 +50,60 @@ pub struct Args {
     #[arg(long, value_name = "REF")]
     pub tag: Option<String>,
 
-    /// Configuration file path.
+    /// Configuration file path. Defaults to `$TIXGRAFT_CONFIG` when set,
+    /// else `./tixgraft.yaml`.
     #[arg(long, value_name = "PATH", default_value = "./tixgraft.yaml")]
     pub config: String,
 
+    /// Whether `config` is the built-in default rather than a path given on
+    /// the command line, so [`Args::apply_config_env`] may replace it.
+    ///
+    /// Filled in by [`Args::parse_ordered`]; false otherwise.
+    #[arg(skip)]
+    pub config_is_default: bool,
+
+    /// Fetch the configuration from a repository instead of a local file:
+    /// `<repository>[#<ref>]:<path>`, e.g. `org/templates#main:configs/web.yaml`.
+    #[arg(long = "config-url", value_name = "URL", conflicts_with = "config")]
+    pub config_url: Option<String>,
+
+    /// Apply a template repository here: fetch the `tixgraft.yaml` at its root
+    /// (`<repository>[#<ref>]`) and run it against the current directory.
+    #[arg(
+        long = "init-from",
+        value_name = "REPO",
+        conflicts_with_all = ["config", "config_url"]
+    )]
+    pub init_from: Option<String>,
+
     /// Preview operations without executing.
     #[arg(long)]
     pub dry_run: bool,
 
+    /// With --dry-run, print every replacement's resolved value, including those
+    /// of graft files in local sources (secrets redacted).
+    #[arg(long = "dump-resolved-replacements", requires = "dry_run")]
+    pub dump_resolved_replacements: bool,
+
+    /// With --dry-run, estimate the size of Git sources by listing the remote tree.
+    #[arg(long = "check-remote", requires = "dry_run")]
+    pub check_remote: bool,
+
+    /// Skip the check that a recent enough Git is installed (at your own risk).
+    /// Dry runs without --check-remote never run it.
+    #[arg(long = "no-git-check")]
+    pub no_git_check: bool,
+
+    /// Abort before copying when `git status` in the current directory shows
+    /// uncommitted changes under any pull target.
+    #[arg(long = "require-clean")]
+    pub require_clean: bool,
+
+    /// List destructive actions (`reset: true` targets, flagged commands) and
+    /// ask for confirmation before running; aborts when not on a terminal.
+    #[arg(long = "confirm")]
+    pub confirm: bool,
+
     /// Enable verbose logging output.
     #[arg(short, long)]
     pub verbose: bool,
@@ -48,7 +116,59 @@ pub struct Args {
     #[arg(long = "to-config", conflicts_with = "to_command_line")]
     pub to_config: bool,
 
-    /// Output format for to-command-line: shell or json.
+    /// Check the environment (git, git-lfs, temp dir, config, repository) and print a report.
+    #[arg(long = "doctor", conflicts_with_all = ["to_command_line", "to_config", "dry_run"])]
+    pub doctor: bool,
+
+    /// Compare two config files semantically and print their differences.
+    #[arg(
+        long = "diff-config",
+        num_args = 2,
+        value_names = ["A", "B"],
+        conflicts_with_all = ["to_command_line", "to_config", "doctor", "dry_run"]
+    )]
+    pub diff_config: Option<Vec<String>>,
+
+    /// Upgrade the config file (--config) to the current config version in place.
+    #[arg(
+        long = "migrate-config",
+        conflicts_with_all = ["to_command_line", "to_config", "doctor", "dry_run", "diff_config", "config_url", "init_from"]
+    )]
+    pub migrate_config: bool,
+
+    /// List the graft files under DIR with their context properties and
+    /// replacement sources, without processing anything.
+    #[arg(
+        long = "list-grafts",
+        value_name = "DIR",
+        conflicts_with_all = ["to_command_line", "to_config", "doctor", "dry_run", "diff_config", "migrate_config"]
+    )]
+    pub list_grafts: Option<String>,
+
+    /// Print the file extensions always treated as text by replacements.
+    #[arg(
+        long = "list-text-extensions",
+        conflicts_with_all = ["to_command_line", "to_config", "doctor", "dry_run", "diff_config", "migrate_config", "list_grafts"]
+    )]
+    pub list_text_extensions: bool,
+
+    /// Report whether FILE is treated as binary (skipped by replacements) and why.
+    #[arg(
+        long = "test-binary",
+        value_name = "FILE",
+        conflicts_with_all = ["to_command_line", "to_config", "doctor", "dry_run", "diff_config", "migrate_config", "list_grafts", "list_text_extensions"]
+    )]
+    pub test_binary: Option<String>,
+
+    /// Print the pulls as a Graphviz DOT graph: grouped by repository and
+    /// reference, in execution order, with overlapping targets flagged.
+    #[arg(
+        long = "print-plan-graph",
+        conflicts_with_all = ["to_command_line", "to_config", "doctor", "dry_run", "diff_config", "migrate_config", "list_grafts", "list_text_extensions", "test_binary"]
+    )]
+    pub print_plan_graph: bool,
+
+    /// Output format for to-command-line: shell, json, or a comma-separated list (e.g. shell,json).
     #[arg(
         long = "output-format",
         value_name = "FORMAT",
@@ -57,6 +177,32 @@ pub struct Args {
     )]
     pub output_format: String,
 
+    /// Program name used as the first token of --to-command-line output (default: tixgraft).
+    #[arg(
+        long = "program-name",
+        value_name = "NAME",
+        requires = "to_command_line"
+    )]
+    pub program_name: Option<String>,
+
+    /// Shell whose quoting rules --to-command-line shell output follows.
+    #[arg(
+        long = "shell-dialect",
+        value_name = "SHELL",
+        default_value = "posix",
+        value_parser = ["posix", "powershell", "cmd"],
+        requires = "to_command_line"
+    )]
+    pub shell_dialect: String,
+
+    /// Use the absolute path of the running tixgraft binary in --to-command-line output.
+    #[arg(
+        long = "absolute-program",
+        requires = "to_command_line",
+        conflicts_with = "program_name"
+    )]
+    pub absolute_program: bool,
+
     /// Context values in KEY=VALUE format (can be specified multiple times).
     /// Multiple values with the same key create an array.
     #[arg(long = "context", value_name = "KEY=VALUE")]
@@ -67,6 +213,150 @@ pub struct Args {
     #[arg(long = "context-json", value_name = "KEY=JSON")]
     pub context_json: Vec<String>,
 
+    /// File name of graft definition files to process (default: `.graft.yaml`).
+    #[arg(long = "graft-file-name", value_name = "NAME")]
+    pub graft_file_name: Option<String>,
+
+    /// Partial-clone filter spec for Git sources (default: `blob:none`),
+    /// e.g. `tree:0` or `blob:limit=1m`.
+    #[arg(long = "clone-filter", value_name = "SPEC")]
+    pub clone_filter: Option<String>,
+
+    /// Abort the whole run if it takes longer than this many seconds,
+    /// stopping the processes it started (git, commands) and their children.
+    #[arg(long = "timeout", value_name = "SECS")]
+    pub timeout: Option<u64>,
+
+    /// Only accept SSH host keys listed in this known_hosts file (sets `GIT_SSH_COMMAND`).
+    #[arg(long = "known-hosts", value_name = "FILE")]
+    pub known_hosts: Option<String>,
+
+    /// Fetch all tags for tag references instead of a single-tag shallow clone.
+    #[arg(long = "fetch-tags")]
+    pub fetch_tags: bool,
+
+    /// Pass `-q` to git clone and checkout.
+    #[arg(long = "quiet-git", conflicts_with = "loud_git")]
+    pub quiet_git: bool,
+
+    /// Copy `.git` directories and files from sources (skipped by default so
+    /// a local source that is itself a repository does not copy its history).
+    #[arg(long = "include-git")]
+    pub include_git: bool,
+
+    /// Show git's clone and checkout progress instead of capturing it.
+    #[arg(long = "loud-git")]
+    pub loud_git: bool,
+
+    /// Validate HTTPS remotes against this CA bundle (sets `GIT_SSL_CAINFO`).
+    #[arg(long = "ca-bundle", value_name = "FILE")]
+    pub ca_bundle: Option<String>,
+
+    /// Keep at most this many bytes of each post-command's stdout and stderr;
+    /// longer output is truncated with a marker.
+    #[arg(
+        long = "max-command-output",
+        value_name = "BYTES",
+        default_value_t = DEFAULT_OUTPUT_LIMIT
+    )]
+    pub max_command_output: usize,
+
+    /// Abort a Git pull, after the filtered clone and before checkout, when
+    /// its source subtree is larger than this many bytes.
+    #[arg(long = "max-clone-size", value_name = "BYTES")]
+    pub max_clone_size: Option<u64>,
+
+    /// Clone every repository and reference the config's Git pulls use
+    /// before copying anything, failing early if any is unreachable.
+    #[arg(long = "prefetch")]
+    pub prefetch: bool,
+
+    /// Write newline-delimited JSON progress events (pull_start, clone_done,
+    /// checkout_done, files_copied, replacements_done, pull_done) to stderr.
+    #[arg(long = "progress-json")]
+    pub progress_json: bool,
+
+    /// Fail when a replacement value resolves to an empty string (e.g. an
+    /// environment variable that is set but empty).
+    #[arg(long = "no-empty-replacements")]
+    pub no_empty_replacements: bool,
+
+    /// Fail, instead of warning, when a replacement resolves to its own
+    /// source (a no-op that usually means its value was never changed).
+    #[arg(long = "strict-replacements")]
+    pub strict_replacements: bool,
+
+    /// After each pull, list every replacement `source` with the number of
+    /// files it changed and occurrences it replaced.
+    #[arg(long = "replacements-report")]
+    pub replacements_report: bool,
+
+    /// Place every relative pull target under this directory instead of the
+    /// config's directory (absolute targets are rejected), e.g. to try a
+    /// config against a throwaway sandbox.
+    #[arg(long = "output-dir", value_name = "DIR")]
+    pub output_dir: Option<String>,
+
+    /// When a pull fails part-way through copying, list the files already
+    /// copied to the target (which is left as-is) so it can be inspected.
+    #[arg(long = "preserve-partial")]
+    pub preserve_partial: bool,
+
+    /// Create temporary clone and extraction directories under this directory
+    /// instead of the OS default (useful when `/tmp` is small or `noexec`).
+    /// Defaults to `$TIXGRAFT_TMPDIR`.
+    #[arg(long = "temp-dir", value_name = "DIR")]
+    pub temp_dir: Option<String>,
+
+    /// Merge replacements and commands from a `tixgraft.pull.yaml` shipped
+    /// in the pulled source (directory pulls only).
+    #[arg(long = "trust-source-config")]
+    pub trust_source_config: bool,
+
+    /// After pulling, keep watching local (`file:`) sources and re-run a
+    /// pull whenever its source changes. Other pulls run once.
+    #[arg(long = "watch", conflicts_with = "dry_run")]
+    pub watch: bool,
+
+    /// Keep running a config's remaining pulls after one fails, then exit
+    /// with code 10 if some pulls succeeded and others failed.
+    #[arg(long = "no-fail-fast")]
+    pub no_fail_fast: bool,
+
+    /// After copying each pull, check that every source file exists in the
+    /// target with the same size, before replacements run.
+    #[arg(long = "verify-copy")]
+    pub verify_copy: bool,
+
+    /// After the run, warn about every replacement that changed no file in
+    /// any pull (an error with `--strict-replacements`).
+    #[arg(long = "list-replacements-unused")]
+    pub list_replacements_unused: bool,
+
+    /// Run every post-command with a cleared environment: only `PATH`,
+    /// `HOME` and the variables tixgraft sets are passed through.
+    #[arg(long = "clean-env")]
+    pub clean_env: bool,
+
+    /// Write the equivalent YAML configuration to this file instead of
+    /// executing (implies `--to-config`).
+    #[arg(
+        long = "save-config",
+        value_name = "PATH",
+        conflicts_with = "to_command_line"
+    )]
+    pub save_config: Option<String>,
+
+    /// Let `--save-config` overwrite an existing file.
+    #[arg(long = "force", requires = "save_config")]
+    pub force: bool,
+
+    /// Forbid network access: only local paths, `file:` repositories and
+    /// local archives may be pulled, and remote ones fail before anything
+    /// runs.
+    #[arg(long = "offline", conflicts_with_all = ["config_url", "init_from", "check_remote"])]
+    pub offline: bool,
+
     /// Pull operations (can be specified multiple times).
     #[command(flatten)]
     pub pulls: PullArgs,
@@ -77,6 +367,66 @@ pub struct Args {
 }
 
 impl Args {
+    /// Default `config` to `$TIXGRAFT_CONFIG` and `temp_dir` to
+    /// `$TIXGRAFT_TMPDIR`, read through `system`, when the option was not
+    /// given on the command line and the variable is set and not empty.
+    #[inline]
+    pub fn apply_config_env(&mut self, system: &dyn System) {
+        if self.config_is_default
+            && let Ok(path) = system.env_var(CONFIG_ENV_VAR)
+            && !path.is_empty()
+        {
+            self.config = path;
+        }
+        if self.temp_dir.is_none()
+            && let Ok(dir) = system.env_var(TEMP_DIR_ENV_VAR)
+            && !dir.is_empty()
+        {
+            self.temp_dir = Some(dir);
+        }
+    }
+
+    /// Parse the process arguments like [`Parser::parse`], also recording
+    /// which `--pull-source` each `--pull-commands` and `--pull-replacement`
+    /// follows.
+    ///
+    /// Exits with clap's usage error when the arguments are invalid.
+    #[must_use]
+    #[inline]
+    pub fn parse_ordered() -> Self {
+        let matches = Self::command().get_matches();
+        Self::from_ordered_matches(&matches).unwrap_or_else(|err| err.exit())
+    }
+
+    /// Parse `args` like [`Parser::try_parse_from`], also recording which
+    /// `--pull-source` each `--pull-commands` and `--pull-replacement` follows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The arguments are invalid
+    #[inline]
+    pub fn try_parse_ordered_from<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let matches = Self::command().try_get_matches_from(args)?;
+        Self::from_ordered_matches(&matches)
+    }
+
+    /// Build `Args` from matches, filling in the command- and
+    /// replacement-to-pull mappings.
+    fn from_ordered_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        let mut args = Self::from_arg_matches(matches)?;
+        args.pulls.command_pulls = owning_pulls(matches, "commands");
+        args.pulls.replacement_pulls = owning_pulls(matches, "replacements");
+        args.pulls.context_pulls = owning_pulls(matches, "contexts");
+        args.pulls.context_json_pulls = owning_pulls(matches, "context_jsons");
+        args.config_is_default = matches.value_source("config") == Some(ValueSource::DefaultValue);
+        Ok(args)
+    }
+
     /// Parse context arguments into a `HashMap`.
     ///
     /// # Errors
@@ -105,8 +455,9 @@ pub struct PullArgs {
     #[arg(long = "pull-tag", value_name = "REF")]
     pub tags: Vec<String>,
 
-    /// Pull type: file or directory.
-    #[arg(long = "pull-type", value_name = "TYPE", value_parser = ["file", "directory"])]
+    /// Pull type: auto (detect from the source, default), file, directory, or
+    /// archive (bundle the pulled files into the .zip/.tar.gz target).
+    #[arg(long = "pull-type", value_name = "TYPE", value_parser = ["auto", "file", "directory", "archive"])]
     pub types: Vec<String>,
 
     /// Source path in Git repository.
@@ -129,14 +480,86 @@ pub struct PullArgs {
     #[arg(long = "pull-must-succeed")]
     pub must_succeeds: Vec<bool>,
 
-    /// Commands to execute after copying.
-    #[arg(long = "pull-commands", value_name = "COMMANDS")]
+    /// Command to execute after copying. Repeat for several commands; each
+    /// applies to the closest preceding --pull-source.
+    #[arg(long = "pull-commands", value_name = "COMMAND")]
     pub commands: Vec<String>,
 
+    /// Index of the pull each `--pull-commands` belongs to, in order.
+    ///
+    /// Filled in by [`Args::parse_ordered`]; when it does not cover every
+    /// command (e.g. plain [`Parser::parse`]), commands are matched to pulls
+    /// by position instead.
+    #[arg(skip)]
+    pub command_pulls: Vec<usize>,
+
+    /// Deprecated: split each --pull-commands value on commas into several
+    /// commands, as older versions did.
+    #[arg(long = "split-pull-commands")]
+    pub split_commands: bool,
+
     /// Text replacements in format "SOURCE=TARGET" or "`SOURCE=env:ENV_VAR`".
-    /// Can be specified multiple times per pull operation.
+    /// Can be specified multiple times per pull operation; each applies to
+    /// the closest preceding --pull-source.
     #[arg(long = "pull-replacement", value_name = "REPLACEMENT")]
     pub replacements: Vec<String>,
+
+    /// Index of the pull each `--pull-replacement` belongs to, in order.
+    ///
+    /// Filled in by [`Args::parse_ordered`]; when it does not cover every
+    /// replacement (e.g. plain [`Parser::parse`]), all replacements apply
+    /// to all pulls. Replacements given before the first `--pull-source`
+    /// belong to the first pull.
+    #[arg(skip)]
+    pub replacement_pulls: Vec<usize>,
+
+    /// Per-pull context value as KEY=VALUE. Can be specified multiple times;
+    /// each applies to the closest preceding --pull-source.
+    #[arg(long = "pull-context", value_name = "KEY=VALUE")]
+    pub contexts: Vec<String>,
+
+    /// Index of the pull each `--pull-context` belongs to, in order.
+    ///
+    /// Filled in by [`Args::parse_ordered`]; when it does not cover every
+    /// value (e.g. plain [`Parser::parse`]), all values apply to all pulls.
+    #[arg(skip)]
+    pub context_pulls: Vec<usize>,
+
+    /// Per-pull context value as KEY=JSON. Can be specified multiple times;
+    /// each applies to the closest preceding --pull-source.
+    #[arg(long = "pull-context-json", value_name = "KEY=JSON")]
+    pub context_jsons: Vec<String>,
+
+    /// Index of the pull each `--pull-context-json` belongs to, in order.
+    ///
+    /// Filled in by [`Args::parse_ordered`]; when it does not cover every
+    /// value (e.g. plain [`Parser::parse`]), all values apply to all pulls.
+    #[arg(skip)]
+    pub context_json_pulls: Vec<usize>,
+
+    /// Copy hidden (dot) files from the source (default: true).
+    #[arg(long = "pull-include-hidden")]
+    pub include_hiddens: Vec<bool>,
+
+    /// Skip source files excluded by .gitignore (default: false).
+    #[arg(long = "pull-respect-gitignore")]
+    pub respect_gitignores: Vec<bool>,
+
+    /// Also substitute replacement placeholders in file and directory names.
+    #[arg(long = "pull-replace-filenames")]
+    pub replace_filenames: Vec<bool>,
+
+    /// Remove N leading path components from each copied file (directory pulls).
+    #[arg(long = "pull-strip-components", value_name = "N")]
+    pub strip_components: Vec<usize>,
+
+    /// Comma-separated paths, relative to the target, that must exist after copying.
+    #[arg(long = "pull-expect-files", value_name = "PATHS")]
+    pub expect_files: Vec<String>,
+
+    /// Template engine for rendering pulled files: none, simple, or handlebars.
+    #[arg(long = "pull-template-engine", value_name = "ENGINE", value_parser = ["none", "simple", "handlebars"])]
+    pub template_engines: Vec<String>,
 }
 
 /// Skill management arguments.
@@ -172,6 +595,30 @@ pub struct SkillArgs {
     pub yes: bool,
 }
 
+/// Files a pull's own `replacements` apply to (`pullReplacementScope`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum PullReplacementScope {
+    /// Every file of the target.
+    #[default]
+    All,
+    /// Only files outside directories that contain a graft file, leaving
+    /// those to the graft's own replacements.
+    NonGraft,
+}
+
+impl PullReplacementScope {
+    /// Whether this is the default scope (skipped when serializing).
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "serde skip_serializing_if passes fields by reference"
+    )]
+    const fn is_all(&self) -> bool {
+        matches!(*self, Self::All)
+    }
+}
+
 /// Individual pull configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -182,12 +629,20 @@ pub struct SkillArgs {
 pub struct PullConfig {
     pub source: String,
     pub target: String,
+    /// Free-form note shown next to the pull in logs, previews and progress
+    /// events; it has no effect on what is pulled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     #[serde(default = "default_pull_type", rename = "type")]
     pub pull_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub repository: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tag: Option<String>,
+    /// Glob (e.g. `v2.*`) resolved to the highest matching remote tag before
+    /// checkout, instead of a fixed `tag` (Git sources only).
+    #[serde(rename = "tagPattern", skip_serializing_if = "Option::is_none")]
+    pub tag_pattern: Option<String>,
     #[serde(default)]
     pub reset: bool,
     #[serde(default = "default_true", rename = "requireCleanTarget")]
@@ -198,18 +653,186 @@ pub struct PullConfig {
     pub commands: Vec<String>,
     #[serde(default)]
     pub replacements: Vec<ReplacementConfig>,
+    /// Names of config-level `replacementGroups` appended to `replacements`.
+    #[serde(default, rename = "useGroups", skip_serializing_if = "Vec::is_empty")]
+    pub use_groups: Vec<String>,
+    /// Files the replacements apply to: `all` (default) or `non-graft`.
+    #[serde(
+        default,
+        rename = "pullReplacementScope",
+        skip_serializing_if = "PullReplacementScope::is_all"
+    )]
+    pub pull_replacement_scope: PullReplacementScope,
     /// Context values for this pull.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub context: HashMap<String, Value>,
+    /// Template engine used to render pulled files (default: `none`).
+    #[serde(
+        default = "default_template_engine",
+        rename = "templateEngine",
+        skip_serializing_if = "is_default_template_engine"
+    )]
+    pub template_engine: String,
+    /// Encoding text replacements read and write files in, e.g. `utf-16`
+    /// or `latin1` (default: UTF-8).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// Copy hidden (dot) files from the source (default: true).
+    #[serde(
+        default = "default_true",
+        rename = "includeHidden",
+        skip_serializing_if = "is_true"
+    )]
+    pub include_hidden: bool,
+    /// Skip source files excluded by `.gitignore` (default: false).
+    #[serde(
+        default,
+        rename = "respectGitignore",
+        skip_serializing_if = "core::ops::Not::not"
+    )]
+    pub respect_gitignore: bool,
+    /// Substitute replacement placeholders in file and directory names (default: false).
+    #[serde(
+        default,
+        rename = "replaceFilenames",
+        skip_serializing_if = "core::ops::Not::not"
+    )]
+    pub replace_filenames: bool,
+    /// Leading path components removed from each copied file (default: 0).
+    #[serde(default, rename = "stripComponents", skip_serializing_if = "is_zero")]
+    pub strip_components: usize,
+    /// Paths, relative to the target, that must exist after copying.
+    #[serde(default, rename = "expectFiles", skip_serializing_if = "Vec::is_empty")]
+    pub expect_files: Vec<String>,
+    /// Patterns passed verbatim to `git sparse-checkout set` instead of the
+    /// one derived from `source` (Git sources only).
+    #[serde(
+        default,
+        rename = "sparsePatterns",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub sparse_patterns: Vec<String>,
+    /// Sources copied over `source`, in order, by a `type: overlay` pull;
+    /// later sources override files from earlier ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+}
+
+impl PullConfig {
+    /// A pull of `source` into `target` with every other field at the
+    /// default a YAML config would give it.
+    #[must_use]
+    #[inline]
+    pub fn new(source: &str, target: &str) -> Self {
+        Self {
+            source: source.to_owned(),
+            target: target.to_owned(),
+            description: None,
+            pull_type: default_pull_type(),
+            repository: None,
+            tag: None,
+            tag_pattern: None,
+            reset: false,
+            require_clean_target: true,
+            must_succeed: true,
+            commands: Vec::new(),
+            replacements: Vec::new(),
+            use_groups: Vec::new(),
+            pull_replacement_scope: PullReplacementScope::All,
+            context: HashMap::new(),
+            template_engine: default_template_engine(),
+            encoding: None,
+            include_hidden: true,
+            respect_gitignore: false,
+            replace_filenames: false,
+            strip_components: 0,
+            expect_files: Vec::new(),
+            sparse_patterns: Vec::new(),
+            sources: Vec::new(),
+        }
+    }
+
+    /// The encoding named by `encoding`, or `None` for UTF-8 (or an unknown
+    /// label, which validation rejects).
+    #[must_use]
+    #[inline]
+    pub fn text_encoding(&self) -> Option<TextEncoding> {
+        self.encoding.as_deref().and_then(TextEncoding::for_label)
+    }
+
+    /// Normalize `source` and `target` to `/` separators and check them.
+    ///
+    /// Returns warnings for patterns that are allowed but probably
+    /// unintended: an absolute `source` (which ignores a `file:` repository's
+    /// path) and a trailing slash on a `type: file` source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target escapes the working directory or is
+    /// absolute.
+    #[inline]
+    pub fn validate_paths(&mut self) -> anyhow::Result<Vec<String>> {
+        self.source = normalize_separators(&self.source);
+        if !is_stdout_target(&self.target) {
+            self.target = normalize_separators(&self.target);
+        }
+
+        validate_path_safety(&self.target).map_err(|err| {
+            GraftError::configuration(format!(
+                "Invalid target '{}': {err}. Targets must stay inside the working directory, e.g. './{}'",
+                self.target,
+                self.target.trim_start_matches(['.', '/'])
+            ))
+        })?;
+
+        let mut warnings = Vec::new();
+        if self.source.starts_with('/')
+            && self
+                .repository
+                .as_deref()
+                .is_some_and(|repository| repository.starts_with("file:"))
+        {
+            warnings.push(format!(
+                "Source '{}' is absolute, so it is not resolved inside the file: repository; drop the leading '/'",
+                self.source
+            ));
+        }
+        if self.pull_type == "file" && self.source.ends_with('/') {
+            warnings.push(format!(
+                "Source '{}' ends with '/' but the pull has type: file",
+                self.source
+            ));
+        }
+        Ok(warnings)
+    }
 }
 
 /// Text replacement configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct ReplacementConfig {
+    /// `text` (default) replaces `source` literally; `json`/`yaml` treat
+    /// `source` as a key path set in matching files.
+    #[serde(default, skip_serializing_if = "ReplacementKind::is_text")]
+    pub kind: ReplacementKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reindent: Option<usize>,
+    /// Only files containing this marker are edited (text replacements).
+    #[serde(rename = "requireContains", skip_serializing_if = "Option::is_none")]
+    pub require_contains: Option<String>,
     pub source: String,
+    /// Removed from the start of the resolved value, before `transform`.
+    #[serde(rename = "stripPrefix", skip_serializing_if = "Option::is_none")]
+    pub strip_prefix: Option<String>,
+    /// Removed from the end of the resolved value, before `transform`.
+    #[serde(rename = "stripSuffix", skip_serializing_if = "Option::is_none")]
+    pub strip_suffix: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub target: Option<String>,
+    #[serde(rename = "targetFromFile", skip_serializing_if = "Option::is_none")]
+    pub target_from_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform>,
     #[serde(rename = "valueFromEnv", skip_serializing_if = "Option::is_none")]
     pub value_from_env: Option<String>,
 }
@@ -223,16 +846,91 @@ impl ReplacementConfig {
         value_from_env: Option<String>,
     ) -> Self {
         Self {
+            kind: ReplacementKind::Text,
+            reindent: None,
+            require_contains: None,
             source,
+            strip_prefix: None,
+            strip_suffix: None,
             target,
+            target_from_file: None,
+            transform: None,
             value_from_env,
         }
     }
+
+    /// Treat `source` as a key path set structurally in JSON or YAML files.
+    #[must_use]
+    #[inline]
+    pub const fn with_kind(mut self, kind: ReplacementKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Indent every inserted line after the first to the placeholder's
+    /// column, plus `extra` spaces.
+    #[must_use]
+    #[inline]
+    pub const fn with_reindent(mut self, extra: usize) -> Self {
+        self.reindent = Some(extra);
+        self
+    }
+
+    /// Only edit files that contain `marker` (`requireContains`).
+    #[must_use]
+    #[inline]
+    pub fn with_require_contains(mut self, marker: &str) -> Self {
+        self.require_contains = Some(marker.to_owned());
+        self
+    }
+
+    /// Remove `prefix` from the start of the resolved value (`stripPrefix`).
+    #[must_use]
+    #[inline]
+    pub fn with_strip_prefix(mut self, prefix: &str) -> Self {
+        self.strip_prefix = Some(prefix.to_owned());
+        self
+    }
+
+    /// Remove `suffix` from the end of the resolved value (`stripSuffix`).
+    #[must_use]
+    #[inline]
+    pub fn with_strip_suffix(mut self, suffix: &str) -> Self {
+        self.strip_suffix = Some(suffix.to_owned());
+        self
+    }
+
+    /// Read the replacement value from the file at `path` (instead of `target`).
+    #[must_use]
+    #[inline]
+    pub fn with_target_from_file(mut self, path: &str) -> Self {
+        self.target = None;
+        self.target_from_file = Some(path.to_owned());
+        self
+    }
+
+    /// Transform the resolved value before it is inserted.
+    #[must_use]
+    #[inline]
+    pub const fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
 }
 
 /// Returns the default pull type value for serde deserialization.
 fn default_pull_type() -> String {
-    "directory".to_owned()
+    "auto".to_owned()
+}
+
+/// Returns the default template engine for serde deserialization.
+fn default_template_engine() -> String {
+    "none".to_owned()
+}
+
+/// Check whether the template engine is the default (skipped when serializing).
+fn is_default_template_engine(engine: &str) -> bool {
+    engine == "none"
 }
 
 /// Returns `true` for serde deserialization default.
@@ -240,9 +938,60 @@ const fn default_true() -> bool {
     true
 }
 
+/// Check whether a flag is `true` (skipped when serializing `true`-default fields).
+#[expect(
+    clippy::trivially_copy_pass_by_ref,
+    reason = "serde skip_serializing_if passes fields by reference"
+)]
+const fn is_true(value: &bool) -> bool {
+    *value
+}
+
+/// Check whether a count is zero (skipped when serializing `0`-default fields).
+#[expect(
+    clippy::trivially_copy_pass_by_ref,
+    reason = "serde skip_serializing_if passes fields by reference"
+)]
+const fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+/// Whether `pull` overlays several sources into its target.
+#[must_use]
+#[inline]
+pub fn is_overlay_pull(pull: &PullConfig) -> bool {
+    pull.pull_type == OVERLAY_PULL_TYPE
+}
+
+/// Index of the pull each occurrence of the argument `id` belongs to: the
+/// closest preceding `--pull-source`, or the first pull when none precedes it.
+fn owning_pulls(matches: &ArgMatches, id: &str) -> Vec<usize> {
+    let source_indices: Vec<usize> = matches
+        .indices_of("sources")
+        .map(Iterator::collect)
+        .unwrap_or_default();
+    matches
+        .indices_of(id)
+        .map(|indices| {
+            indices
+                .map(|arg_index| {
+                    source_indices
+                        .iter()
+                        .filter(|&&source_index| source_index < arg_index)
+                        .count()
+                        .saturating_sub(1)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Parse context arguments from CLI into a `HashMap`.
 /// Handles both --context and --context-json flags.
 /// Multiple values with the same key create an array.
+///
+/// A key given with both flags takes only its `--context-json` value(s):
+/// the typed JSON replaces the plain strings, whatever the flag order.
 #[expect(
     clippy::iter_over_hash_type,
     reason = "iteration order does not matter for context key-value collection"
@@ -260,6 +1009,7 @@ fn parse_context_args(
     }
 
     // Parse --context-json arguments
+    let mut json_result: HashMap<String, Vec<Value>> = HashMap::new();
     for arg in context_json_args {
         let (key, json_str) = parse_key_value(arg)?;
         let value: Value = serde_json::from_str(&json_str).map_err(|err| {
@@ -267,7 +1017,12 @@ fn parse_context_args(
                 "Invalid JSON in --context-json for key '{key}': {err}\nValue: {json_str}"
             )
         })?;
-        result.entry(key).or_default().push(value);
+        json_result.entry(key).or_default().push(value);
+    }
+
+    // JSON values replace string values given for the same key
+    for (key, values) in json_result {
+        result.insert(key, values);
     }
 
     // Convert Vec<Value> to Value (single value or array)
@@ -289,6 +1044,24 @@ fn parse_context_args(
     Ok(final_result)
 }
 
+/// Parse the `--pull-context` / `--pull-context-json` values of a single pull.
+///
+/// Each value is one `KEY=VALUE` (or `KEY=JSON`) pair, with the same merging
+/// rules as `--context` / `--context-json`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A pair is not in `KEY=VALUE` format
+/// - A JSON value is invalid
+#[inline]
+pub fn parse_pull_context(
+    contexts: &[String],
+    context_jsons: &[String],
+) -> anyhow::Result<HashMap<String, Value>> {
+    parse_context_args(contexts, context_jsons)
+}
+
 /// Parse a KEY=VALUE string into its key and value components.
 #[expect(
     clippy::indexing_slicing,
@@ -58,6 +58,25 @@ fn parse_mixed_context() {
     );
 }
 
+#[test]
+fn json_context_replaces_string_context_for_same_key() {
+    let context = vec!["name=x".to_owned(), "port=8080".to_owned()];
+    let json = vec![r#"name=["a","b"]"#.to_owned()];
+    let result = parse_context_args(&context, &json).unwrap();
+
+    assert_eq!(result.get("name"), Some(&serde_json::json!(["a", "b"])));
+    assert_eq!(result.get("port"), Some(&Value::String("8080".to_owned())));
+}
+
+#[test]
+fn repeated_json_context_replaces_repeated_string_context() {
+    let context = vec!["items=a".to_owned(), "items=b".to_owned()];
+    let json = vec!["items=1".to_owned(), "items=2".to_owned()];
+    let result = parse_context_args(&context, &json).unwrap();
+
+    assert_eq!(result.get("items"), Some(&serde_json::json!([1, 2])));
+}
+
 #[test]
 fn invalid_context_format() {
     let context = vec!["invalid".to_owned()];
@@ -82,3 +101,24 @@ fn invalid_json() {
     assert!(result.is_err());
     assert!(result.unwrap_err().to_string().contains("Invalid JSON"));
 }
+
+#[test]
+fn parse_pull_context_keeps_commas_in_values() {
+    let result = parse_pull_context(
+        &["name=billing, inc".to_owned(), "port=8080".to_owned()],
+        &[r#"tags=["a","b"]"#.to_owned()],
+    )
+    .unwrap();
+
+    assert_eq!(
+        result.get("name"),
+        Some(&Value::String("billing, inc".to_owned()))
+    );
+    assert_eq!(result.get("port"), Some(&Value::String("8080".to_owned())));
+    assert_eq!(result.get("tags"), Some(&serde_json::json!(["a", "b"])));
+}
+
+#[test]
+fn parse_pull_context_empty() {
+    assert!(parse_pull_context(&[], &[]).unwrap().is_empty());
+}
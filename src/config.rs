@@ -3,8 +3,12 @@
 //! Handles YAML configuration parsing, JSON schema validation, and configuration merging.
 
 pub mod context;
+pub mod diff;
 pub mod graft_yaml;
+pub mod migrate;
+pub mod remote;
 pub mod schema;
+pub mod source_pull;
 pub mod validation;
 pub mod yaml;
 
@@ -13,17 +17,25 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::cli::PullConfig;
+use crate::cli::{PullConfig, ReplacementConfig};
+use crate::config::graft_yaml::PostCommand;
+use crate::error::GraftError;
+use crate::git::GitRunner;
+use crate::operations::discovery::DEFAULT_GRAFT_FILE_NAME;
 use os_shim::System;
 
 /// Main configuration structure.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[non_exhaustive]
 #[expect(
     clippy::arbitrary_source_item_ordering,
     reason = "field order matches YAML config schema for readability"
 )]
 pub struct Config {
+    /// Config format version (see [`schema::CURRENT_CONFIG_VERSION`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+
     /// Global repository URL or account/repo format.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub repository: Option<String>,
@@ -32,10 +44,34 @@ pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tag: Option<String>,
 
+    /// Channel names (e.g. `stable`, `beta`) mapped to the Git reference
+    /// they currently point at. Once any channel is defined, every `tag`
+    /// must name one of them.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub channels: HashMap<String, String>,
+
+    /// Default reference per repository (as written in `repository`), used
+    /// by pulls of that repository that set no `tag` of their own. Takes
+    /// precedence over the config's `tag`.
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        rename = "repositoryDefaults"
+    )]
+    pub repository_defaults: HashMap<String, RepoDefaults>,
+
     /// Global context values.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub context: HashMap<String, Value>,
 
+    /// Named replacement sets that pulls include with `useGroups`.
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        rename = "replacementGroups"
+    )]
+    pub replacement_groups: HashMap<String, Vec<ReplacementConfig>>,
+
     /// List of pull operations.
     #[serde(default)]
     pub pulls: Vec<PullConfig>,
@@ -51,9 +87,110 @@ pub struct Config {
         rename = "processChildrenFirst"
     )]
     pub process_children_first: bool,
+
+    /// File name of graft definition files (default: `.graft.yaml`).
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "graftFileName"
+    )]
+    pub graft_file_name: Option<String>,
+
+    /// Partial-clone filter spec for Git sources (default: `blob:none`).
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "cloneFilter"
+    )]
+    pub clone_filter: Option<String>,
+
+    /// Commands to run once after all pulls and children of this config.
+    ///
+    /// Commands run in the config file's directory (or their own `cwd`,
+    /// relative to it) with `TIXGRAFT_TARGETS` set to the newline-separated
+    /// list of pull targets, including those of child configs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty", rename = "postPull")]
+    pub post_pull: Vec<PostCommand>,
+}
+
+/// Kind of Git reference a name refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum RefType {
+    /// A branch name.
+    Branch,
+    /// A commit SHA.
+    Commit,
+    /// A tag name.
+    Tag,
+}
+
+/// Defaults applied to pulls of one repository (`repositoryDefaults`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct RepoDefaults {
+    /// What kind of reference `tag` is, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refType")]
+    pub ref_type: Option<RefType>,
+    /// Reference used when a pull of this repository sets no `tag`.
+    pub tag: String,
 }
 
 impl Config {
+    /// List the semantic differences to `other`, one line per field.
+    ///
+    /// Cosmetic differences (field order, defaults written out or omitted)
+    /// are ignored; see [`diff::diff_configs`].
+    #[must_use]
+    #[inline]
+    pub fn diff(&self, other: &Self) -> Vec<String> {
+        diff::diff_configs(self, other)
+    }
+
+    /// Append the replacements of each pull's `useGroups` to its own, in
+    /// the order listed, and clear `useGroups`.
+    ///
+    /// The pull's own replacements come first, so they take precedence over
+    /// a group's replacement for the same placeholder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pull uses a group that is not defined in
+    /// `replacementGroups`.
+    #[inline]
+    pub fn expand_replacement_groups(&mut self) -> anyhow::Result<()> {
+        for (index, pull) in self.pulls.iter_mut().enumerate() {
+            for group in core::mem::take(&mut pull.use_groups) {
+                let replacements = self.replacement_groups.get(&group).ok_or_else(|| {
+                    let mut available: Vec<&str> =
+                        self.replacement_groups.keys().map(String::as_str).collect();
+                    available.sort_unstable();
+                    GraftError::configuration(format!(
+                        "Pull operation #{}: unknown replacement group '{group}' (defined groups: {})",
+                        index.saturating_add(1),
+                        if available.is_empty() {
+                            "none".to_owned()
+                        } else {
+                            available.join(", ")
+                        }
+                    ))
+                })?;
+                pull.replacements.extend(replacements.iter().cloned());
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the effective graft file name, falling back to `.graft.yaml`.
+    #[must_use]
+    #[inline]
+    pub fn graft_file_name(&self) -> &str {
+        self.graft_file_name
+            .as_deref()
+            .unwrap_or(DEFAULT_GRAFT_FILE_NAME)
+    }
+
     /// Load configuration from file.
     ///
     /// # Errors
@@ -65,6 +202,61 @@ impl Config {
         yaml::load_config(system, path)
     }
 
+    /// Load configuration from file for a dry run, which reports unset
+    /// `valueFromEnv` variables itself instead of failing to load.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The configuration file cannot be loaded or parsed.
+    #[inline]
+    pub fn load_from_file_for_dry_run(system: &dyn System, path: &str) -> anyhow::Result<Self> {
+        yaml::load_config_for_dry_run(system, path)
+    }
+
+    /// Fetch and load configuration from a repository, given as
+    /// `<repository>[#<ref>]:<path>` (see [`remote::ConfigUrl`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The config URL is malformed.
+    /// - The config file cannot be fetched, loaded, or parsed.
+    #[inline]
+    pub fn load_from_url(
+        system: &dyn System,
+        runner: &dyn GitRunner,
+        spec: &str,
+    ) -> anyhow::Result<Self> {
+        remote::load_remote_config(system, runner, spec)
+    }
+
+    /// Fetch a template repository's root `tixgraft.yaml`, given as
+    /// `<repository>[#<ref>]`; pulls without a repository come from the
+    /// template (see [`remote::load_template_config`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The template spec is malformed.
+    /// - The config file cannot be fetched, loaded, or parsed.
+    #[inline]
+    pub fn load_template(
+        system: &dyn System,
+        runner: &dyn GitRunner,
+        spec: &str,
+    ) -> anyhow::Result<Self> {
+        remote::load_template_config(system, runner, spec)
+    }
+
+    /// Whether `other` describes the same operations, ignoring cosmetic
+    /// differences (see [`Config::diff`]).
+    #[must_use]
+    #[inline]
+    pub fn semantic_eq(&self, other: &Self) -> bool {
+        self.diff(other).is_empty()
+    }
+
     /// Validate configuration against JSON schema.
     ///
     /// # Errors
@@ -5,6 +5,7 @@
 
 use crate::error::GraftError;
 use anyhow::{Context as _, Result};
+use os_shim::System;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -28,6 +29,11 @@ pub struct ContextPropertyDefinition {
     /// Data type of the property.
     pub data_type: ContextDataType,
 
+    /// Environment variable whose value is the default when the property is
+    /// not provided (if set, property is optional while the variable is set).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_from_env: Option<String>,
+
     /// Default value (if present, property is optional).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_value: Option<Value>,
@@ -78,6 +84,9 @@ impl ValidatedContext {
 
     /// Create a new validated context.
     ///
+    /// `defaultFromEnv` defaults are not consulted; use
+    /// [`ValidatedContext::with_env`] to resolve them.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
@@ -88,7 +97,28 @@ impl ValidatedContext {
         provided_values: ContextValues,
     ) -> Result<Self> {
         // Validate and merge values
-        let values = validate_and_merge_values(&definitions, provided_values)?;
+        let values = validate_and_merge_values(&definitions, provided_values, None)?;
+
+        Ok(Self {
+            definitions,
+            values,
+        })
+    }
+
+    /// Create a new validated context, resolving `defaultFromEnv` defaults
+    /// from `system`'s environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The context values are invalid
+    #[inline]
+    pub fn with_env(
+        definitions: Vec<ContextPropertyDefinition>,
+        provided_values: ContextValues,
+        system: &dyn System,
+    ) -> Result<Self> {
+        let values = validate_and_merge_values(&definitions, provided_values, Some(system))?;
 
         Ok(Self {
             definitions,
@@ -98,17 +128,29 @@ impl ValidatedContext {
 }
 
 /// Validate context values against definitions and apply defaults.
+///
+/// Properties that are not provided fall back to their `defaultFromEnv`
+/// variable (when `system` is given and the variable is set), then to their
+/// `defaultValue`.
 fn validate_and_merge_values(
     definitions: &[ContextPropertyDefinition],
     mut provided_values: ContextValues,
+    system: Option<&dyn System>,
 ) -> Result<ContextValues> {
     let mut result = HashMap::new();
     let mut missing_required = Vec::new();
     let mut type_errors = Vec::new();
 
     for def in definitions {
+        let env_default = def
+            .default_from_env
+            .as_deref()
+            .zip(system)
+            .and_then(|(env_var, env)| env.env_var(env_var).ok())
+            .map(Value::String);
+
         // Check if value is provided
-        if let Some(value) = provided_values.remove(&def.name) {
+        if let Some(value) = provided_values.remove(&def.name).or(env_default) {
             // Empty string means remove from context
             if is_empty_string(&value) {
                 continue;
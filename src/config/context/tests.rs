@@ -15,12 +15,14 @@ fn validate_required_properties() {
     let definitions = vec![
         ContextPropertyDefinition {
             data_type: ContextDataType::String,
+            default_from_env: None,
             default_value: None,
             description: "Project name".to_owned(),
             name: "projectName".to_owned(),
         },
         ContextPropertyDefinition {
             data_type: ContextDataType::Number,
+            default_from_env: None,
             default_value: Some(json!(10_i64)),
             description: "Max GB per pod".to_owned(),
             name: "maxGbPerPod".to_owned(),
@@ -29,7 +31,7 @@ fn validate_required_properties() {
 
     // Missing required property
     let values = HashMap::new();
-    let result = validate_and_merge_values(&definitions, values);
+    let result = validate_and_merge_values(&definitions, values, None);
     assert!(result.is_err());
     assert!(
         result
@@ -41,7 +43,7 @@ fn validate_required_properties() {
     // Provide required property
     let mut values = HashMap::new();
     values.insert("projectName".to_owned(), json!("my-app"));
-    let result = validate_and_merge_values(&definitions, values);
+    let result = validate_and_merge_values(&definitions, values, None);
     assert!(result.is_ok());
     let merged = result.unwrap();
     assert_eq!(merged.get("projectName"), Some(&json!("my-app")));
@@ -52,6 +54,7 @@ fn validate_required_properties() {
 fn type_coercion_string_to_number() {
     let definitions = vec![ContextPropertyDefinition {
         data_type: ContextDataType::Number,
+        default_from_env: None,
         default_value: None,
         description: "Port number".to_owned(),
         name: "port".to_owned(),
@@ -60,7 +63,7 @@ fn type_coercion_string_to_number() {
     // String "8080" should coerce to number
     let mut values = HashMap::new();
     values.insert("port".to_owned(), json!("8080"));
-    let result = validate_and_merge_values(&definitions, values);
+    let result = validate_and_merge_values(&definitions, values, None);
     assert!(result.is_ok());
     let merged = result.unwrap();
     assert_eq!(merged.get("port"), Some(&json!(8080_i64)));
@@ -70,6 +73,7 @@ fn type_coercion_string_to_number() {
 fn type_coercion_string_to_boolean() {
     let definitions = vec![ContextPropertyDefinition {
         data_type: ContextDataType::Boolean,
+        default_from_env: None,
         default_value: None,
         description: "Enable feature".to_owned(),
         name: "enabled".to_owned(),
@@ -78,7 +82,7 @@ fn type_coercion_string_to_boolean() {
     // String "true" should coerce to boolean
     let mut values = HashMap::new();
     values.insert("enabled".to_owned(), json!("true"));
-    let result = validate_and_merge_values(&definitions, values);
+    let result = validate_and_merge_values(&definitions, values, None);
     assert!(result.is_ok());
     let merged = result.unwrap();
     assert_eq!(merged.get("enabled"), Some(&json!(true)));
@@ -88,6 +92,7 @@ fn type_coercion_string_to_boolean() {
 fn empty_string_removes_property() {
     let definitions = vec![ContextPropertyDefinition {
         data_type: ContextDataType::String,
+        default_from_env: None,
         default_value: Some(json!("default")),
         description: "Optional property".to_owned(),
         name: "optional".to_owned(),
@@ -96,7 +101,7 @@ fn empty_string_removes_property() {
     // Empty string should remove property
     let mut values = HashMap::new();
     values.insert("optional".to_owned(), json!(""));
-    let result = validate_and_merge_values(&definitions, values);
+    let result = validate_and_merge_values(&definitions, values, None);
     assert!(result.is_ok());
     let merged = result.unwrap();
     assert_eq!(merged.get("optional"), None);
@@ -106,6 +111,7 @@ fn empty_string_removes_property() {
 fn array_validation() {
     let definitions = vec![ContextPropertyDefinition {
         data_type: ContextDataType::Array,
+        default_from_env: None,
         default_value: None,
         description: "List of items".to_owned(),
         name: "items".to_owned(),
@@ -114,13 +120,13 @@ fn array_validation() {
     // Valid array
     let mut values = HashMap::new();
     values.insert("items".to_owned(), json!(["a", "b", "c"]));
-    let result = validate_and_merge_values(&definitions, values);
+    let result = validate_and_merge_values(&definitions, values, None);
     result.unwrap();
 
     // Invalid (not an array)
     let mut values_2 = HashMap::new();
     values_2.insert("items".to_owned(), json!("not-an-array"));
-    let result_2 = validate_and_merge_values(&definitions, values_2);
+    let result_2 = validate_and_merge_values(&definitions, values_2, None);
     result_2.unwrap_err();
 }
 
@@ -209,6 +215,7 @@ fn coerce_float_to_boolean_fails() {
 fn type_error_in_validation() {
     let definitions = vec![ContextPropertyDefinition {
         data_type: ContextDataType::Number,
+        default_from_env: None,
         default_value: None,
         description: "Count".to_owned(),
         name: "count".to_owned(),
@@ -216,7 +223,7 @@ fn type_error_in_validation() {
 
     let mut values = HashMap::new();
     values.insert("count".to_owned(), json!(true)); // bool can't be number
-    let result = validate_and_merge_values(&definitions, values);
+    let result = validate_and_merge_values(&definitions, values, None);
     assert!(result.is_err());
     assert!(
         result
@@ -225,3 +232,29 @@ fn type_error_in_validation() {
             .contains("Invalid context values")
     );
 }
+
+#[test]
+fn default_from_env_applies_when_not_provided() {
+    let system = os_shim::mock::MockSystem::new()
+        .with_env("TIXGRAFT_TEST_OWNER", "ci-bot")
+        .unwrap();
+    let definitions = vec![ContextPropertyDefinition {
+        data_type: ContextDataType::String,
+        default_from_env: Some("TIXGRAFT_TEST_OWNER".to_owned()),
+        default_value: None,
+        description: "Owner".to_owned(),
+        name: "owner".to_owned(),
+    }];
+
+    let merged = validate_and_merge_values(&definitions, HashMap::new(), Some(&system)).unwrap();
+    assert_eq!(merged.get("owner"), Some(&json!("ci-bot")));
+
+    let mut values = HashMap::new();
+    values.insert("owner".to_owned(), json!("alice"));
+    let merged = validate_and_merge_values(&definitions, values, Some(&system)).unwrap();
+    assert_eq!(merged.get("owner"), Some(&json!("alice")));
+
+    // Unset variable (or no environment) leaves the property required
+    let err = validate_and_merge_values(&definitions, HashMap::new(), None).unwrap_err();
+    assert!(err.to_string().contains("owner"));
+}
@@ -0,0 +1,101 @@
+//! Semantic comparison of configurations (`--diff-config`).
+//!
+//! Configs are normalized before comparing, so cosmetic differences do not
+//! count: field order, a default written out versus omitted, or a pull
+//! repeating the config's own `repository`/`tag`.
+
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::git::DEFAULT_CLONE_FILTER;
+use crate::operations::discovery::DEFAULT_GRAFT_FILE_NAME;
+
+/// List the semantic differences between two configs, one line per
+/// differing field (e.g. `pulls[0].target: "a" -> "b"`).
+///
+/// Returns an empty list when the configs are semantically equal.
+#[must_use]
+#[inline]
+pub fn diff_configs(left: &Config, right: &Config) -> Vec<String> {
+    let mut differences = Vec::new();
+    collect_differences("", &normalize(left), &normalize(right), &mut differences);
+    differences
+}
+
+/// Append the differences between `left` and `right` below `path`.
+fn collect_differences(path: &str, left: &Value, right: &Value, differences: &mut Vec<String>) {
+    match (left, right) {
+        (Value::Object(left_map), Value::Object(right_map)) => {
+            let keys: BTreeSet<&String> = left_map.keys().chain(right_map.keys()).collect();
+            for key in keys {
+                let key_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                match (left_map.get(key), right_map.get(key)) {
+                    (Some(left_value), Some(right_value)) => {
+                        collect_differences(&key_path, left_value, right_value, differences);
+                    }
+                    (Some(left_value), None) => {
+                        differences.push(format!("{key_path}: removed (was {left_value})"));
+                    }
+                    (None, Some(right_value)) => {
+                        differences.push(format!("{key_path}: added {right_value}"));
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(left_items), Value::Array(right_items)) => {
+            let len = left_items.len().max(right_items.len());
+            for index in 0..len {
+                let item_path = format!("{path}[{index}]");
+                match (left_items.get(index), right_items.get(index)) {
+                    (Some(left_item), Some(right_item)) => {
+                        collect_differences(&item_path, left_item, right_item, differences);
+                    }
+                    (Some(left_item), None) => {
+                        differences.push(format!("{item_path}: removed (was {left_item})"));
+                    }
+                    (None, Some(right_item)) => {
+                        differences.push(format!("{item_path}: added {right_item}"));
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        _ => {
+            if left != right {
+                differences.push(format!("{path}: {left} -> {right}"));
+            }
+        }
+    }
+}
+
+/// Serialize a config with explicit defaults and redundant overrides removed.
+///
+/// Serialization already skips most default values; this additionally drops
+/// optional fields set to their default and per-pull `repository`/`tag`
+/// values that repeat the config's own.
+fn normalize(config: &Config) -> Value {
+    let mut normalized = config.clone();
+    if normalized.graft_file_name.as_deref() == Some(DEFAULT_GRAFT_FILE_NAME) {
+        normalized.graft_file_name = None;
+    }
+    if normalized.clone_filter.as_deref() == Some(DEFAULT_CLONE_FILTER) {
+        normalized.clone_filter = None;
+    }
+    for pull in &mut normalized.pulls {
+        if pull.repository.is_some() && pull.repository == config.repository {
+            pull.repository = None;
+        }
+        if pull.tag.is_some() && pull.tag == config.tag {
+            pull.tag = None;
+        }
+    }
+
+    serde_json::to_value(&normalized).unwrap_or_default()
+}
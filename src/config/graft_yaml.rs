@@ -5,6 +5,8 @@
 
 use crate::config::context::{ContextDataType, ContextPropertyDefinition};
 use crate::error::GraftError;
+use crate::operations::transform::Transform;
+use crate::utils::path::validate_path_safety;
 use anyhow::{Context as _, Result};
 use os_shim::System;
 use serde::{Deserialize, Serialize};
@@ -20,6 +22,11 @@ pub struct GraftConfig {
     #[serde(default)]
     pub context: Vec<ContextPropertyDefinition>,
 
+    /// Shared context-definition file whose properties are merged in, relative
+    /// to the graft file's directory or a `file:` URL (`contextFrom`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_from: Option<String>,
+
     /// Post-commands to execute after replacements.
     #[serde(default)]
     pub post_commands: Vec<PostCommand>,
@@ -34,13 +41,33 @@ pub struct GraftConfig {
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct GraftReplacement {
+    /// Path relative to the pull target that `scope: path` applies to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Which files the replacement applies to (default: the graft's directory).
+    #[serde(default, skip_serializing_if = "ReplacementScope::is_directory")]
+    pub scope: ReplacementScope,
+
     /// Source pattern to search for.
     pub source: String,
 
+    /// Removed from the start of the resolved value, before `transform`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strip_prefix: Option<String>,
+
+    /// Removed from the end of the resolved value, before `transform`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strip_suffix: Option<String>,
+
     /// Static replacement value.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub target: Option<String>,
 
+    /// Transform applied to the resolved value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform>,
+
     /// Context property to get value from.
     #[serde(rename = "valueFromContext", skip_serializing_if = "Option::is_none")]
     pub value_from_context: Option<String>,
@@ -48,6 +75,30 @@ pub struct GraftReplacement {
     /// Environment variable to get value from.
     #[serde(rename = "valueFromEnv", skip_serializing_if = "Option::is_none")]
     pub value_from_env: Option<String>,
+
+    /// Key path in another file of the graft to get value from.
+    #[serde(rename = "valueFromJsonPath", skip_serializing_if = "Option::is_none")]
+    pub value_from_json_path: Option<JsonPathSource>,
+}
+
+/// Value read from a key path in a JSON or YAML file (`valueFromJsonPath`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct JsonPathSource {
+    /// File to read, relative to the graft's directory (`.yaml`/`.yml` files
+    /// are parsed as YAML, anything else as JSON).
+    pub file: String,
+
+    /// Dotted key path of the value (`version`, `services.0.image`).
+    pub path: String,
+}
+
+impl JsonPathSource {
+    #[must_use]
+    #[inline]
+    pub const fn new(file: String, path: String) -> Self {
+        Self { file, path }
+    }
 }
 
 impl GraftReplacement {
@@ -60,12 +111,92 @@ impl GraftReplacement {
         value_from_context: Option<String>,
     ) -> Self {
         Self {
+            path: None,
+            scope: ReplacementScope::Directory,
             source,
+            strip_prefix: None,
+            strip_suffix: None,
             target,
+            transform: None,
             value_from_context,
             value_from_env,
+            value_from_json_path: None,
         }
     }
+
+    /// Apply the replacement to the whole pull target instead of the graft's directory.
+    #[must_use]
+    #[inline]
+    pub const fn with_scope(mut self, scope: ReplacementScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Apply the replacement to `path`, relative to the pull target (`scope: path`).
+    #[must_use]
+    #[inline]
+    pub fn with_scope_path(mut self, path: &str) -> Self {
+        self.scope = ReplacementScope::Path;
+        self.path = Some(path.to_owned());
+        self
+    }
+
+    /// Remove `prefix` from the start of the resolved value (`stripPrefix`).
+    #[must_use]
+    #[inline]
+    pub fn with_strip_prefix(mut self, prefix: &str) -> Self {
+        self.strip_prefix = Some(prefix.to_owned());
+        self
+    }
+
+    /// Remove `suffix` from the end of the resolved value (`stripSuffix`).
+    #[must_use]
+    #[inline]
+    pub fn with_strip_suffix(mut self, suffix: &str) -> Self {
+        self.strip_suffix = Some(suffix.to_owned());
+        self
+    }
+
+    /// Read the value from a key path in another file of the graft.
+    #[must_use]
+    #[inline]
+    pub fn with_value_from_json_path(mut self, source: JsonPathSource) -> Self {
+        self.value_from_json_path = Some(source);
+        self
+    }
+
+    /// Transform the resolved value before it is inserted.
+    #[must_use]
+    #[inline]
+    pub const fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+}
+
+/// Files a `.graft.yaml` replacement applies to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ReplacementScope {
+    /// The directory containing the `.graft.yaml` (and its subdirectories).
+    #[default]
+    Directory,
+    /// The file or directory at `path`, relative to the pull target.
+    Path,
+    /// The whole pull target.
+    Target,
+}
+
+impl ReplacementScope {
+    /// Whether this is the default scope (skipped when serializing).
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "serde skip_serializing_if passes fields by reference"
+    )]
+    const fn is_directory(&self) -> bool {
+        matches!(*self, Self::Directory)
+    }
 }
 
 /// Post-command configuration (enum for different types).
@@ -83,6 +214,18 @@ pub enum PostCommand {
         args: Vec<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         cwd: Option<String>,
+        /// Context key to store the command's trimmed stdout under, for
+        /// grafts processed later in the same pull.
+        #[serde(rename = "captureInto", skip_serializing_if = "Option::is_none")]
+        capture_into: Option<String>,
+        /// Run with a cleared environment: only `PATH`, `HOME` and the
+        /// variables tixgraft sets itself are passed through.
+        #[serde(
+            rename = "cleanEnv",
+            default,
+            skip_serializing_if = "core::ops::Not::not"
+        )]
+        clean_env: bool,
     },
 }
 
@@ -90,7 +233,13 @@ impl PostCommand {
     #[must_use]
     #[inline]
     pub const fn new(command: String, args: Vec<String>, cwd: Option<String>) -> Self {
-        Self::Command { command, args, cwd }
+        Self::Command {
+            command,
+            args,
+            cwd,
+            capture_into: None,
+            clean_env: false,
+        }
     }
 }
 
@@ -147,11 +296,15 @@ impl TestCommand {
 impl GraftConfig {
     /// Load .graft.yaml from file.
     ///
+    /// A `contextFrom` file is loaded and its definitions merged in, with the
+    /// graft's own definitions overriding shared ones of the same name.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - The `.graft.yaml` file cannot be found
     /// - The `.graft.yaml` file cannot be read
+    /// - The `contextFrom` file cannot be found, read or parsed
     /// - The `.graft.yaml` configuration is invalid
     #[inline]
     pub fn load_from_file(system: &dyn System, path: &Path) -> Result<Self> {
@@ -167,11 +320,22 @@ impl GraftConfig {
             .read_to_string(path)
             .with_context(|| format!("Failed to read .graft.yaml file: {}", path.display()))?;
 
-        Self::load_from_string(&content)
+        let mut config = Self::load_from_string(&content)?;
+        if let Some(context_from) = config.context_from.as_deref() {
+            let graft_dir = path.parent().unwrap_or_else(|| Path::new("."));
+            let shared = load_shared_context(system, graft_dir, context_from)
+                .with_context(|| format!("Failed to load contextFrom of {}", path.display()))?;
+            config.context = merge_context_definitions(shared, config.context);
+            config.validate()?;
+        }
+        Ok(config)
     }
 
     /// Load .graft.yaml from string content.
     ///
+    /// `contextFrom` is kept but not resolved, since there is no directory
+    /// to resolve it against (see [`GraftConfig::load_from_file`]).
+    ///
     /// # Errors
     ///
     /// Returns an error if:
@@ -217,6 +381,25 @@ impl GraftConfig {
                 .into());
             }
 
+            if def.default_value.is_some() && def.default_from_env.is_some() {
+                return Err(GraftError::configuration(format!(
+                    "Context property '{}' cannot set both 'defaultValue' and 'defaultFromEnv'",
+                    def.name
+                ))
+                .into());
+            }
+            if def
+                .default_from_env
+                .as_deref()
+                .is_some_and(|env_var| env_var.trim().is_empty())
+            {
+                return Err(GraftError::configuration(format!(
+                    "Context property '{}' has an empty 'defaultFromEnv'",
+                    def.name
+                ))
+                .into());
+            }
+
             // Validate default value type matches data_type
             if let Some(default_value) = def.default_value.as_ref() {
                 validate_value_type(&def.name, default_value, &def.data_type)?;
@@ -228,17 +411,43 @@ impl GraftConfig {
             let source_count = u8::from(replacement.target.is_some())
                 .checked_add(u8::from(replacement.value_from_env.is_some()))
                 .and_then(|sum| sum.checked_add(u8::from(replacement.value_from_context.is_some())))
+                .and_then(|sum| {
+                    sum.checked_add(u8::from(replacement.value_from_json_path.is_some()))
+                })
                 .unwrap_or(u8::MAX);
 
             if source_count != 1_u8 {
                 return Err(GraftError::configuration(
                     format!(
-                        "Replacement for '{}' must specify exactly one of: target, valueFromEnv, or valueFromContext",
+                        "Replacement for '{}' must specify exactly one of: target, valueFromEnv, valueFromContext, or valueFromJsonPath",
                         replacement.source
                     )
                 )
                 .into());
             }
+
+            match (replacement.scope, replacement.path.as_deref()) {
+                (ReplacementScope::Path, Some(path)) => {
+                    validate_path_safety(path).with_context(|| {
+                        format!("Invalid path for replacement of '{}'", replacement.source)
+                    })?;
+                }
+                (ReplacementScope::Path, None) => {
+                    return Err(GraftError::configuration(format!(
+                        "Replacement for '{}' has scope 'path' but no 'path'",
+                        replacement.source
+                    ))
+                    .into());
+                }
+                (_, Some(_)) => {
+                    return Err(GraftError::configuration(format!(
+                        "Replacement for '{}' sets 'path' but its scope is not 'path'",
+                        replacement.source
+                    ))
+                    .into());
+                }
+                (_, None) => {}
+            }
         }
 
         Ok(())
@@ -253,6 +462,8 @@ impl Default for PostCommand {
             command: String::new(),
             args: Vec::new(),
             cwd: None,
+            capture_into: None,
+            clean_env: false,
         }
     }
 }
@@ -311,7 +522,23 @@ impl<'de> Deserialize<'de> for PostCommand {
                         .and_then(|val| val.as_str())
                         .map(ToOwned::to_owned);
 
-                    Ok(Self::Command { command, args, cwd })
+                    let capture_into = obj
+                        .get("captureInto")
+                        .and_then(|val| val.as_str())
+                        .map(ToOwned::to_owned);
+
+                    let clean_env = obj
+                        .get("cleanEnv")
+                        .and_then(|val| val.as_bool())
+                        .unwrap_or(false);
+
+                    Ok(Self::Command {
+                        command,
+                        args,
+                        cwd,
+                        capture_into,
+                        clean_env,
+                    })
                 }
                 "choice" => {
                     // Parse as choice type
@@ -353,11 +580,83 @@ impl<'de> Deserialize<'de> for PostCommand {
                 .and_then(|val| val.as_str())
                 .map(ToOwned::to_owned);
 
-            Ok(Self::Command { command, args, cwd })
+            let capture_into = obj
+                .get("captureInto")
+                .and_then(|val| val.as_str())
+                .map(ToOwned::to_owned);
+
+            let clean_env = obj
+                .get("cleanEnv")
+                .and_then(|val| val.as_bool())
+                .unwrap_or(false);
+
+            Ok(Self::Command {
+                command,
+                args,
+                cwd,
+                capture_into,
+                clean_env,
+            })
         }
     }
 }
 
+/// Shared context-definition file referenced by `contextFrom`.
+#[derive(Debug, Deserialize)]
+struct SharedContext {
+    /// Context property definitions, in the same form as in `.graft.yaml`.
+    #[serde(default)]
+    context: Vec<ContextPropertyDefinition>,
+}
+
+/// Load the definitions of the `contextFrom` file `reference`, a path
+/// relative to `graft_dir` or a `file:` URL.
+fn load_shared_context(
+    system: &dyn System,
+    graft_dir: &Path,
+    reference: &str,
+) -> Result<Vec<ContextPropertyDefinition>> {
+    let location = reference
+        .strip_prefix("file://")
+        .or_else(|| reference.strip_prefix("file:"))
+        .unwrap_or(reference);
+    if location.contains("://") || location.starts_with("git@") {
+        return Err(GraftError::configuration(format!(
+            "contextFrom '{reference}' must be a local path or a file: URL"
+        ))
+        .into());
+    }
+
+    let path = graft_dir.join(location);
+    if !system.exists(&path)? {
+        return Err(GraftError::configuration(format!(
+            "contextFrom file not found: {}",
+            path.display()
+        ))
+        .into());
+    }
+    let content = system
+        .read_to_string(&path)
+        .with_context(|| format!("Failed to read contextFrom file: {}", path.display()))?;
+    let shared: SharedContext = serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse contextFrom file: {}", path.display()))?;
+    Ok(shared.context)
+}
+
+/// Merge `shared` definitions with the graft's `local` ones; a local
+/// definition replaces the shared one of the same name.
+fn merge_context_definitions(
+    shared: Vec<ContextPropertyDefinition>,
+    local: Vec<ContextPropertyDefinition>,
+) -> Vec<ContextPropertyDefinition> {
+    let mut merged: Vec<ContextPropertyDefinition> = shared
+        .into_iter()
+        .filter(|def| !local.iter().any(|local_def| local_def.name == def.name))
+        .collect();
+    merged.extend(local);
+    merged
+}
+
 /// Validate that a value matches the expected data type.
 fn validate_value_type(name: &str, value: &Value, expected_type: &ContextDataType) -> Result<()> {
     let matches = match *expected_type {
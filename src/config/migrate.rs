@@ -0,0 +1,165 @@
+//! Config version migration (`--migrate-config`).
+//!
+//! Migrations run on the raw YAML tree, before it is parsed into [`Config`],
+//! because the fields they rewrite are exactly the ones the current parser
+//! no longer understands (and would otherwise silently ignore).
+//!
+//! [`Config`]: crate::config::Config
+
+use anyhow::{Context as _, Result};
+use serde_yaml::{Mapping, Value};
+
+use crate::config::schema::CURRENT_CONFIG_VERSION;
+use crate::error::GraftError;
+
+/// Pre-versioning spellings of top-level keys and their current names.
+const LEGACY_CONFIG_KEYS: &[(&str, &str)] = &[
+    ("clone_filter", "cloneFilter"),
+    ("graft_file_name", "graftFileName"),
+    ("post_pull", "postPull"),
+    ("process_children_first", "processChildrenFirst"),
+];
+
+/// Pre-versioning spellings of pull keys and their current names.
+const LEGACY_PULL_KEYS: &[(&str, &str)] = &[
+    ("include_hidden", "includeHidden"),
+    ("must_succeed", "mustSucceed"),
+    ("replace_filenames", "replaceFilenames"),
+    ("require_clean_target", "requireCleanTarget"),
+    ("respect_gitignore", "respectGitignore"),
+    ("strip_components", "stripComponents"),
+    ("template_engine", "templateEngine"),
+];
+
+/// Pre-versioning spellings of replacement keys and their current names.
+const LEGACY_REPLACEMENT_KEYS: &[(&str, &str)] = &[("value_from_env", "valueFromEnv")];
+
+/// Result of migrating a config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Migration {
+    /// One line per change made, e.g. `pulls[0].must_succeed -> mustSucceed`.
+    pub changes: Vec<String>,
+    /// The migrated config as YAML.
+    pub content: String,
+    /// Version the config declared before migrating (`0` when unversioned).
+    pub from_version: u32,
+}
+
+/// Upgrade a config's YAML to [`CURRENT_CONFIG_VERSION`].
+///
+/// Unversioned configs are treated as version 0. Version 0 → 1 renames the
+/// snake_case keys older configs used (`must_succeed`, `value_from_env`, ...)
+/// to their camelCase names; when both spellings are present the camelCase
+/// one wins. The result always carries a `version` field.
+///
+/// Comments and formatting are not preserved.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The content is not a YAML mapping
+/// - The config declares a version newer than this build supports
+#[inline]
+pub fn migrate_config(content: &str) -> Result<Migration> {
+    let value: Value = serde_yaml::from_str(content).context("Failed to parse YAML config")?;
+    let Value::Mapping(mut root) = value else {
+        return Err(GraftError::configuration(
+            "Config must be a YAML mapping to be migrated".to_owned(),
+        )
+        .into());
+    };
+
+    let from_version = match root.get("version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|number| u32::try_from(number).ok())
+            .ok_or_else(|| {
+                GraftError::configuration(
+                    "Config 'version' must be a non-negative integer".to_owned(),
+                )
+            })?,
+    };
+    if from_version > CURRENT_CONFIG_VERSION {
+        return Err(GraftError::configuration(format!(
+            "Config version {from_version} is newer than the latest supported version {CURRENT_CONFIG_VERSION}; upgrade tixgraft"
+        ))
+        .into());
+    }
+
+    let mut changes = Vec::new();
+    if from_version < 1 {
+        migrate_v0_to_v1(&mut root, &mut changes);
+    }
+
+    if from_version < CURRENT_CONFIG_VERSION {
+        changes.push(format!(
+            "version: {from_version} -> {CURRENT_CONFIG_VERSION}"
+        ));
+    }
+    let mut migrated = Mapping::new();
+    migrated.insert(Value::from("version"), Value::from(CURRENT_CONFIG_VERSION));
+    for (key, entry) in root {
+        if key.as_str() != Some("version") {
+            migrated.insert(key, entry);
+        }
+    }
+
+    let content = serde_yaml::to_string(&Value::Mapping(migrated))
+        .context("Failed to serialize migrated config")?;
+    Ok(Migration {
+        changes,
+        content,
+        from_version,
+    })
+}
+
+/// Version 0 → 1: rename snake_case keys to their camelCase names.
+fn migrate_v0_to_v1(root: &mut Mapping, changes: &mut Vec<String>) {
+    rename_keys(root, "", LEGACY_CONFIG_KEYS, changes);
+
+    let Some(pulls) = root.get_mut("pulls").and_then(Value::as_sequence_mut) else {
+        return;
+    };
+    for (pull_index, pull) in pulls.iter_mut().enumerate() {
+        let Some(pull_map) = pull.as_mapping_mut() else {
+            continue;
+        };
+        let pull_path = format!("pulls[{pull_index}].");
+        rename_keys(pull_map, &pull_path, LEGACY_PULL_KEYS, changes);
+
+        let Some(replacements) = pull_map
+            .get_mut("replacements")
+            .and_then(Value::as_sequence_mut)
+        else {
+            continue;
+        };
+        for (replacement_index, replacement) in replacements.iter_mut().enumerate() {
+            if let Some(replacement_map) = replacement.as_mapping_mut() {
+                let replacement_path = format!("{pull_path}replacements[{replacement_index}].");
+                rename_keys(
+                    replacement_map,
+                    &replacement_path,
+                    LEGACY_REPLACEMENT_KEYS,
+                    changes,
+                );
+            }
+        }
+    }
+}
+
+/// Rename each legacy key of `map` found in `renames`, recording the change.
+fn rename_keys(map: &mut Mapping, path: &str, renames: &[(&str, &str)], changes: &mut Vec<String>) {
+    for &(old, new) in renames {
+        let Some(value) = map.remove(old) else {
+            continue;
+        };
+        if map.contains_key(new) {
+            changes.push(format!("{path}{old}: removed ({path}{new} is already set)"));
+        } else {
+            map.insert(Value::from(new), value);
+            changes.push(format!("{path}{old} -> {new}"));
+        }
+    }
+}
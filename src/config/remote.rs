@@ -0,0 +1,198 @@
+//! Loading configuration from a repository (`--config-url`, `--init-from`).
+//!
+//! A config URL has the form `<repository>[#<ref>]:<path>`, for example
+//! `org/templates#main:configs/web.yaml`. Only the config file is fetched,
+//! using the same sparse checkout as pulls.
+//!
+//! A template (`--init-from <repository>[#<ref>]`) is a repository with a
+//! `tixgraft.yaml` at its root whose pulls default to the template itself.
+
+use crate::config::Config;
+use crate::config::yaml::load_config;
+use crate::error::GraftError;
+use crate::git::{ArchiveCheckout, GitRunner, Repository, SparseCheckout};
+use anyhow::{Context as _, Result};
+use os_shim::System;
+
+/// Reference checked out when a config URL does not specify one.
+const DEFAULT_CONFIG_REFERENCE: &str = "main";
+
+/// Config file looked up at the root of a template repository.
+pub const TEMPLATE_CONFIG_FILE: &str = "tixgraft.yaml";
+
+/// A parsed `--config-url` value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ConfigUrl {
+    /// Path of the config file within the repository.
+    pub path: String,
+    /// Git reference to check out, if given after `#`.
+    pub reference: Option<String>,
+    /// Repository URL or `account/repo` shorthand.
+    pub repository: String,
+}
+
+impl ConfigUrl {
+    /// Parse `<repository>[#<ref>]:<path>`.
+    ///
+    /// The path is taken after the last `:`, so SSH and HTTPS repository URLs
+    /// (which contain `:` themselves) are supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The path or repository part is missing
+    /// - The reference after `#` is empty
+    #[inline]
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = |reason: &str| -> anyhow::Error {
+            GraftError::configuration(format!(
+                "Invalid --config-url '{spec}': {reason}. Expected <repository>[#<ref>]:<path>, e.g. org/templates#main:configs/web.yaml"
+            ))
+            .into()
+        };
+
+        let (location, path) = spec
+            .rsplit_once(':')
+            .ok_or_else(|| invalid("missing ':<path>'"))?;
+        if path.is_empty() || path.starts_with("//") {
+            return Err(invalid("missing ':<path>'"));
+        }
+
+        let (repository, reference) = match location.rsplit_once('#') {
+            Some((_, "")) => return Err(invalid("empty reference after '#'")),
+            Some((repository, reference)) => (repository, Some(reference.to_owned())),
+            None => (location, None),
+        };
+        if repository.is_empty() {
+            return Err(invalid("missing repository"));
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+            reference,
+            repository: repository.to_owned(),
+        })
+    }
+
+    /// Parse a template spec, `<repository>[#<ref>]`, pointing at the
+    /// [`TEMPLATE_CONFIG_FILE`] at the repository root.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The repository part is missing
+    /// - The reference after `#` is empty
+    #[inline]
+    pub fn template(spec: &str) -> Result<Self> {
+        let invalid = |reason: &str| -> anyhow::Error {
+            GraftError::configuration(format!(
+                "Invalid --init-from '{spec}': {reason}. Expected <repository>[#<ref>], e.g. org/template-repo#v2"
+            ))
+            .into()
+        };
+
+        let (repository, reference) = match spec.rsplit_once('#') {
+            Some((_, "")) => return Err(invalid("empty reference after '#'")),
+            Some((repository, reference)) => (repository, Some(reference.to_owned())),
+            None => (spec, None),
+        };
+        if repository.is_empty() {
+            return Err(invalid("missing repository"));
+        }
+
+        Ok(Self {
+            path: TEMPLATE_CONFIG_FILE.to_owned(),
+            reference,
+            repository: repository.to_owned(),
+        })
+    }
+}
+
+/// Fetch and load the config described by a `--config-url` value.
+///
+/// Local (`file:`) repositories are read in place and archives are
+/// extracted to a temporary directory; Git repositories are
+/// sparse-checked-out through `runner` into a temporary directory that is
+/// removed once the config is loaded.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The config URL cannot be parsed
+/// - The repository cannot be cloned or the reference checked out
+/// - The config file is missing, cannot be parsed, or is invalid
+#[inline]
+pub fn load_remote_config(
+    system: &dyn System,
+    runner: &dyn GitRunner,
+    spec: &str,
+) -> Result<Config> {
+    fetch_config(system, runner, &ConfigUrl::parse(spec)?, spec)
+}
+
+/// Fetch and load a template's root config (`--init-from`).
+///
+/// Pulls that name no repository come from the template itself, at the
+/// template's reference when the config sets no `tag`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The template spec cannot be parsed
+/// - The repository cannot be cloned or the reference checked out
+/// - The template has no root config, or it cannot be parsed
+#[inline]
+pub fn load_template_config(
+    system: &dyn System,
+    runner: &dyn GitRunner,
+    spec: &str,
+) -> Result<Config> {
+    let config_url = ConfigUrl::template(spec)?;
+    let mut config = fetch_config(system, runner, &config_url, spec)
+        .with_context(|| format!("Template {spec} has no usable {TEMPLATE_CONFIG_FILE}"))?;
+
+    if config.repository.is_none() {
+        config.repository = Some(config_url.repository);
+        if config.tag.is_none() {
+            config.tag = config_url.reference;
+        }
+    }
+    Ok(config)
+}
+
+/// Fetch the config file `config_url` points at and load it; `spec` is the
+/// value the user gave, for error messages.
+fn fetch_config(
+    system: &dyn System,
+    runner: &dyn GitRunner,
+    config_url: &ConfigUrl,
+    spec: &str,
+) -> Result<Config> {
+    let repository = Repository::new(system, &config_url.repository)?;
+
+    if let Some(local_path) = repository.local_path() {
+        let config_path = local_path.join(&config_url.path);
+        return load_config(system, &config_path.to_string_lossy());
+    }
+
+    if let Some((format, location)) = repository.archive() {
+        let archive = ArchiveCheckout::extract(system, format, location)
+            .with_context(|| format!("Failed to fetch config from {spec}"))?;
+        let config_path = archive.root().join(&config_url.path);
+        return load_config(system, &config_path.to_string_lossy());
+    }
+
+    let reference = config_url
+        .reference
+        .clone()
+        .unwrap_or_else(|| DEFAULT_CONFIG_REFERENCE.to_owned());
+    let checkout =
+        SparseCheckout::new(repository, reference, config_url.path.clone())?.with_runner(runner);
+    let config_path = checkout
+        .execute()
+        .with_context(|| format!("Failed to fetch config from {spec}"))?;
+
+    load_config(system, &config_path.to_string_lossy())
+        .with_context(|| format!("Failed to load config fetched from {spec}"))
+}
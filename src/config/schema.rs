@@ -4,6 +4,36 @@ use anyhow::{Result, anyhow};
 use jsonschema::{Draft, Validator};
 use serde_json::Value;
 
+/// Config `version` written by this build; older configs can be upgraded
+/// with `--migrate-config`.
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// Check a config's declared `version`.
+///
+/// Returns a warning for configs without a version or with an older one.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The version is newer than [`CURRENT_CONFIG_VERSION`]
+#[inline]
+pub fn check_config_version(version: Option<u32>) -> Result<Option<String>> {
+    match version {
+        None => Ok(Some(format!(
+            "Config has no 'version'; assuming a pre-versioning config. \
+             Run `tixgraft --migrate-config` to upgrade it to version {CURRENT_CONFIG_VERSION}"
+        ))),
+        Some(version) if version < CURRENT_CONFIG_VERSION => Ok(Some(format!(
+            "Config version {version} is older than the current version {CURRENT_CONFIG_VERSION}. \
+             Run `tixgraft --migrate-config` to upgrade it"
+        ))),
+        Some(version) if version > CURRENT_CONFIG_VERSION => Err(anyhow!(
+            "Config version {version} is newer than the latest supported version {CURRENT_CONFIG_VERSION}; upgrade tixgraft"
+        )),
+        Some(_) => Ok(None),
+    }
+}
+
 /// Get the embedded JSON schema for tixgraft configuration.
 ///
 /// # Errors
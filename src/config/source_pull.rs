@@ -0,0 +1,101 @@
+//! Parser for source-provided pull fragments (`tixgraft.pull.yaml`).
+//!
+//! A source repository can ship a `tixgraft.pull.yaml` next to its content
+//! describing how consumers should transform it. The fragment is only read
+//! with `--trust-source-config`, and is merged into the consumer's pull:
+//! the consumer's replacements and commands run first.
+
+use crate::cli::{PullConfig, ReplacementConfig};
+use crate::error::GraftError;
+use crate::operations::commands::validate_commands;
+use anyhow::{Context as _, Result};
+use os_shim::System;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// File name of the source-provided pull fragment.
+pub const SOURCE_PULL_FILE_NAME: &str = "tixgraft.pull.yaml";
+
+/// Replacements and commands contributed by a source repository.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SourcePullConfig {
+    /// Commands to execute in the target after the consumer's own.
+    #[serde(default)]
+    pub commands: Vec<String>,
+
+    /// Text replacements to apply after the consumer's own.
+    #[serde(default)]
+    pub replacements: Vec<ReplacementConfig>,
+}
+
+impl SourcePullConfig {
+    /// Refuse fragments whose commands look destructive or reach the network.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Any command is flagged by the dangerous-command check
+    #[inline]
+    pub fn check_commands(&self) -> Result<()> {
+        let flagged: Vec<String> = validate_commands(&self.commands)?
+            .into_iter()
+            .filter(|validation| !validation.potential_issues.is_empty())
+            .map(|validation| {
+                format!(
+                    "  {}: {}",
+                    validation.command,
+                    validation.potential_issues.join("; ")
+                )
+            })
+            .collect();
+
+        if flagged.is_empty() {
+            return Ok(());
+        }
+
+        Err(GraftError::command(format!(
+            "Refusing to run commands from {SOURCE_PULL_FILE_NAME}:\n{}",
+            flagged.join("\n")
+        ))
+        .into())
+    }
+
+    /// Load a fragment from file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The file cannot be read
+    /// - The file is not a valid fragment
+    #[inline]
+    pub fn load_from_file(system: &dyn System, path: &Path) -> Result<Self> {
+        let content = system.read_to_string(path).with_context(|| {
+            format!(
+                "Failed to read {SOURCE_PULL_FILE_NAME} file: {}",
+                path.display()
+            )
+        })?;
+
+        serde_yaml::from_str(&content).map_err(|err| {
+            GraftError::configuration(format!(
+                "Failed to parse {SOURCE_PULL_FILE_NAME} at {}: {err}",
+                path.display()
+            ))
+            .into()
+        })
+    }
+
+    /// Return a copy of `pull` with this fragment's replacements and commands
+    /// appended to its own.
+    #[must_use]
+    #[inline]
+    pub fn merge_into(&self, pull: &PullConfig) -> PullConfig {
+        let mut merged = pull.clone();
+        merged
+            .replacements
+            .extend(self.replacements.iter().cloned());
+        merged.commands.extend(self.commands.iter().cloned());
+        merged
+    }
+}
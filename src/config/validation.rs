@@ -2,8 +2,14 @@
 
 use std::path::{Path, PathBuf};
 
-use crate::cli::{PullConfig, ReplacementConfig};
+use crate::cli::{OVERLAY_PULL_TYPE, PullConfig, ReplacementConfig, is_overlay_pull};
 use crate::config::Config;
+use crate::git::{ArchiveFormat, EMPTY_REPOSITORY_MESSAGE, validate_clone_filter};
+use crate::operations::archive_target::{
+    ARCHIVE_PULL_TYPE, archive_target_format, is_archive_pull,
+};
+use crate::operations::stdout::{STDOUT_TARGET, is_stdout_target};
+use crate::operations::template::{TEMPLATE_ENGINES, is_template_engine_available};
 use anyhow::{Result, anyhow};
 use os_shim::System;
 use regex::Regex;
@@ -35,17 +41,84 @@ pub fn validate_config(system: &dyn System, config: &Config) -> Result<()> {
 /// - The repository URL is invalid
 /// - The pull configuration is invalid
 /// - A child config path is invalid
+/// - A stdout target (`-`) is combined with other pulls
+/// - A channel has an empty name or reference
+/// - A pull uses a replacement group that is not defined
 #[inline]
 pub fn validate_config_with_base_dir(
     system: &dyn System,
     config: &Config,
     base_dir: Option<&Path>,
+) -> Result<()> {
+    validate_config_checked(system, config, base_dir, true)
+}
+
+/// Like [`validate_config_with_base_dir`], but without requiring
+/// `valueFromEnv` variables to be set: a dry run lists unset ones together
+/// with its other problems instead.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The configuration is invalid for any reason other than an unset
+///   `valueFromEnv` variable
+#[inline]
+pub fn validate_config_for_dry_run(
+    system: &dyn System,
+    config: &Config,
+    base_dir: Option<&Path>,
+) -> Result<()> {
+    validate_config_checked(system, config, base_dir, false)
+}
+
+/// Validate a complete configuration; `check_env` requires the variables
+/// named by `valueFromEnv` to be set.
+fn validate_config_checked(
+    system: &dyn System,
+    config: &Config,
+    base_dir: Option<&Path>,
+    check_env: bool,
 ) -> Result<()> {
     // Validate global repository if present
     if let Some(repo) = config.repository.as_ref() {
         validate_repository_url(repo)?;
     }
 
+    // Validate graft file name if present
+    if let Some(graft_file_name) = config.graft_file_name.as_ref() {
+        validate_graft_file_name(graft_file_name)?;
+    }
+
+    // Validate clone filter if present
+    if let Some(clone_filter) = config.clone_filter.as_ref() {
+        validate_clone_filter(clone_filter)?;
+    }
+
+    // Every channel must map to a reference
+    if let Some((channel, _)) = config
+        .channels
+        .iter()
+        .find(|&(channel, reference)| channel.trim().is_empty() || reference.trim().is_empty())
+    {
+        return Err(anyhow!(
+            "Channel '{channel}' must have a non-empty name and reference"
+        ));
+    }
+
+    // Every repository default must name a reference
+    if let Some((repository, _)) =
+        config
+            .repository_defaults
+            .iter()
+            .find(|&(repository, defaults)| {
+                repository.trim().is_empty() || defaults.tag.trim().is_empty()
+            })
+    {
+        return Err(anyhow!(
+            "repositoryDefaults entry '{repository}' must have a non-empty repository and tag"
+        ));
+    }
+
     // Must have at least pulls or children
     if config.pulls.is_empty() && config.children.is_empty() {
         return Err(anyhow!(
@@ -55,7 +128,29 @@ pub fn validate_config_with_base_dir(
 
     // Validate each pull configuration
     for (index, pull) in config.pulls.iter().enumerate() {
-        validate_pull_config(system, pull, index)?;
+        validate_pull_config(system, pull, index, check_env)?;
+        if let Some(group) = pull
+            .use_groups
+            .iter()
+            .find(|&group| !config.replacement_groups.contains_key(group))
+        {
+            return Err(anyhow!(
+                "Pull operation #{}: unknown replacement group '{group}'",
+                index.saturating_add(1)
+            ));
+        }
+    }
+
+    // Stdout can only carry a single file
+    if config.pulls.len() > 1
+        && config
+            .pulls
+            .iter()
+            .any(|pull| is_stdout_target(&pull.target))
+    {
+        return Err(anyhow!(
+            "Target '{STDOUT_TARGET}' (stdout) is only allowed when the config has a single pull"
+        ));
     }
 
     // Validate children paths
@@ -66,8 +161,30 @@ pub fn validate_config_with_base_dir(
     Ok(())
 }
 
+/// Validate a custom graft file name.
+///
+/// The name must be a plain file name: no path separators and no `..`.
+fn validate_graft_file_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(anyhow!("Graft file name cannot be empty"));
+    }
+
+    if name.contains(['/', '\\']) || name == "." || name == ".." {
+        return Err(anyhow!(
+            "Invalid graft file name '{name}': must be a plain file name without path separators"
+        ));
+    }
+
+    Ok(())
+}
+
 /// Validate a single pull configuration.
-fn validate_pull_config(system: &dyn System, pull: &PullConfig, index: usize) -> Result<()> {
+fn validate_pull_config(
+    system: &dyn System,
+    pull: &PullConfig,
+    index: usize,
+    check_env: bool,
+) -> Result<()> {
     let display_index = index.saturating_add(1);
     let context = format!("Pull operation #{display_index}");
 
@@ -78,7 +195,9 @@ fn validate_pull_config(system: &dyn System, pull: &PullConfig, index: usize) ->
 
     // Validate source path
     if pull.source.trim().is_empty() {
-        return Err(anyhow!("{context}: Source path cannot be empty"));
+        return Err(anyhow!(
+            "{context}: Source path cannot be empty \u{2014} set it via --pull-source or `source` in the pull"
+        ));
     }
 
     // Validate target path
@@ -87,17 +206,101 @@ fn validate_pull_config(system: &dyn System, pull: &PullConfig, index: usize) ->
     }
 
     // Validate pull type
-    if !matches!(pull.pull_type.as_str(), "file" | "directory") {
+    if !matches!(
+        pull.pull_type.as_str(),
+        "auto" | "file" | "directory" | ARCHIVE_PULL_TYPE | OVERLAY_PULL_TYPE
+    ) {
         return Err(anyhow!(
-            "{}: Invalid pull type '{}'. Must be 'file' or 'directory'",
+            "{}: Invalid pull type '{}'. Must be 'auto', 'file', 'directory', 'archive' or 'overlay'",
             context,
             pull.pull_type
         ));
     }
+    if is_archive_pull(pull) {
+        validate_archive_pull(pull, &context)?;
+    }
+    if is_overlay_pull(pull) {
+        validate_overlay_pull(pull, &context)?;
+    } else if !pull.sources.is_empty() {
+        return Err(anyhow!(
+            "{context}: `sources` is only used by overlay pulls; set type: overlay"
+        ));
+    }
+
+    // A pinned tag and a tag pattern would contradict each other
+    if let Some(pattern) = pull.tag_pattern.as_ref() {
+        if pull.tag.is_some() {
+            return Err(anyhow!(
+                "{context}: Set either `tag` or `tagPattern`, not both"
+            ));
+        }
+        if pattern.trim().is_empty() {
+            return Err(anyhow!("{context}: tagPattern cannot be empty"));
+        }
+    }
+
+    // Validate template engine
+    if !TEMPLATE_ENGINES.contains(&pull.template_engine.as_str()) {
+        return Err(anyhow!(
+            "{}: Invalid templateEngine '{}'. Must be one of: {}",
+            context,
+            pull.template_engine,
+            TEMPLATE_ENGINES.join(", ")
+        ));
+    }
+    if !is_template_engine_available(&pull.template_engine) {
+        return Err(anyhow!(
+            "{}: templateEngine '{}' requires tixgraft to be built with the `{}` feature",
+            context,
+            pull.template_engine,
+            pull.template_engine
+        ));
+    }
+
+    // Explicit sparse-checkout patterns must not be blank
+    if pull
+        .sparse_patterns
+        .iter()
+        .any(|pattern| pattern.trim().is_empty())
+    {
+        return Err(anyhow!("{context}: sparsePatterns entries cannot be empty"));
+    }
+
+    // Validate text encoding
+    if let Some(encoding) = pull.encoding.as_ref()
+        && pull.text_encoding().is_none()
+    {
+        return Err(anyhow!(
+            "{context}: Unknown encoding '{encoding}'. Use a label such as 'utf-16', 'latin1' or 'shift_jis'"
+        ));
+    }
+
+    // Stdout target only works for single files and has no directory to run commands in
+    if is_stdout_target(&pull.target) {
+        if pull.pull_type == "directory" {
+            return Err(anyhow!(
+                "{context}: Target '{STDOUT_TARGET}' (stdout) is only valid for file pulls"
+            ));
+        }
+        if !pull.commands.is_empty() {
+            return Err(anyhow!(
+                "{context}: Commands cannot be used with target '{STDOUT_TARGET}' (stdout)"
+            ));
+        }
+    }
 
     // Validate path safety (prevent path traversal)
     validate_path_safety(&pull.target).map_err(|err| anyhow!("{context}: {err}"))?;
 
+    // Expected files are checked under the target, so they must stay inside it
+    for expected in &pull.expect_files {
+        if expected.trim().is_empty() {
+            return Err(anyhow!("{context}: expectFiles entries cannot be empty"));
+        }
+        validate_path_safety(expected)
+            .map_err(|err| anyhow!("{context}: expectFiles entry '{expected}': {err}"))?;
+    }
+
     // Validate commands
     for (cmd_index, command) in pull.commands.iter().enumerate() {
         if command.trim().is_empty() {
@@ -110,12 +313,50 @@ fn validate_pull_config(system: &dyn System, pull: &PullConfig, index: usize) ->
 
     // Validate replacements
     for (repl_index, replacement) in pull.replacements.iter().enumerate() {
-        validate_replacement(system, replacement, index, repl_index)?;
+        validate_replacement(system, replacement, index, repl_index, check_env)?;
     }
 
     Ok(())
 }
 
+/// Validate an archive pull: the target must name a supported archive and
+/// there is no directory for commands to run in.
+fn validate_archive_pull(pull: &PullConfig, context: &str) -> Result<()> {
+    if is_stdout_target(&pull.target) || archive_target_format(&pull.target).is_none() {
+        return Err(anyhow!(
+            "{context}: Archive target '{}' must end in .zip, .tar.gz or .tgz",
+            pull.target
+        ));
+    }
+    if !pull.commands.is_empty() {
+        return Err(anyhow!(
+            "{context}: Commands cannot be used with an archive target"
+        ));
+    }
+    Ok(())
+}
+
+/// Validate an overlay pull: it needs sources to lay over `source` and a
+/// directory target to copy them into.
+fn validate_overlay_pull(pull: &PullConfig, context: &str) -> Result<()> {
+    if pull.sources.is_empty() {
+        return Err(anyhow!(
+            "{context}: Overlay pulls need at least one entry in `sources` to copy over `source`"
+        ));
+    }
+    for source in &pull.sources {
+        if source.trim().is_empty() {
+            return Err(anyhow!("{context}: sources entries cannot be empty"));
+        }
+    }
+    if is_stdout_target(&pull.target) {
+        return Err(anyhow!(
+            "{context}: Target '{STDOUT_TARGET}' (stdout) is only valid for file pulls"
+        ));
+    }
+    Ok(())
+}
+
 /// Validate a child config path.
 ///
 /// Children must be descendants of the parent config directory:
@@ -167,8 +408,13 @@ fn validate_child_path(
 /// - The repository URL is invalid
 #[inline]
 pub fn validate_repository_url(url: &str) -> Result<()> {
-    // ONLY accept "file:" prefix for local filesystem paths
-    if url.starts_with("file:") {
+    if url.trim().is_empty() {
+        return Err(anyhow!(EMPTY_REPOSITORY_MESSAGE));
+    }
+
+    // ONLY accept "file:" prefix for local filesystem paths; archive
+    // locations are checked in Repository::new()
+    if url.starts_with("file:") || ArchiveFormat::detect(url).is_some() {
         // Local path - detailed validation will be done in Repository::new()
         return Ok(());
     }
@@ -193,7 +439,8 @@ pub fn validate_repository_url(url: &str) -> Result<()> {
         - Short format: my_organization/repo\n\
         - HTTPS: https://github.com/my_organization/repo.git\n\
         - SSH: git@github.com:my_organization/repo.git\n\
-        - Local: file:/path/to/repo or file:///path/to/repo"
+        - Local: file:/path/to/repo or file:///path/to/repo\n\
+        - Archive: https://example.com/repo.tar.gz, file:/path/to/repo.zip, or a tar:/zip: prefix"
     ))
 }
 
@@ -227,6 +474,7 @@ fn validate_replacement(
     replacement: &ReplacementConfig,
     pull_index: usize,
     repl_index: usize,
+    check_env: bool,
 ) -> Result<()> {
     let display_pull = pull_index.saturating_add(1);
     let display_repl = repl_index.saturating_add(1);
@@ -237,18 +485,50 @@ fn validate_replacement(
         return Err(anyhow!("{context}: Replacement source cannot be empty"));
     }
 
-    // Must have exactly one of target or value_from_env
+    if !replacement.kind.is_text() && replacement.reindent.is_some() {
+        return Err(anyhow!(
+            "{context}: 'reindent' only applies to text replacements"
+        ));
+    }
+
+    if !replacement.kind.is_text() && replacement.require_contains.is_some() {
+        return Err(anyhow!(
+            "{context}: 'requireContains' only applies to text replacements"
+        ));
+    }
+
+    // Deleting lines takes no value
+    if replacement.kind.is_delete_lines() {
+        if replacement.target.is_some()
+            || replacement.target_from_file.is_some()
+            || replacement.value_from_env.is_some()
+        {
+            return Err(anyhow!(
+                "{context}: 'deleteLines' replacements take no 'target', 'targetFromFile' or 'valueFromEnv'"
+            ));
+        }
+        return Ok(());
+    }
+
+    // Must have exactly one of target, target_from_file or value_from_env
     match (
         replacement.target.as_ref(),
+        replacement.target_from_file.as_ref(),
         replacement.value_from_env.as_ref(),
     ) {
-        (Some(target), None) => {
+        (Some(target), None, None) => {
             // String literal replacement - target can be empty
             if target.trim().is_empty() {
                 return Err(anyhow!("{context}: Replacement target cannot be empty"));
             }
         }
-        (None, Some(env_var)) => {
+        (None, Some(path), None) => {
+            // Block content read from a file when replacements are applied
+            if path.trim().is_empty() {
+                return Err(anyhow!("{context}: 'targetFromFile' path cannot be empty"));
+            }
+        }
+        (None, None, Some(env_var)) => {
             // Environment variable replacement
             if env_var.trim().is_empty() {
                 return Err(anyhow!(
@@ -257,20 +537,20 @@ fn validate_replacement(
             }
 
             // Check if environment variable exists
-            if system.env_var(env_var).is_err() {
+            if check_env && system.env_var(env_var).is_err() {
                 return Err(anyhow!(
                     "{context}: Environment variable '{env_var}' is not set"
                 ));
             }
         }
-        (Some(_), Some(_)) => {
+        (None, None, None) => {
             return Err(anyhow!(
-                "{context}: Cannot specify both 'target' and 'valueFromEnv'"
+                "{context}: Must specify either 'target', 'targetFromFile' or 'valueFromEnv'"
             ));
         }
-        (None, None) => {
+        _ => {
             return Err(anyhow!(
-                "{context}: Must specify either 'target' or 'valueFromEnv'"
+                "{context}: Cannot specify both 'target'/'targetFromFile' and 'valueFromEnv', or both 'target' and 'targetFromFile'"
             ));
         }
     }
@@ -1,10 +1,12 @@
 //! YAML configuration loading and parsing.
 
-use crate::config::schema::validate_against_schema;
-use crate::config::{Config, validation::validate_config_with_base_dir};
+use crate::config::Config;
+use crate::config::schema::{check_config_version, validate_against_schema};
+use crate::config::validation::{validate_config_for_dry_run, validate_config_with_base_dir};
 use anyhow::{Context as _, Result, anyhow};
 use os_shim::System;
 use std::path::Path;
+use tracing::warn;
 
 /// Load and parse YAML configuration from file.
 ///
@@ -15,8 +17,47 @@ use std::path::Path;
 /// - The configuration file cannot be read
 /// - The configuration file cannot be parsed
 /// - The configuration is invalid
+/// - The configuration declares a version newer than this build supports
 #[inline]
 pub fn load_config(system: &dyn System, path: &str) -> Result<Config> {
+    let config = parse_config(system, path)?;
+
+    // Validate configuration logic (path safety, env vars, etc.)
+    // Resolve children paths relative to the config file's directory so that
+    // nested configs (A→B→C) validate correctly regardless of CWD.
+    let base_dir = Path::new(path).parent();
+    validate_config_with_base_dir(system, &config, base_dir)
+        .context("Configuration validation failed")?;
+
+    Ok(config)
+}
+
+/// Like [`load_config`], but leave unset `valueFromEnv` variables for the
+/// dry run to report (see [`validate_config_for_dry_run`]).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The configuration file cannot be found
+/// - The configuration file cannot be read
+/// - The configuration file cannot be parsed
+/// - The configuration is invalid for any reason other than an unset
+///   `valueFromEnv` variable
+/// - The configuration declares a version newer than this build supports
+#[inline]
+pub fn load_config_for_dry_run(system: &dyn System, path: &str) -> Result<Config> {
+    let config = parse_config(system, path)?;
+
+    let base_dir = Path::new(path).parent();
+    validate_config_for_dry_run(system, &config, base_dir)
+        .context("Configuration validation failed")?;
+
+    Ok(config)
+}
+
+/// Read, parse and schema-check the configuration at `path`, before the
+/// configuration logic is validated.
+fn parse_config(system: &dyn System, path: &str) -> Result<Config> {
     let path_obj = Path::new(path);
 
     // Check if file exists using System trait
@@ -33,25 +74,41 @@ pub fn load_config(system: &dyn System, path: &str) -> Result<Config> {
         .with_context(|| format!("Failed to read configuration file: {path}"))?;
 
     // Parse YAML
-    let config: Config = serde_yaml::from_str(&content).with_context(|| {
+    let mut config: Config = serde_yaml::from_str(&content).with_context(|| {
         format!(
             "Failed to parse YAML configuration in file: {path}\n\
             Please check the syntax and structure of your configuration file"
         )
     })?;
 
+    // Warn about unversioned or outdated configs, refuse newer ones
+    if let Some(warning) = check_config_version(config.version)
+        .with_context(|| format!("Unsupported configuration file: {path}"))?
+    {
+        warn!("{path}: {warning}");
+    }
+
+    // Expand `useGroups` into each pull's replacements
+    config
+        .expand_replacement_groups()
+        .with_context(|| format!("Configuration validation failed: {path}"))?;
+
+    // Normalize pull paths and flag suspicious ones
+    for (index, pull) in config.pulls.iter_mut().enumerate() {
+        let display_index = index.saturating_add(1);
+        let warnings = pull.validate_paths().with_context(|| {
+            format!("Configuration validation failed: pull operation #{display_index} in {path}")
+        })?;
+        for warning in warnings {
+            warn!("{path}: pull operation #{display_index}: {warning}");
+        }
+    }
+
     // Validate against JSON schema
     let config_value = serde_json::to_value(&config)
         .context("Failed to convert configuration to JSON for validation")?;
 
     validate_against_schema(&config_value).context("Configuration validation failed")?;
 
-    // Validate configuration logic (path safety, env vars, etc.)
-    // Resolve children paths relative to the config file's directory so that
-    // nested configs (A→B→C) validate correctly regardless of CWD.
-    let base_dir = Path::new(path).parent();
-    validate_config_with_base_dir(system, &config, base_dir)
-        .context("Configuration validation failed")?;
-
     Ok(config)
 }
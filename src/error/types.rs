@@ -22,6 +22,11 @@ pub enum GraftError {
     #[error("Git error: {message}")]
     Git { message: String },
 
+    /// Partial Failure - with `--no-fail-fast`, some pulls failed while
+    /// others succeeded.
+    #[error("Partial failure: {}", describe_failures(.outcomes))]
+    PartialFailure { outcomes: Vec<PullOutcome> },
+
     /// Skill Error - skill management operation failed.
     #[error("Skill error: {message}")]
     Skill { message: String },
@@ -29,6 +34,10 @@ pub enum GraftError {
     /// Source Error - source path not found in repository.
     #[error("Source error: {message}")]
     Source { message: String },
+
+    /// Timeout Error - the run exceeded `--timeout`.
+    #[error("Timeout error: {message}")]
+    Timeout { message: String },
 }
 
 impl GraftError {
@@ -65,6 +74,8 @@ impl GraftError {
             Self::Git { .. } => 4,
             Self::Filesystem { .. } => 5,
             Self::Skill { .. } => 6,
+            Self::Timeout { .. } => 7,
+            Self::PartialFailure { .. } => 10,
         }
     }
 
@@ -101,6 +112,12 @@ impl GraftError {
         }
     }
 
+    /// Create a partial-failure error from the outcome of every pull that ran.
+    #[inline]
+    pub const fn partial_failure(outcomes: Vec<PullOutcome>) -> Self {
+        Self::PartialFailure { outcomes }
+    }
+
     /// Create a skill error.
     #[inline]
     pub fn skill<S>(message: S) -> Self
@@ -111,4 +128,88 @@ impl GraftError {
             message: message.into(),
         }
     }
+
+    /// Create a timeout error.
+    #[inline]
+    pub fn timeout<S>(message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Timeout {
+            message: message.into(),
+        }
+    }
+}
+
+/// Outcome of one pull in a `--no-fail-fast` run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PullOutcome {
+    /// Why the pull failed; `None` when it succeeded.
+    pub error: Option<String>,
+    /// 1-based position of the pull in its config.
+    pub index: usize,
+    /// Source path of the pull.
+    pub source: String,
+    /// Target path of the pull.
+    pub target: String,
+}
+
+impl PullOutcome {
+    /// Outcome of a pull that failed with `error`.
+    #[inline]
+    pub fn failed<S>(index: usize, source: S, target: S, error: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            error: Some(error.into()),
+            index,
+            source: source.into(),
+            target: target.into(),
+        }
+    }
+
+    /// Whether the pull failed.
+    #[must_use]
+    #[inline]
+    pub const fn is_failure(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Outcome of a pull that succeeded.
+    #[inline]
+    pub fn succeeded<S>(index: usize, source: S, target: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            error: None,
+            index,
+            source: source.into(),
+            target: target.into(),
+        }
+    }
+}
+
+/// Summarize the failed pulls among `outcomes`, e.g.
+/// `1 of 2 pulls failed: pull #2 (src -> dst): <error>`.
+fn describe_failures(outcomes: &[PullOutcome]) -> String {
+    let failures: Vec<String> = outcomes
+        .iter()
+        .filter_map(|outcome| {
+            outcome.error.as_ref().map(|error| {
+                format!(
+                    "pull #{} ({} -> {}): {error}",
+                    outcome.index, outcome.source, outcome.target
+                )
+            })
+        })
+        .collect();
+    format!(
+        "{} of {} pulls failed: {}",
+        failures.len(),
+        outcomes.len(),
+        failures.join("; ")
+    )
 }
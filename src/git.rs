@@ -4,8 +4,14 @@
 
 #![expect(clippy::pub_use, reason = "deliberate module re-export for public API")]
 
+pub mod archive;
 pub mod repository;
+pub mod runner;
 pub mod sparse_checkout;
+pub mod tags;
 
+pub use archive::*;
 pub use repository::*;
+pub use runner::*;
 pub use sparse_checkout::*;
+pub use tags::*;
@@ -0,0 +1,336 @@
+//! Archive repository sources (`.tar.gz`, `.tgz`, `.zip`).
+//!
+//! A repository URL ending in an archive extension, or prefixed with `tar:`
+//! or `zip:`, is read from disk (`file:`) or downloaded (HTTP/HTTPS) into a
+//! temporary directory, extracted there through [`System`], and then read
+//! like a local checkout.
+
+use crate::error::GraftError;
+use anyhow::{Context as _, Result};
+use flate2::read::GzDecoder;
+use os_shim::System;
+use std::io::{Cursor, Read as _};
+use std::path::{Component, Path, PathBuf};
+use tempfile::TempDir;
+use tracing::debug;
+
+/// Compression format of an archive source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ArchiveFormat {
+    /// Gzip-compressed tarball (`.tar.gz`, `.tgz`, `tar:` prefix).
+    TarGz,
+    /// Zip archive (`.zip`, `zip:` prefix).
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detect whether `url` is an archive source.
+    ///
+    /// Returns the format and the URL with any `tar:`/`zip:` prefix removed,
+    /// or `None` when `url` is not an archive.
+    #[must_use]
+    #[inline]
+    pub fn detect(url: &str) -> Option<(Self, &str)> {
+        if let Some(location) = url.strip_prefix("tar:") {
+            return Some((Self::TarGz, location));
+        }
+        if let Some(location) = url.strip_prefix("zip:") {
+            return Some((Self::Zip, location));
+        }
+
+        let path = Path::new(url);
+        let is_tarball = has_extension(path, "tgz")
+            || (has_extension(path, "gz")
+                && path
+                    .file_stem()
+                    .is_some_and(|stem| has_extension(Path::new(stem), "tar")));
+        if is_tarball {
+            Some((Self::TarGz, url))
+        } else if has_extension(path, "zip") {
+            Some((Self::Zip, url))
+        } else {
+            None
+        }
+    }
+}
+
+/// Where an archive source is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ArchiveLocation {
+    /// Archive file on the local filesystem.
+    Local(PathBuf),
+    /// HTTP or HTTPS URL to download the archive from.
+    Remote(String),
+}
+
+/// An archive entry read into memory, ready to be written out.
+struct ArchiveEntry {
+    /// File contents, or `None` for a directory.
+    contents: Option<Vec<u8>>,
+    /// Path of the entry inside the archive.
+    path: PathBuf,
+}
+
+/// An archive source extracted into a temporary directory.
+///
+/// The directory is removed when this value is dropped, so it must be kept
+/// alive while the extracted files are in use.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ArchiveCheckout {
+    /// Root of the extracted tree.
+    root: PathBuf,
+    /// Temporary directory holding the download and the extracted tree.
+    temp_dir: TempDir,
+}
+
+impl ArchiveCheckout {
+    /// Download (if remote) and extract an archive.
+    ///
+    /// When the archive holds a single top-level directory (as release
+    /// tarballs usually do), that directory becomes the root.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The temporary directory cannot be created
+    /// - The archive cannot be downloaded
+    /// - The archive cannot be read or extracted
+    /// - An entry would be written outside the extraction directory
+    #[inline]
+    pub fn extract(
+        system: &dyn System,
+        format: ArchiveFormat,
+        location: &ArchiveLocation,
+    ) -> Result<Self> {
+        Self::extract_in(system, format, location, None)
+    }
+
+    /// Like [`ArchiveCheckout::extract`], but create the temporary directory
+    /// under `temp_root` (`--temp-dir`) instead of the OS default location.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The temporary directory cannot be created
+    /// - The archive cannot be downloaded
+    /// - The archive cannot be read or extracted
+    /// - An entry would be written outside the extraction directory
+    #[inline]
+    #[expect(
+        clippy::pattern_type_mismatch,
+        reason = "matching on &ArchiveLocation; dereferencing would require ref bindings which conflict with clippy::ref_patterns"
+    )]
+    pub fn extract_in(
+        system: &dyn System,
+        format: ArchiveFormat,
+        location: &ArchiveLocation,
+        temp_root: Option<&Path>,
+    ) -> Result<Self> {
+        let temp_dir = temp_root
+            .map_or_else(TempDir::new, TempDir::new_in)
+            .context("Failed to create temporary directory for archive")?;
+
+        let archive_path = match location {
+            ArchiveLocation::Local(path) => path.clone(),
+            ArchiveLocation::Remote(url) => download(system, url, temp_dir.path())?,
+        };
+
+        let mut file = system
+            .open(&archive_path)
+            .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)
+            .with_context(|| format!("Failed to read archive: {}", archive_path.display()))?;
+
+        let entries = read_entries(format, bytes).map_err(|err| {
+            GraftError::from_source(format!(
+                "Failed to extract archive '{}': {err}",
+                archive_path.display()
+            ))
+        })?;
+
+        let extract_dir = temp_dir.path().join("extracted");
+        system.create_dir_all(&extract_dir).with_context(|| {
+            format!(
+                "Failed to create extraction directory: {}",
+                extract_dir.display()
+            )
+        })?;
+        let top_level = single_top_level_dir(&entries);
+        write_entries(system, &extract_dir, entries)?;
+        debug!(
+            "Extracted {} into {}",
+            archive_path.display(),
+            extract_dir.display()
+        );
+
+        let root = top_level.map_or_else(|| extract_dir.clone(), |dir| extract_dir.join(dir));
+        Ok(Self { root, temp_dir })
+    }
+
+    /// Root of the extracted tree; pull sources are resolved against it.
+    #[must_use]
+    #[inline]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Get the path to the temporary directory.
+    #[must_use]
+    #[inline]
+    pub fn temp_path(&self) -> &Path {
+        self.temp_dir.path()
+    }
+}
+
+/// Download `url` into `directory` through `system`, returning the path of
+/// the downloaded file.
+fn download(system: &dyn System, url: &str, directory: &Path) -> Result<PathBuf> {
+    debug!("Downloading archive {url}");
+    let mut response = ureq::get(url).call().map_err(|err| {
+        GraftError::from_source(format!("Failed to download archive '{url}': {err}"))
+    })?;
+    let mut bytes = Vec::new();
+    response
+        .body_mut()
+        .as_reader()
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to download archive '{url}'"))?;
+
+    let archive_path = directory.join("download");
+    system
+        .write(&archive_path, &bytes)
+        .with_context(|| format!("Failed to write file: {}", archive_path.display()))?;
+    Ok(archive_path)
+}
+
+/// Whether `path` has extension `extension` (case-insensitive).
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
+
+/// Read the regular files and directories of an archive into memory.
+///
+/// Symlinks and other special entries are skipped.
+fn read_entries(format: ArchiveFormat, bytes: Vec<u8>) -> Result<Vec<ArchiveEntry>> {
+    let mut entries = Vec::new();
+    match format {
+        ArchiveFormat::TarGz => {
+            let mut archive = tar::Archive::new(GzDecoder::new(Cursor::new(bytes)));
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let path = entry.path()?.into_owned();
+                let entry_type = entry.header().entry_type();
+                if entry_type.is_dir() {
+                    entries.push(ArchiveEntry {
+                        path,
+                        contents: None,
+                    });
+                } else if entry_type.is_file() {
+                    let mut contents = Vec::new();
+                    entry.read_to_end(&mut contents)?;
+                    entries.push(ArchiveEntry {
+                        path,
+                        contents: Some(contents),
+                    });
+                } else {
+                    debug!("Skipping archive entry {}", path.display());
+                }
+            }
+        }
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+            for index in 0..archive.len() {
+                let mut file = archive.by_index(index)?;
+                let path = PathBuf::from(file.name());
+                if file.is_dir() {
+                    entries.push(ArchiveEntry {
+                        path,
+                        contents: None,
+                    });
+                } else if file.is_file() {
+                    let mut contents = Vec::new();
+                    file.read_to_end(&mut contents)?;
+                    entries.push(ArchiveEntry {
+                        path,
+                        contents: Some(contents),
+                    });
+                } else {
+                    debug!("Skipping archive entry {}", path.display());
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Write archive entries below `extract_dir` through `system`.
+fn write_entries(
+    system: &dyn System,
+    extract_dir: &Path,
+    entries: Vec<ArchiveEntry>,
+) -> Result<()> {
+    for entry in entries {
+        let relative = enclosed_path(&entry.path).ok_or_else(|| {
+            GraftError::from_source(format!(
+                "Archive entry '{}' points outside the extraction directory",
+                entry.path.display()
+            ))
+        })?;
+        let destination = extract_dir.join(relative);
+
+        let Some(contents) = entry.contents else {
+            system.create_dir_all(&destination).with_context(|| {
+                format!("Failed to create directory: {}", destination.display())
+            })?;
+            continue;
+        };
+        if let Some(parent) = destination.parent() {
+            system
+                .create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        system
+            .write(&destination, &contents)
+            .with_context(|| format!("Failed to write file: {}", destination.display()))?;
+    }
+    Ok(())
+}
+
+/// `path` with `.` components dropped, or `None` when it is absolute or
+/// climbs out of its parent with `..`.
+fn enclosed_path(path: &Path) -> Option<PathBuf> {
+    let mut enclosed = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => enclosed.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(enclosed)
+}
+
+/// The single top-level directory that holds every entry, if there is one.
+fn single_top_level_dir(entries: &[ArchiveEntry]) -> Option<PathBuf> {
+    let mut top_level: Option<PathBuf> = None;
+    for entry in entries {
+        let relative = enclosed_path(&entry.path)?;
+        let mut components = relative.components();
+        let first = PathBuf::from(components.next()?.as_os_str());
+        // A file at the top level means there is no wrapping directory
+        if entry.contents.is_some() && components.next().is_none() {
+            return None;
+        }
+        match top_level.as_ref() {
+            Some(existing) if *existing != first => return None,
+            Some(_) => {}
+            None => top_level = Some(first),
+        }
+    }
+    top_level
+}
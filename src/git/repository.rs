@@ -1,14 +1,29 @@
 //! Git repository handling and URL parsing.
 
 use crate::error::GraftError;
+use crate::git::archive::{ArchiveFormat, ArchiveLocation};
+use crate::utils::path::to_windows;
 use anyhow::Result;
 use os_shim::System;
 use std::path::{Path, PathBuf};
 
-/// Represents a repository source - either Git or local filesystem.
+/// Message for an empty repository URL, a common first-time mistake.
+pub const EMPTY_REPOSITORY_MESSAGE: &str =
+    "Repository URL is empty \u{2014} set it via --repository or `repository` in the config";
+
+/// Represents a repository source - Git, local filesystem, or archive.
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub enum RepositorySource {
+    /// Archive (`.tar.gz`, `.zip`) that is extracted and read like a local path.
+    Archive {
+        /// Compression format of the archive.
+        format: ArchiveFormat,
+        /// Where the archive is read from.
+        location: ArchiveLocation,
+        /// Original URL as provided by user.
+        original_url: String,
+    },
     /// Git repository with URL.
     Git {
         /// Normalized URL for Git operations.
@@ -36,6 +51,22 @@ pub struct Repository {
 }
 
 impl Repository {
+    /// Get the archive format and location (returns None if this is not an archive source).
+    #[must_use]
+    #[inline]
+    #[expect(
+        clippy::pattern_type_mismatch,
+        reason = "matching on &RepositorySource; dereferencing would require ref bindings which conflict with clippy::ref_patterns"
+    )]
+    pub const fn archive(&self) -> Option<(ArchiveFormat, &ArchiveLocation)> {
+        match &self.source {
+            RepositorySource::Archive {
+                format, location, ..
+            } => Some((*format, location)),
+            RepositorySource::Git { .. } | RepositorySource::Local { .. } => None,
+        }
+    }
+
     /// Get the normalized URL for Git operations (panics if called on Local source).
     ///
     /// # Errors
@@ -53,9 +84,19 @@ impl Repository {
             RepositorySource::Local { .. } => {
                 Err(GraftError::git("git_url() called on Local repository source").into())
             }
+            RepositorySource::Archive { .. } => {
+                Err(GraftError::git("git_url() called on Archive repository source").into())
+            }
         }
     }
 
+    /// Check if this is an archive source.
+    #[must_use]
+    #[inline]
+    pub const fn is_archive(&self) -> bool {
+        matches!(self.source, RepositorySource::Archive { .. })
+    }
+
     /// Check if this is a Git repository.
     #[must_use]
     #[inline]
@@ -70,6 +111,24 @@ impl Repository {
         matches!(self.source, RepositorySource::Local { .. })
     }
 
+    /// Check if reading this source needs network access: a Git repository
+    /// or an archive downloaded over HTTP(S).
+    #[must_use]
+    #[inline]
+    #[expect(
+        clippy::pattern_type_mismatch,
+        reason = "matching on &RepositorySource; dereferencing would require ref bindings which conflict with clippy::ref_patterns"
+    )]
+    pub const fn is_remote(&self) -> bool {
+        match &self.source {
+            RepositorySource::Git { .. } => true,
+            RepositorySource::Archive { location, .. } => {
+                matches!(*location, ArchiveLocation::Remote(_))
+            }
+            RepositorySource::Local { .. } => false,
+        }
+    }
+
     /// Get the local path (returns None if this is a Git source).
     #[must_use]
     #[inline]
@@ -80,7 +139,7 @@ impl Repository {
     pub const fn local_path(&self) -> Option<&PathBuf> {
         match &self.source {
             RepositorySource::Local { resolved_path, .. } => Some(resolved_path),
-            RepositorySource::Git { .. } => None,
+            RepositorySource::Archive { .. } | RepositorySource::Git { .. } => None,
         }
     }
 
@@ -108,20 +167,27 @@ impl Repository {
     }
 }
 
-/// Detect whether the source is a Git repository or local filesystem path.
+/// Detect whether the source is a Git repository, archive, or local filesystem path.
 fn detect_source_type(system: &dyn System, url: &str) -> Result<RepositorySource> {
+    if url.trim().is_empty() {
+        return Err(GraftError::configuration(EMPTY_REPOSITORY_MESSAGE.to_owned()).into());
+    }
+
+    // Archives are recognized by extension or an explicit tar:/zip: prefix
+    if let Some((format, location)) = ArchiveFormat::detect(url) {
+        return create_archive_source(system, url, format, location);
+    }
+
     // ONLY accept "file:" prefix for local filesystem sources
     // This is explicit and leaves room for future prefixes like s3:, gdrive:, etc.
-    if url.starts_with("file:") {
-        // Support both file:// and file:/ formats
-        let path_str = if url.starts_with("file://") {
-            url.strip_prefix("file://")
-                .ok_or_else(|| anyhow::anyhow!("Failed to strip prefix from URL"))?
-        } else {
-            url.strip_prefix("file:")
-                .ok_or_else(|| anyhow::anyhow!("Failed to strip prefix from URL"))?
-        };
-        return create_local_source(system, url, path_str);
+    if let Some(path_str) = strip_file_prefix(url) {
+        if path_str.trim().is_empty() {
+            return Err(GraftError::configuration(format!(
+                "Local repository path is empty in '{url}' \u{2014} expected file:/path/to/repo"
+            ))
+            .into());
+        }
+        return create_local_source(system, url, &path_str);
     }
 
     // Everything else is treated as a Git repository
@@ -132,38 +198,76 @@ fn detect_source_type(system: &dyn System, url: &str) -> Result<RepositorySource
     })
 }
 
-/// Create a local repository source, resolving the path.
-fn create_local_source(
+/// Local path of a `file:` URL, or `None` without the prefix.
+///
+/// Both `file://` and `file:` are accepted, so `file:///srv/repo`,
+/// `file:/srv/repo` and `file:./repo` all name a path. On Windows, a drive
+/// letter may follow the prefix with or without a leading slash
+/// (`file:///C:/repo`, `file://C:/repo`, `file:/C:/repo`), and forward
+/// slashes become backslashes.
+fn strip_file_prefix(url: &str) -> Option<String> {
+    let path = url
+        .strip_prefix("file://")
+        .or_else(|| url.strip_prefix("file:"))?;
+    if cfg!(windows) {
+        let path = path
+            .strip_prefix('/')
+            .filter(|rest| starts_with_drive_letter(rest))
+            .unwrap_or(path);
+        return Some(to_windows(path));
+    }
+    Some(path.to_owned())
+}
+
+/// Whether `path` starts with a Windows drive, like `C:` or `C:/`.
+fn starts_with_drive_letter(path: &str) -> bool {
+    let mut chars = path.chars();
+    matches!(
+        (chars.next(), chars.next(), chars.next()),
+        (Some(letter), Some(':'), None | Some('/' | '\\')) if letter.is_ascii_alphabetic()
+    )
+}
+
+/// Create an archive source, resolving a local archive path.
+fn create_archive_source(
     system: &dyn System,
     original: &str,
-    path_str: &str,
+    format: ArchiveFormat,
+    location: &str,
 ) -> Result<RepositorySource> {
-    // Expand ~ to home directory
-    let expanded_path = if path_str.starts_with('~') {
-        let home = system
-            .env_var("HOME")
-            .or_else(|_| system.env_var("USERPROFILE"))
-            .map_err(|err| {
-                GraftError::configuration(format!(
-                    "Cannot determine home directory for ~ expansion. Error: {err}"
-                ))
-            })?;
-        path_str.replacen('~', &home, 1)
+    let archive_location = if let Some(path_str) = strip_file_prefix(location) {
+        let resolved_path = resolve_local_path(system, &path_str)?;
+        if !system.is_file(&resolved_path)? {
+            return Err(GraftError::from_source(format!(
+                "Archive file does not exist: '{}'",
+                resolved_path.display()
+            ))
+            .into());
+        }
+        ArchiveLocation::Local(resolved_path)
+    } else if location.starts_with("https://") || location.starts_with("http://") {
+        ArchiveLocation::Remote(location.to_owned())
     } else {
-        path_str.to_owned()
+        return Err(GraftError::configuration(format!(
+            "Unsupported archive location '{location}' \u{2014} expected file:/path/to/archive.tar.gz or an http(s) URL"
+        ))
+        .into());
     };
 
-    let path = PathBuf::from(&expanded_path);
+    Ok(RepositorySource::Archive {
+        format,
+        location: archive_location,
+        original_url: original.to_owned(),
+    })
+}
 
-    // Resolve to absolute path
-    let resolved_path = if path.is_absolute() {
-        path
-    } else {
-        system
-            .current_dir()
-            .map_err(|err| GraftError::filesystem(format!("Cannot get current directory: {err}")))?
-            .join(&path)
-    };
+/// Create a local repository source, resolving the path.
+fn create_local_source(
+    system: &dyn System,
+    original: &str,
+    path_str: &str,
+) -> Result<RepositorySource> {
+    let resolved_path = resolve_local_path(system, path_str)?;
 
     // Verify the path exists
     if !system.exists(&resolved_path)? {
@@ -189,10 +293,42 @@ fn create_local_source(
     })
 }
 
+/// Expand `~` and resolve a local path against the current directory.
+fn resolve_local_path(system: &dyn System, path_str: &str) -> Result<PathBuf> {
+    // Expand ~ to home directory
+    let expanded_path = if path_str.starts_with('~') {
+        let home = system
+            .env_var("HOME")
+            .or_else(|_| system.env_var("USERPROFILE"))
+            .map_err(|err| {
+                GraftError::configuration(format!(
+                    "Cannot determine home directory for ~ expansion. Error: {err}"
+                ))
+            })?;
+        path_str.replacen('~', &home, 1)
+    } else {
+        path_str.to_owned()
+    };
+
+    let path = PathBuf::from(&expanded_path);
+
+    // Resolve to absolute path
+    if path.is_absolute() {
+        Ok(path)
+    } else {
+        Ok(system
+            .current_dir()
+            .map_err(|err| GraftError::filesystem(format!("Cannot get current directory: {err}")))?
+            .join(&path))
+    }
+}
+
 /// Normalize a repository URL to a format suitable for Git operations.
 fn normalize_repository_url(url: &str) -> Result<String> {
     // Handle different URL formats
-    if url.starts_with("https://") || url.starts_with("http://") {
+    if url.trim().is_empty() {
+        Err(GraftError::configuration(EMPTY_REPOSITORY_MESSAGE.to_owned()).into())
+    } else if url.starts_with("https://") || url.starts_with("http://") {
         // Already a full HTTP/HTTPS URL
         if Path::new(url)
             .extension()
@@ -242,8 +378,8 @@ fn normalize_repository_url(url: &str) -> Result<String> {
     reason = "matching on &RepositorySource; dereferencing would require ref bindings which conflict with clippy::ref_patterns"
 )]
 pub fn validate_repository_access(repo: &Repository, tag: &str) -> Result<()> {
-    // For local repositories, we've already validated the path exists in detect_source_type
-    if repo.is_local() {
+    // For local and archive sources, we've already validated the path exists in detect_source_type
+    if repo.is_local() || repo.is_archive() {
         // Local sources don't need tag validation (ignored)
         return Ok(());
     }
@@ -252,7 +388,7 @@ pub fn validate_repository_access(repo: &Repository, tag: &str) -> Result<()> {
     match &repo.source {
         RepositorySource::Git { normalized_url, .. } => {
             if normalized_url.is_empty() {
-                return Err(GraftError::git("Repository URL cannot be empty".to_owned()).into());
+                return Err(GraftError::git(EMPTY_REPOSITORY_MESSAGE.to_owned()).into());
             }
 
             if tag.is_empty() {
@@ -262,7 +398,7 @@ pub fn validate_repository_access(repo: &Repository, tag: &str) -> Result<()> {
                 .into());
             }
         }
-        RepositorySource::Local { .. } => {
+        RepositorySource::Archive { .. } | RepositorySource::Local { .. } => {
             // Already handled above
         }
     }
@@ -270,6 +406,24 @@ pub fn validate_repository_access(repo: &Repository, tag: &str) -> Result<()> {
     Ok(())
 }
 
+/// Check that `repo` can be read without network access (`--offline`).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is a Git remote or an archive downloaded over HTTP(S)
+#[inline]
+pub fn ensure_offline_access(repo: &Repository) -> Result<()> {
+    if repo.is_remote() {
+        return Err(GraftError::configuration(format!(
+            "Repository '{}' needs network access, which --offline forbids; use a file: URL or a local path",
+            repo.url
+        ))
+        .into());
+    }
+    Ok(())
+}
+
 /// Tests for private functions only. Public API tests are in `tests/repository_unit_tests.rs`.
 #[cfg(test)]
 mod tests;
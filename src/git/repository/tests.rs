@@ -1,6 +1,7 @@
 #![expect(clippy::unwrap_used, reason = "These are unit tests")]
 
 use super::*;
+use os_shim::mock::MockSystem;
 
 #[test]
 fn normalize_repository_url_tst() {
@@ -28,3 +29,26 @@ fn invalid_repository_urls() {
     normalize_repository_url("").unwrap_err();
     normalize_repository_url("too/many/slashes").unwrap_err();
 }
+
+#[test]
+fn empty_repository_url_message() {
+    let system = MockSystem::new();
+
+    for url in ["", "   "] {
+        let err = Repository::new(&system, url).unwrap_err();
+        assert!(
+            err.to_string().contains("Repository URL is empty"),
+            "unexpected error: {err}"
+        );
+        assert!(
+            !err.to_string()
+                .contains("Unsupported repository URL format")
+        );
+    }
+
+    let err = normalize_repository_url("").unwrap_err();
+    assert!(err.to_string().contains("--repository"));
+
+    let err = Repository::new(&system, "file:").unwrap_err();
+    assert!(err.to_string().contains("Local repository path is empty"));
+}
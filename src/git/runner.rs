@@ -0,0 +1,262 @@
+//! Git command execution abstraction.
+//!
+//! All git invocations made by [`SparseCheckout`](crate::git::SparseCheckout)
+//! go through a [`GitRunner`], so tests can record and script them with
+//! [`MockGitRunner`] instead of spawning the `git` binary.
+
+use crate::utils::process::{TrackedChild, spawn_child};
+use anyhow::{Context as _, Result, anyhow};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, PoisonError};
+
+/// A single `git` invocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GitInvocation {
+    /// Arguments passed to `git` (without the program name).
+    pub args: Vec<String>,
+    /// Working directory for the command, if any.
+    pub cwd: Option<PathBuf>,
+    /// Extra environment variables set on the `git` process.
+    pub env: Vec<(String, String)>,
+    /// Let git write its standard error to the terminal instead of
+    /// capturing it.
+    pub inherit_stderr: bool,
+}
+
+impl GitInvocation {
+    /// Create a new invocation from arguments and an optional working directory.
+    #[must_use]
+    #[inline]
+    pub fn new<I, S>(args: I, cwd: Option<&Path>) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            args: args.into_iter().map(Into::into).collect(),
+            cwd: cwd.map(Path::to_path_buf),
+            env: Vec::new(),
+            inherit_stderr: false,
+        }
+    }
+
+    /// The value of an extra environment variable, if set.
+    #[must_use]
+    #[inline]
+    pub fn env_var(&self, key: &str) -> Option<&str> {
+        self.env
+            .iter()
+            .find(|entry| entry.0 == key)
+            .map(|entry| entry.1.as_str())
+    }
+
+    /// The git subcommand (first argument), e.g. `clone`.
+    #[must_use]
+    #[inline]
+    pub fn subcommand(&self) -> Option<&str> {
+        self.args.first().map(String::as_str)
+    }
+
+    /// Set extra environment variables on the `git` process.
+    #[must_use]
+    #[inline]
+    pub fn with_env(mut self, env: Vec<(String, String)>) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Let git write its standard error (including progress) to the terminal.
+    #[must_use]
+    #[inline]
+    pub const fn with_inherited_stderr(mut self, inherit_stderr: bool) -> Self {
+        self.inherit_stderr = inherit_stderr;
+        self
+    }
+}
+
+/// Captured result of a `git` invocation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GitOutput {
+    /// Standard error, lossily decoded.
+    pub stderr: String,
+    /// Standard output, lossily decoded.
+    pub stdout: String,
+    /// Whether the process exited successfully.
+    pub success: bool,
+}
+
+impl GitOutput {
+    /// A failed invocation with the given standard error.
+    #[must_use]
+    #[inline]
+    pub fn failure(stderr: &str) -> Self {
+        Self {
+            stderr: stderr.to_owned(),
+            stdout: String::new(),
+            success: false,
+        }
+    }
+
+    /// A successful invocation with the given standard output.
+    #[must_use]
+    #[inline]
+    pub fn success(stdout: &str) -> Self {
+        Self {
+            stderr: String::new(),
+            stdout: stdout.to_owned(),
+            success: true,
+        }
+    }
+}
+
+/// Runs `git` commands.
+pub trait GitRunner: Send + Sync {
+    /// Run a single git invocation and capture its output.
+    ///
+    /// A non-zero exit status is reported through [`GitOutput::success`],
+    /// not as an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The `git` process cannot be spawned
+    fn run(&self, invocation: &GitInvocation) -> Result<GitOutput>;
+}
+
+/// [`GitRunner`] that spawns the `git` binary found in `PATH`.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct RealGitRunner;
+
+impl RealGitRunner {
+    /// Create a new runner.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl GitRunner for RealGitRunner {
+    #[inline]
+    fn run(&self, invocation: &GitInvocation) -> Result<GitOutput> {
+        let mut command = Command::new("git");
+        command
+            .args(&invocation.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(cwd) = invocation.cwd.as_ref() {
+            command.current_dir(cwd);
+        }
+        command.envs(invocation.env.iter().map(|entry| (&entry.0, &entry.1)));
+        if invocation.inherit_stderr {
+            command.stderr(Stdio::inherit());
+        }
+
+        let output = spawn_child(&mut command)
+            .and_then(TrackedChild::wait_with_output)
+            .with_context(|| {
+                format!(
+                    "Failed to execute git {}",
+                    invocation.subcommand().unwrap_or_default()
+                )
+            })?;
+
+        Ok(GitOutput {
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            success: output.status.success(),
+        })
+    }
+}
+
+/// [`GitRunner`] for tests: records every invocation and returns scripted
+/// outputs keyed by subcommand (successful empty output by default).
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct MockGitRunner {
+    /// Scripted outputs keyed by working directory and subcommand, which
+    /// take precedence over `responses`.
+    cwd_responses: HashMap<(PathBuf, String), GitOutput>,
+    /// Simulate `git` missing from `PATH` (every invocation fails to spawn).
+    git_missing: bool,
+    /// Invocations received so far, in order.
+    invocations: Mutex<Vec<GitInvocation>>,
+    /// Scripted outputs keyed by subcommand.
+    responses: HashMap<String, GitOutput>,
+}
+
+impl MockGitRunner {
+    /// All invocations received so far, in order.
+    #[must_use]
+    #[inline]
+    pub fn invocations(&self) -> Vec<GitInvocation> {
+        self.invocations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Create a mock that succeeds for every invocation.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return `output` for every invocation of `subcommand`.
+    #[must_use]
+    #[inline]
+    pub fn with_response(mut self, subcommand: &str, output: GitOutput) -> Self {
+        self.responses.insert(subcommand.to_owned(), output);
+        self
+    }
+
+    /// Return `output` for every invocation of `subcommand` run in `cwd`.
+    #[must_use]
+    #[inline]
+    pub fn with_response_in(mut self, cwd: &Path, subcommand: &str, output: GitOutput) -> Self {
+        self.cwd_responses
+            .insert((cwd.to_path_buf(), subcommand.to_owned()), output);
+        self
+    }
+
+    /// Simulate `git` missing from `PATH`: every invocation fails to spawn.
+    #[must_use]
+    #[inline]
+    pub const fn without_git(mut self) -> Self {
+        self.git_missing = true;
+        self
+    }
+}
+
+impl GitRunner for MockGitRunner {
+    #[inline]
+    fn run(&self, invocation: &GitInvocation) -> Result<GitOutput> {
+        self.invocations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(invocation.clone());
+
+        if self.git_missing {
+            return Err(anyhow!("Failed to execute git: No such file or directory"));
+        }
+
+        let Some(subcommand) = invocation.subcommand() else {
+            return Ok(GitOutput::success(""));
+        };
+        let in_cwd = invocation.cwd.as_ref().and_then(|cwd| {
+            self.cwd_responses
+                .get(&(cwd.clone(), subcommand.to_owned()))
+        });
+        Ok(in_cwd
+            .or_else(|| self.responses.get(subcommand))
+            .cloned()
+            .unwrap_or_else(|| GitOutput::success("")))
+    }
+}
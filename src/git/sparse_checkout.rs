@@ -2,6 +2,8 @@
 
 use crate::error::GraftError;
 use crate::git::Repository;
+use crate::git::runner::{GitInvocation, GitOutput, GitRunner, RealGitRunner};
+use crate::utils::process::child_output;
 use anyhow::{Context as _, Result};
 use core::fmt::Write as _;
 use std::path::{Path, PathBuf};
@@ -9,35 +11,185 @@ use std::process::Command;
 use tempfile::TempDir;
 use tracing::debug;
 
+/// Default partial-clone filter spec.
+pub const DEFAULT_CLONE_FILTER: &str = "blob:none";
+
+/// How much of git's own output reaches the terminal during clone and checkout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GitVerbosity {
+    /// Capture git's output and only surface it on error.
+    #[default]
+    Captured,
+    /// Let git write its progress to the terminal (`--loud-git`).
+    Loud,
+    /// Pass `-q` to clone and checkout (`--quiet-git`).
+    Quiet,
+}
+
+/// Tuning options for the clone performed by [`SparseCheckout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CloneOptions {
+    /// CA bundle for HTTPS remotes, exported as `GIT_SSL_CAINFO`.
+    pub ca_bundle: Option<PathBuf>,
+    /// Fetch all tags even for tag references, instead of a single-tag shallow clone.
+    pub fetch_tags: bool,
+    /// Partial-clone filter spec passed as `--filter=<spec>`.
+    pub filter: String,
+    /// `known_hosts` file for SSH remotes, enforced through `GIT_SSH_COMMAND`.
+    pub known_hosts: Option<PathBuf>,
+    /// Largest source subtree, in bytes, that is checked out (`--max-clone-size`).
+    pub max_clone_size: Option<u64>,
+    /// How git's clone and checkout output is handled.
+    pub verbosity: GitVerbosity,
+}
+
+impl Default for CloneOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            ca_bundle: None,
+            fetch_tags: false,
+            filter: DEFAULT_CLONE_FILTER.to_owned(),
+            known_hosts: None,
+            max_clone_size: None,
+            verbosity: GitVerbosity::Captured,
+        }
+    }
+}
+
+impl CloneOptions {
+    /// Environment variables pinning the trust anchors for every git command.
+    #[must_use]
+    #[inline]
+    pub fn git_env(&self) -> Vec<(String, String)> {
+        let mut env = Vec::new();
+        if let Some(ca_bundle) = self.ca_bundle.as_ref() {
+            env.push(("GIT_SSL_CAINFO".to_owned(), ca_bundle.display().to_string()));
+        }
+        if let Some(known_hosts) = self.known_hosts.as_ref() {
+            env.push((
+                "GIT_SSH_COMMAND".to_owned(),
+                format!(
+                    "ssh -o UserKnownHostsFile=\"{}\" -o StrictHostKeyChecking=yes",
+                    known_hosts.display()
+                ),
+            ));
+        }
+        env
+    }
+
+    /// Validate HTTPS remotes against the CA bundle at `path`.
+    #[must_use]
+    #[inline]
+    pub fn with_ca_bundle(mut self, path: &Path) -> Self {
+        self.ca_bundle = Some(path.to_path_buf());
+        self
+    }
+
+    /// Fetch all tags even when the reference looks like a tag (`--fetch-tags`).
+    #[must_use]
+    #[inline]
+    pub const fn with_fetch_tags(mut self, fetch_tags: bool) -> Self {
+        self.fetch_tags = fetch_tags;
+        self
+    }
+
+    /// Use `filter` as the partial-clone filter spec.
+    #[must_use]
+    #[inline]
+    pub fn with_filter(mut self, filter: &str) -> Self {
+        filter.clone_into(&mut self.filter);
+        self
+    }
+
+    /// Only accept SSH host keys listed in the `known_hosts` file at `path`.
+    #[must_use]
+    #[inline]
+    pub fn with_known_hosts(mut self, path: &Path) -> Self {
+        self.known_hosts = Some(path.to_path_buf());
+        self
+    }
+
+    /// Abort before checkout when the source subtree is larger than `bytes`.
+    #[must_use]
+    #[inline]
+    pub const fn with_max_clone_size(mut self, bytes: u64) -> Self {
+        self.max_clone_size = Some(bytes);
+        self
+    }
+
+    /// Choose how git's clone and checkout output is handled.
+    #[must_use]
+    #[inline]
+    pub const fn with_verbosity(mut self, verbosity: GitVerbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+}
+
 /// Performs sparse checkout of a specific path from a Git repository.
 #[non_exhaustive]
-pub struct SparseCheckout {
+pub struct SparseCheckout<'runner> {
+    /// Clone tuning options.
+    pub options: CloneOptions,
     /// Git reference (tag/branch) to checkout.
     pub reference: String,
     /// Repository to clone from.
     pub repository: Repository,
+    /// Runner used for all git invocations.
+    pub runner: &'runner dyn GitRunner,
     /// Path within the repository to sparse-checkout.
     pub source_path: String,
+    /// Patterns passed to `git sparse-checkout set` instead of `source_path`
+    /// (`sparsePatterns`).
+    pub sparse_patterns: Vec<String>,
     /// Temporary directory for the sparse checkout.
     pub temp_dir: TempDir,
 }
 
-impl SparseCheckout {
+impl<'runner> SparseCheckout<'runner> {
+    /// Abort when the source subtree of the cloned repository is larger than
+    /// [`CloneOptions::max_clone_size`].
+    fn check_source_size(&self, repo_path: &Path) -> Result<()> {
+        let Some(limit) = self.options.max_clone_size else {
+            return Ok(());
+        };
+
+        let (files, bytes) = self.list_source_size(repo_path)?;
+        debug!(
+            "check_source_size -> '{}' is {bytes} byte(s) in {files} file(s)",
+            self.source_path
+        );
+        if bytes > limit {
+            return Err(GraftError::git(format!(
+                "Source '{}' at reference '{}' is {bytes} bytes ({files} files), over the --max-clone-size limit of {limit} bytes",
+                self.source_path, self.reference
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
     /// Checkout the specified reference.
     fn checkout_reference(&self, repo_path: &Path) -> Result<()> {
         debug!(
             "checkout_reference -> Checking out reference: {}",
             self.reference
         );
-        let output = Command::new("git")
-            .args(["checkout", &self.reference])
-            .current_dir(repo_path)
-            .output()
+        let mut args = vec!["checkout"];
+        if self.options.verbosity == GitVerbosity::Quiet {
+            args.push("-q");
+        }
+        args.push(&self.reference);
+        let output = self
+            .run_noisy_git(&args, Some(repo_path))
             .context("Failed to execute git checkout")?;
 
-        if !output.status.success() {
+        if !output.success {
             debug!("checkout_reference -> Failed to checkout reference");
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = output.stderr;
             return Err(GraftError::git(format!(
                 "Failed to checkout reference '{}': {}",
                 self.reference,
@@ -51,23 +203,47 @@ impl SparseCheckout {
         Ok(())
     }
 
-    /// Clone the repository with blob filter and no checkout.
+    /// Clone the repository with the configured partial-clone filter and no checkout.
+    ///
+    /// Relative and symbolic references (`HEAD~2`, `@`, `main^`) are resolved
+    /// against the commit graph, so they get an unfiltered clone with full
+    /// history instead. References that look like tags are fetched alone with
+    /// `--branch <tag> --depth 1`, unless [`CloneOptions::fetch_tags`] is set.
     fn clone_repository(&self, repo_path: &Path) -> Result<()> {
-        let output = Command::new("git")
-            .args([
-                "clone",
-                "--filter=blob:none",
-                "--no-checkout",
-                self.repository.git_url()?,
-                repo_path.to_str().ok_or_else(|| {
-                    anyhow::anyhow!("Failed to convert repository path to string")
-                })?,
-            ])
-            .output()
+        let filter_arg = format!("--filter={}", self.options.filter);
+        let mut args = vec!["clone"];
+        if self.options.verbosity == GitVerbosity::Quiet {
+            args.push("-q");
+        }
+        if is_relative_reference(&self.reference) {
+            debug!(
+                "clone_repository -> '{}' is a relative reference, cloning full history",
+                self.reference
+            );
+        } else {
+            args.push(&filter_arg);
+        }
+        if !self.options.fetch_tags && looks_like_tag(&self.reference) {
+            debug!(
+                "clone_repository -> '{}' looks like a tag, fetching only that tag",
+                self.reference
+            );
+            args.extend(["--branch", &self.reference, "--depth", "1"]);
+        }
+        args.push("--no-checkout");
+        args.push(self.repository.git_url()?);
+        args.push(
+            repo_path
+                .to_str()
+                .ok_or_else(|| anyhow::anyhow!("Failed to convert repository path to string"))?,
+        );
+
+        let output = self
+            .run_noisy_git(&args, None)
             .context("Failed to execute git clone command")?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.success {
+            let stderr = output.stderr;
             return Err(GraftError::git(format!(
                 "Failed to clone repository '{}': {}",
                 self.repository.original_url(),
@@ -79,12 +255,31 @@ impl SparseCheckout {
         Ok(())
     }
 
+    /// Estimate the file count and total bytes of the source path at the
+    /// reference, without checking anything out (`git ls-tree -r -l`).
+    ///
+    /// Branches that only exist on the remote are looked up as
+    /// `origin/<reference>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The repository cannot be cloned
+    /// - The reference cannot be resolved
+    #[inline]
+    pub fn estimate_size(&self) -> Result<(usize, u64)> {
+        let repo_path = self.temp_dir.path();
+        self.clone_repository(repo_path)?;
+        self.list_source_size(repo_path)
+    }
+
     /// Execute the sparse checkout operation.
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - The repository cannot be cloned
+    /// - The source is larger than [`CloneOptions::max_clone_size`]
     /// - The sparse checkout cannot be initialized
     /// - The sparse checkout patterns cannot be set
     /// - The reference cannot be checked out
@@ -97,10 +292,11 @@ impl SparseCheckout {
         self.clone_repository(repo_path)?;
 
         debug!("Repository cloned");
+        self.check_source_size(repo_path)?;
         debug!("Initializing sparse checkout");
 
         // Step 2: Initialize sparse checkout
-        Self::init_sparse_checkout(repo_path)?;
+        self.init_sparse_checkout(repo_path)?;
 
         debug!("Sparse checkout initialized");
         debug!("Setting sparse checkout patterns");
@@ -122,6 +318,32 @@ impl SparseCheckout {
         Ok(result_path)
     }
 
+    /// Resolve the commit SHA of the checked-out reference (`git rev-parse HEAD`).
+    ///
+    /// Only meaningful after [`SparseCheckout::execute`] has succeeded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - `git rev-parse` cannot be run or fails
+    #[inline]
+    pub fn resolve_sha(&self) -> Result<String> {
+        let output = self
+            .run_git(&["rev-parse", "HEAD"], Some(self.temp_dir.path()))
+            .context("Failed to execute git rev-parse")?;
+
+        if !output.success {
+            return Err(GraftError::git(format!(
+                "Failed to resolve commit for reference '{}': {}",
+                self.reference,
+                output.stderr.trim()
+            ))
+            .into());
+        }
+
+        Ok(output.stdout.trim().to_owned())
+    }
+
     /// Get diagnostic information about what was actually checked out.
     /// This is useful for debugging when `source_exists()` returns false.
     ///
@@ -184,15 +406,25 @@ impl SparseCheckout {
     }
 
     /// Initialize sparse checkout configuration.
-    fn init_sparse_checkout(repo_path: &Path) -> Result<()> {
-        let output = Command::new("git")
-            .args(["sparse-checkout", "init", "--cone"])
-            .current_dir(repo_path)
-            .output()
+    ///
+    /// Cone mode is used unless explicit patterns need gitignore-style
+    /// matching (see [`is_cone_pattern`]).
+    fn init_sparse_checkout(&self, repo_path: &Path) -> Result<()> {
+        let mode = if self
+            .sparse_patterns
+            .iter()
+            .all(|pattern| is_cone_pattern(pattern))
+        {
+            "--cone"
+        } else {
+            "--no-cone"
+        };
+        let output = self
+            .run_git(&["sparse-checkout", "init", mode], Some(repo_path))
             .context("Failed to execute git sparse-checkout init")?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.success {
+            let stderr = output.stderr;
             return Err(GraftError::git(format!(
                 "Failed to initialize sparse checkout: {}",
                 stderr.trim()
@@ -203,6 +435,33 @@ impl SparseCheckout {
         Ok(())
     }
 
+    /// File count and total bytes of the source path at the reference in the
+    /// cloned repository (`git ls-tree -r -l`).
+    fn list_source_size(&self, repo_path: &Path) -> Result<(usize, u64)> {
+        let remote_reference = format!("origin/{}", self.reference);
+        let mut last_error = String::new();
+        for tree_ish in [self.reference.as_str(), remote_reference.as_str()] {
+            let output = self
+                .run_git(
+                    &["ls-tree", "-r", "-l", tree_ish, "--", &self.source_path],
+                    Some(repo_path),
+                )
+                .context("Failed to execute git ls-tree")?;
+            if output.success {
+                return Ok(parse_ls_tree_sizes(&output.stdout));
+            }
+            last_error = output.stderr;
+        }
+
+        Err(GraftError::git(format!(
+            "Failed to list '{}' at reference '{}': {}",
+            self.source_path,
+            self.reference,
+            last_error.trim()
+        ))
+        .into())
+    }
+
     /// Create a new sparse checkout operation.
     ///
     /// # Errors
@@ -211,27 +470,72 @@ impl SparseCheckout {
     /// - The temporary directory cannot be created
     #[inline]
     pub fn new(repository: Repository, reference: String, source_path: String) -> Result<Self> {
-        let temp_dir =
-            TempDir::new().context("Failed to create temporary directory for Git operations")?;
+        Self::new_in(repository, reference, source_path, None)
+    }
+
+    /// Create a new sparse checkout operation whose temporary directory is
+    /// created under `temp_root` (`--temp-dir`), or in the OS default
+    /// location when `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The temporary directory cannot be created
+    #[inline]
+    pub fn new_in(
+        repository: Repository,
+        reference: String,
+        source_path: String,
+        temp_root: Option<&Path>,
+    ) -> Result<Self> {
+        let temp_dir = temp_root
+            .map_or_else(TempDir::new, TempDir::new_in)
+            .context("Failed to create temporary directory for Git operations")?;
 
         Ok(Self {
+            options: CloneOptions::default(),
             reference,
             repository,
+            runner: &RealGitRunner,
             source_path,
+            sparse_patterns: Vec::new(),
             temp_dir,
         })
     }
 
-    /// Set sparse checkout patterns.
+    /// Run a git command through the configured runner.
+    ///
+    /// The pinned trust anchors from [`CloneOptions`] apply to every command,
+    /// since a partial clone keeps fetching from the remote after the clone.
+    fn run_git(&self, args: &[&str], cwd: Option<&Path>) -> Result<GitOutput> {
+        self.runner
+            .run(&GitInvocation::new(args.iter().copied(), cwd).with_env(self.options.git_env()))
+    }
+
+    /// Run a git command whose progress output is shown with
+    /// [`GitVerbosity::Loud`] (clone and checkout).
+    fn run_noisy_git(&self, args: &[&str], cwd: Option<&Path>) -> Result<GitOutput> {
+        let invocation = GitInvocation::new(args.iter().copied(), cwd)
+            .with_env(self.options.git_env())
+            .with_inherited_stderr(self.options.verbosity == GitVerbosity::Loud);
+        self.runner.run(&invocation)
+    }
+
+    /// Set sparse checkout patterns: the explicit `sparse_patterns`, or
+    /// else the source path.
     fn set_sparse_patterns(&self, repo_path: &Path) -> Result<()> {
-        let output = Command::new("git")
-            .args(["sparse-checkout", "set", &self.source_path])
-            .current_dir(repo_path)
-            .output()
+        let mut args = vec!["sparse-checkout", "set"];
+        if self.sparse_patterns.is_empty() {
+            args.push(&self.source_path);
+        } else {
+            args.extend(self.sparse_patterns.iter().map(String::as_str));
+        }
+        let output = self
+            .run_git(&args, Some(repo_path))
             .context("Failed to execute git sparse-checkout set")?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.success {
+            let stderr = output.stderr;
             return Err(GraftError::git(format!(
                 "Failed to set sparse checkout patterns: {}",
                 stderr.trim()
@@ -255,6 +559,141 @@ impl SparseCheckout {
     pub fn temp_path(&self) -> &Path {
         self.temp_dir.path()
     }
+
+    /// Use the given clone options.
+    #[must_use]
+    #[inline]
+    pub fn with_options(mut self, options: CloneOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Route all git invocations through `runner`.
+    #[must_use]
+    #[inline]
+    pub fn with_runner(mut self, runner: &'runner dyn GitRunner) -> Self {
+        self.runner = runner;
+        self
+    }
+
+    /// Check out `patterns` instead of the source path (`sparsePatterns`).
+    #[must_use]
+    #[inline]
+    pub fn with_sparse_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.sparse_patterns = patterns;
+        self
+    }
+}
+
+/// Check whether a sparse-checkout pattern can be used in cone mode.
+///
+/// Cone mode only takes plain directory paths; a pattern with a leading
+/// `/`, a `!` exclusion or glob characters needs non-cone
+/// (gitignore-style) matching.
+#[must_use]
+#[inline]
+pub fn is_cone_pattern(pattern: &str) -> bool {
+    !pattern.starts_with(['/', '!']) && !pattern.contains(['*', '?', '[', '\\'])
+}
+
+/// Check whether a reference is relative to or symbolic for another commit.
+///
+/// Recognizes `HEAD`, `@`, ancestry suffixes (`~`, `^`) and reflog selectors
+/// (`@{...}`). None of these are valid in branch or tag names, so plain
+/// branch and tag names never match.
+#[must_use]
+#[inline]
+pub fn is_relative_reference(reference: &str) -> bool {
+    reference == "HEAD"
+        || reference == "@"
+        || reference.contains(['~', '^'])
+        || reference.contains("@{")
+}
+
+/// Check whether a reference looks like a release tag.
+///
+/// Matches version-style names such as `v1.2.0`, `1.4` or `v2.0.0-rc.1`: an
+/// optional `v`, a digit, and at least one `.`. Branch names rarely look like
+/// this, and a false positive only costs the single-tag shallow fetch.
+#[must_use]
+#[inline]
+pub fn looks_like_tag(reference: &str) -> bool {
+    let version = reference.strip_prefix(['v', 'V']).unwrap_or(reference);
+    version.starts_with(|ch: char| ch.is_ascii_digit())
+        && version.contains('.')
+        && version
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '+' | '_'))
+}
+
+/// Sum the blob sizes in `git ls-tree -l` output.
+///
+/// Returns the number of blobs and their total size in bytes. Lines that are
+/// not blobs (submodules) or carry no size are skipped.
+#[must_use]
+#[inline]
+pub fn parse_ls_tree_sizes(output: &str) -> (usize, u64) {
+    let mut files: usize = 0;
+    let mut bytes: u64 = 0;
+    for line in output.lines() {
+        let Some((meta, _)) = line.split_once('\t') else {
+            continue;
+        };
+        let mut fields = meta.split_whitespace();
+        if fields.nth(1) != Some("blob") {
+            continue;
+        }
+        if let Some(size) = fields.nth(1).and_then(|size| size.parse::<u64>().ok()) {
+            files = files.saturating_add(1);
+            bytes = bytes.saturating_add(size);
+        }
+    }
+    (files, bytes)
+}
+
+/// Validate a partial-clone filter spec.
+///
+/// Accepts the filter syntaxes understood by `git clone --filter`:
+/// `blob:none`, `blob:limit=<n>[kmg]`, `tree:<depth>`, `object:type=<type>`,
+/// `sparse:oid=<blob-ish>` and `combine:<filter>+<filter>...`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The spec does not match any known filter syntax
+#[inline]
+pub fn validate_clone_filter(spec: &str) -> Result<()> {
+    if is_valid_clone_filter(spec) {
+        Ok(())
+    } else {
+        Err(GraftError::configuration(format!(
+            "Invalid clone filter '{spec}'. Expected one of: blob:none, blob:limit=<n>[kmg], tree:<depth>, object:type=<blob|tree|commit|tag>, sparse:oid=<blob-ish>, combine:<filter>+<filter>"
+        ))
+        .into())
+    }
+}
+
+/// Check a partial-clone filter spec against the known git syntaxes.
+fn is_valid_clone_filter(spec: &str) -> bool {
+    match spec.split_once(':') {
+        Some(("blob", "none")) => true,
+        Some(("blob", rest)) => rest.strip_prefix("limit=").is_some_and(is_size_spec),
+        Some(("tree", depth)) => !depth.is_empty() && depth.chars().all(|ch| ch.is_ascii_digit()),
+        Some(("object", rest)) => {
+            matches!(rest, "type=blob" | "type=tree" | "type=commit" | "type=tag")
+        }
+        Some(("sparse", rest)) => rest.strip_prefix("oid=").is_some_and(|oid| !oid.is_empty()),
+        Some(("combine", rest)) => !rest.is_empty() && rest.split('+').all(is_valid_clone_filter),
+        _ => false,
+    }
+}
+
+/// Check a size spec such as `1024`, `512k` or `1m`.
+fn is_size_spec(value: &str) -> bool {
+    let digits = value
+        .strip_suffix(['k', 'm', 'g', 'K', 'M', 'G'])
+        .unwrap_or(value);
+    !digits.is_empty() && digits.chars().all(|ch| ch.is_ascii_digit())
 }
 
 /// Check if Git is available and meets minimum version requirements.
@@ -267,9 +706,7 @@ impl SparseCheckout {
 /// - The Git version is too old
 #[inline]
 pub fn check_git_availability() -> Result<()> {
-    let output = Command::new("git")
-        .args(["--version"])
-        .output()
+    let output = child_output(Command::new("git").args(["--version"]))
         .context("Git command not found. Please ensure Git is installed and available in PATH")?;
 
     if !output.status.success() {
@@ -0,0 +1,180 @@
+//! Remote tag resolution for `tagPattern`.
+//!
+//! A pull with `tagPattern: "v2.*"` tracks the latest matching release
+//! instead of a pinned tag: the remote's tags are listed with
+//! `git ls-remote --tags` and the highest match, by version order, is
+//! checked out.
+
+use crate::error::GraftError;
+use crate::git::runner::{GitInvocation, GitRunner};
+use anyhow::{Context as _, Result};
+use core::cmp::Ordering;
+use regex::Regex;
+use tracing::debug;
+
+/// Characters a tag pattern may not contain (besides whitespace): they are
+/// not allowed in Git ref names.
+const INVALID_PATTERN_CHARS: &[char] = &['~', '^', ':', '\\', '[', ']'];
+
+/// A run of digits or of other characters within a pre-release identifier.
+///
+/// Numbers sort below text, as numeric identifiers do in Semantic
+/// Versioning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PreReleasePart<'tag> {
+    /// A run of ASCII digits, compared numerically.
+    Number(u64),
+    /// A run of other characters (or digits too long for a number),
+    /// compared as text.
+    Text(&'tag str),
+}
+
+/// Resolve `pattern` to the highest tag of the repository at `url` that
+/// matches it.
+///
+/// `*` matches any run of characters and `?` any single character; the
+/// pattern must match the whole tag name. Matching tags are ordered by their
+/// numeric components (`v2.10.0` above `v2.9.3`), a pre-release (`v2.1.0-rc1`)
+/// below its release and pre-releases by their numbers too (`rc10` above
+/// `rc2`), and tags without a version last. `env` is set on the
+/// `git ls-remote` process (e.g. [`CloneOptions::git_env`](crate::git::CloneOptions::git_env)).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The pattern is empty, contains whitespace, or has characters not
+///   allowed in tag names
+/// - `git ls-remote` cannot be run or fails
+/// - No tag matches the pattern
+#[inline]
+pub fn resolve_tag_pattern(
+    runner: &dyn GitRunner,
+    url: &str,
+    pattern: &str,
+    env: Vec<(String, String)>,
+) -> Result<String> {
+    let matcher = pattern_regex(pattern)?;
+
+    let output = runner
+        .run(&GitInvocation::new(["ls-remote", "--tags", "--refs", url], None).with_env(env))
+        .context("Failed to execute git ls-remote")?;
+    if !output.success {
+        return Err(GraftError::git(format!(
+            "Failed to list tags of '{url}': {}",
+            output.stderr.trim()
+        ))
+        .into());
+    }
+
+    let mut tags: Vec<&str> = output
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .filter_map(|reference| reference.strip_prefix("refs/tags/"))
+        .filter(|tag| matcher.is_match(tag))
+        .collect();
+    tags.sort_by(|first, second| compare_versions(first, second));
+    debug!("Tags of {url} matching '{pattern}': {tags:?}");
+
+    tags.last().map(|tag| (*tag).to_owned()).ok_or_else(|| {
+        GraftError::git(format!("No tag of '{url}' matches tagPattern '{pattern}'")).into()
+    })
+}
+
+/// Check `pattern` and compile it to a regex matching whole tag names.
+fn pattern_regex(pattern: &str) -> Result<Regex> {
+    if pattern.is_empty()
+        || pattern.chars().any(char::is_whitespace)
+        || pattern.contains(INVALID_PATTERN_CHARS)
+        || pattern.contains("..")
+    {
+        return Err(GraftError::configuration(format!(
+            "Invalid tagPattern '{pattern}': use a tag name with * and ? wildcards, e.g. 'v2.*'"
+        ))
+        .into());
+    }
+
+    let body: String = pattern
+        .chars()
+        .map(|character| match character {
+            '*' => ".*".to_owned(),
+            '?' => ".".to_owned(),
+            other => regex::escape(&other.to_string()),
+        })
+        .collect();
+    Regex::new(&format!("^{body}$")).with_context(|| format!("Invalid tagPattern '{pattern}'"))
+}
+
+/// Order two tags by version: numeric components first, then a release
+/// above its pre-releases. Tags without a version sort below all others,
+/// by name.
+fn compare_versions(first: &str, second: &str) -> Ordering {
+    match (version_key(first), version_key(second)) {
+        (Some(first_key), Some(second_key)) => first_key
+            .0
+            .cmp(&second_key.0)
+            .then_with(|| match (first_key.1, second_key.1) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(first_pre), Some(second_pre)) => {
+                    pre_release_key(first_pre).cmp(&pre_release_key(second_pre))
+                }
+            })
+            .then_with(|| first.cmp(second)),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => first.cmp(second),
+    }
+}
+
+/// Sort key of a pre-release suffix like `rc.10` or `beta2`: its
+/// dot-separated identifiers, each split into runs of digits and of other
+/// characters.
+///
+/// Identifiers compare left to right and runs of digits numerically, so
+/// `rc10` sorts above `rc2` and `rc.10` above `rc.2`; a suffix that is a
+/// prefix of another (`rc`, `rc.1`) sorts below it.
+fn pre_release_key(pre_release: &str) -> Vec<Vec<PreReleasePart<'_>>> {
+    pre_release
+        .split('.')
+        .map(|identifier| {
+            let mut parts = Vec::new();
+            let mut rest = identifier;
+            while let Some(first) = rest.chars().next() {
+                let is_digit = first.is_ascii_digit();
+                let end = rest
+                    .find(|character: char| character.is_ascii_digit() != is_digit)
+                    .unwrap_or(rest.len());
+                let Some((run, tail)) = rest.split_at_checked(end) else {
+                    break;
+                };
+                parts.push(match run.parse() {
+                    Ok(number) if is_digit => PreReleasePart::Number(number),
+                    _ => PreReleasePart::Text(run),
+                });
+                rest = tail;
+            }
+            parts
+        })
+        .collect()
+}
+
+/// Numeric components and pre-release suffix of a tag like `v2.10.1-rc1`,
+/// or `None` when it holds no version.
+fn version_key(tag: &str) -> Option<(Vec<u64>, Option<&str>)> {
+    let start = tag.find(|character: char| character.is_ascii_digit())?;
+    let version = tag.get(start..)?;
+    // Build metadata (`+build.5`) does not affect ordering
+    let version = version.split_once('+').map_or(version, |parts| parts.0);
+    let (numbers, pre_release) = match version.split_once('-') {
+        Some((numbers, suffix)) => (numbers, Some(suffix)),
+        None => (version, None),
+    };
+    let components = numbers
+        .split('.')
+        .map(str::parse)
+        .collect::<Result<Vec<u64>, _>>()
+        .ok()?;
+    Some((components, pre_release))
+}
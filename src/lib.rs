@@ -11,15 +11,29 @@ pub mod git;
 pub mod operations;
 pub mod utils;
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, anyhow};
 use cli::Args;
 use config::Config;
-use operations::pull::PullOperation;
+use config::migrate::migrate_config;
+use config::schema::CURRENT_CONFIG_VERSION;
+use core::time::Duration;
+use error::GraftError;
+use git::RealGitRunner;
+use operations::discovery::{DEFAULT_GRAFT_FILE_NAME, describe_grafts};
+use operations::doctor::diagnose;
+use operations::plan_graph::plan_graph;
+use operations::pull::{PullOperation, build_merged_config};
 use operations::skill::{self, SkillStatus};
-use operations::to_command_line::{OutputFormat, generate_command_line};
+use operations::to_command_line::{CommandLineOptions, generate_command_line};
 use operations::to_config::generate_yaml_config;
 use os_shim::System;
 use os_shim::real::RealSystem;
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use tracing::debug;
+use utils::fs::{classify_file, text_extensions};
+use utils::process::{enable_process_groups, terminate_child_process_groups};
 
 /// Main entry point for the tixgraft library.
 ///
@@ -30,13 +44,75 @@ use os_shim::real::RealSystem;
 /// - Git operations fail (clone, sparse checkout)
 /// - File operations fail (copy, read, write)
 /// - Post-processing commands fail
+/// - The run exceeds `--timeout`
 #[inline]
 pub fn run(args: Args) -> Result<()> {
+    let Some(secs) = args.timeout else {
+        return run_pull(args);
+    };
+
+    // Children lead their own process group so a timeout can stop their
+    // descendants as well
+    enable_process_groups();
+    let result = run_with_timeout(Duration::from_secs(secs), move || run_pull(args));
+    let timed_out = result
+        .as_ref()
+        .err()
+        .and_then(|err| err.downcast_ref::<GraftError>())
+        .is_some_and(|err| matches!(*err, GraftError::Timeout { .. }));
+    if timed_out {
+        terminate_child_process_groups();
+    }
+    result
+}
+
+/// Run the pull operation against the real filesystem.
+fn run_pull(args: Args) -> Result<()> {
     let system = RealSystem::new();
     let pull_operation = PullOperation::new(args, &system)?;
     pull_operation.execute()
 }
 
+/// Run `operation` on a worker thread and wait for it with a deadline.
+///
+/// This does not cancel `operation`: on timeout the worker thread keeps
+/// running until it returns or the process exits, and its result is
+/// discarded. Callers are expected to exit the process soon after a timeout
+/// (`main` does), after stopping child processes with
+/// [`terminate_child_process_groups`].
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The operation itself fails
+/// - The operation does not finish within `timeout` ([`GraftError::Timeout`])
+/// - The worker thread cannot be spawned or panics
+#[inline]
+pub fn run_with_timeout<F>(timeout: Duration, operation: F) -> Result<()>
+where
+    F: FnOnce() -> Result<()> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new()
+        .name("tixgraft-run".to_owned())
+        .spawn(move || {
+            if sender.send(operation()).is_err() {
+                debug!("Run finished after the timeout elapsed; result discarded");
+            }
+        })
+        .context("Failed to spawn worker thread")?;
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            Err(GraftError::timeout(format!("Run did not finish within {timeout:?}")).into())
+        }
+        Err(RecvTimeoutError::Disconnected) => Err(anyhow!(
+            "Run aborted: worker thread exited without a result"
+        )),
+    }
+}
+
 /// Run the to-command-line command.
 ///
 /// # Errors
@@ -49,9 +125,9 @@ pub fn run(args: Args) -> Result<()> {
 #[inline]
 pub fn run_to_command_line(
     config_path: &str,
-    format: OutputFormat,
     repo_override: Option<String>,
     tag_override: Option<String>,
+    options: &CommandLineOptions<'_>,
 ) -> Result<()> {
     let system = RealSystem::new();
 
@@ -70,7 +146,7 @@ pub fn run_to_command_line(
     config.validate(&system)?;
 
     // Generate command line
-    let command_line = generate_command_line(&config, format)?;
+    let command_line = generate_command_line(&config, options)?;
 
     // Output to stdout (not using logging)
     println!("{command_line}");
@@ -78,6 +154,151 @@ pub fn run_to_command_line(
     Ok(())
 }
 
+/// Run the environment diagnostics and print the report.
+///
+/// Returns an exit code: 0 when no check failed, 1 otherwise.
+#[expect(clippy::print_stdout, reason = "This is a CLI tool")]
+#[must_use]
+#[inline]
+pub fn run_doctor(config_path: &str) -> i32 {
+    let system = RealSystem::new();
+    let report = diagnose(&system, &RealGitRunner::new(), config_path);
+
+    // Output to stdout (not using logging)
+    print!("{report}");
+
+    i32::from(report.has_failures())
+}
+
+/// Compare two config files and print their semantic differences.
+///
+/// Returns an exit code: 0 when the configs are semantically equal, 1
+/// otherwise.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Either configuration file cannot be loaded or parsed
+#[expect(clippy::print_stdout, reason = "This is a CLI tool")]
+#[inline]
+pub fn run_diff_config(left_path: &str, right_path: &str) -> Result<i32> {
+    let system = RealSystem::new();
+    let left = Config::load_from_file(&system, left_path)?;
+    let right = Config::load_from_file(&system, right_path)?;
+
+    let differences = left.diff(&right);
+    if differences.is_empty() {
+        println!("Configs are equivalent");
+        return Ok(0_i32);
+    }
+
+    // Output to stdout (not using logging)
+    for difference in &differences {
+        println!("{difference}");
+    }
+    Ok(1_i32)
+}
+
+/// Print the graft files under `dir` with their context properties and
+/// replacement sources (`--list-grafts`).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `dir` is not a directory
+/// - A graft file cannot be loaded or parsed
+#[expect(clippy::print_stdout, reason = "This is a CLI tool")]
+#[inline]
+pub fn run_list_grafts(dir: &str, graft_file_name: Option<&str>) -> Result<()> {
+    let system = RealSystem::new();
+    let listing = describe_grafts(
+        &system,
+        Path::new(dir),
+        graft_file_name.unwrap_or(DEFAULT_GRAFT_FILE_NAME),
+    )?;
+
+    // Output to stdout (not using logging)
+    print!("{listing}");
+    Ok(())
+}
+
+/// Print the extensions always treated as text, one per line
+/// (`--list-text-extensions`).
+#[expect(clippy::print_stdout, reason = "This is a CLI tool")]
+#[inline]
+pub fn run_list_text_extensions() {
+    // Output to stdout (not using logging)
+    for extension in text_extensions() {
+        println!(".{extension}");
+    }
+}
+
+/// Upgrade the config file at `path` to the current config version in place.
+///
+/// Prints each change made, or a note when the config is already current.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The configuration file cannot be read or written
+/// - The configuration cannot be migrated (see [`migrate_config`])
+#[expect(clippy::print_stdout, reason = "This is a CLI tool")]
+#[inline]
+pub fn run_migrate_config(path: &str) -> Result<()> {
+    let system = RealSystem::new();
+    let content = system
+        .read_to_string(Path::new(path))
+        .with_context(|| format!("Failed to read configuration file: {path}"))?;
+    let migration = migrate_config(&content)
+        .with_context(|| format!("Failed to migrate configuration file: {path}"))?;
+
+    if migration.changes.is_empty() {
+        println!("{path} is already at config version {CURRENT_CONFIG_VERSION}");
+        return Ok(());
+    }
+
+    system
+        .write(Path::new(path), migration.content.as_bytes())
+        .with_context(|| format!("Failed to write configuration file: {path}"))?;
+
+    // Output to stdout (not using logging)
+    println!(
+        "Migrated {path} from version {} to {CURRENT_CONFIG_VERSION}:",
+        migration.from_version
+    );
+    for change in &migration.changes {
+        println!("  {change}");
+    }
+    Ok(())
+}
+
+/// Report whether the file at `path` is treated as binary and why
+/// (`--test-binary`).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The file does not exist or cannot be read
+#[expect(clippy::print_stdout, reason = "This is a CLI tool")]
+#[inline]
+pub fn run_test_binary(path: &str) -> Result<()> {
+    let system = RealSystem::new();
+    let file_path = Path::new(path);
+    if !system.exists(file_path)? {
+        return Err(GraftError::filesystem(format!("File does not exist: {path}")).into());
+    }
+    let classification = classify_file(&system, file_path)?;
+
+    // Output to stdout (not using logging)
+    let kind = if classification.is_binary() {
+        "binary (replacements skip it)"
+    } else {
+        "text"
+    };
+    println!("{path}: {kind}: {}", classification.reason());
+    Ok(())
+}
+
 /// Install the tixgraft Claude Code skill.
 ///
 /// # Errors
@@ -147,6 +368,24 @@ pub fn run_skill_test(global: bool, auto_yes: bool) -> Result<i32> {
     }
 }
 
+/// Print the resolved config's pulls as a DOT graph (`--print-plan-graph`).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The configuration cannot be loaded, merged with CLI overrides or validated
+/// - A pull's reference cannot be resolved
+#[expect(clippy::print_stdout, reason = "This is a CLI tool")]
+#[inline]
+pub fn run_print_plan_graph(args: &Args, system: &dyn System) -> Result<()> {
+    let config = build_merged_config(args, system)?;
+    config.validate(system)?;
+
+    // Output to stdout (not using logging)
+    print!("{}", plan_graph(&config)?);
+    Ok(())
+}
+
 /// Run the to-config command.
 ///
 /// # Errors
@@ -154,14 +393,38 @@ pub fn run_skill_test(global: bool, auto_yes: bool) -> Result<i32> {
 /// Returns an error if:
 /// - YAML configuration generation fails
 /// - Arguments cannot be converted to valid configuration
+/// - The `--save-config` file exists (without `--force`) or cannot be written
 #[expect(clippy::print_stdout, reason = "This is a CLI tool")]
 #[inline]
 pub fn run_to_config(args: &Args, system: &dyn System) -> Result<()> {
     // Generate YAML config
     let yaml = generate_yaml_config(args, system)?;
 
+    if let Some(path) = args.save_config.as_deref() {
+        return save_config(system, Path::new(path), &yaml, args.force);
+    }
+
     // Output to stdout (not using logging)
     println!("{yaml}");
 
     Ok(())
 }
+
+/// Write generated YAML to `path` (`--save-config`), refusing to replace an
+/// existing file unless `force` is set.
+#[expect(clippy::print_stdout, reason = "This is a CLI tool")]
+fn save_config(system: &dyn System, path: &Path, yaml: &str, force: bool) -> Result<()> {
+    if !force && system.exists(path)? {
+        return Err(GraftError::configuration(format!(
+            "Refusing to overwrite existing file {}; pass --force to replace it",
+            path.display()
+        ))
+        .into());
+    }
+    system
+        .write(path, format!("{yaml}\n").as_bytes())
+        .with_context(|| format!("Failed to write configuration file: {}", path.display()))?;
+
+    println!("Saved configuration to {}", path.display());
+    Ok(())
+}
@@ -33,12 +33,15 @@
 //! © 2024 `TixGraft` Authors. MIT or Apache-2.0 licensed. See README and LICENSE files for more info.
 
 use anyhow::Result;
-use clap::Parser as _;
 use os_shim::real::RealSystem;
+use std::io;
 use std::process::exit;
 use tixgraft::cli::Args;
 use tixgraft::error::GraftError;
-use tixgraft::operations::to_command_line::OutputFormat;
+use tixgraft::operations::stdout::is_stdout_target;
+use tixgraft::operations::to_command_line::{
+    CommandLineOptions, ShellDialect, parse_output_formats, resolve_program_name,
+};
 use tracing::error;
 use tracing_subscriber::{EnvFilter, fmt};
 
@@ -60,10 +63,22 @@ fn result_to_exit_code(result: Result<()>) -> i32 {
 }
 
 /// Initialize the tracing subscriber with the appropriate log level.
+///
+/// Logs go to stderr when a `--pull-target -` writes a file to stdout, so the
+/// output can be piped.
 fn init_tracing(args: &Args) {
     let is_skill_mode =
         args.skill.skill_install || args.skill.skill_uninstall || args.skill.skill_test;
-    let log_level = if args.to_command_line || args.to_config || is_skill_mode {
+    let log_level = if args.to_command_line
+        || args.to_config
+        || args.save_config.is_some()
+        || args.doctor
+        || args.diff_config.is_some()
+        || args.migrate_config
+        || args.list_grafts.is_some()
+        || args.print_plan_graph
+        || is_skill_mode
+    {
         "error"
     } else if args.verbose {
         "debug"
@@ -71,11 +86,22 @@ fn init_tracing(args: &Args) {
         "info"
     };
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(log_level));
-    fmt().with_target(false).with_env_filter(filter).init();
+    let subscriber = fmt().with_target(false).with_env_filter(filter);
+    if args
+        .pulls
+        .targets
+        .iter()
+        .any(|target| is_stdout_target(target))
+    {
+        subscriber.with_writer(io::stderr).init();
+    } else {
+        subscriber.init();
+    }
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse_ordered();
+    args.apply_config_env(&RealSystem::new());
     init_tracing(&args);
 
     // Validate skill flag constraints
@@ -110,27 +136,87 @@ fn main() -> Result<()> {
         }
     }
 
+    // Handle doctor mode
+    if args.doctor {
+        exit(tixgraft::run_doctor(&args.config));
+    }
+
+    // Handle diff-config mode
+    if let Some([left, right]) = args.diff_config.as_deref() {
+        match tixgraft::run_diff_config(left, right) {
+            Ok(code) => exit(code),
+            Err(err) => {
+                error!("{:#}", err);
+                exit(error_to_exit_code(&err));
+            }
+        }
+    }
+
+    // Handle migrate-config mode
+    if args.migrate_config {
+        exit(result_to_exit_code(tixgraft::run_migrate_config(
+            &args.config,
+        )));
+    }
+
+    // Handle list-grafts mode
+    if let Some(dir) = args.list_grafts.as_deref() {
+        exit(result_to_exit_code(tixgraft::run_list_grafts(
+            dir,
+            args.graft_file_name.as_deref(),
+        )));
+    }
+
+    // Handle list-text-extensions mode
+    if args.list_text_extensions {
+        tixgraft::run_list_text_extensions();
+        exit(0_i32);
+    }
+
+    // Handle test-binary mode
+    if let Some(path) = args.test_binary.as_deref() {
+        exit(result_to_exit_code(tixgraft::run_test_binary(path)));
+    }
+
+    // Handle print-plan-graph mode
+    if args.print_plan_graph {
+        let system = RealSystem::new();
+        exit(result_to_exit_code(tixgraft::run_print_plan_graph(
+            &args, &system,
+        )));
+    }
+
     // Handle to-config mode
-    if args.to_config {
+    if args.to_config || args.save_config.is_some() {
         let system = RealSystem::new();
         exit(result_to_exit_code(tixgraft::run_to_config(&args, &system)));
     }
 
     // Handle to-command-line mode
     if args.to_command_line {
-        let format = args
-            .output_format
-            .parse::<OutputFormat>()
+        let formats = parse_output_formats(&args.output_format).unwrap_or_else(|err| {
+            error!("{}", err);
+            exit(1_i32);
+        });
+        let dialect = args
+            .shell_dialect
+            .parse::<ShellDialect>()
             .unwrap_or_else(|err| {
                 error!("{}", err);
                 exit(1_i32);
             });
-        let result = tixgraft::run_to_command_line(
-            &args.config,
-            format,
-            args.repository.clone(),
-            args.tag.clone(),
-        );
+        let result = resolve_program_name(args.program_name.as_deref(), args.absolute_program)
+            .and_then(|program| {
+                tixgraft::run_to_command_line(
+                    &args.config,
+                    args.repository.clone(),
+                    args.tag.clone(),
+                    &CommandLineOptions::new()
+                        .with_dialect(dialect)
+                        .with_formats(&formats)
+                        .with_program(&program),
+                )
+            });
         exit(result_to_exit_code(result));
     }
 
@@ -4,15 +4,28 @@
 
 #![expect(clippy::pub_use, reason = "deliberate module re-export for public API")]
 
+pub mod archive_target;
 pub mod commands;
+pub mod confirm;
 pub mod copy;
 pub mod discovery;
+pub mod doctor;
+pub mod plan_graph;
 pub mod post_commands;
+pub mod prefetch;
+pub mod progress;
 pub mod pull;
 pub mod replace;
+pub mod replacement_audit;
 pub mod skill;
+pub mod stdout;
+pub mod structured;
+pub mod template;
 pub mod to_command_line;
 pub mod to_config;
+pub mod transform;
+pub mod warnings;
+pub mod watch;
 
 pub use commands::*;
 pub use copy::*;
@@ -0,0 +1,193 @@
+//! Bundling a pull into an archive (`type: archive`).
+//!
+//! The source is copied into a scratch directory, the pull's replacements
+//! run there as usual, and the result is packed into the `.zip`, `.tar.gz`
+//! or `.tgz` file named by `target`, with paths relative to the source.
+//! Only the archive itself is written to the target location.
+
+use crate::cli::PullConfig;
+use crate::error::GraftError;
+use crate::git::ArchiveFormat;
+use crate::operations::copy::{CopyOptions, copy_files_with_options};
+use crate::operations::replace::{
+    apply_replacements_to_file_with_encoding, apply_replacements_with_encoding,
+};
+use anyhow::{Context as _, Result};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use os_shim::System;
+use std::fs;
+use std::io::{Cursor, Write as _};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use zip::write::SimpleFileOptions;
+
+/// Pull type that writes the pulled files into an archive at `target`.
+pub const ARCHIVE_PULL_TYPE: &str = "archive";
+
+/// Whether `pull` bundles its output into an archive.
+#[must_use]
+#[inline]
+pub fn is_archive_pull(pull: &PullConfig) -> bool {
+    pull.pull_type == ARCHIVE_PULL_TYPE
+}
+
+/// Archive format of an archive pull's `target`, from its extension.
+#[must_use]
+#[inline]
+pub fn archive_target_format(target: &str) -> Option<ArchiveFormat> {
+    ArchiveFormat::detect(target)
+        .filter(|&(_, location)| location == target)
+        .map(|(format, _)| format)
+}
+
+/// Copy `source` into a scratch directory, apply `pull`'s replacements, and
+/// pack the result into the archive at `pull.target`. `.git` is left out
+/// unless `include_git` is set.
+///
+/// Returns the number of files archived and replacements applied.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The target has no archive extension
+/// - The source cannot be staged or the replacements cannot be applied
+/// - The archive cannot be built or written
+#[inline]
+pub fn write_archive_target(
+    system: &dyn System,
+    source: &Path,
+    pull: &PullConfig,
+    temp_root: Option<&Path>,
+    include_git: bool,
+) -> Result<(usize, usize)> {
+    let format = archive_target_format(&pull.target).ok_or_else(|| {
+        GraftError::configuration(format!(
+            "Archive target '{}' must end in .zip, .tar.gz or .tgz",
+            pull.target
+        ))
+    })?;
+
+    let staging = temp_root
+        .map_or_else(TempDir::new, TempDir::new_in)
+        .context("Failed to create staging directory for archive target")?;
+    let content_dir = staging.path().join("content");
+    let replacements_applied = stage_and_replace(system, source, &content_dir, pull, include_git)?;
+
+    let mut files = Vec::new();
+    collect_files(&content_dir, &content_dir, &mut files)?;
+    files.sort();
+
+    let bytes = match format {
+        ArchiveFormat::TarGz => build_tar_gz(&files)?,
+        ArchiveFormat::Zip => build_zip(&files)?,
+    };
+
+    let target = Path::new(&pull.target);
+    if let Some(parent) = target.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        system
+            .create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    system
+        .write(target, &bytes)
+        .with_context(|| format!("Failed to write archive: {}", target.display()))?;
+
+    Ok((files.len(), replacements_applied))
+}
+
+/// Pack `files` (archive name, path on disk) into a gzip-compressed tarball.
+fn build_tar_gz(files: &[(String, PathBuf)]) -> Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (name, path) in files {
+        builder
+            .append_path_with_name(path, name)
+            .with_context(|| format!("Failed to add {name} to archive"))?;
+    }
+    let encoder = builder
+        .into_inner()
+        .context("Failed to finish tar archive")?;
+    encoder.finish().context("Failed to compress tar archive")
+}
+
+/// Pack `files` (archive name, path on disk) into a zip archive.
+fn build_zip(files: &[(String, PathBuf)]) -> Result<Vec<u8>> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, path) in files {
+        let content =
+            fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        writer
+            .start_file(name.as_str(), SimpleFileOptions::default())
+            .with_context(|| format!("Failed to add {name} to archive"))?;
+        writer
+            .write_all(&content)
+            .with_context(|| format!("Failed to add {name} to archive"))?;
+    }
+    let cursor = writer.finish().context("Failed to finish zip archive")?;
+    Ok(cursor.into_inner())
+}
+
+/// Collect the files below `dir` with their `/`-separated path relative to `base`.
+fn collect_files(base: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) -> Result<()> {
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            let name = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+/// Copy `source` into `content_dir` and apply `pull`'s replacements there.
+///
+/// Returns the number of replacements applied.
+fn stage_and_replace(
+    system: &dyn System,
+    source: &Path,
+    content_dir: &Path,
+    pull: &PullConfig,
+    include_git: bool,
+) -> Result<usize> {
+    let options = CopyOptions::new(pull.include_hidden, pull.respect_gitignore)
+        .with_include_git(include_git)
+        .with_strip_components(pull.strip_components);
+
+    if system.is_file(source)? {
+        let file_name = source.file_name().ok_or_else(|| {
+            GraftError::from_source(format!("Source has no file name: {}", source.display()))
+        })?;
+        let staged_file = content_dir.join(file_name);
+        copy_files_with_options(
+            system,
+            source,
+            &staged_file.to_string_lossy(),
+            "file",
+            false,
+            options,
+        )?;
+        return apply_replacements_to_file_with_encoding(
+            system,
+            &staged_file,
+            &pull.replacements,
+            pull.text_encoding(),
+        );
+    }
+
+    let content_target = content_dir.to_string_lossy();
+    copy_files_with_options(system, source, &content_target, "directory", false, options)?;
+    apply_replacements_with_encoding(
+        system,
+        &content_target,
+        &pull.replacements,
+        pull.text_encoding(),
+    )
+}
@@ -1,10 +1,11 @@
 //! Command execution with proper working directory context.
 
 use crate::error::GraftError;
+use crate::utils::process::{child_output, child_status};
 use anyhow::{Context as _, Result};
 use core::fmt::Write as _;
 use std::path::Path;
-use std::process::{Command, Stdio};
+use std::process::Command;
 use tracing::info;
 
 /// Information about command validation.
@@ -107,7 +108,9 @@ pub fn validate_commands(commands: &[String]) -> Result<Vec<CommandValidation>>
 }
 
 /// Analyze a command for potential security or safety issues.
-fn analyze_command_safety(command: &str) -> Vec<String> {
+#[must_use]
+#[inline]
+pub fn analyze_command_safety(command: &str) -> Vec<String> {
     let mut issues = Vec::new();
 
     // Check for potentially destructive commands
@@ -159,13 +162,12 @@ fn execute_single_command(command: &str, working_dir: &Path, command_number: usi
     cmd_args.push(command.to_owned());
 
     // Execute command
-    let output = Command::new(&shell)
-        .args(&cmd_args)
-        .current_dir(working_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .with_context(|| format!("Failed to execute command #{command_number}: {command}"))?;
+    let output = child_output(
+        Command::new(&shell)
+            .args(&cmd_args)
+            .current_dir(working_dir),
+    )
+    .with_context(|| format!("Failed to execute command #{command_number}: {command}"))?;
 
     // Check exit status
     if !output.status.success() {
@@ -219,11 +221,12 @@ fn execute_single_command_interactive(
     cmd_args.push(command.to_owned());
 
     // Execute command with inherited stdio for real-time output
-    let status = Command::new(&shell)
-        .args(&cmd_args)
-        .current_dir(working_dir)
-        .status()
-        .with_context(|| format!("Failed to execute command #{command_number}: {command}"))?;
+    let status = child_status(
+        Command::new(&shell)
+            .args(&cmd_args)
+            .current_dir(working_dir),
+    )
+    .with_context(|| format!("Failed to execute command #{command_number}: {command}"))?;
 
     // Check exit status
     if !status.success() {
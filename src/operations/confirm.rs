@@ -0,0 +1,150 @@
+//! Interactive confirmation before destructive operations (`--confirm`).
+//!
+//! Pulls with `reset: true` wipe their target, and post-commands flagged by
+//! the command safety checks may delete or overwrite files. With
+//! `--confirm`, these actions are listed up front and the run only goes
+//! ahead once a [`Prompter`] answers yes.
+
+use crate::config::Config;
+use crate::error::GraftError;
+use crate::operations::commands::analyze_command_safety;
+use crate::operations::skill::prompt_yes_no;
+use anyhow::Result;
+use core::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{IsTerminal as _, stdin};
+use tracing::info;
+
+/// Asks the user a yes/no question.
+pub trait Prompter {
+    /// Ask `question`, returning whether the answer was yes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No answer can be obtained (e.g. there is no terminal to ask on)
+    fn confirm(&self, question: &str) -> Result<bool>;
+}
+
+/// [`Prompter`] that asks on the terminal, and refuses to guess when stdin
+/// is not one.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TerminalPrompter;
+
+impl TerminalPrompter {
+    /// Create a new prompter.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Prompter for TerminalPrompter {
+    #[inline]
+    fn confirm(&self, question: &str) -> Result<bool> {
+        if !stdin().is_terminal() {
+            return Err(GraftError::configuration(
+                "--confirm needs an interactive terminal to ask for confirmation; aborting"
+                    .to_owned(),
+            )
+            .into());
+        }
+        Ok(prompt_yes_no(question)?)
+    }
+}
+
+/// [`Prompter`] for tests: gives scripted answers in order, then fails.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct ScriptedPrompter {
+    /// Answers not given yet, in order.
+    answers: RefCell<VecDeque<bool>>,
+    /// Questions asked so far, in order.
+    questions: RefCell<Vec<String>>,
+}
+
+impl ScriptedPrompter {
+    /// Create a prompter that answers with `answers`, in order.
+    #[must_use]
+    #[inline]
+    pub fn new(answers: &[bool]) -> Self {
+        Self {
+            answers: RefCell::new(answers.iter().copied().collect()),
+            questions: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Questions asked so far, in order.
+    #[must_use]
+    #[inline]
+    pub fn questions(&self) -> Vec<String> {
+        self.questions.borrow().clone()
+    }
+}
+
+impl Prompter for ScriptedPrompter {
+    #[inline]
+    fn confirm(&self, question: &str) -> Result<bool> {
+        self.questions.borrow_mut().push(question.to_owned());
+        self.answers.borrow_mut().pop_front().ok_or_else(|| {
+            GraftError::configuration(format!("No scripted answer for: {question}")).into()
+        })
+    }
+}
+
+/// Describe the destructive actions `config`'s pulls would take: targets
+/// wiped by `reset: true` and post-commands with safety issues.
+#[must_use]
+#[inline]
+pub fn destructive_actions(config: &Config) -> Vec<String> {
+    let mut actions = Vec::new();
+    for (index, pull) in config.pulls.iter().enumerate() {
+        let display_index = index.saturating_add(1);
+        if pull.reset {
+            actions.push(format!(
+                "Pull #{display_index}: wipe target directory {} (reset: true)",
+                pull.target
+            ));
+        }
+        for command in &pull.commands {
+            let issues = analyze_command_safety(command);
+            if !issues.is_empty() {
+                actions.push(format!(
+                    "Pull #{display_index}: run '{command}' ({})",
+                    issues.join("; ")
+                ));
+            }
+        }
+    }
+    actions
+}
+
+/// List `actions` and ask `prompter` whether to go ahead. Nothing is asked
+/// when there are no actions.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The prompter cannot get an answer
+/// - The answer is no
+#[inline]
+pub fn confirm_destructive_actions(prompter: &dyn Prompter, actions: &[String]) -> Result<()> {
+    if actions.is_empty() {
+        return Ok(());
+    }
+
+    info!("This run will perform destructive actions:");
+    for action in actions {
+        info!("  - {action}");
+    }
+    if prompter.confirm("Proceed?")? {
+        return Ok(());
+    }
+    Err(GraftError::configuration(format!(
+        "Aborted: {} destructive action(s) were not confirmed",
+        actions.len()
+    ))
+    .into())
+}
@@ -1,14 +1,96 @@
 //! File and directory copying operations.
 
 use crate::error::GraftError;
-use crate::utils::fs::create_parent_directories;
+use crate::utils::fs::{create_parent_directories, get_file_size};
 use anyhow::{Context as _, Result};
 use os_shim::System;
-use std::path::{Path, PathBuf};
-use tracing::debug;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+use tracing::{debug, warn};
+
+/// Options controlling which source entries a directory copy includes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CopyOptions {
+    /// Copy `.git` directories and files (default: false).
+    pub include_git: bool,
+    /// Copy hidden (dot) files and directories (default: true).
+    pub include_hidden: bool,
+    /// Skip files excluded by `.gitignore` and other ignore files (default: false).
+    pub respect_gitignore: bool,
+    /// Leading path components removed from each copied path (default: 0).
+    pub strip_components: usize,
+}
+
+impl CopyOptions {
+    /// Create copy options.
+    #[must_use]
+    #[inline]
+    pub const fn new(include_hidden: bool, respect_gitignore: bool) -> Self {
+        Self {
+            include_git: false,
+            include_hidden,
+            respect_gitignore,
+            strip_components: 0,
+        }
+    }
+
+    /// Also copy `.git` directories and files, which are skipped by default
+    /// so a source that is itself a repository does not drag its history
+    /// into the target.
+    #[must_use]
+    #[inline]
+    pub const fn with_include_git(mut self, include_git: bool) -> Self {
+        self.include_git = include_git;
+        self
+    }
+
+    /// Remove `count` leading path components from each copied path, like
+    /// `tar --strip-components`.
+    #[must_use]
+    #[inline]
+    pub const fn with_strip_components(mut self, count: usize) -> Self {
+        self.strip_components = count;
+        self
+    }
+}
+
+impl Default for CopyOptions {
+    /// A faithful copy: hidden files included (except `.git`), ignore files
+    /// not consulted.
+    #[inline]
+    fn default() -> Self {
+        Self::new(true, false)
+    }
+}
+
+/// Context attached to a directory copy that failed part-way.
+///
+/// Lists the target files written before the failure, which stay in place.
+/// Recover it with `err.downcast_ref::<PartialCopy>()`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Copy stopped after {count} file(s)", count = .copied.len())]
+#[non_exhaustive]
+pub struct PartialCopy {
+    /// Target paths copied before the failure, in copy order.
+    pub copied: Vec<PathBuf>,
+}
+
+/// A file or directory found under a copy source.
+struct SourceEntry {
+    /// Whether the entry is a directory.
+    is_dir: bool,
+    /// Whether the entry is a regular file.
+    is_file: bool,
+    /// Full path of the entry.
+    path: PathBuf,
+}
 
 /// Copy files or directories from source to target.
 ///
+/// Uses the default [`CopyOptions`].
+///
 /// # Errors
 ///
 /// Returns an error if:
@@ -23,6 +105,34 @@ pub fn copy_files(
     target: &str,
     pull_type: &str,
     reset: bool,
+) -> Result<usize> {
+    copy_files_with_options(
+        system,
+        source,
+        target,
+        pull_type,
+        reset,
+        CopyOptions::default(),
+    )
+}
+
+/// Copy files or directories from source to target with explicit [`CopyOptions`].
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The source path does not exist
+/// - The source directory cannot be reset
+/// - The source directory cannot be removed
+/// - The source file cannot be copied
+#[inline]
+pub fn copy_files_with_options(
+    system: &dyn System,
+    source: &Path,
+    target: &str,
+    pull_type: &str,
+    reset: bool,
+    options: CopyOptions,
 ) -> Result<usize> {
     let target_path = PathBuf::from(target);
 
@@ -47,8 +157,8 @@ pub fn copy_files(
 
     // Perform the copy based on type
     match pull_type {
-        "file" => copy_file(system, source, &target_path),
-        "directory" => copy_directory(system, source, &target_path),
+        "file" => copy_file(system, source, &file_target_path(system, source, target)?),
+        "directory" => copy_directory_with_options(system, source, &target_path, options),
         _ => Err(GraftError::configuration(format!(
             "Invalid pull type: '{pull_type}'. Must be 'file' or 'directory'"
         ))
@@ -56,6 +166,33 @@ pub fn copy_files(
     }
 }
 
+/// Where a `type: file` pull of `source` into `target` writes the file.
+///
+/// A target ending with `/` or naming an existing directory receives the
+/// file under its source name. Any other target is the destination file
+/// name, so the file is renamed.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The target cannot be inspected
+/// - The target is a directory and the source has no file name
+#[inline]
+pub fn file_target_path(system: &dyn System, source: &Path, target: &str) -> Result<PathBuf> {
+    let target_path = PathBuf::from(target);
+    if !target.ends_with(['/', '\\']) && !system.is_dir(&target_path)? {
+        return Ok(target_path);
+    }
+
+    let file_name = source.file_name().ok_or_else(|| {
+        GraftError::from_source(format!(
+            "Source {} has no file name to place in directory {target}",
+            source.display()
+        ))
+    })?;
+    Ok(target_path.join(file_name))
+}
+
 /// Copy a single file.
 ///
 /// # Errors
@@ -91,6 +228,8 @@ pub fn copy_file(system: &dyn System, source: &Path, target: &Path) -> Result<us
 
 /// Copy a directory recursively.
 ///
+/// Uses the default [`CopyOptions`].
+///
 /// # Errors
 ///
 /// Returns an error if:
@@ -100,6 +239,31 @@ pub fn copy_file(system: &dyn System, source: &Path, target: &Path) -> Result<us
 /// - The source file cannot be copied
 #[inline]
 pub fn copy_directory(system: &dyn System, source: &Path, target: &Path) -> Result<usize> {
+    copy_directory_with_options(system, source, target, CopyOptions::default())
+}
+
+/// Copy a directory recursively with explicit [`CopyOptions`].
+///
+/// With [`CopyOptions::strip_components`], files whose relative path has no
+/// more components than are stripped are skipped with a warning.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The source path is not a directory
+/// - The source directory cannot be walked
+/// - The source directory cannot be created
+/// - The source file cannot be copied
+///
+/// An error part-way through the copy carries a [`PartialCopy`] context
+/// listing the files copied before it.
+#[inline]
+pub fn copy_directory_with_options(
+    system: &dyn System,
+    source: &Path,
+    target: &Path,
+    options: CopyOptions,
+) -> Result<usize> {
     // Validate source is actually a directory
     if !system.is_dir(source)? {
         return Err(GraftError::from_source(format!(
@@ -116,64 +280,285 @@ pub fn copy_directory(system: &dyn System, source: &Path, target: &Path) -> Resu
             .with_context(|| format!("Failed to create target directory: {}", target.display()))?;
     }
 
-    let mut files_copied: usize = 0;
+    let mut copied = Vec::new();
 
     // Walk through source directory using System abstraction
-    let entries = system
-        .walk_dir(source, false, false)
-        .with_context(|| format!("Failed to walk directory: {}", source.display()))?;
+    let entries = list_source_entries(system, source, options)?;
 
     for entry in entries {
-        let source_path = &entry.path;
+        match copy_entry(system, source, target, &entry, options) {
+            Ok(Some(target_path)) => copied.push(target_path),
+            Ok(None) => {}
+            Err(err) => return Err(err.context(PartialCopy { copied })),
+        }
+    }
 
-        // Calculate relative path from source root
-        let relative_path = source_path
-            .strip_prefix(source)
-            .context("Failed to calculate relative path")?;
+    if copied.is_empty() {
+        return Err(GraftError::from_source(format!(
+            "No files found to copy in directory: {}",
+            source.display()
+        ))
+        .into());
+    }
 
-        let target_path = target.join(relative_path);
+    Ok(copied.len())
+}
 
-        if entry.is_dir {
-            // Create directory
-            if !system.exists(&target_path)? {
-                system.create_dir_all(&target_path).with_context(|| {
-                    format!("Failed to create directory: {}", target_path.display())
-                })?;
-            }
-        } else if entry.is_file {
-            // Create parent directories if needed
-            if let Some(parent) = target_path.parent()
-                && !system.exists(parent)?
-            {
-                system.create_dir_all(parent).with_context(|| {
-                    format!("Failed to create parent directory: {}", parent.display())
-                })?;
-            }
+/// Check that `target` is a faithful copy of `source` (`--verify-copy`):
+/// every file the copy should have written exists below `target` with the
+/// size of its source file.
+///
+/// Files in `target` that are not in `source` (e.g. left from an earlier
+/// pull without `reset`) are not an error.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The source or target cannot be walked, or a file size cannot be read
+/// - A copied file is missing from `target` or its size differs from the source
+#[inline]
+pub fn verify_copy(
+    system: &dyn System,
+    source: &Path,
+    target: &Path,
+    pull_type: &str,
+    options: CopyOptions,
+) -> Result<()> {
+    let expected = if pull_type == "file" {
+        vec![(source.to_path_buf(), target.to_path_buf())]
+    } else {
+        list_source_entries(system, source, options)?
+            .into_iter()
+            .filter(|entry| entry.is_file)
+            .filter_map(|entry| {
+                let relative = entry.path.strip_prefix(source).ok()?;
+                let target_file = target.join(strip_leading_components(
+                    relative,
+                    options.strip_components,
+                )?);
+                Some((entry.path, target_file))
+            })
+            .collect()
+    };
 
-            // Copy file
-            system.copy(source_path, &target_path).with_context(|| {
-                format!(
-                    "Failed to copy file from {} to {}",
-                    source_path.display(),
-                    target_path.display()
-                )
+    let mut problems = Vec::new();
+    for (source_file, target_file) in expected {
+        if !system.exists(&target_file)? {
+            problems.push(format!("missing {}", target_file.display()));
+            continue;
+        }
+        let source_size = get_file_size(system, &source_file)?;
+        let target_size = get_file_size(system, &target_file)?;
+        if source_size != target_size {
+            problems.push(format!(
+                "{} is {target_size} bytes, expected {source_size}",
+                target_file.display()
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        debug!(
+            "Verified copy of {} into {}",
+            source.display(),
+            target.display()
+        );
+        return Ok(());
+    }
+    Err(GraftError::filesystem(format!(
+        "Copy verification failed for {}: {}",
+        target.display(),
+        problems.join("; ")
+    ))
+    .into())
+}
+
+/// Copy one source entry below `target`, creating directories as needed.
+///
+/// Returns the target path when a file was copied.
+fn copy_entry(
+    system: &dyn System,
+    source: &Path,
+    target: &Path,
+    entry: &SourceEntry,
+    options: CopyOptions,
+) -> Result<Option<PathBuf>> {
+    let source_path = &entry.path;
+
+    // Calculate relative path from source root
+    let full_relative_path = source_path
+        .strip_prefix(source)
+        .context("Failed to calculate relative path")?;
+
+    let Some(relative_path) =
+        strip_leading_components(full_relative_path, options.strip_components)
+    else {
+        if entry.is_file {
+            warn!(
+                "Skipping {}: it has fewer path components than stripComponents ({})",
+                full_relative_path.display(),
+                options.strip_components
+            );
+        }
+        return Ok(None);
+    };
+
+    let target_path = target.join(relative_path);
+
+    if entry.is_dir {
+        // Create directory
+        if !system.exists(&target_path)? {
+            system.create_dir_all(&target_path).with_context(|| {
+                format!("Failed to create directory: {}", target_path.display())
             })?;
+        }
+        return Ok(None);
+    }
+    if !entry.is_file {
+        debug!("Skipping file: {}", source_path.display());
+        return Ok(None);
+    }
+
+    // Create parent directories if needed
+    if let Some(parent) = target_path.parent()
+        && !system.exists(parent)?
+    {
+        system
+            .create_dir_all(parent)
+            .with_context(|| format!("Failed to create parent directory: {}", parent.display()))?;
+    }
+
+    // Copy file
+    system.copy(source_path, &target_path).with_context(|| {
+        format!(
+            "Failed to copy file from {} to {}",
+            source_path.display(),
+            target_path.display()
+        )
+    })?;
+    debug!(
+        "Copied {} -> {}",
+        source_path.display(),
+        target_path.display()
+    );
+
+    Ok(Some(target_path))
+}
+
+/// List the entries under `source` that a directory copy should include.
+fn list_source_entries(
+    system: &dyn System,
+    source: &Path,
+    options: CopyOptions,
+) -> Result<Vec<SourceEntry>> {
+    let entries = if options.respect_gitignore {
+        system
+            .walk_dir(source, false, false)
+            .with_context(|| format!("Failed to walk directory: {}", source.display()))?
+            .into_iter()
+            .map(|entry| SourceEntry {
+                is_dir: entry.is_dir,
+                is_file: entry.is_file,
+                path: entry.path,
+            })
+            .collect()
+    } else {
+        let mut entries = Vec::new();
+        let mut ancestors = HashSet::from([canonical_dir(system, source)]);
+        collect_source_entries(system, source, &mut ancestors, &mut entries)?;
+        entries
+    };
+
+    Ok(entries
+        .into_iter()
+        .filter(|entry| options.include_git || !is_git_below(source, &entry.path))
+        .filter(|entry| options.include_hidden || !is_hidden_below(source, &entry.path))
+        .collect())
+}
 
-            files_copied = files_copied.saturating_add(1);
+/// Canonical form of `dir`, or `dir` itself when it cannot be resolved.
+fn canonical_dir(system: &dyn System, dir: &Path) -> PathBuf {
+    system
+        .canonicalize(dir)
+        .unwrap_or_else(|_| dir.to_path_buf())
+}
+
+/// Recursively collect every entry under `dir`, without consulting ignore files.
+///
+/// `ancestors` holds the canonical paths of the directories being walked,
+/// from the source down to `dir`, so a symlink leading back to one of them
+/// (e.g. one pointing at its own parent) is skipped with a warning instead
+/// of recursing forever. A symlink to a sibling directory is copied again.
+fn collect_source_entries(
+    system: &dyn System,
+    dir: &Path,
+    ancestors: &mut HashSet<PathBuf>,
+    entries: &mut Vec<SourceEntry>,
+) -> Result<()> {
+    let children = system
+        .read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+
+    for path in children {
+        if system.is_dir(&path)? {
+            let canonical = canonical_dir(system, &path);
+            if !ancestors.insert(canonical.clone()) {
+                warn!(
+                    "Skipping {}: it links back to {}, which is already being copied",
+                    path.display(),
+                    canonical.display()
+                );
+                continue;
+            }
+            entries.push(SourceEntry {
+                is_dir: true,
+                is_file: false,
+                path: path.clone(),
+            });
+            collect_source_entries(system, &path, ancestors, entries)?;
+            ancestors.remove(&canonical);
         } else {
-            debug!("Skipping file: {}", source_path.display());
+            let is_file = system.is_file(&path)?;
+            entries.push(SourceEntry {
+                is_dir: false,
+                is_file,
+                path,
+            });
         }
     }
 
-    if files_copied == 0 {
-        return Err(GraftError::from_source(format!(
-            "No files found to copy in directory: {}",
-            source.display()
-        ))
-        .into());
+    Ok(())
+}
+
+/// Remove `count` leading components from `path`.
+///
+/// Returns `None` when nothing would be left of the path.
+fn strip_leading_components(path: &Path, count: usize) -> Option<PathBuf> {
+    let mut components = path.components();
+    for _ in 0..count {
+        components.next()?;
     }
+    let stripped = components.as_path();
+    (!stripped.as_os_str().is_empty()).then(|| stripped.to_path_buf())
+}
+
+/// Check whether any component of `path` below `root` is `.git`.
+fn is_git_below(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root).is_ok_and(|relative| {
+        relative
+            .components()
+            .any(|component| component == Component::Normal(".git".as_ref()))
+    })
+}
 
-    Ok(files_copied)
+/// Check whether any component of `path` below `root` is hidden (starts with `.`).
+fn is_hidden_below(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root).is_ok_and(|relative| {
+        relative.components().any(|component| {
+            matches!(component, Component::Normal(name)
+                if name.to_str().is_some_and(|name_str| name_str.starts_with('.')))
+        })
+    })
 }
 
 /// Calculate the total size of files to be copied (for progress indication).
@@ -19,6 +19,7 @@
 //! of `.graft.yaml` files themselves.
 
 use anyhow::{Context as _, Result};
+use core::fmt::Write as _;
 use std::{
     ffi::OsStr,
     path::{Path, PathBuf},
@@ -26,6 +27,11 @@ use std::{
 
 use os_shim::System;
 
+use crate::config::graft_yaml::GraftConfig;
+
+/// Default file name of graft definition files.
+pub const DEFAULT_GRAFT_FILE_NAME: &str = ".graft.yaml";
+
 /// A discovered .graft.yaml file with its location and hierarchy information.
 #[derive(Debug, Clone)]
 #[non_exhaustive]
@@ -87,6 +93,84 @@ impl DiscoveredGraft {
 pub fn discover_graft_files(
     system: &dyn System,
     target_dir: &Path,
+) -> Result<Vec<DiscoveredGraft>> {
+    discover_graft_files_named(system, target_dir, DEFAULT_GRAFT_FILE_NAME)
+}
+
+/// Describe the graft files named `file_name` under `root` without processing
+/// them (`--list-grafts`).
+///
+/// Lists each file (relative to `root`), its declared context properties with
+/// their type and whether they are required, and its replacement sources.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Discovery fails (see [`discover_graft_files_named`])
+/// - A graft file cannot be loaded or parsed
+#[inline]
+pub fn describe_grafts(system: &dyn System, root: &Path, file_name: &str) -> Result<String> {
+    let grafts = discover_graft_files_named(system, root, file_name)?;
+    let base = system
+        .canonicalize(root)
+        .unwrap_or_else(|_| root.to_path_buf());
+
+    let mut output = String::new();
+    if grafts.is_empty() {
+        writeln!(output, "No {file_name} files found in {}", root.display())?;
+        return Ok(output);
+    }
+
+    for graft in &grafts {
+        let config = GraftConfig::load_from_file(system, &graft.path)
+            .with_context(|| format!("Failed to load {}", graft.path.display()))?;
+        let relative = graft.path.strip_prefix(&base).unwrap_or(&graft.path);
+        writeln!(output, "{}", relative.display())?;
+
+        if !config.context.is_empty() {
+            writeln!(output, "  context:")?;
+        }
+        for def in &config.context {
+            let data_type = format!("{:?}", def.data_type).to_lowercase();
+            let requirement = match (def.default_value.as_ref(), def.default_from_env.as_deref()) {
+                (Some(default_value), _) => format!("default {default_value}"),
+                (None, Some(env_var)) => format!("default from ${env_var}"),
+                (None, None) => "required".to_owned(),
+            };
+            writeln!(
+                output,
+                "    - {} ({data_type}, {requirement}): {}",
+                def.name, def.description
+            )?;
+        }
+
+        if !config.replacements.is_empty() {
+            writeln!(output, "  replacements:")?;
+        }
+        for replacement in &config.replacements {
+            writeln!(output, "    - {}", replacement.source)?;
+        }
+    }
+
+    Ok(output)
+}
+
+/// Discover all graft files named `file_name` in a target directory recursively.
+///
+/// Behaves like [`discover_graft_files`] but matches `file_name` instead of
+/// the default `.graft.yaml`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Target directory does not exist
+/// - Target path is not a directory
+/// - Directory cannot be canonicalized
+#[inline]
+pub fn discover_graft_files_named(
+    system: &dyn System,
+    target_dir: &Path,
+    file_name: &str,
 ) -> Result<Vec<DiscoveredGraft>> {
     if !system.exists(target_dir)? {
         return Err(anyhow::anyhow!(
@@ -123,11 +207,11 @@ pub fn discover_graft_files(
     for entry in entries {
         let path = &entry.path;
 
-        // Check if this is a .graft.yaml file
-        if entry.is_file && path.file_name() == Some(OsStr::new(".graft.yaml")) {
+        // Check if this is a graft file
+        if entry.is_file && path.file_name() == Some(OsStr::new(file_name)) {
             let directory = path
                 .parent()
-                .ok_or_else(|| anyhow::anyhow!("Failed to get parent directory of .graft.yaml"))?
+                .ok_or_else(|| anyhow::anyhow!("Failed to get parent directory of {file_name}"))?
                 .to_path_buf();
 
             // Calculate depth relative to target_dir
@@ -184,14 +268,30 @@ fn find_parent_graft(grafts: &[DiscoveredGraft], directory: &Path) -> Option<Box
 /// - The .graft.yaml files cannot be deleted
 #[inline]
 pub fn cleanup_graft_files(system: &dyn System, target_dir: &Path) -> Result<usize> {
-    let grafts = discover_graft_files(system, target_dir)?;
+    cleanup_graft_files_named(system, target_dir, DEFAULT_GRAFT_FILE_NAME)
+}
+
+/// Delete all graft files named `file_name` from a target directory.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The target directory does not exist
+/// - The graft files cannot be deleted
+#[inline]
+pub fn cleanup_graft_files_named(
+    system: &dyn System,
+    target_dir: &Path,
+    file_name: &str,
+) -> Result<usize> {
+    let grafts = discover_graft_files_named(system, target_dir, file_name)?;
     let mut deleted_count: usize = 0;
 
     for graft in grafts {
         if system.exists(&graft.path)? {
             system.remove_file(&graft.path).with_context(|| {
                 format!(
-                    "Failed to delete .graft.yaml file: {}",
+                    "Failed to delete {file_name} file: {}",
                     graft.path.display()
                 )
             })?;
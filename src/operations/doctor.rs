@@ -0,0 +1,308 @@
+//! Environment diagnostics (`--doctor`).
+//!
+//! Aggregates the checks tixgraft otherwise performs piecemeal into one
+//! report that can be pasted into bug reports:
+//!
+//! - `git` presence and version (2.25+ is required for sparse checkout)
+//! - `git-lfs` presence
+//! - Temporary directory writability (clones are staged there)
+//! - Config file presence and validity
+//! - Reachability of the configured repository (`git ls-remote`)
+
+use crate::config::Config;
+use crate::git::{GitInvocation, GitRunner, Repository, parse_git_version};
+use core::fmt;
+use os_shim::System;
+use std::env;
+use std::path::Path;
+use std::process;
+
+/// Outcome of a single diagnostic check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CheckStatus {
+    /// The check failed; tixgraft will not work until it is fixed.
+    Fail,
+    /// The check passed.
+    Ok,
+    /// The check found something worth attention, but not fatal.
+    Warn,
+}
+
+impl CheckStatus {
+    /// Short label used in the report.
+    #[must_use]
+    #[inline]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Fail => "fail",
+            Self::Ok => "ok",
+            Self::Warn => "warn",
+        }
+    }
+}
+
+/// Result of a single diagnostic check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DoctorCheck {
+    /// Remediation hint, shown for warnings and failures.
+    pub hint: Option<String>,
+    /// What the check found.
+    pub message: String,
+    /// Name of the check (e.g. `git`).
+    pub name: String,
+    /// Outcome of the check.
+    pub status: CheckStatus,
+}
+
+impl DoctorCheck {
+    /// A failed check with a remediation hint.
+    #[must_use]
+    #[inline]
+    pub fn fail(name: &str, message: String, hint: &str) -> Self {
+        Self {
+            hint: Some(hint.to_owned()),
+            message,
+            name: name.to_owned(),
+            status: CheckStatus::Fail,
+        }
+    }
+
+    /// A passed check.
+    #[must_use]
+    #[inline]
+    pub fn ok(name: &str, message: String) -> Self {
+        Self {
+            hint: None,
+            message,
+            name: name.to_owned(),
+            status: CheckStatus::Ok,
+        }
+    }
+
+    /// A warning with a remediation hint.
+    #[must_use]
+    #[inline]
+    pub fn warn(name: &str, message: String, hint: &str) -> Self {
+        Self {
+            hint: Some(hint.to_owned()),
+            message,
+            name: name.to_owned(),
+            status: CheckStatus::Warn,
+        }
+    }
+}
+
+/// All diagnostic checks, in the order they ran.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DoctorReport {
+    /// Individual check results.
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    /// Find a check by name.
+    #[must_use]
+    #[inline]
+    pub fn check(&self, name: &str) -> Option<&DoctorCheck> {
+        self.checks.iter().find(|check| check.name == name)
+    }
+
+    /// Whether any check failed.
+    #[must_use]
+    #[inline]
+    pub fn has_failures(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.status == CheckStatus::Fail)
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "tixgraft doctor")?;
+        for check in &self.checks {
+            let label = format!("[{}]", check.status.label());
+            writeln!(formatter, "  {label:<6} {}: {}", check.name, check.message)?;
+            if let Some(hint) = check.hint.as_ref() {
+                writeln!(formatter, "         hint: {hint}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run every diagnostic check and collect the results.
+///
+/// Never fails: problems are reported as failed checks instead.
+#[must_use]
+#[inline]
+pub fn diagnose(system: &dyn System, runner: &dyn GitRunner, config_path: &str) -> DoctorReport {
+    let mut checks = vec![
+        check_git(runner),
+        check_git_lfs(runner),
+        check_temp_dir(system),
+    ];
+
+    let config = check_config(system, config_path, &mut checks);
+    if let Some(repository) = config.as_ref().and_then(|cfg| cfg.repository.as_deref()) {
+        checks.push(check_repository(system, runner, repository));
+    }
+
+    DoctorReport { checks }
+}
+
+/// Check that the config file exists and is valid, returning it when it is.
+fn check_config(
+    system: &dyn System,
+    config_path: &str,
+    checks: &mut Vec<DoctorCheck>,
+) -> Option<Config> {
+    if !system.exists(Path::new(config_path)).unwrap_or(false) {
+        checks.push(DoctorCheck::warn(
+            "config",
+            format!("No config file at {config_path}"),
+            "Pass --config <PATH>, or define pulls with --pull-* flags",
+        ));
+        return None;
+    }
+
+    let loaded = Config::load_from_file(system, config_path)
+        .and_then(|config| config.validate(system).map(|()| config));
+    match loaded {
+        Ok(config) => {
+            checks.push(DoctorCheck::ok(
+                "config",
+                format!("{config_path} is valid ({} pulls)", config.pulls.len()),
+            ));
+            Some(config)
+        }
+        Err(err) => {
+            checks.push(DoctorCheck::fail(
+                "config",
+                format!("{config_path} is invalid: {err:#}"),
+                "Fix the reported problem; see docs/schema.json for the config format",
+            ));
+            None
+        }
+    }
+}
+
+/// Check that `git` is installed and recent enough for sparse checkout.
+fn check_git(runner: &dyn GitRunner) -> DoctorCheck {
+    let install_hint = "Install Git 2.25.0 or later and make sure it is on PATH";
+
+    let output = match runner.run(&GitInvocation::new(["--version"], None)) {
+        Ok(output) if output.success => output,
+        Ok(output) => {
+            return DoctorCheck::fail(
+                "git",
+                format!("`git --version` failed: {}", output.stderr.trim()),
+                install_hint,
+            );
+        }
+        Err(err) => {
+            return DoctorCheck::fail("git", format!("git not found: {err:#}"), install_hint);
+        }
+    };
+
+    let version_line = output.stdout.trim().to_owned();
+    match version_line
+        .split_whitespace()
+        .nth(2)
+        .map(parse_git_version)
+    {
+        Some(Ok(version)) if version < (2, 25, 0) => DoctorCheck::fail(
+            "git",
+            format!("{version_line} is too old for sparse checkout"),
+            install_hint,
+        ),
+        Some(Ok(_)) => DoctorCheck::ok("git", version_line),
+        Some(Err(_)) | None => DoctorCheck::warn(
+            "git",
+            format!("Could not parse git version from '{version_line}'"),
+            "Make sure `git --version` reports Git 2.25.0 or later",
+        ),
+    }
+}
+
+/// Check whether `git-lfs` is installed.
+fn check_git_lfs(runner: &dyn GitRunner) -> DoctorCheck {
+    match runner.run(&GitInvocation::new(["lfs", "version"], None)) {
+        Ok(output) if output.success => DoctorCheck::ok("git-lfs", output.stdout.trim().to_owned()),
+        Ok(_) | Err(_) => DoctorCheck::warn(
+            "git-lfs",
+            "git-lfs is not installed".to_owned(),
+            "Install git-lfs if your source repositories store files with Git LFS",
+        ),
+    }
+}
+
+/// Check that the configured repository is reachable.
+fn check_repository(system: &dyn System, runner: &dyn GitRunner, url: &str) -> DoctorCheck {
+    let hint = "Check the repository URL, network access, and Git credentials";
+
+    let repository = match Repository::new(system, url) {
+        Ok(repository) => repository,
+        Err(err) => return DoctorCheck::fail("repository", format!("{err:#}"), hint),
+    };
+
+    if repository.is_local() {
+        return DoctorCheck::ok("repository", format!("{url} is a local source"));
+    }
+
+    if repository.is_archive() {
+        return DoctorCheck::ok("repository", format!("{url} is an archive source"));
+    }
+
+    let git_url = match repository.git_url() {
+        Ok(git_url) => git_url,
+        Err(err) => return DoctorCheck::fail("repository", format!("{err:#}"), hint),
+    };
+
+    match runner.run(&GitInvocation::new(["ls-remote", git_url, "HEAD"], None)) {
+        Ok(output) if output.success => {
+            DoctorCheck::ok("repository", format!("{git_url} is reachable"))
+        }
+        Ok(output) => DoctorCheck::fail(
+            "repository",
+            format!("{git_url} is not reachable: {}", output.stderr.trim()),
+            hint,
+        ),
+        Err(err) => DoctorCheck::fail(
+            "repository",
+            format!("Could not run git ls-remote: {err:#}"),
+            hint,
+        ),
+    }
+}
+
+/// Check that the temporary directory used for clones is writable.
+fn check_temp_dir(system: &dyn System) -> DoctorCheck {
+    let temp_dir = env::temp_dir();
+    let probe = temp_dir.join(format!(".tixgraft-doctor-{}", process::id()));
+
+    match system.write(&probe, b"") {
+        Ok(()) => {
+            if system.remove_file(&probe).is_err() {
+                return DoctorCheck::warn(
+                    "temp dir",
+                    format!(
+                        "{} is writable, but the probe file could not be removed",
+                        temp_dir.display()
+                    ),
+                    "Remove stray .tixgraft-doctor-* files from the temporary directory",
+                );
+            }
+            DoctorCheck::ok("temp dir", format!("{} is writable", temp_dir.display()))
+        }
+        Err(err) => DoctorCheck::fail(
+            "temp dir",
+            format!("{} is not writable: {err}", temp_dir.display()),
+            "Set TMPDIR to a writable directory",
+        ),
+    }
+}
@@ -0,0 +1,136 @@
+//! Pull dependency graph (`--print-plan-graph`).
+//!
+//! Renders the resolved config's pulls as a Graphviz DOT graph without
+//! running anything:
+//!
+//! - One node per pull, labelled with its source, target and type
+//! - One cluster per repository and reference; pulls in a cluster share a
+//!   checkout
+//! - Solid edges in execution order
+//! - Red dashed edges, plus a `// warning:` comment, between pulls whose
+//!   targets overlap (one is the other or lies inside it)
+
+use crate::cli::{PullConfig, is_overlay_pull};
+use crate::config::Config;
+use crate::operations::pull::{PullOperation, description_suffix};
+use crate::operations::stdout::is_stdout_target;
+use crate::utils::path::normalize;
+use anyhow::Result;
+use core::fmt::Write as _;
+use std::path::Path;
+
+/// Label of the cluster for pulls with no repository at all.
+const NO_REPOSITORY: &str = "(no repository)";
+
+/// Render `config`'s pulls as a DOT graph.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A pull's reference cannot be resolved (e.g. an unknown channel)
+#[inline]
+pub fn plan_graph(config: &Config) -> Result<String> {
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    for (index, pull) in config.pulls.iter().enumerate() {
+        let repository = pull
+            .repository
+            .as_deref()
+            .or(config.repository.as_deref())
+            .unwrap_or(NO_REPOSITORY);
+        let reference = PullOperation::resolve_reference(config, pull)?;
+        let label = format!("{repository}@{}", reference.name);
+        match groups.iter_mut().find(|group| group.0 == label) {
+            Some(group) => group.1.push(index),
+            None => groups.push((label, vec![index])),
+        }
+    }
+
+    let mut output = String::from("digraph plan {\n  rankdir=LR;\n  node [shape=box];\n");
+    for (cluster, group) in groups.iter().enumerate() {
+        writeln!(output, "  subgraph cluster_{cluster} {{")?;
+        writeln!(output, "    label=\"{}\";", escape(&group.0))?;
+        for &index in &group.1 {
+            if let Some(pull) = config.pulls.get(index) {
+                writeln!(
+                    output,
+                    "    pull{index} [label=\"{}\"];",
+                    escape(&node_label(index, pull))
+                )?;
+            }
+        }
+        writeln!(output, "  }}")?;
+    }
+
+    for index in 1..config.pulls.len() {
+        writeln!(output, "  pull{} -> pull{index};", index.saturating_sub(1))?;
+    }
+
+    for (first, second) in overlapping_targets(config) {
+        let (Some(first_pull), Some(second_pull)) =
+            (config.pulls.get(first), config.pulls.get(second))
+        else {
+            continue;
+        };
+        writeln!(
+            output,
+            "  // warning: targets of pull #{} ({}) and pull #{} ({}) overlap",
+            first.saturating_add(1),
+            first_pull.target,
+            second.saturating_add(1),
+            second_pull.target
+        )?;
+        writeln!(
+            output,
+            "  pull{first} -> pull{second} [color=red, style=dashed, dir=none, label=\"overlap\"];"
+        )?;
+    }
+    output.push_str("}\n");
+    Ok(output)
+}
+
+/// Pairs of pull indices (in order) whose targets overlap.
+#[must_use]
+#[inline]
+pub fn overlapping_targets(config: &Config) -> Vec<(usize, usize)> {
+    let targets: Vec<_> = config
+        .pulls
+        .iter()
+        .map(|pull| (!is_stdout_target(&pull.target)).then(|| normalize(Path::new(&pull.target))))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for (first, first_target) in targets.iter().enumerate() {
+        for (second, second_target) in targets.iter().enumerate().skip(first.saturating_add(1)) {
+            if let (Some(first_path), Some(second_path)) =
+                (first_target.as_ref(), second_target.as_ref())
+                && (first_path.starts_with(second_path) || second_path.starts_with(first_path))
+            {
+                pairs.push((first, second));
+            }
+        }
+    }
+    pairs
+}
+
+/// Node label for the pull at `index`.
+fn node_label(index: usize, pull: &PullConfig) -> String {
+    let source = if is_overlay_pull(pull) {
+        let mut layers = vec![pull.source.as_str()];
+        layers.extend(pull.sources.iter().map(String::as_str));
+        layers.join(" + ")
+    } else {
+        pull.source.clone()
+    };
+    format!(
+        "#{} {source} -> {} ({}){}",
+        index.saturating_add(1),
+        pull.target,
+        pull.pull_type,
+        description_suffix(pull)
+    )
+}
+
+/// Escape `text` for use inside a double-quoted DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -2,28 +2,248 @@
 //!
 //! Handles execution of commands after graft processing, including
 //! simple commands and conditional choice-based execution.
+//!
+//! Post-commands are spawned directly, not through a shell, so `$VAR` in
+//! their `command`, `args` or `cwd` stays literal. When run with
+//! [`CommandVariables`], `${VAR}` references are expanded before spawning.
+//!
+//! A command with `cleanEnv` (or every command, with `--clean-env`) starts
+//! from an empty environment: only [`CLEAN_ENV_PASSTHROUGH`] and the
+//! variables tixgraft sets itself are passed through.
 
+use crate::config::context::{ContextValues, value_to_string};
 use crate::config::graft_yaml::{ChoiceOption, PostCommand, TestCommand};
 use crate::error::GraftError;
-use anyhow::{Context as _, Result};
+use crate::utils::process::spawn_child;
+use anyhow::{Context as _, Result, anyhow};
+use os_shim::System;
+use os_shim::real::RealSystem;
 use regex::Regex;
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::LazyLock;
+use std::thread;
+
+/// Variables of tixgraft's own environment kept in a clean environment.
+pub const CLEAN_ENV_PASSTHROUGH: &[&str] = &["PATH", "HOME"];
+
+/// Default cap on the captured stdout and stderr of each command, in bytes.
+pub const DEFAULT_OUTPUT_LIMIT: usize = 64 * 1024;
+
+/// A `${NAME}` or `${NAME:-fallback}` reference, compiled once.
+static VARIABLE_REFERENCE: LazyLock<Result<Regex, regex::Error>> =
+    LazyLock::new(|| Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(?::-([^}]*))?\}"));
+
+/// Values that `${NAME}` references in post-commands expand to.
+///
+/// A name is looked up in the context first, then in the environment.
+/// `${NAME:-fallback}` expands to `fallback` when the name is undefined; any
+/// other undefined reference is an error. Bare `$NAME` is left as-is.
+#[derive(Clone, Copy)]
+#[non_exhaustive]
+pub struct CommandVariables<'vars> {
+    /// Context values, checked before the environment.
+    pub context: &'vars ContextValues,
+    /// Source of environment variables.
+    pub system: &'vars dyn System,
+}
+
+impl<'vars> CommandVariables<'vars> {
+    /// Expand every `${NAME}` and `${NAME:-fallback}` reference in `input`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - A reference without a fallback names an undefined variable
+    /// - A context value cannot be converted to a string
+    #[inline]
+    pub fn expand(&self, input: &str) -> Result<String> {
+        let reference = VARIABLE_REFERENCE
+            .as_ref()
+            .map_err(|err| anyhow!("Failed to compile variable reference pattern: {err}"))?;
+
+        let mut expanded = String::with_capacity(input.len());
+        let mut copied_up_to = 0;
+        for caps in reference.captures_iter(input) {
+            let (Some(whole), Some(name)) = (caps.get(0), caps.get(1)) else {
+                continue;
+            };
+            let value = match self.lookup(name.as_str())? {
+                Some(value) => value,
+                None => caps
+                    .get(2)
+                    .map(|fallback| fallback.as_str().to_owned())
+                    .ok_or_else(|| {
+                        GraftError::configuration(format!(
+                            "Undefined variable '{}' in post-command '{input}'; set it or use ${{{}:-default}}",
+                            whole.as_str(),
+                            name.as_str()
+                        ))
+                    })?,
+            };
+            expanded.push_str(input.get(copied_up_to..whole.start()).unwrap_or_default());
+            expanded.push_str(&value);
+            copied_up_to = whole.end();
+        }
+        expanded.push_str(input.get(copied_up_to..).unwrap_or_default());
+        Ok(expanded)
+    }
+
+    /// The value of `name` from the context or the environment, if defined.
+    fn lookup(&self, name: &str) -> Result<Option<String>> {
+        if let Some(value) = self.context.get(name) {
+            return value_to_string(value).map(Some);
+        }
+        Ok(self.system.env_var(name).ok())
+    }
+
+    /// Expand references against `context`, then the environment of `system`.
+    #[must_use]
+    #[inline]
+    pub const fn new(system: &'vars dyn System, context: &'vars ContextValues) -> Self {
+        Self { context, system }
+    }
+}
 
 /// Result of executing a post-command.
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub struct ExecutionResult {
+    /// Context key the output should be captured into (`captureInto`).
+    pub capture_into: Option<String>,
     /// The type of command that was executed.
     pub command_type: String,
-    /// Error output, if any.
+    /// Error output, if any, truncated to the output limit.
     pub error: Option<String>,
-    /// Standard output from the command.
+    /// Standard output from the command, truncated to the output limit.
     pub output: String,
     /// Whether the command succeeded.
     pub success: bool,
 }
 
+/// Exit status and capped output of a finished process.
+struct CapturedOutput {
+    /// Exit status of the process.
+    status: ExitStatus,
+    /// Standard error, truncated to the output limit.
+    stderr: String,
+    /// Standard output, truncated to the output limit.
+    stdout: String,
+}
+
+/// Options shared by every process a post-command spawns: extra
+/// environment variables, the output cap, `${VAR}` expansion and
+/// `--clean-env`.
+///
+/// [`CLEAN_ENV_PASSTHROUGH`] variables are read from `system`.
+#[derive(Clone, Copy)]
+#[non_exhaustive]
+pub struct PostCommandOptions<'env> {
+    /// Run every process in a clean environment, as if each command had
+    /// `cleanEnv` set (default: false).
+    pub clean_env: bool,
+    /// Extra `(name, value)` environment variables set on every process,
+    /// including the test commands of choices (default: none).
+    pub envs: &'env [(String, String)],
+    /// Maximum bytes kept of each of stdout and stderr; longer output is
+    /// cut off and ends with a truncation marker (default:
+    /// [`DEFAULT_OUTPUT_LIMIT`]).
+    pub output_limit: usize,
+    /// Source of the variables passed through to a clean environment.
+    pub system: &'env dyn System,
+    /// Values `${VAR}` references expand to; references stay literal
+    /// without (default: none).
+    pub variables: Option<CommandVariables<'env>>,
+}
+
+impl<'env> PostCommandOptions<'env> {
+    /// A `Command` for `program` with the extra environment variables set,
+    /// on a cleared environment when `clean_env` is set.
+    fn command(&self, program: &str, clean_env: bool) -> Command {
+        let mut command = Command::new(program);
+        if clean_env {
+            command.env_clear();
+            for name in CLEAN_ENV_PASSTHROUGH {
+                if let Ok(value) = self.system.env_var(name) {
+                    command.env(name, value);
+                }
+            }
+        }
+        command.envs(self.envs.iter().cloned());
+        command
+    }
+
+    /// Expand `${VAR}` references in a command, its arguments and its
+    /// working directory (unchanged without variables).
+    fn expand(
+        &self,
+        command: &str,
+        args: &[String],
+        cwd: Option<&str>,
+    ) -> Result<(String, Vec<String>, Option<String>)> {
+        let Some(variables) = self.variables else {
+            return Ok((command.to_owned(), args.to_vec(), cwd.map(str::to_owned)));
+        };
+        Ok((
+            variables.expand(command)?,
+            args.iter()
+                .map(|arg| variables.expand(arg))
+                .collect::<Result<_>>()?,
+            cwd.map(|dir| variables.expand(dir)).transpose()?,
+        ))
+    }
+
+    /// Options with the inherited environment, the default output cap and
+    /// no `${VAR}` expansion.
+    #[must_use]
+    #[inline]
+    pub const fn new(system: &'env dyn System) -> Self {
+        Self {
+            clean_env: false,
+            envs: &[],
+            output_limit: DEFAULT_OUTPUT_LIMIT,
+            system,
+            variables: None,
+        }
+    }
+
+    /// Run every command in a clean environment holding only
+    /// [`CLEAN_ENV_PASSTHROUGH`] and the extra variables.
+    #[must_use]
+    #[inline]
+    pub const fn with_clean_env(mut self, clean_env: bool) -> Self {
+        self.clean_env = clean_env;
+        self
+    }
+
+    /// Set `envs` on every spawned process.
+    #[must_use]
+    #[inline]
+    pub const fn with_envs(mut self, envs: &'env [(String, String)]) -> Self {
+        self.envs = envs;
+        self
+    }
+
+    /// Keep at most `output_limit` bytes of each command's stdout and stderr.
+    #[must_use]
+    #[inline]
+    pub const fn with_output_limit(mut self, output_limit: usize) -> Self {
+        self.output_limit = output_limit;
+        self
+    }
+
+    /// Expand `${VAR}` references in each command, its arguments and its
+    /// working directory with `variables`. A command with an undefined
+    /// reference fails without being spawned.
+    #[must_use]
+    #[inline]
+    pub const fn with_variables(mut self, variables: CommandVariables<'env>) -> Self {
+        self.variables = Some(variables);
+        self
+    }
+}
+
 /// Execute all post-commands in order.
 ///
 /// Commands execute in the directory containing the .graft.yaml file.
@@ -38,15 +258,35 @@ pub struct ExecutionResult {
 pub fn execute_post_commands(
     commands: &[PostCommand],
     graft_directory: &Path,
+) -> Result<Vec<ExecutionResult>> {
+    let system = RealSystem::new();
+    execute_post_commands_with_options(commands, graft_directory, &PostCommandOptions::new(&system))
+}
+
+/// Execute all post-commands in order, as configured by `options`.
+///
+/// Behaves like [`execute_post_commands`] otherwise.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The post-commands cannot be executed
+#[inline]
+#[expect(clippy::module_name_repetitions, reason = "PostCommands")]
+pub fn execute_post_commands_with_options(
+    commands: &[PostCommand],
+    graft_directory: &Path,
+    options: &PostCommandOptions<'_>,
 ) -> Result<Vec<ExecutionResult>> {
     let mut results = Vec::new();
 
     for command in commands {
-        let result = match execute_post_command(command, graft_directory) {
+        let result = match execute_post_command_with_options(command, graft_directory, options) {
             Ok(result) => result,
             Err(err) => {
                 // Convert execution errors into failed ExecutionResult
                 ExecutionResult {
+                    capture_into: None,
                     command_type: "command".to_owned(),
                     error: Some(format!("{err:#}")),
                     output: String::new(),
@@ -67,21 +307,49 @@ pub fn execute_post_commands(
 /// Returns an error if:
 /// - The post-command cannot be executed
 #[inline]
+pub fn execute_post_command(
+    post_command: &PostCommand,
+    graft_directory: &Path,
+) -> Result<ExecutionResult> {
+    let system = RealSystem::new();
+    execute_post_command_with_options(
+        post_command,
+        graft_directory,
+        &PostCommandOptions::new(&system),
+    )
+}
+
+/// Execute a single post-command as configured by `options`.
 #[expect(
     clippy::ref_patterns,
     reason = "ref is required to borrow fields when matching on a dereferenced enum to satisfy pattern_type_mismatch"
 )]
-pub fn execute_post_command(
+fn execute_post_command_with_options(
     post_command: &PostCommand,
     graft_directory: &Path,
+    options: &PostCommandOptions<'_>,
 ) -> Result<ExecutionResult> {
     match *post_command {
         PostCommand::Command {
             ref command,
             ref args,
             ref cwd,
-        } => execute_simple_command(command, args, cwd.as_deref(), graft_directory),
-        PostCommand::Choice { ref options } => execute_choice(options, graft_directory),
+            ref capture_into,
+            clean_env,
+        } => Ok(ExecutionResult {
+            capture_into: capture_into.clone(),
+            ..execute_simple_command(
+                command,
+                args,
+                cwd.as_deref(),
+                graft_directory,
+                options,
+                clean_env || options.clean_env,
+            )?
+        }),
+        PostCommand::Choice {
+            options: ref choices,
+        } => execute_choice(choices, graft_directory, options),
     }
 }
 
@@ -131,10 +399,14 @@ pub fn resolve_working_directory(cwd: Option<&str>, graft_directory: &Path) -> R
 /// The `expectedOutput` field is treated as a regular expression pattern.
 /// Simple strings like "version" will match anywhere in the output.
 /// More complex patterns like "^v\\d+\\.\\d+\\.\\d+$" can be used for precise matching.
-fn execute_choice(options: &[ChoiceOption], graft_directory: &Path) -> Result<ExecutionResult> {
+fn execute_choice(
+    choices: &[ChoiceOption],
+    graft_directory: &Path,
+    options: &PostCommandOptions<'_>,
+) -> Result<ExecutionResult> {
     // Try each option in order
-    for option in options {
-        let test_result = execute_test_command(&option.test, graft_directory)?;
+    for option in choices {
+        let test_result = execute_test_command(&option.test, graft_directory, options)?;
 
         // Check if output matches expected pattern (regex)
         let pattern = Regex::new(&option.expected_output).with_context(|| {
@@ -146,12 +418,13 @@ fn execute_choice(options: &[ChoiceOption], graft_directory: &Path) -> Result<Ex
 
         if pattern.is_match(&test_result.output) {
             // Match found, execute the onMatch command
-            return execute_post_command(&option.on_match, graft_directory);
+            return execute_post_command_with_options(&option.on_match, graft_directory, options);
         }
     }
 
     // No matches found, return a no-op result
     Ok(ExecutionResult {
+        capture_into: None,
         command_type: "choice".to_owned(),
         error: None,
         output: "No matching option found".to_owned(),
@@ -165,67 +438,149 @@ fn execute_simple_command(
     args: &[String],
     cwd: Option<&str>,
     graft_directory: &Path,
+    options: &PostCommandOptions<'_>,
+    clean_env: bool,
 ) -> Result<ExecutionResult> {
-    let working_dir = resolve_working_directory(cwd, graft_directory)?;
-
-    let output = Command::new(command)
-        .args(args)
-        .current_dir(&working_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .with_context(|| {
-            format!(
-                "Failed to execute command '{}' in directory '{}'",
-                command,
-                working_dir.display()
-            )
-        })?;
+    let (command, args, cwd) = options.expand(command, args, cwd)?;
+    let working_dir = resolve_working_directory(cwd.as_deref(), graft_directory)?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let output = run_captured(
+        options
+            .command(&command, clean_env)
+            .args(&args)
+            .current_dir(&working_dir),
+        options.output_limit,
+    )
+    .with_context(|| {
+        format!(
+            "Failed to execute command '{}' in directory '{}'",
+            command,
+            working_dir.display()
+        )
+    })?;
 
     Ok(ExecutionResult {
+        capture_into: None,
         command_type: "command".to_owned(),
-        error: if stderr.is_empty() {
+        error: if output.stderr.is_empty() {
             None
         } else {
-            Some(stderr)
+            Some(output.stderr)
         },
-        output: stdout,
+        output: output.stdout,
         success: output.status.success(),
     })
 }
 
 /// Execute a test command (for choice conditions).
-fn execute_test_command(test: &TestCommand, graft_directory: &Path) -> Result<ExecutionResult> {
-    let working_dir = resolve_working_directory(test.cwd.as_deref(), graft_directory)?;
-
-    let output = Command::new(&test.command)
-        .args(&test.args)
-        .current_dir(&working_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .with_context(|| {
-            format!(
-                "Failed to execute test command '{}' in directory '{}'",
-                test.command,
-                working_dir.display()
-            )
-        })?;
+fn execute_test_command(
+    test: &TestCommand,
+    graft_directory: &Path,
+    options: &PostCommandOptions<'_>,
+) -> Result<ExecutionResult> {
+    let (command, args, cwd) = options.expand(&test.command, &test.args, test.cwd.as_deref())?;
+    let working_dir = resolve_working_directory(cwd.as_deref(), graft_directory)?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let output = run_captured(
+        options
+            .command(&command, options.clean_env)
+            .args(&args)
+            .current_dir(&working_dir),
+        options.output_limit,
+    )
+    .with_context(|| {
+        format!(
+            "Failed to execute test command '{}' in directory '{}'",
+            command,
+            working_dir.display()
+        )
+    })?;
 
     Ok(ExecutionResult {
+        capture_into: None,
         command_type: "test".to_owned(),
-        error: if stderr.is_empty() {
+        error: if output.stderr.is_empty() {
             None
         } else {
-            Some(stderr)
+            Some(output.stderr)
         },
-        output: stdout,
+        output: output.stdout,
         success: output.status.success(),
     })
 }
+
+/// Spawn `command` and wait for it, keeping at most `limit` bytes of each
+/// of its stdout and stderr in memory.
+///
+/// Both pipes are read concurrently so that a process filling one of them
+/// cannot block on the other.
+fn run_captured(command: &mut Command, limit: usize) -> io::Result<CapturedOutput> {
+    let mut child = spawn_child(command.stdout(Stdio::piped()).stderr(Stdio::piped()))?;
+
+    let stderr_pipe = child.take_stderr();
+    let stderr_reader = thread::spawn(move || {
+        stderr_pipe.map_or_else(|| Ok(String::new()), |pipe| read_capped(pipe, limit))
+    });
+    let stdout = child
+        .take_stdout()
+        .map_or_else(|| Ok(String::new()), |pipe| read_capped(pipe, limit))?;
+    let stderr = stderr_reader
+        .join()
+        .map_err(|_panic| io::Error::other("stderr reader thread panicked"))??;
+
+    Ok(CapturedOutput {
+        status: child.wait()?,
+        stderr,
+        stdout,
+    })
+}
+
+/// Read at most `limit` bytes of `pipe`, then drain and count the rest so
+/// the process can run to completion.
+fn read_capped<R: Read>(mut pipe: R, limit: usize) -> io::Result<String> {
+    let mut kept = Vec::new();
+    pipe.by_ref()
+        .take(u64::try_from(limit).unwrap_or(u64::MAX))
+        .read_to_end(&mut kept)?;
+    let drained = io::copy(&mut pipe, &mut io::sink())?;
+    if drained == 0 {
+        return Ok(truncate_output(&kept, limit));
+    }
+
+    let total = drained.saturating_add(u64::try_from(kept.len()).unwrap_or(u64::MAX));
+    // Drop a character cut in half by the limit
+    if let Err(err) = core::str::from_utf8(&kept)
+        && err.error_len().is_none()
+    {
+        kept.truncate(err.valid_up_to());
+    }
+    let omitted = total.saturating_sub(u64::try_from(kept.len()).unwrap_or(u64::MAX));
+    Ok(format!(
+        "{}\n... [output truncated: {omitted} of {total} bytes omitted]",
+        String::from_utf8_lossy(&kept)
+    ))
+}
+
+/// Decode captured process output, keeping at most `limit` bytes.
+///
+/// Longer output is cut at a character boundary and ends with a marker
+/// saying how much was dropped.
+#[must_use]
+#[inline]
+pub fn truncate_output(bytes: &[u8], limit: usize) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= limit {
+        return text.into_owned();
+    }
+
+    let mut cut = limit;
+    while !text.is_char_boundary(cut) {
+        cut = cut.saturating_sub(1);
+    }
+    let kept = text.get(..cut).unwrap_or_default();
+    format!(
+        "{kept}\n... [output truncated: {} of {} bytes omitted]",
+        text.len().saturating_sub(cut),
+        text.len()
+    )
+}
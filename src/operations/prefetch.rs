@@ -0,0 +1,211 @@
+//! Up-front fetching of Git sources (`--prefetch`).
+//!
+//! Without `--prefetch`, each pull clones its repository when its turn
+//! comes, so an unreachable repository only surfaces at that pull, after
+//! earlier pulls have already written their targets. With it, every unique
+//! repository and reference among the config's Git pulls is cloned once,
+//! sparse-checked-out with the sources of all the pulls that use it, before
+//! anything is copied. Pulls then read their sources from these checkouts.
+
+use crate::cli::PullConfig;
+use crate::config::Config;
+use crate::error::GraftError;
+use crate::git::{CloneOptions, GitRunner, Repository, SparseCheckout};
+use crate::operations::pull::PullOperation;
+use anyhow::{Context as _, Result};
+use os_shim::System;
+use std::collections::HashMap;
+use std::path::Path;
+use tempfile::TempDir;
+use tracing::{debug, info};
+
+/// Checkouts made by [`prefetch_sources`], keyed by repository URL and
+/// reference.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct PrefetchedSources {
+    /// Checkout of each repository URL and reference.
+    checkouts: HashMap<(String, String), PrefetchedCheckout>,
+}
+
+impl PrefetchedSources {
+    /// The checkout of `repository` at `reference`, if it was prefetched.
+    #[must_use]
+    #[inline]
+    pub fn get(&self, repository: &str, reference: &str) -> Option<&PrefetchedCheckout> {
+        self.checkouts
+            .get(&(repository.to_owned(), reference.to_owned()))
+    }
+
+    /// Whether nothing was prefetched (the config has no Git pulls).
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.checkouts.is_empty()
+    }
+
+    /// Number of repository and reference pairs prefetched.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.checkouts.len()
+    }
+}
+
+/// A repository checked out at a reference by [`prefetch_sources`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct PrefetchedCheckout {
+    /// Temporary directory holding the checkout; removed when dropped.
+    root: TempDir,
+    /// Commit the reference resolved to.
+    sha: Option<String>,
+}
+
+impl PrefetchedCheckout {
+    /// Root of the checked-out working tree; pull sources are relative to it.
+    #[must_use]
+    #[inline]
+    pub fn root(&self) -> &Path {
+        self.root.path()
+    }
+
+    /// Commit the reference resolved to, when `git rev-parse` succeeded.
+    #[must_use]
+    #[inline]
+    pub fn sha(&self) -> Option<&str> {
+        self.sha.as_deref()
+    }
+}
+
+/// A repository and reference to prefetch, with the sparse-checkout
+/// patterns of every pull that uses it.
+struct PrefetchGroup {
+    /// Sparse-checkout patterns, in first-seen order without duplicates.
+    patterns: Vec<String>,
+    /// Git reference to check out.
+    reference: String,
+    /// Repository to clone.
+    repository: Repository,
+    /// Repository URL as configured.
+    url: String,
+}
+
+/// Clone every unique repository and reference among `config`'s Git pulls
+/// through `runner`, before any pull runs.
+///
+/// Pulls from local directories and archives are not prefetched, and
+/// neither are pulls with a `tagPattern` or the pulls of child configs,
+/// which still fetch when they run.
+/// `--max-clone-size` applies to everything checked out for a repository
+/// and reference.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A pull's repository URL or reference cannot be resolved
+/// - A repository cannot be cloned or checked out
+#[inline]
+pub fn prefetch_sources(
+    system: &dyn System,
+    config: &Config,
+    runner: &dyn GitRunner,
+    options: &CloneOptions,
+    temp_root: Option<&Path>,
+) -> Result<PrefetchedSources> {
+    let groups = prefetch_groups(system, config)?;
+    if !groups.is_empty() {
+        info!(
+            "Prefetching {} repository reference(s) before pulling...",
+            groups.len()
+        );
+    }
+
+    let mut prefetched = PrefetchedSources::default();
+    for group in groups {
+        debug!(
+            "Prefetching {} at {} with patterns {:?}",
+            group.url, group.reference, group.patterns
+        );
+        let checkout = SparseCheckout::new_in(
+            group.repository,
+            group.reference.clone(),
+            ".".to_owned(),
+            temp_root,
+        )
+        .context("Failed to create sparse checkout")?
+        .with_options(options.clone())
+        .with_sparse_patterns(group.patterns)
+        .with_runner(runner);
+        checkout.execute().with_context(|| {
+            format!(
+                "Prefetch failed for repository '{}' at reference '{}'",
+                group.url, group.reference
+            )
+        })?;
+
+        let sha = checkout.resolve_sha().ok();
+        prefetched.checkouts.insert(
+            (group.url, group.reference),
+            PrefetchedCheckout {
+                root: checkout.temp_dir,
+                sha,
+            },
+        );
+    }
+    Ok(prefetched)
+}
+
+/// Group `config`'s Git pulls by repository URL and reference, in order.
+fn prefetch_groups(system: &dyn System, config: &Config) -> Result<Vec<PrefetchGroup>> {
+    let mut groups: Vec<PrefetchGroup> = Vec::new();
+    for (index, pull) in config.pulls.iter().enumerate() {
+        let url = pull
+            .repository
+            .as_ref()
+            .or(config.repository.as_ref())
+            .ok_or_else(|| {
+                GraftError::configuration(format!(
+                    "No repository specified for pull #{}",
+                    index.saturating_add(1)
+                ))
+            })?;
+        let repository = Repository::new(system, url).context("Failed to create repository")?;
+        // A `tagPattern` is only resolved to a tag when its pull runs
+        if !repository.is_git() || pull.tag_pattern.is_some() {
+            continue;
+        }
+        let reference = PullOperation::resolve_reference(config, pull)?.name;
+
+        let patterns = pull_patterns(pull);
+        if let Some(group) = groups
+            .iter_mut()
+            .find(|group| group.url == *url && group.reference == reference)
+        {
+            for pattern in patterns {
+                if !group.patterns.contains(&pattern) {
+                    group.patterns.push(pattern);
+                }
+            }
+        } else {
+            groups.push(PrefetchGroup {
+                patterns,
+                reference,
+                repository,
+                url: url.clone(),
+            });
+        }
+    }
+    Ok(groups)
+}
+
+/// Sparse-checkout patterns a pull needs: its `sparsePatterns`, or else its
+/// source and any overlay `sources`.
+fn pull_patterns(pull: &PullConfig) -> Vec<String> {
+    if !pull.sparse_patterns.is_empty() {
+        return pull.sparse_patterns.clone();
+    }
+    let mut patterns = vec![pull.source.clone()];
+    patterns.extend(pull.sources.iter().cloned());
+    patterns
+}
@@ -0,0 +1,143 @@
+//! Machine-readable progress events (`--progress-json`).
+//!
+//! Wrappers and GUIs can follow a run through newline-delimited JSON written
+//! to stderr: one object per line, named by its `event` field and tied to a
+//! pull by its `target`.
+
+use core::fmt;
+use serde::Serialize;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+/// A lifecycle milestone of a pull.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ProgressEvent {
+    /// The source is available (checked out, extracted or found locally).
+    CheckoutDone {
+        /// Commit the reference resolved to (Git sources only).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sha: Option<String>,
+        /// Pull target.
+        target: String,
+    },
+    /// A Git repository was cloned for the pull.
+    CloneDone {
+        /// Reference that was cloned.
+        reference: String,
+        /// Repository URL.
+        repository: String,
+        /// Pull target.
+        target: String,
+    },
+    /// Files were copied into the target. Emitted once per pull, after the
+    /// copy, with the total; there are no per-file events.
+    FilesCopied {
+        /// Number of files copied.
+        files: usize,
+        /// Pull target.
+        target: String,
+    },
+    /// The pull finished successfully.
+    PullDone {
+        /// Number of post-copy commands executed.
+        commands: usize,
+        /// Number of files copied.
+        files: usize,
+        /// Number of replacements applied.
+        replacements: usize,
+        /// Pull target.
+        target: String,
+    },
+    /// A pull is about to start.
+    PullStart {
+        /// The pull's `description`, if it has one.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// Pull source.
+        source: String,
+        /// Pull target.
+        target: String,
+    },
+    /// Replacements, templates and grafts were applied to the target.
+    ReplacementsDone {
+        /// Number of replacements applied.
+        replacements: usize,
+        /// Pull target.
+        target: String,
+    },
+}
+
+/// Shared writer progress events are written to.
+type SharedWriter = Arc<Mutex<Box<dyn Write + Send>>>;
+
+/// Writes [`ProgressEvent`]s as NDJSON, or drops them when disabled.
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub struct ProgressSink {
+    /// Destination of the events; `None` when progress reporting is off.
+    writer: Option<SharedWriter>,
+}
+
+impl fmt::Debug for ProgressSink {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ProgressSink")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+impl ProgressSink {
+    /// Write `event` as one JSON line. Write failures are logged and ignored.
+    #[inline]
+    pub fn emit(&self, event: &ProgressEvent) {
+        let Some(writer) = self.writer.as_ref() else {
+            return;
+        };
+        let Ok(mut line) = serde_json::to_vec(event) else {
+            return;
+        };
+        line.push(b'\n');
+
+        let Ok(mut writer) = writer.lock() else {
+            return;
+        };
+        if let Err(err) = writer.write_all(&line).and_then(|()| writer.flush()) {
+            debug!("Failed to write progress event: {err}");
+        }
+    }
+
+    /// Whether events are written anywhere.
+    #[must_use]
+    #[inline]
+    pub const fn is_enabled(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// A sink that drops every event.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A sink writing to stderr.
+    #[must_use]
+    #[inline]
+    pub fn stderr() -> Self {
+        Self::to_writer(io::stderr())
+    }
+
+    /// A sink writing to `writer`.
+    #[must_use]
+    #[inline]
+    pub fn to_writer<W: Write + Send + 'static>(writer: W) -> Self {
+        Self {
+            writer: Some(Arc::new(Mutex::new(Box::new(writer)))),
+        }
+    }
+}
@@ -1,28 +1,94 @@
 //! Pull operation coordination.
 
-use crate::cli::{Args, PullArgs, PullConfig, ReplacementConfig};
-use crate::config::Config;
+use crate::cli::{
+    Args, OVERLAY_PULL_TYPE, PullArgs, PullConfig, PullReplacementScope, ReplacementConfig,
+    is_overlay_pull, parse_pull_context,
+};
 use crate::config::context::{ContextValues, ValidatedContext, merge_context_values};
-use crate::config::graft_yaml::GraftConfig;
-use crate::config::validation::validate_config_with_base_dir;
-use crate::error::GraftError;
-use crate::git::{Repository, SparseCheckout, check_git_availability};
-use crate::operations::discovery::{DiscoveredGraft, cleanup_graft_files, discover_graft_files};
-use crate::operations::post_commands::execute_post_commands;
+use crate::config::graft_yaml::{GraftConfig, GraftReplacement, PostCommand, ReplacementScope};
+use crate::config::source_pull::{SOURCE_PULL_FILE_NAME, SourcePullConfig};
+use crate::config::validation::{
+    validate_config_for_dry_run, validate_config_with_base_dir, validate_path_safety,
+};
+use crate::config::{Config, RefType};
+use crate::error::{GraftError, PullOutcome};
+use crate::git::{
+    ArchiveCheckout, ArchiveFormat, CloneOptions, GitInvocation, GitRunner, GitVerbosity,
+    RealGitRunner, Repository, SparseCheckout, check_git_availability, ensure_offline_access,
+    resolve_tag_pattern,
+};
+use crate::operations::archive_target::{ARCHIVE_PULL_TYPE, is_archive_pull, write_archive_target};
+use crate::operations::confirm::{
+    Prompter, TerminalPrompter, confirm_destructive_actions, destructive_actions,
+};
+use crate::operations::discovery::{
+    DiscoveredGraft, cleanup_graft_files_named, discover_graft_files_named,
+};
+use crate::operations::post_commands::{
+    CommandVariables, DEFAULT_OUTPUT_LIMIT, PostCommandOptions, execute_post_commands_with_options,
+};
+use crate::operations::prefetch::{PrefetchedCheckout, PrefetchedSources, prefetch_sources};
+use crate::operations::progress::{ProgressEvent, ProgressSink};
+use crate::operations::replacement_audit::ReplacementAudit;
+use crate::operations::stdout::{is_stdout_target, write_file_to_stdout};
+use crate::operations::structured::ReplacementKind;
+use crate::operations::template::render_templates;
+use crate::operations::warnings::WarningSink;
+use crate::operations::watch::{WATCH_DEBOUNCE, WatchedPull, run_watch_loop, start_watcher};
 use crate::operations::{
-    apply_graft_replacements, apply_replacements, copy_files, execute_commands,
+    CopyOptions, PartialCopy, ReplacementCount, ResolvedReplacement, apply_filename_replacements,
+    apply_graft_replacements, apply_replacements_counted, calculate_copy_size,
+    check_no_empty_graft_replacements, check_no_empty_replacements,
+    check_no_identity_graft_replacements, check_no_identity_replacements, copy_files_with_options,
+    count_files_to_copy, execute_commands, file_target_path, resolve_graft_replacement,
+    resolve_json_path_replacements, resolve_replacement, verify_copy,
+    warn_identity_graft_replacements, warn_identity_replacements,
 };
+use crate::utils::fs::{format_file_size, validate_temp_root};
+use crate::utils::path::{escapes_from_base, normalize};
+use crate::utils::process::child_output;
 use anyhow::{Context as _, Result};
+use core::slice;
 use os_shim::System;
+use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::io;
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
-use tracing::{debug, info, warn};
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
 
 /// Max nesting depth for children configs.
 const MAX_CHILDREN_DEPTH: usize = 11;
 
+/// Reference used when neither the pull nor its config sets a `tag`.
+const DEFAULT_REFERENCE: &str = "main";
+
+/// ` — <description>` for a pull with a `description`, to append to the
+/// lines that name it; empty otherwise.
+#[must_use]
+#[inline]
+pub fn description_suffix(pull: &PullConfig) -> String {
+    pull.description
+        .as_deref()
+        .map_or_else(String::new, |description| {
+            format!(" \u{2014} {description}")
+        })
+}
+
+/// The effective Git reference of a pull.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResolvedRef {
+    /// Reference name (branch, tag, or commit), with any channel resolved.
+    pub name: String,
+    /// Kind of reference, when the config states it (`repositoryDefaults`).
+    pub ref_type: Option<RefType>,
+    /// Commit the reference resolved to, once known (Git pulls only).
+    pub sha: Option<String>,
+}
+
 /// Coordinates the complete pull operation.
 #[non_exhaustive]
 #[expect(
@@ -36,11 +102,21 @@ pub struct PullOperation<'src> {
     config_path: String,
     /// Whether to only preview operations without executing them.
     dry_run: bool,
+    /// Run-level flags from the command line.
+    options: RunOptions,
+    /// Asks before destructive actions (`--confirm`), if set.
+    prompter: Option<&'src dyn Prompter>,
     /// The system abstraction for filesystem operations.
     system: &'src dyn System,
 }
 
 impl<'src> PullOperation<'src> {
+    /// Start building a pull operation in code instead of from CLI arguments.
+    #[inline]
+    pub fn builder() -> PullOperationBuilder<'src> {
+        PullOperationBuilder::default()
+    }
+
     /// Execute the pull operation, including recursive child execution.
     ///
     /// # Errors
@@ -50,6 +126,7 @@ impl<'src> PullOperation<'src> {
     /// - A child config fails to load, validate, or execute.
     /// - A circular dependency is detected among child configs.
     /// - Max nesting depth is exceeded.
+    /// - `--watch` finds no local pull to watch, or cannot watch a source.
     #[inline]
     pub fn execute(&self) -> Result<()> {
         if self.dry_run {
@@ -59,14 +136,135 @@ impl<'src> PullOperation<'src> {
         let config_dir = Path::new(&self.config_path)
             .parent()
             .unwrap_or_else(|| Path::new("."));
+        if let Some(prompter) = self.prompter {
+            let resolved = resolve_pull_targets(&self.config, config_dir, &self.options)?;
+            confirm_destructive_actions(prompter, &destructive_actions(&resolved))?;
+        }
+        if self.options.require_clean {
+            let mut targets = Vec::new();
+            collect_pull_targets(
+                self.system,
+                &self.config,
+                config_dir,
+                &mut HashSet::new(),
+                0,
+                &self.options,
+                &mut targets,
+            )?;
+            check_working_tree_clean(self.system, &RealGitRunner::new(), &targets)?;
+        }
+
+        // `--prefetch` clones every Git source before anything is copied
+        let options = if self.options.prefetch {
+            let prefetched = prefetch_sources(
+                self.system,
+                &self.config,
+                &RealGitRunner::new(),
+                &build_clone_options(&self.config, &self.options),
+                self.options.temp_root.as_deref(),
+            )?;
+            RunOptions {
+                prefetched: Some(Arc::new(prefetched)),
+                ..self.options.clone()
+            }
+        } else {
+            self.options.clone()
+        };
+
         let mut visited = HashSet::new();
+        let warnings = WarningSink::new();
+
+        let result = execute_config_recursive(
+            self.system,
+            &self.config,
+            config_dir,
+            &mut visited,
+            0,
+            &warnings,
+            &options,
+        );
+
+        let result = result.and_then(|_targets| audit_unused_replacements(&options, &warnings));
+
+        if let Some(summary) = warnings.summary() {
+            warn!("\n{summary}");
+        }
+
+        result?;
+        if self.options.watch {
+            return watch_local_pulls(
+                self.system,
+                &self.config,
+                config_dir,
+                &warnings,
+                &self.options,
+            );
+        }
+        Ok(())
+    }
+
+    /// Resolve the effective Git reference for `pull` within `config`.
+    ///
+    /// The pull's own `tag` wins over the `repositoryDefaults` entry of the
+    /// pull's repository, then the config's `tag`, then `main`. When the
+    /// config defines `channels`, a configured tag is a channel name and
+    /// resolves to the reference it maps to. The SHA is left unset; it is
+    /// filled in once a Git checkout has happened.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config defines `channels` and the tag names
+    /// none of them.
+    #[inline]
+    pub fn resolve_reference(config: &Config, pull: &PullConfig) -> Result<ResolvedRef> {
+        let repo_defaults = pull
+            .repository
+            .as_ref()
+            .or(config.repository.as_ref())
+            .and_then(|repository| config.repository_defaults.get(repository));
+        let (tag, ref_type) = match (pull.tag.as_ref(), repo_defaults) {
+            (Some(tag), _) => (tag, None),
+            (None, Some(defaults)) => (&defaults.tag, defaults.ref_type),
+            (None, None) => {
+                let Some(tag) = config.tag.as_ref() else {
+                    return Ok(ResolvedRef {
+                        name: DEFAULT_REFERENCE.to_owned(),
+                        ref_type: None,
+                        sha: None,
+                    });
+                };
+                (tag, None)
+            }
+        };
+        if config.channels.is_empty() {
+            return Ok(ResolvedRef {
+                name: tag.clone(),
+                ref_type,
+                sha: None,
+            });
+        }
 
-        execute_config_recursive(self.system, &self.config, config_dir, &mut visited, 0)
+        let Some(name) = config.channels.get(tag) else {
+            let mut available: Vec<&str> = config.channels.keys().map(String::as_str).collect();
+            available.sort_unstable();
+            return Err(GraftError::configuration(format!(
+                "Unknown channel '{tag}'; available channels: {}",
+                available.join(", ")
+            ))
+            .into());
+        };
+        debug!("Channel '{tag}' resolves to '{name}'");
+        Ok(ResolvedRef {
+            name: name.clone(),
+            ref_type,
+            sha: None,
+        })
     }
 
-    /// Quick check if a URL is a local filesystem path.
+    /// Quick check if a URL is a local filesystem path or an archive (no Git needed).
     fn is_local_url(url: &str) -> bool {
-        url.starts_with("file://")
+        ArchiveFormat::detect(url).is_some()
+            || url.starts_with("file://")
             || url.starts_with('~')
             || url.starts_with("./")
             || url.starts_with("../")
@@ -89,8 +287,16 @@ impl<'src> PullOperation<'src> {
     )]
     pub fn new(args: Args, system: &'src dyn System) -> Result<Self> {
         // Load configuration
-        let mut config = if Path::new(&args.config).exists() {
-            Config::load_from_file(system, &args.config)?
+        let mut config = if let Some(config_url) = args.config_url.as_deref() {
+            Config::load_from_url(system, &RealGitRunner::new(), config_url)?
+        } else if let Some(template) = args.init_from.as_deref() {
+            Config::load_template(system, &RealGitRunner::new(), template)?
+        } else if Path::new(&args.config).exists() {
+            if args.dry_run {
+                Config::load_from_file_for_dry_run(system, &args.config)?
+            } else {
+                Config::load_from_file(system, &args.config)?
+            }
         } else if !args.config.ends_with("tixgraft.yaml") || !args.pulls.sources.is_empty() {
             // If non-default config file specified but doesn't exist, or CLI args provided, that's an error
             if !args.config.ends_with("tixgraft.yaml") {
@@ -109,6 +315,13 @@ impl<'src> PullOperation<'src> {
                 pulls: Vec::new(),
                 children: Vec::new(),
                 process_children_first: false,
+                graft_file_name: args.graft_file_name.clone(),
+                post_pull: Vec::new(),
+                clone_filter: args.clone_filter.clone(),
+                channels: HashMap::new(),
+                repository_defaults: HashMap::new(),
+                replacement_groups: HashMap::new(),
+                version: None,
             }
         } else {
             return Err(GraftError::configuration(
@@ -122,21 +335,54 @@ impl<'src> PullOperation<'src> {
         // Validate merged configuration.
         // Use the config file's parent directory as base_dir so that children
         // paths are resolved relative to the config file, not the process CWD.
+        // A dry run reports unset valueFromEnv variables with its other problems.
         let base_dir = Path::new(&args.config).parent();
-        validate_config_with_base_dir(system, &config, base_dir)?;
+        if args.dry_run {
+            validate_config_for_dry_run(system, &config, base_dir)?;
+        } else {
+            validate_config_with_base_dir(system, &config, base_dir)?;
+        }
+
+        if args.offline {
+            Self::check_offline(system, &config)?;
+        }
 
-        // Check if any pull operations require Git (i.e., not all are local)
-        let needs_git = Self::requires_git(&config);
+        // Check if any pull operations require Git (i.e., not all are local).
+        // A dry run only touches Git with --check-remote.
+        let needs_git = Self::requires_git(&config)
+            && !args.no_git_check
+            && (!args.dry_run || args.check_remote);
 
         if needs_git {
             // Only check Git availability if we have at least one Git-based pull
             check_git_availability().context("Git validation failed")?;
         }
 
+        // Pinned trust anchors must exist before anything is cloned
+        for (flag, path) in [
+            ("--ca-bundle", args.ca_bundle.as_deref()),
+            ("--known-hosts", args.known_hosts.as_deref()),
+        ] {
+            if let Some(path_str) = path
+                && !system.is_file(Path::new(path_str)).unwrap_or(false)
+            {
+                return Err(GraftError::configuration(format!(
+                    "{flag} file not found: {path_str}"
+                ))
+                .into());
+            }
+        }
+
+        if let Some(temp_dir) = args.temp_dir.as_deref() {
+            validate_temp_root(system, Path::new(temp_dir)).context("--temp-dir is unusable")?;
+        }
+
         Ok(PullOperation {
             config,
             config_path: args.config.clone(),
             dry_run: args.dry_run,
+            options: RunOptions::from_args(&args),
+            prompter: args.confirm.then_some::<&dyn Prompter>(&TerminalPrompter),
             system,
         })
     }
@@ -152,14 +398,45 @@ impl<'src> PullOperation<'src> {
             .unwrap_or_else(|| Path::new("."));
         let mut visited = HashSet::new();
 
-        preview_config_recursive(self.system, &self.config, config_dir, &mut visited, 0, "")?;
+        let issues = preview_config_recursive(
+            self.system,
+            &self.config,
+            config_dir,
+            &mut visited,
+            0,
+            "",
+            &self.options,
+        )?;
 
         info!("");
+        if !issues.is_empty() {
+            let listed: Vec<String> = issues.iter().map(|issue| format!("  - {issue}")).collect();
+            return Err(GraftError::configuration(format!(
+                "Dry run found {} problem(s) that would fail the pull:\n{}",
+                issues.len(),
+                listed.join("\n")
+            ))
+            .into());
+        }
         info!("Run without --dry-run to execute these operations.");
 
         Ok(())
     }
 
+    /// Fail if any of `config`'s pulls reads a source that needs network
+    /// access (`--offline`). Child configs are checked when they run.
+    fn check_offline(system: &dyn System, config: &Config) -> Result<()> {
+        for (index, pull) in config.pulls.iter().enumerate() {
+            let Some(repo_url) = pull.repository.as_ref().or(config.repository.as_ref()) else {
+                continue;
+            };
+            let repository = Repository::new(system, repo_url)?;
+            ensure_offline_access(&repository)
+                .with_context(|| format!("Pull #{} cannot run offline", index.saturating_add(1)))?;
+        }
+        Ok(())
+    }
+
     /// Check if the configuration requires Git (has at least one non-local repository).
     fn requires_git(config: &Config) -> bool {
         // Check global repository
@@ -188,6 +465,243 @@ impl<'src> PullOperation<'src> {
     }
 }
 
+/// Builds a [`PullOperation`] for library consumers, without going through
+/// CLI argument parsing.
+///
+/// Run-level flags keep their CLI defaults. Relative pull targets resolve
+/// against [`config_dir`](Self::config_dir), which defaults to the current
+/// directory. The configuration is validated by [`build`](Self::build).
+#[derive(Default)]
+#[non_exhaustive]
+#[must_use]
+#[expect(
+    clippy::module_name_repetitions,
+    reason = "named after the PullOperation it builds"
+)]
+pub struct PullOperationBuilder<'src> {
+    /// The configuration being assembled.
+    config: Config,
+    /// Directory standing in for the config file's directory.
+    config_dir: Option<PathBuf>,
+    /// Whether to only preview operations without executing them.
+    dry_run: bool,
+    /// Asks before destructive actions, if set.
+    prompter: Option<&'src dyn Prompter>,
+    /// The system abstraction for filesystem operations.
+    system: Option<&'src dyn System>,
+}
+
+impl<'src> PullOperationBuilder<'src> {
+    /// Add a pull to the configuration.
+    #[inline]
+    pub fn add_pull(mut self, pull: PullConfig) -> Self {
+        self.config.pulls.push(pull);
+        self
+    }
+
+    /// Validate the configuration and create the pull operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No system was set.
+    /// - The configuration is invalid.
+    /// - Git availability check fails.
+    #[inline]
+    pub fn build(self) -> Result<PullOperation<'src>> {
+        let system = self.system.ok_or_else(|| {
+            GraftError::configuration(
+                "PullOperation builder needs a system; call .system(...)".to_owned(),
+            )
+        })?;
+        let config_dir = self.config_dir.unwrap_or_else(|| PathBuf::from("."));
+        validate_config_with_base_dir(system, &self.config, Some(config_dir.as_path()))?;
+
+        if PullOperation::requires_git(&self.config) {
+            check_git_availability().context("Git validation failed")?;
+        }
+
+        Ok(PullOperation {
+            config: self.config,
+            config_path: config_dir
+                .join("tixgraft.yaml")
+                .to_string_lossy()
+                .to_string(),
+            dry_run: self.dry_run,
+            options: RunOptions {
+                max_command_output: DEFAULT_OUTPUT_LIMIT,
+                ..RunOptions::default()
+            },
+            prompter: self.prompter,
+            system,
+        })
+    }
+
+    /// Start from an already loaded configuration, e.g. one from
+    /// [`Config::load_template`]. Pulls added before are replaced.
+    #[inline]
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Directory relative pull targets resolve against (default: `.`).
+    #[inline]
+    pub fn config_dir(mut self, dir: &Path) -> Self {
+        self.config_dir = Some(dir.to_path_buf());
+        self
+    }
+
+    /// Only preview operations without executing them.
+    #[inline]
+    pub const fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Ask `prompter` before destructive actions (`reset: true` targets,
+    /// flagged commands), aborting unless it answers yes.
+    #[inline]
+    pub const fn prompter(mut self, prompter: &'src dyn Prompter) -> Self {
+        self.prompter = Some(prompter);
+        self
+    }
+
+    /// Global repository URL or account/repo format.
+    #[inline]
+    pub fn repository(mut self, repository: &str) -> Self {
+        self.config.repository = Some(repository.to_owned());
+        self
+    }
+
+    /// The system abstraction for filesystem operations (required).
+    #[inline]
+    pub const fn system(mut self, system: &'src dyn System) -> Self {
+        self.system = Some(system);
+        self
+    }
+
+    /// Global Git reference (branch, tag, or commit).
+    #[inline]
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.config.tag = Some(tag.to_owned());
+        self
+    }
+}
+
+/// Run-level flags that only come from the command line.
+#[derive(Debug, Clone, Default)]
+struct RunOptions {
+    /// CA bundle that HTTPS remotes are validated against.
+    ca_bundle: Option<PathBuf>,
+    /// Estimate Git source sizes during dry-run by listing the remote tree.
+    check_remote: bool,
+    /// Run post-commands in a clean environment (`--clean-env`).
+    clean_env: bool,
+    /// Print each replacement's resolved value during dry-run.
+    dump_resolved_replacements: bool,
+    /// Fetch all tags instead of a single-tag shallow clone.
+    fetch_tags: bool,
+    /// How git's clone and checkout output is handled.
+    git_verbosity: GitVerbosity,
+    /// Copy `.git` directories found in sources.
+    include_git: bool,
+    /// `known_hosts` file that SSH remotes are validated against.
+    known_hosts: Option<PathBuf>,
+    /// Bytes of each post-command's stdout and stderr kept.
+    max_command_output: usize,
+    /// Largest Git source subtree, in bytes, that is checked out.
+    max_clone_size: Option<u64>,
+    /// Fail when a replacement value resolves to an empty string.
+    no_empty_replacements: bool,
+    /// Keep running a config's remaining pulls after one fails
+    /// (`--no-fail-fast`).
+    no_fail_fast: bool,
+    /// Refuse sources that need network access (`--offline`).
+    offline: bool,
+    /// Directory relative targets are placed under (`--output-dir`).
+    output_dir: Option<OutputDir>,
+    /// Clone all Git sources before any pull runs (`--prefetch`).
+    prefetch: bool,
+    /// Checkouts made by `--prefetch`, shared by the pulls that use them.
+    prefetched: Option<Arc<PrefetchedSources>>,
+    /// List the files copied before a failed copy.
+    preserve_partial: bool,
+    /// Where progress events go (`--progress-json`).
+    progress: ProgressSink,
+    /// Tallies what each replacement changed over the run
+    /// (`--list-replacements-unused`).
+    replacement_audit: ReplacementAudit,
+    /// Log what each replacement changed, per pull.
+    replacements_report: bool,
+    /// Abort when the working tree has changes under a pull target.
+    require_clean: bool,
+    /// Fail when a replacement value resolves to its own source.
+    strict_replacements: bool,
+    /// Directory temporary clones and extractions are created under.
+    temp_root: Option<PathBuf>,
+    /// Merge source-provided `tixgraft.pull.yaml` fragments into pulls.
+    trust_source_config: bool,
+    /// Check each copied target against its source (`--verify-copy`).
+    verify_copy: bool,
+    /// Re-run local pulls whenever their source changes.
+    watch: bool,
+}
+
+impl RunOptions {
+    /// Collect run options from CLI arguments.
+    fn from_args(args: &Args) -> Self {
+        Self {
+            ca_bundle: args.ca_bundle.as_deref().map(PathBuf::from),
+            check_remote: args.check_remote,
+            clean_env: args.clean_env,
+            dump_resolved_replacements: args.dump_resolved_replacements,
+            fetch_tags: args.fetch_tags,
+            git_verbosity: if args.loud_git {
+                GitVerbosity::Loud
+            } else if args.quiet_git {
+                GitVerbosity::Quiet
+            } else {
+                GitVerbosity::Captured
+            },
+            include_git: args.include_git,
+            known_hosts: args.known_hosts.as_deref().map(PathBuf::from),
+            max_command_output: args.max_command_output,
+            max_clone_size: args.max_clone_size,
+            no_empty_replacements: args.no_empty_replacements,
+            no_fail_fast: args.no_fail_fast,
+            offline: args.offline,
+            output_dir: args.output_dir.as_deref().map(|base| OutputDir {
+                base: PathBuf::from(base),
+                config_root: Path::new(&args.config)
+                    .parent()
+                    .unwrap_or_else(|| Path::new("."))
+                    .to_path_buf(),
+            }),
+            prefetch: args.prefetch,
+            prefetched: None,
+            preserve_partial: args.preserve_partial,
+            progress: if args.progress_json {
+                ProgressSink::stderr()
+            } else {
+                ProgressSink::new()
+            },
+            replacement_audit: if args.list_replacements_unused {
+                ReplacementAudit::enabled()
+            } else {
+                ReplacementAudit::new()
+            },
+            replacements_report: args.replacements_report,
+            require_clean: args.require_clean,
+            strict_replacements: args.strict_replacements,
+            temp_root: args.temp_dir.as_deref().map(PathBuf::from),
+            trust_source_config: args.trust_source_config,
+            verify_copy: args.verify_copy,
+            watch: args.watch,
+        }
+    }
+}
+
 /// Result of processing .graft.yaml files.
 #[derive(Debug, Default)]
 struct GraftProcessingResult {
@@ -206,33 +720,88 @@ struct PullResult {
     files_copied: usize,
     /// Number of text replacements applied in copied files.
     replacements_applied: usize,
+    /// Commit the reference resolved to (Git pulls only).
+    sha: Option<String>,
+}
+
+/// Base directory that every relative target is placed under (`--output-dir`).
+#[derive(Debug, Clone)]
+struct OutputDir {
+    /// The output directory.
+    base: PathBuf,
+    /// Directory of the root config; child configs keep their location
+    /// relative to it below `base`.
+    config_root: PathBuf,
+}
+
+impl OutputDir {
+    /// Place `target`, relative to the config in `config_dir`, under the
+    /// output directory.
+    fn place(&self, config_dir: &Path, target: &Path) -> PathBuf {
+        let relative_dir = normalize(
+            config_dir
+                .strip_prefix(&self.config_root)
+                .unwrap_or(config_dir),
+        );
+        if relative_dir.is_absolute() || relative_dir.starts_with("..") {
+            return self.base.join(target);
+        }
+        self.base.join(relative_dir).join(target)
+    }
 }
 
 /// Resolve relative pull target paths against the config file's directory.
 ///
 /// Absolute targets are left unchanged.  Relative targets (e.g. `./foo` or
 /// `bar`) are joined onto `config_dir` so that the same config file produces
-/// the same result regardless of the process working directory.
-fn resolve_pull_targets(config: &Config, config_dir: &Path) -> Config {
+/// the same result regardless of the process working directory. With
+/// `--output-dir`, they are placed under the output directory instead, and
+/// absolute targets are rejected.
+fn resolve_pull_targets(
+    config: &Config,
+    config_dir: &Path,
+    options: &RunOptions,
+) -> Result<Config> {
     let mut resolved = config.clone();
     for pull in &mut resolved.pulls {
+        if is_stdout_target(&pull.target) {
+            continue;
+        }
         let target_path = Path::new(&pull.target);
-        if !target_path.is_absolute() {
+        if let Some(output_dir) = options.output_dir.as_ref() {
+            if target_path.is_absolute() {
+                return Err(GraftError::configuration(format!(
+                    "Target '{}' is absolute and cannot be placed under --output-dir {}",
+                    pull.target,
+                    output_dir.base.display()
+                ))
+                .into());
+            }
+            pull.target = output_dir
+                .place(config_dir, target_path)
+                .to_string_lossy()
+                .to_string();
+        } else if !target_path.is_absolute() {
             let resolved_target = config_dir.join(target_path);
             pull.target = resolved_target.to_string_lossy().to_string();
         }
     }
-    resolved
+    Ok(resolved)
 }
 
 /// Execute a config recursively, processing pulls and children.
+///
+/// Returns the targets of the config's pulls followed by those of its
+/// children, recursively.
 fn execute_config_recursive(
     system: &dyn System,
     config: &Config,
     config_dir: &Path,
     visited: &mut HashSet<PathBuf>,
     depth: usize,
-) -> Result<()> {
+    warnings: &WarningSink,
+    options: &RunOptions,
+) -> Result<Vec<String>> {
     if depth > MAX_CHILDREN_DEPTH {
         return Err(GraftError::configuration(format!(
             "Max children depth ({MAX_CHILDREN_DEPTH}) exceeded"
@@ -253,15 +822,50 @@ fn execute_config_recursive(
     // Resolve relative pull targets against the config file's directory so
     // that the same config file produces the same result regardless of the
     // process working directory.
-    let resolved_config = resolve_pull_targets(config, config_dir);
+    let resolved_config = resolve_pull_targets(config, config_dir, options)?;
 
-    if resolved_config.process_children_first {
-        execute_children(system, &resolved_config, config_dir, visited, depth)?;
-        execute_pulls(system, &resolved_config)?;
+    // A partial failure (`--no-fail-fast`) is reported only after the
+    // children and postPull commands have run
+    let (failed_outcomes, child_targets) = if resolved_config.process_children_first {
+        let child_targets = execute_children(
+            system,
+            &resolved_config,
+            config_dir,
+            visited,
+            depth,
+            warnings,
+            options,
+        )?;
+        let failed_outcomes = execute_pulls(system, &resolved_config, warnings, options)?;
+        (failed_outcomes, child_targets)
     } else {
-        execute_pulls(system, &resolved_config)?;
-        execute_children(system, &resolved_config, config_dir, visited, depth)?;
-    }
+        let failed_outcomes = execute_pulls(system, &resolved_config, warnings, options)?;
+        let child_targets = execute_children(
+            system,
+            &resolved_config,
+            config_dir,
+            visited,
+            depth,
+            warnings,
+            options,
+        )?;
+        (failed_outcomes, child_targets)
+    };
+
+    let mut targets: Vec<String> = resolved_config
+        .pulls
+        .iter()
+        .map(|pull| pull.target.clone())
+        .collect();
+    targets.extend(child_targets);
+    execute_post_pull(
+        system,
+        &resolved_config,
+        &targets,
+        config_dir,
+        warnings,
+        options,
+    )?;
 
     // Remove from visited after processing to allow diamond-pattern
     // re-execution (same child referenced from multiple parents).
@@ -270,17 +874,31 @@ fn execute_config_recursive(
     // where both B and C reference D.
     visited.remove(&canonical);
 
-    Ok(())
+    if let Some(outcomes) = failed_outcomes {
+        return Err(GraftError::partial_failure(outcomes).into());
+    }
+    Ok(targets)
 }
 
 /// Execute all pull operations for a config.
+///
+/// A failing pull stops the run unless it has `mustSucceed: false`. With
+/// `--no-fail-fast`, the remaining pulls still run; if some of them
+/// succeed, the outcomes are returned so the caller can report them as
+/// [`GraftError::PartialFailure`], and if none does, the first failure is
+/// returned as-is.
 #[expect(
     clippy::arithmetic_side_effects,
     reason = "Simple counter increments on usize totals that cannot realistically overflow"
 )]
-fn execute_pulls(system: &dyn System, config: &Config) -> Result<()> {
+fn execute_pulls(
+    system: &dyn System,
+    config: &Config,
+    warnings: &WarningSink,
+    options: &RunOptions,
+) -> Result<Option<Vec<PullOutcome>>> {
     if config.pulls.is_empty() {
-        return Ok(());
+        return Ok(None);
     }
 
     info!("Starting tixgraft pull operation...");
@@ -288,10 +906,16 @@ fn execute_pulls(system: &dyn System, config: &Config) -> Result<()> {
     let mut total_files = 0_usize;
     let mut total_replacements = 0_usize;
     let mut total_commands = 0_usize;
+    let mut outcomes: Vec<PullOutcome> = Vec::new();
+    let mut first_failure: Option<anyhow::Error> = None;
 
     for (index, pull) in config.pulls.iter().enumerate() {
         let display_index = index.saturating_add(1);
-        info!("\n=> Pull operation #{}", display_index);
+        info!(
+            "\n=> Pull operation #{}{}",
+            display_index,
+            description_suffix(pull)
+        );
 
         // Determine repository and reference
         let repo_url = pull
@@ -305,49 +929,290 @@ fn execute_pulls(system: &dyn System, config: &Config) -> Result<()> {
             })?;
         debug!("Repository URL: {}", repo_url);
 
-        let default_tag = "main".to_owned();
-        let reference = pull
-            .tag
-            .as_ref()
-            .or(config.tag.as_ref())
-            .unwrap_or(&default_tag)
-            .clone();
+        let reference = resolve_tag_pattern_reference(
+            system,
+            config,
+            pull,
+            repo_url,
+            PullOperation::resolve_reference(config, pull)?,
+            options,
+        )?;
 
         debug!("Pull config: {:?}", pull);
-        match execute_single_pull(system, config, pull, repo_url, &reference) {
+        emit_pull_start(options, pull);
+        match execute_single_pull(
+            system,
+            config,
+            pull,
+            repo_url,
+            &reference.name,
+            warnings,
+            options,
+        ) {
             Ok(result) => {
                 debug!("execute_single_pull Result: {:?}", result);
+                emit_pull_done(options, pull, &result);
+                if let Some(sha) = result.sha.as_ref() {
+                    debug!("Reference '{}' resolved to {sha}", reference.name);
+                }
 
                 total_files += result.files_copied;
                 total_replacements += result.replacements_applied;
                 total_commands += result.commands_executed;
 
                 info!(
-                    "  \u{2713} {} \u{2192} {} ({}, {} files)",
-                    pull.source, pull.target, pull.pull_type, result.files_copied
+                    "  \u{2713} {} \u{2192} {} ({}, {} files){}",
+                    pull.source,
+                    pull.target,
+                    pull.pull_type,
+                    result.files_copied,
+                    description_suffix(pull)
                 );
+                outcomes.push(PullOutcome::succeeded(
+                    display_index,
+                    pull.source.as_str(),
+                    pull.target.as_str(),
+                ));
             }
             Err(err) => {
+                if pull.must_succeed && options.no_fail_fast {
+                    error!("  \u{2717} Pull #{display_index} failed: {err:#}");
+                    outcomes.push(PullOutcome::failed(
+                        display_index,
+                        pull.source.as_str(),
+                        pull.target.as_str(),
+                        format!("{err:#}").as_str(),
+                    ));
+                    first_failure.get_or_insert(err);
+                    continue;
+                }
                 if pull.must_succeed {
                     return Err(err);
                 }
-                warn!(
-                    "  \u{26a0} Pull #{} failed (mustSucceed: false, continuing): {}",
-                    display_index, err
+                warnings.push(
+                    Some(Path::new(&pull.target)),
+                    &format!(
+                        "\u{26a0} Pull #{display_index} failed (mustSucceed: false, continuing): {err:#}"
+                    ),
                 );
             }
         }
     }
 
-    info!("\n\u{2713} Completed pull operations successfully");
+    // With `--no-fail-fast`, failures are partial only if some pull succeeded
+    let partial = match first_failure {
+        Some(err) if outcomes.iter().all(PullOutcome::is_failure) => return Err(err),
+        Some(_) => true,
+        None => false,
+    };
+    if partial {
+        info!("\n\u{2717} Completed pull operations with failures");
+    } else {
+        info!("\n\u{2713} Completed pull operations successfully");
+    }
     info!("  Files copied: {}", total_files);
     info!("  Text replacements: {}", total_replacements);
     info!("  Commands executed: {}", total_commands);
 
+    Ok(partial.then_some(outcomes))
+}
+
+/// The highest remote tag matching the pull's `tagPattern`, or `reference`
+/// unchanged when the pull has none.
+fn resolve_tag_pattern_reference(
+    system: &dyn System,
+    config: &Config,
+    pull: &PullConfig,
+    repo_url: &str,
+    reference: ResolvedRef,
+    options: &RunOptions,
+) -> Result<ResolvedRef> {
+    let Some(pattern) = pull.tag_pattern.as_deref() else {
+        return Ok(reference);
+    };
+    let repository = Repository::new(system, repo_url).context("Failed to create repository")?;
+    if options.offline {
+        ensure_offline_access(&repository)?;
+    }
+    if !repository.is_git() {
+        return Err(GraftError::configuration(format!(
+            "tagPattern '{pattern}' needs a Git repository, but '{repo_url}' is not one"
+        ))
+        .into());
+    }
+
+    let tag = resolve_tag_pattern(
+        &RealGitRunner::new(),
+        repository.git_url()?,
+        pattern,
+        build_clone_options(config, options).git_env(),
+    )?;
+    info!("  tagPattern '{pattern}' resolved to {tag}");
+    Ok(ResolvedRef {
+        name: tag,
+        ref_type: Some(RefType::Tag),
+        sha: None,
+    })
+}
+
+/// Watch the local sources of `config`'s pulls and re-run a pull whenever
+/// its source changes (`--watch`). Runs until interrupted.
+///
+/// Pulls from Git repositories and archives, and child configs, are not
+/// watched: they ran once already.
+fn watch_local_pulls(
+    system: &dyn System,
+    config: &Config,
+    config_dir: &Path,
+    warnings: &WarningSink,
+    options: &RunOptions,
+) -> Result<()> {
+    let resolved_config = resolve_pull_targets(config, config_dir, options)?;
+
+    let mut watched = Vec::new();
+    for (index, pull) in resolved_config.pulls.iter().enumerate() {
+        let Some(repo_url) = pull
+            .repository
+            .as_ref()
+            .or(resolved_config.repository.as_ref())
+        else {
+            continue;
+        };
+        let repository = Repository::new(system, repo_url)?;
+        if repository.is_git() || repository.archive().is_some() {
+            continue;
+        }
+        let source = local_source_path(system, &repository, pull, repo_url)?;
+        let source = system.canonicalize(&source).unwrap_or(source);
+        watched.push(WatchedPull::new(index, source));
+    }
+
+    if watched.is_empty() {
+        return Err(GraftError::configuration(
+            "--watch requires at least one pull from a local (file:) source".to_owned(),
+        )
+        .into());
+    }
+
+    let (_watcher, events) = start_watcher(&watched)?;
+    info!(
+        "\nWatching {} local source(s) for changes (Ctrl-C to stop)",
+        watched.len()
+    );
+    run_watch_loop(&events, &watched, WATCH_DEBOUNCE, |index| {
+        rerun_pull(system, &resolved_config, index, warnings, options)
+    });
+    Ok(())
+}
+
+/// Report that `pull` is starting (`--progress-json`).
+fn emit_pull_start(options: &RunOptions, pull: &PullConfig) {
+    options.progress.emit(&ProgressEvent::PullStart {
+        description: pull.description.clone(),
+        source: pull.source.clone(),
+        target: pull.target.clone(),
+    });
+}
+
+/// Report that `pull` finished with `result` (`--progress-json`).
+fn emit_pull_done(options: &RunOptions, pull: &PullConfig, result: &PullResult) {
+    options.progress.emit(&ProgressEvent::PullDone {
+        commands: result.commands_executed,
+        files: result.files_copied,
+        replacements: result.replacements_applied,
+        target: pull.target.clone(),
+    });
+}
+
+/// Execute the pull at `index` of `config` again after its source changed.
+fn rerun_pull(
+    system: &dyn System,
+    config: &Config,
+    index: usize,
+    warnings: &WarningSink,
+    options: &RunOptions,
+) -> Result<()> {
+    let pull = config
+        .pulls
+        .get(index)
+        .ok_or_else(|| GraftError::configuration(format!("No pull at index {index} to re-run")))?;
+    let repo_url = pull
+        .repository
+        .as_ref()
+        .or(config.repository.as_ref())
+        .ok_or_else(|| GraftError::configuration("No repository specified".to_owned()))?;
+    let reference = PullOperation::resolve_reference(config, pull)?;
+
+    emit_pull_start(options, pull);
+    let result = execute_single_pull(
+        system,
+        config,
+        pull,
+        repo_url,
+        &reference.name,
+        warnings,
+        options,
+    )?;
+    emit_pull_done(options, pull, &result);
+    info!(
+        "\u{21bb} {} \u{2192} {} ({} files)",
+        pull.source, pull.target, result.files_copied
+    );
+    Ok(())
+}
+
+/// Run the config-level `postPull` commands once all pulls and children are done.
+///
+/// Commands run in `config_dir` with `TIXGRAFT_TARGETS` listing `targets`
+/// (the pull targets of this config and of its children), one per line, and
+/// `${VAR}` references expanded from the config's context and the
+/// environment. Failures are collected as warnings.
+fn execute_post_pull(
+    system: &dyn System,
+    config: &Config,
+    targets: &[String],
+    config_dir: &Path,
+    warnings: &WarningSink,
+    options: &RunOptions,
+) -> Result<()> {
+    if config.post_pull.is_empty() {
+        return Ok(());
+    }
+
+    let envs = [("TIXGRAFT_TARGETS".to_owned(), targets.join("\n"))];
+
+    info!(
+        "\n=> Running {} postPull command(s)",
+        config.post_pull.len()
+    );
+
+    let command_options = PostCommandOptions::new(system)
+        .with_clean_env(options.clean_env)
+        .with_envs(&envs)
+        .with_output_limit(options.max_command_output)
+        .with_variables(CommandVariables::new(system, &config.context));
+    let results =
+        execute_post_commands_with_options(&config.post_pull, config_dir, &command_options)
+            .context("Failed to execute postPull commands")?;
+
+    for result in results {
+        if !result.success {
+            warnings.push(
+                Some(config_dir),
+                &format!(
+                    "postPull command failed: {}",
+                    result.error.unwrap_or_else(|| "Unknown error".to_owned())
+                ),
+            );
+        }
+    }
+
     Ok(())
 }
 
 /// Execute all children for a config.
+///
+/// Returns the pull targets of every child, recursively.
 #[expect(
     clippy::arithmetic_side_effects,
     reason = "Depth increment cannot overflow for practical recursion depths"
@@ -358,7 +1223,10 @@ fn execute_children(
     config_dir: &Path,
     visited: &mut HashSet<PathBuf>,
     depth: usize,
-) -> Result<()> {
+    warnings: &WarningSink,
+    options: &RunOptions,
+) -> Result<Vec<String>> {
+    let mut targets = Vec::new();
     for child_path_str in &config.children {
         let child_config_path = config_dir.join(child_path_str);
         let child_dir = child_config_path.parent().unwrap_or_else(|| Path::new("."));
@@ -368,16 +1236,44 @@ fn execute_children(
         let child_config_path_str = child_config_path.to_string_lossy();
         // load_from_file validates the config with children paths resolved
         // relative to the config file's directory (not CWD).
-        let child_config = Config::load_from_file(system, &child_config_path_str)
+        let mut child_config = Config::load_from_file(system, &child_config_path_str)
             .with_context(|| format!("Error in child '{child_path_str}': failed to load config"))?;
 
+        // Children inherit the parent's graft file name, clone filter,
+        // channels and repository defaults unless they set their own
+        if child_config.graft_file_name.is_none() {
+            child_config
+                .graft_file_name
+                .clone_from(&config.graft_file_name);
+        }
+        if child_config.clone_filter.is_none() {
+            child_config.clone_filter.clone_from(&config.clone_filter);
+        }
+        if child_config.channels.is_empty() {
+            child_config.channels.clone_from(&config.channels);
+        }
+        if child_config.repository_defaults.is_empty() {
+            child_config
+                .repository_defaults
+                .clone_from(&config.repository_defaults);
+        }
+
         // Target resolution is handled inside execute_config_recursive via
         // resolve_pull_targets, so we pass the raw child config here.
-        execute_config_recursive(system, &child_config, child_dir, visited, depth + 1)
-            .with_context(|| format!("Error in child '{child_path_str}'"))?;
+        let child_targets = execute_config_recursive(
+            system,
+            &child_config,
+            child_dir,
+            visited,
+            depth + 1,
+            warnings,
+            options,
+        )
+        .with_context(|| format!("Error in child '{child_path_str}'"))?;
+        targets.extend(child_targets);
     }
 
-    Ok(())
+    Ok(targets)
 }
 
 /// Preview a config recursively, showing pulls and children with hierarchy.
@@ -388,7 +1284,8 @@ fn preview_config_recursive(
     visited: &mut HashSet<PathBuf>,
     depth: usize,
     indent: &str,
-) -> Result<()> {
+    options: &RunOptions,
+) -> Result<Vec<String>> {
     if depth > MAX_CHILDREN_DEPTH {
         return Err(GraftError::configuration(format!(
             "Max children depth ({MAX_CHILDREN_DEPTH}) exceeded"
@@ -407,24 +1304,54 @@ fn preview_config_recursive(
     }
 
     // Resolve relative pull targets against the config file's directory.
-    let resolved_config = resolve_pull_targets(config, config_dir);
+    let resolved_config = resolve_pull_targets(config, config_dir, options)?;
 
+    let mut issues = Vec::new();
     if resolved_config.process_children_first {
-        preview_children(system, &resolved_config, config_dir, visited, depth, indent)?;
-        preview_pulls(&resolved_config, indent)?;
+        issues.extend(preview_children(
+            system,
+            &resolved_config,
+            config_dir,
+            visited,
+            depth,
+            indent,
+            options,
+        )?);
+        issues.extend(preview_pulls(system, &resolved_config, indent, options)?);
     } else {
-        preview_pulls(&resolved_config, indent)?;
-        preview_children(system, &resolved_config, config_dir, visited, depth, indent)?;
-    }
+        issues.extend(preview_pulls(system, &resolved_config, indent, options)?);
+        issues.extend(preview_children(
+            system,
+            &resolved_config,
+            config_dir,
+            visited,
+            depth,
+            indent,
+            options,
+        )?);
+    }
+
+    preview_post_pull(&resolved_config, indent);
 
     // Remove from visited after processing to allow diamond-pattern
     visited.remove(&canonical);
 
-    Ok(())
+    Ok(issues)
 }
 
 /// Preview all pull operations for a config at a given indentation level.
-fn preview_pulls(config: &Config, indent: &str) -> Result<()> {
+///
+/// Returns the problems that would make the pulls fail (see
+/// [`collect_plan_issues`]), including replacement values that
+/// `--dump-resolved-replacements` could not resolve.
+fn preview_pulls(
+    system: &dyn System,
+    config: &Config,
+    indent: &str,
+    options: &RunOptions,
+) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+
     for (index, pull) in config.pulls.iter().enumerate() {
         let display_index = index.saturating_add(1);
         let repo_url = pull
@@ -437,19 +1364,48 @@ fn preview_pulls(config: &Config, indent: &str) -> Result<()> {
                 ))
             })?;
 
-        let default_tag = "main".to_owned();
-        let reference = pull
-            .tag
-            .as_ref()
-            .or(config.tag.as_ref())
-            .unwrap_or(&default_tag);
-
         info!(
-            "{indent}  [{}] Pull {} \u{2192} {} ({})",
-            display_index, pull.source, pull.target, pull.pull_type
+            "{indent}  [{}] Pull {} \u{2192} {} ({}){}",
+            display_index,
+            pull.source,
+            pull.target,
+            pull.pull_type,
+            description_suffix(pull)
         );
         info!("{indent}      - Repository: {}", repo_url);
-        info!("{indent}      - Reference: {}", reference);
+        if is_overlay_pull(pull) {
+            info!(
+                "{indent}      - Overlays: {} (later sources override earlier files)",
+                pull.sources.join(", ")
+            );
+        }
+        let reference = match PullOperation::resolve_reference(config, pull) {
+            Ok(reference) => reference,
+            Err(err) => {
+                let issue = format!("Pull #{display_index}: {err:#}");
+                info!("{indent}      - Would fail: {issue}");
+                issues.push(issue);
+                continue;
+            }
+        };
+        info!("{indent}      - Reference: {}", reference.name);
+        // The dump reports unresolvable replacement values itself
+        let check_replacement_env = !options.dump_resolved_replacements;
+        for issue in
+            collect_plan_issues(system, pull, repo_url, display_index, check_replacement_env)
+        {
+            info!("{indent}      - Would fail: {issue}");
+            issues.push(issue);
+        }
+        preview_source_size(
+            system,
+            config,
+            pull,
+            repo_url,
+            &reference.name,
+            indent,
+            options,
+        );
 
         if pull.reset {
             info!("{indent}      - Would reset target directory (reset: true)");
@@ -471,6 +1427,16 @@ fn preview_pulls(config: &Config, indent: &str) -> Result<()> {
                 pull.replacements.len()
             );
         }
+        if options.dump_resolved_replacements {
+            issues.extend(dump_resolved_replacements(
+                system,
+                config,
+                pull,
+                repo_url,
+                display_index,
+                indent,
+            ));
+        }
 
         if !pull.commands.is_empty() {
             info!(
@@ -483,7 +1449,272 @@ fn preview_pulls(config: &Config, indent: &str) -> Result<()> {
         }
     }
 
-    Ok(())
+    Ok(issues)
+}
+
+/// Find problems that would make a pull fail, without fetching anything:
+/// an unusable repository URL, an unsafe source path, a source missing from
+/// a local repository, or (with `check_replacement_env`) a replacement whose
+/// environment variable is unset.
+fn collect_plan_issues(
+    system: &dyn System,
+    pull: &PullConfig,
+    repo_url: &str,
+    display_index: usize,
+    check_replacement_env: bool,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    if let Err(err) = validate_path_safety(&pull.source) {
+        issues.push(format!("Pull #{display_index}: source: {err}"));
+    }
+    match Repository::new(system, repo_url) {
+        Ok(repository) => {
+            if let Some(base_path) = repository.local_path() {
+                for source in slice::from_ref(&pull.source).iter().chain(&pull.sources) {
+                    if !system.exists(&base_path.join(source)).unwrap_or(false) {
+                        issues.push(format!(
+                            "Pull #{display_index}: source '{source}' not found in {repo_url}"
+                        ));
+                    }
+                }
+            }
+        }
+        Err(err) => issues.push(format!(
+            "Pull #{display_index}: repository '{repo_url}': {err:#}"
+        )),
+    }
+    if !check_replacement_env {
+        return issues;
+    }
+    for replacement in &pull.replacements {
+        if let Some(env_var) = replacement.value_from_env.as_deref()
+            && system.env_var(env_var).is_err()
+        {
+            issues.push(format!(
+                "Pull #{display_index}: replacement '{}': environment variable '{env_var}' is not set",
+                replacement.source
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Print the estimated file count and size of a pull's source.
+///
+/// Local sources are walked directly; Git sources are only estimated with
+/// `--check-remote`. Estimation problems are reported, never fatal.
+fn preview_source_size(
+    system: &dyn System,
+    config: &Config,
+    pull: &PullConfig,
+    repo_url: &str,
+    reference: &str,
+    indent: &str,
+    options: &RunOptions,
+) {
+    let Ok(repository) = Repository::new(system, repo_url) else {
+        return;
+    };
+
+    let estimate = if let Some(base_path) = repository.local_path() {
+        let source_path = base_path.join(&pull.source);
+        let pull_type = match pull.pull_type.as_str() {
+            "auto" | ARCHIVE_PULL_TYPE if system.is_file(&source_path).unwrap_or(false) => "file",
+            "auto" | ARCHIVE_PULL_TYPE | OVERLAY_PULL_TYPE => "directory",
+            explicit => explicit,
+        };
+        count_files_to_copy(system, &source_path, pull_type).and_then(|files| {
+            calculate_copy_size(system, &source_path, pull_type).map(|bytes| (files, bytes))
+        })
+    } else if repository.is_git() && options.check_remote {
+        estimate_remote_size(config, pull, repository, reference, options)
+    } else {
+        return;
+    };
+
+    match estimate {
+        Ok((files, bytes)) => info!(
+            "{indent}      - Estimated size: {files} file(s), {}",
+            format_file_size(bytes)
+        ),
+        Err(err) => info!("{indent}      - Could not estimate size: {err:#}"),
+    }
+}
+
+/// Estimate the size of a Git pull's source by cloning it without checking
+/// anything out (`--check-remote`).
+///
+/// The clone lives in a temporary directory owned by the checkout, which is
+/// dropped, and the directory removed, before this returns, whether or not
+/// the estimate succeeded. A dry run leaves nothing behind.
+fn estimate_remote_size(
+    config: &Config,
+    pull: &PullConfig,
+    repository: Repository,
+    reference: &str,
+    options: &RunOptions,
+) -> Result<(usize, u64)> {
+    let checkout = SparseCheckout::new_in(
+        repository,
+        reference.to_owned(),
+        pull.source.clone(),
+        options.temp_root.as_deref(),
+    )?
+    .with_options(build_clone_options(config, options));
+    let estimate = checkout.estimate_size();
+    drop(checkout);
+    estimate
+}
+
+/// Print each replacement of a pull, and of the graft files in a local
+/// source, with its resolved value.
+///
+/// Graft files of Git sources are not fetched for this, and values that
+/// post-commands would capture (`captureInto`) are not available yet.
+///
+/// Returns one issue per replacement whose value could not be resolved.
+fn dump_resolved_replacements(
+    system: &dyn System,
+    config: &Config,
+    pull: &PullConfig,
+    repo_url: &str,
+    display_index: usize,
+    indent: &str,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+    for replacement in &pull.replacements {
+        let resolved = resolve_replacement(system, replacement);
+        if let Some(err) = print_resolved_replacement(&replacement.source, resolved, indent) {
+            issues.push(format!(
+                "Pull #{display_index}: replacement '{}': {err}",
+                replacement.source
+            ));
+        }
+    }
+
+    let Some(source_path) = Repository::new(system, repo_url)
+        .ok()
+        .and_then(|repository| repository.local_path().map(|base| base.join(&pull.source)))
+        .filter(|path| system.is_dir(path).unwrap_or(false))
+    else {
+        return issues;
+    };
+
+    let graft_file_name = config.graft_file_name();
+    let discovered_grafts = match discover_graft_files_named(system, &source_path, graft_file_name)
+    {
+        Ok(grafts) => grafts,
+        Err(err) => {
+            warn!("{indent}        Could not discover {graft_file_name} files: {err:#}");
+            issues.push(format!(
+                "Pull #{display_index}: could not discover {graft_file_name} files: {err:#}"
+            ));
+            return issues;
+        }
+    };
+
+    let base_context = merge_context_values(config.context.clone(), pull.context.clone());
+    let graft_indent = format!("{indent}  ");
+    for discovered in &discovered_grafts {
+        let relative = discovered
+            .path
+            .strip_prefix(&source_path)
+            .unwrap_or(&discovered.path);
+        info!("{indent}        {}:", relative.display());
+
+        let resolved = GraftConfig::load_from_file(system, &discovered.path).and_then(|graft| {
+            let context = if graft.context.is_empty() {
+                base_context.clone()
+            } else {
+                ValidatedContext::with_env(graft.context.clone(), base_context.clone(), system)
+                    .context("Context validation failed")?
+                    .values
+            };
+            let replacements =
+                resolve_json_path_replacements(system, &discovered.directory, &graft.replacements)?;
+            Ok((context, replacements))
+        });
+        let (context, replacements) = match resolved {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                warn!("{graft_indent}        (unresolved) {err:#}");
+                issues.push(format!(
+                    "Pull #{display_index}: {}: {err:#}",
+                    relative.display()
+                ));
+                continue;
+            }
+        };
+
+        for replacement in &replacements {
+            let resolved = resolve_graft_replacement(system, replacement, &context);
+            if let Some(err) =
+                print_resolved_replacement(&replacement.source, resolved, &graft_indent)
+            {
+                issues.push(format!(
+                    "Pull #{display_index}: {}: replacement '{}': {err}",
+                    relative.display(),
+                    replacement.source
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Print a replacement's resolved value, or why it could not be resolved.
+///
+/// Returns the reason when the value could not be resolved.
+fn print_resolved_replacement(
+    source: &str,
+    resolved: Result<ResolvedReplacement>,
+    indent: &str,
+) -> Option<String> {
+    match resolved {
+        Ok(resolved) => {
+            info!(
+                "{indent}        {} -> {}",
+                resolved.source,
+                resolved.display_value()
+            );
+            None
+        }
+        Err(err) => {
+            warn!("{indent}        {source} -> (unresolved) {err:#}");
+            Some(format!("{err:#}"))
+        }
+    }
+}
+
+/// Preview the config-level `postPull` commands.
+#[expect(
+    clippy::ref_patterns,
+    reason = "ref is required to borrow fields when matching on a dereferenced enum to satisfy pattern_type_mismatch"
+)]
+fn preview_post_pull(config: &Config, indent: &str) {
+    if config.post_pull.is_empty() {
+        return;
+    }
+
+    info!(
+        "{indent}  Would run {} postPull command(s):",
+        config.post_pull.len()
+    );
+    for post_command in &config.post_pull {
+        match *post_command {
+            PostCommand::Command {
+                ref command,
+                ref args,
+                ..
+            } => info!("{indent}    * {} {}", command, args.join(" ")),
+            PostCommand::Choice { ref options } => {
+                info!("{indent}    * choice ({} options)", options.len());
+            }
+        }
+    }
 }
 
 /// Preview all children for a config, recursing into each child.
@@ -498,7 +1729,9 @@ fn preview_children(
     visited: &mut HashSet<PathBuf>,
     depth: usize,
     indent: &str,
-) -> Result<()> {
+    options: &RunOptions,
+) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
     for child_path_str in &config.children {
         let child_config_path = config_dir.join(child_path_str);
         let child_dir = child_config_path.parent().unwrap_or_else(|| Path::new("."));
@@ -507,152 +1740,590 @@ fn preview_children(
         info!("{indent}  Child: {}", child_path_str);
 
         let child_config_path_str = child_config_path.to_string_lossy();
-        // load_from_file validates the config with children paths resolved
-        // relative to the config file's directory (not CWD).
-        let child_config = Config::load_from_file(system, &child_config_path_str)
+        // Loading validates the config with children paths resolved relative
+        // to the config file's directory (not CWD); unset valueFromEnv
+        // variables are left to collect_plan_issues.
+        let child_config = Config::load_from_file_for_dry_run(system, &child_config_path_str)
             .with_context(|| format!("Error in child '{child_path_str}': failed to load config"))?;
 
         // Target resolution is handled inside preview_config_recursive via
         // resolve_pull_targets, so we pass the raw child config here.
         let child_indent = format!("{indent}  ");
-        preview_config_recursive(
+        let child_issues = preview_config_recursive(
             system,
             &child_config,
             child_dir,
             visited,
             depth + 1,
             &child_indent,
+            options,
         )
         .with_context(|| format!("Error in child '{child_path_str}'"))?;
+        issues.extend(
+            child_issues
+                .into_iter()
+                .map(|issue| format!("Child '{child_path_str}': {issue}")),
+        );
     }
 
-    Ok(())
+    Ok(issues)
 }
 
 /// Execute a single pull operation.
-#[expect(
-    clippy::arithmetic_side_effects,
-    reason = "Simple counter increments on usize totals that cannot realistically overflow"
-)]
 fn execute_single_pull(
     system: &dyn System,
     config: &Config,
     pull: &PullConfig,
     repo_url: &str,
     reference: &str,
+    warnings: &WarningSink,
+    options: &RunOptions,
 ) -> Result<PullResult> {
     debug!("Executing single pull operation: {repo_url} - {reference:?}");
 
     // Check for uncommitted changes in target if required
-    if pull.require_clean_target {
+    if pull.require_clean_target && !is_stdout_target(&pull.target) {
         check_target_is_clean(&pull.target)?;
     }
 
-    // Create repository and determine source type
-    let repository = Repository::new(system, repo_url).context("Failed to create repository")?;
+    // `type: overlay` copies each source into the target in turn
+    if is_overlay_pull(pull) {
+        return execute_overlay_pull(system, config, pull, repo_url, reference, warnings, options);
+    }
 
-    // For Git repositories, we need to keep the SparseCheckout alive to prevent
-    // the TempDir from being deleted before we finish copying files
-    let sparse_checkout_guard;
+    // Get source path based on repository type. The checkout must stay alive
+    // until the files are copied, or its TempDir is cleaned up.
+    let (checkout, source_path) =
+        checkout_source(system, config, pull, repo_url, reference, options)?;
+    let sha = checkout.resolve_sha();
+    options.progress.emit(&ProgressEvent::CheckoutDone {
+        sha: sha.clone(),
+        target: pull.target.clone(),
+    });
+
+    // Resolve `type: auto` by inspecting the source
+    let detected_pull = detect_pull_type(system, pull, &source_path)?;
+    let typed_pull = detected_pull.as_ref().unwrap_or(pull);
+    check_pull_replacement_values(system, typed_pull, options)?;
+
+    // `target: "-"` writes the replaced file to stdout and touches nothing else
+    if is_stdout_target(&typed_pull.target) {
+        let replacements_applied =
+            write_file_to_stdout(system, &source_path, typed_pull, &mut io::stdout().lock())?;
+        return Ok(PullResult {
+            commands_executed: 0,
+            files_copied: 1,
+            replacements_applied,
+            sha,
+        });
+    }
 
-    // Get source path based on repository type
-    let source_path = if repository.is_git() {
-        debug!("Repository is a Git repository");
+    // `type: archive` packs the replaced files into the target archive
+    if is_archive_pull(typed_pull) {
+        let (files_copied, replacements_applied) = write_archive_target(
+            system,
+            &source_path,
+            typed_pull,
+            options.temp_root.as_deref(),
+            options.include_git,
+        )?;
+        return Ok(PullResult {
+            commands_executed: 0,
+            files_copied,
+            replacements_applied,
+            sha,
+        });
+    }
 
-        // Git repository - use sparse checkout
-        let sparse_checkout =
-            SparseCheckout::new(repository, reference.to_owned(), pull.source.clone())
-                .context("Failed to create sparse checkout")?;
+    // A file pulled into a directory keeps its name; later steps see the file
+    let placed_pull = place_file_pull(system, typed_pull, &source_path)?;
+    let typed_pull = placed_pull.as_ref().unwrap_or(typed_pull);
 
-        debug!("Sparse checkout created");
+    // Copy files
+    let copy_options = CopyOptions::new(typed_pull.include_hidden, typed_pull.respect_gitignore)
+        .with_include_git(options.include_git)
+        .with_strip_components(typed_pull.strip_components);
+    let files_copied = copy_files_with_options(
+        system,
+        &source_path,
+        &typed_pull.target,
+        &typed_pull.pull_type,
+        typed_pull.reset,
+        copy_options,
+    )
+    .inspect_err(|err| {
+        if options.preserve_partial {
+            report_partial_copy(err, &typed_pull.target);
+        }
+    })?;
+    // Verify before replacements change file sizes
+    if options.verify_copy {
+        verify_copy(
+            system,
+            &source_path,
+            Path::new(&typed_pull.target),
+            &typed_pull.pull_type,
+            copy_options,
+        )?;
+    }
 
-        // Execute sparse checkout
-        let checkout_path = sparse_checkout
-            .execute()
-            .context("Sparse checkout failed")?;
+    drop(checkout);
+    options.progress.emit(&ProgressEvent::FilesCopied {
+        files: files_copied,
+        target: typed_pull.target.clone(),
+    });
+    check_expected_files(system, typed_pull)?;
 
-        debug!("Sparse checkout executed");
+    // Merge the source's own tixgraft.pull.yaml (--trust-source-config)
+    let trusted_pull = merge_source_pull_config(system, typed_pull, options)?;
+    let (replacements_applied, commands_executed) = transform_target(
+        system,
+        config,
+        trusted_pull.as_ref().unwrap_or(typed_pull),
+        warnings,
+        options,
+    )?;
+    options.progress.emit(&ProgressEvent::ReplacementsDone {
+        replacements: replacements_applied,
+        target: typed_pull.target.clone(),
+    });
 
-        // Verify source exists
-        if !sparse_checkout.source_exists() {
-            let diagnostics = sparse_checkout.get_checkout_diagnostics()?;
-            return Err(GraftError::from_source(format!(
-                "Source path '{}' not found in repository '{}' at reference '{}'\n\n{}",
-                pull.source, repo_url, reference, diagnostics
-            ))
-            .into());
-        }
+    Ok(PullResult {
+        commands_executed,
+        files_copied,
+        replacements_applied,
+        sha,
+    })
+}
 
-        // IMPORTANT: Keep sparse_checkout alive until after copy_files completes
-        // to prevent TempDir cleanup
-        sparse_checkout_guard = Some(sparse_checkout);
+/// Execute a `type: overlay` pull: copy `source` and then each of `sources`
+/// into the target as directories, so later sources override earlier files,
+/// and transform the combined result once.
+#[expect(
+    clippy::arithmetic_side_effects,
+    reason = "Simple counter increments on usize totals that cannot realistically overflow"
+)]
+fn execute_overlay_pull(
+    system: &dyn System,
+    config: &Config,
+    pull: &PullConfig,
+    repo_url: &str,
+    reference: &str,
+    warnings: &WarningSink,
+    options: &RunOptions,
+) -> Result<PullResult> {
+    let mut layered = pull.clone();
+    "directory".clone_into(&mut layered.pull_type);
+    check_pull_replacement_values(system, &layered, options)?;
 
-        checkout_path
-    } else {
-        debug!("Repository is a local filesystem");
+    // One checkout holds every layer; it must stay alive until they are copied
+    let (checkout, layer_paths) =
+        checkout_overlay_sources(system, config, &layered, repo_url, reference, options)?;
+    let sha = checkout.resolve_sha();
 
-        // Local filesystem - construct path directly
-        let base_path = repository
-            .local_path()
-            .ok_or_else(|| GraftError::from_source("Invalid local repository".to_owned()))?;
+    let mut files_copied = 0;
+    for (index, (source, source_path)) in slice::from_ref(&pull.source)
+        .iter()
+        .chain(&pull.sources)
+        .zip(layer_paths)
+        .enumerate()
+    {
+        let mut layer = layered.clone();
+        source.clone_into(&mut layer.source);
+
+        let copy_options = CopyOptions::new(layer.include_hidden, layer.respect_gitignore)
+            .with_include_git(options.include_git)
+            .with_strip_components(layer.strip_components);
+        let copied = copy_files_with_options(
+            system,
+            &source_path,
+            &layer.target,
+            &layer.pull_type,
+            layer.reset && index == 0,
+            copy_options,
+        )
+        .inspect_err(|err| {
+            if options.preserve_partial {
+                report_partial_copy(err, &layer.target);
+            }
+        })?;
+        if options.verify_copy {
+            verify_copy(
+                system,
+                &source_path,
+                Path::new(&layer.target),
+                &layer.pull_type,
+                copy_options,
+            )?;
+        }
+        debug!("Overlay layer '{source}': {copied} file(s)");
+        files_copied += copied;
+    }
+    drop(checkout);
+    options.progress.emit(&ProgressEvent::FilesCopied {
+        files: files_copied,
+        target: layered.target.clone(),
+    });
+    check_expected_files(system, &layered)?;
+
+    let trusted_pull = merge_source_pull_config(system, &layered, options)?;
+    let (replacements_applied, commands_executed) = transform_target(
+        system,
+        config,
+        trusted_pull.as_ref().unwrap_or(&layered),
+        warnings,
+        options,
+    )?;
+    options.progress.emit(&ProgressEvent::ReplacementsDone {
+        replacements: replacements_applied,
+        target: layered.target.clone(),
+    });
 
-        let source_path = base_path.join(&pull.source);
+    Ok(PullResult {
+        commands_executed,
+        files_copied,
+        replacements_applied,
+        sha,
+    })
+}
 
-        // Verify source exists
-        if !source_path.exists() {
+/// Check out every layer of a `type: overlay` pull at once.
+///
+/// A Git source is cloned once, with a sparse checkout covering all layers
+/// (unless the pull sets its own `sparsePatterns`). Returns the checkout,
+/// which must be kept alive while its files are in use, and the path of each
+/// layer in order.
+fn checkout_overlay_sources(
+    system: &dyn System,
+    config: &Config,
+    pull: &PullConfig,
+    repo_url: &str,
+    reference: &str,
+    options: &RunOptions,
+) -> Result<(SourceCheckout, Vec<PathBuf>)> {
+    let mut checkout_pull = pull.clone();
+    if checkout_pull.sparse_patterns.is_empty() {
+        checkout_pull.sparse_patterns = slice::from_ref(&pull.source)
+            .iter()
+            .chain(&pull.sources)
+            .cloned()
+            .collect();
+    }
+    let (checkout, first_path) =
+        checkout_source(system, config, &checkout_pull, repo_url, reference, options)?;
+
+    // The first layer's path is the checkout root joined with its source
+    let source_depth = Path::new(&pull.source)
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .count();
+    let root = first_path
+        .ancestors()
+        .nth(source_depth)
+        .map_or_else(|| first_path.clone(), Path::to_path_buf);
+
+    let mut layer_paths = vec![first_path];
+    for source in &pull.sources {
+        let layer_path = root.join(source);
+        if !system.is_dir(&layer_path)? {
             return Err(GraftError::from_source(format!(
-                "Source path '{}' not found in local repository '{}'",
-                pull.source, repo_url
+                "Overlay source '{source}' is not a directory in repository '{repo_url}' at reference '{reference}'"
             ))
             .into());
         }
+        layer_paths.push(layer_path);
+    }
+    Ok((checkout, layer_paths))
+}
 
-        // Verify source matches expected type
-        let is_file = source_path.is_file();
-        let is_dir = source_path.is_dir();
+/// Check a pull's replacement values up front: none may be empty with
+/// `--no-empty-replacements`, or equal to its source with
+/// `--strict-replacements` (otherwise such values are warned about once).
+fn check_pull_replacement_values(
+    system: &dyn System,
+    pull: &PullConfig,
+    options: &RunOptions,
+) -> Result<()> {
+    if options.no_empty_replacements {
+        check_no_empty_replacements(system, &pull.replacements)?;
+    }
+    if options.strict_replacements {
+        check_no_identity_replacements(system, &pull.replacements)?;
+    } else {
+        warn_identity_replacements(system, &pull.replacements);
+    }
+    Ok(())
+}
 
-        if pull.pull_type == "file" && !is_file {
-            return Err(GraftError::from_source(format!(
-                "Source path '{}' is not a file (type specified as 'file')",
-                source_path.display()
-            ))
-            .into());
-        }
+/// A checked-out pull source whose files live in a temporary directory.
+enum SourceCheckout {
+    /// Extracted archive.
+    Archive(
+        #[expect(
+            dead_code,
+            reason = "held only to keep the extracted TempDir alive until the files are copied"
+        )]
+        ArchiveCheckout,
+    ),
+    /// Sparse checkout of a Git repository.
+    Git(SparseCheckout<'static>),
+    /// Local filesystem source, read in place.
+    Local,
+    /// Source in a `--prefetch` checkout, with the commit it resolved to.
+    Prefetched(Option<String>),
+}
 
-        if pull.pull_type == "directory" && !is_dir {
-            return Err(GraftError::from_source(format!(
-                "Source path '{}' is not a directory (type specified as 'directory')",
-                source_path.display()
-            ))
-            .into());
+impl SourceCheckout {
+    /// Commit the reference resolved to (Git sources only).
+    #[expect(
+        clippy::pattern_type_mismatch,
+        reason = "matching on &SourceCheckout; dereferencing would require ref bindings which conflict with clippy::ref_patterns"
+    )]
+    fn resolve_sha(&self) -> Option<String> {
+        match self {
+            Self::Git(checkout) => checkout.resolve_sha().ok(),
+            Self::Prefetched(sha) => sha.clone(),
+            Self::Archive(_) | Self::Local => None,
         }
+    }
+}
 
-        sparse_checkout_guard = None;
+/// Check out a pull's source according to its repository type.
+///
+/// Returns the checkout, which must be kept alive while its files are in
+/// use, and the path of the source.
+fn checkout_source(
+    system: &dyn System,
+    config: &Config,
+    pull: &PullConfig,
+    repo_url: &str,
+    reference: &str,
+    options: &RunOptions,
+) -> Result<(SourceCheckout, PathBuf)> {
+    let repository = Repository::new(system, repo_url).context("Failed to create repository")?;
+    if options.offline {
+        ensure_offline_access(&repository)?;
+    }
 
-        source_path
-    };
+    if let Some(prefetched) = options
+        .prefetched
+        .as_ref()
+        .and_then(|sources| sources.get(repo_url, reference))
+    {
+        debug!("Repository was prefetched");
+        let source_path = prefetched_source_path(system, prefetched, pull, repo_url, reference)?;
+        options.progress.emit(&ProgressEvent::CloneDone {
+            reference: reference.to_owned(),
+            repository: repo_url.to_owned(),
+            target: pull.target.clone(),
+        });
+        return Ok((
+            SourceCheckout::Prefetched(prefetched.sha().map(ToOwned::to_owned)),
+            source_path,
+        ));
+    }
 
-    // Copy files
-    let files_copied = copy_files(
-        system,
-        &source_path,
-        &pull.target,
-        &pull.pull_type,
-        pull.reset,
-    )?;
+    if repository.is_git() {
+        debug!("Repository is a Git repository");
+        let (sparse_checkout, checkout_path) =
+            checkout_git_source(config, pull, repository, repo_url, reference, options)?;
+        return Ok((SourceCheckout::Git(sparse_checkout), checkout_path));
+    }
 
-    drop(sparse_checkout_guard);
+    if let Some((format, location)) = repository.archive() {
+        debug!("Repository is an archive");
+        let archive =
+            ArchiveCheckout::extract_in(system, format, location, options.temp_root.as_deref())?;
+        let archive_path = checked_source_path(system, archive.root(), pull, repo_url)?;
+        return Ok((SourceCheckout::Archive(archive), archive_path));
+    }
 
-    // Apply text replacements
-    let mut replacements_applied = if pull.replacements.is_empty() {
-        0
+    debug!("Repository is a local filesystem");
+    let local_path = local_source_path(system, &repository, pull, repo_url)?;
+    Ok((SourceCheckout::Local, local_path))
+}
+
+/// Sparse-check-out a pull's source from a Git repository.
+///
+/// Returns the checkout, which must be kept alive while its files are in
+/// use, and the path of the checked-out source.
+fn checkout_git_source(
+    config: &Config,
+    pull: &PullConfig,
+    repository: Repository,
+    repo_url: &str,
+    reference: &str,
+    options: &RunOptions,
+) -> Result<(SparseCheckout<'static>, PathBuf)> {
+    let sparse_checkout = SparseCheckout::new_in(
+        repository,
+        reference.to_owned(),
+        pull.source.clone(),
+        options.temp_root.as_deref(),
+    )
+    .context("Failed to create sparse checkout")?
+    .with_options(build_clone_options(config, options))
+    .with_sparse_patterns(pull.sparse_patterns.clone());
+
+    debug!("Sparse checkout created");
+
+    // Execute sparse checkout
+    let checkout_path = sparse_checkout
+        .execute()
+        .context("Sparse checkout failed")?;
+
+    debug!("Sparse checkout executed");
+    options.progress.emit(&ProgressEvent::CloneDone {
+        reference: reference.to_owned(),
+        repository: repo_url.to_owned(),
+        target: pull.target.clone(),
+    });
+
+    // Verify source exists
+    if !sparse_checkout.source_exists() {
+        let diagnostics = sparse_checkout.get_checkout_diagnostics()?;
+        return Err(GraftError::from_source(format!(
+            "Source path '{}' not found in repository '{}' at reference '{}'\n\n{}",
+            pull.source, repo_url, reference, diagnostics
+        ))
+        .into());
+    }
+
+    Ok((sparse_checkout, checkout_path))
+}
+
+/// Path of a pull's source in its `--prefetch` checkout.
+fn prefetched_source_path(
+    system: &dyn System,
+    prefetched: &PrefetchedCheckout,
+    pull: &PullConfig,
+    repo_url: &str,
+    reference: &str,
+) -> Result<PathBuf> {
+    let source_path = prefetched.root().join(&pull.source);
+    if !system.exists(&source_path)? {
+        return Err(GraftError::from_source(format!(
+            "Source path '{}' not found in repository '{}' at reference '{}'",
+            pull.source, repo_url, reference
+        ))
+        .into());
+    }
+    Ok(source_path)
+}
+
+/// Resolve a `type: auto` pull to `file` or `directory` from its source.
+///
+/// Returns `None` when the pull already has an explicit type.
+fn detect_pull_type(
+    system: &dyn System,
+    pull: &PullConfig,
+    source_path: &Path,
+) -> Result<Option<PullConfig>> {
+    if pull.pull_type != "auto" {
+        return Ok(None);
+    }
+
+    let detected = if system.is_file(source_path)? {
+        "file"
+    } else if system.is_dir(source_path)? {
+        "directory"
     } else {
-        apply_replacements(system, &pull.target, &pull.replacements)
-            .context("Text replacement failed")?
+        return Err(GraftError::from_source(format!(
+            "Source path '{}' is neither a file nor a directory",
+            source_path.display()
+        ))
+        .into());
     };
 
+    debug!("Detected pull type '{detected}' for {}", pull.source);
+    let mut typed = pull.clone();
+    detected.clone_into(&mut typed.pull_type);
+    Ok(Some(typed))
+}
+
+/// Point a `type: file` pull whose target is a directory at the file it
+/// copies into that directory (see [`file_target_path`]).
+///
+/// Returns `None` when the target already names the destination file.
+fn place_file_pull(
+    system: &dyn System,
+    pull: &PullConfig,
+    source_path: &Path,
+) -> Result<Option<PullConfig>> {
+    if pull.pull_type != "file" {
+        return Ok(None);
+    }
+    let target = file_target_path(system, source_path, &pull.target)?;
+    if target == Path::new(&pull.target) {
+        return Ok(None);
+    }
+
+    debug!("Placing {} at {}", pull.source, target.display());
+    let mut placed = pull.clone();
+    placed.target = target.to_string_lossy().to_string();
+    Ok(Some(placed))
+}
+
+/// Merge the source-provided `tixgraft.pull.yaml` into `pull`.
+///
+/// Only done for directory pulls with `--trust-source-config`; returns
+/// `None` otherwise or when the source ships no fragment. The fragment is
+/// removed from the target once read, and its commands must pass the
+/// dangerous-command check.
+fn merge_source_pull_config(
+    system: &dyn System,
+    pull: &PullConfig,
+    options: &RunOptions,
+) -> Result<Option<PullConfig>> {
+    if !options.trust_source_config || pull.pull_type != "directory" {
+        return Ok(None);
+    }
+
+    let fragment_path = Path::new(&pull.target).join(SOURCE_PULL_FILE_NAME);
+    if !system.exists(&fragment_path)? {
+        return Ok(None);
+    }
+
+    let fragment = SourcePullConfig::load_from_file(system, &fragment_path)?;
+    fragment.check_commands()?;
+    system.remove_file(&fragment_path).with_context(|| {
+        format!(
+            "Failed to remove {SOURCE_PULL_FILE_NAME} from target: {}",
+            fragment_path.display()
+        )
+    })?;
+
+    info!(
+        "  Merging trusted {SOURCE_PULL_FILE_NAME}: {} replacement(s), {} command(s)",
+        fragment.replacements.len(),
+        fragment.commands.len()
+    );
+    Ok(Some(fragment.merge_into(pull)))
+}
+
+/// Apply replacements, templates, `.graft.yaml` files and commands to a
+/// pull's copied target.
+///
+/// Returns the number of replacements applied and commands executed.
+#[expect(
+    clippy::arithmetic_side_effects,
+    reason = "Simple counter increments on usize totals that cannot realistically overflow"
+)]
+fn transform_target(
+    system: &dyn System,
+    config: &Config,
+    pull: &PullConfig,
+    warnings: &WarningSink,
+    options: &RunOptions,
+) -> Result<(usize, usize)> {
+    // Apply text replacements (and filename replacements with replaceFilenames)
+    let mut replacements_applied = apply_pull_replacements(system, config, pull, options)?;
+
+    // Render templates (templateEngine)
+    replacements_applied += render_pull_templates(system, config, pull)?;
+
     // Process .graft.yaml files (context feature)
-    let graft_result = process_graft_files(system, config, pull)?;
+    let graft_result = process_graft_files(system, config, pull, warnings, options)?;
     replacements_applied += graft_result.replacements_applied;
 
     // Execute commands
@@ -673,11 +2344,247 @@ fn execute_single_pull(
     };
     commands_executed += graft_result.commands_executed;
 
-    Ok(PullResult {
-        commands_executed,
-        files_copied,
-        replacements_applied,
-    })
+    Ok((replacements_applied, commands_executed))
+}
+
+/// Locate a pull's source in a local repository and check it matches the pull type.
+fn local_source_path(
+    system: &dyn System,
+    repository: &Repository,
+    pull: &PullConfig,
+    repo_url: &str,
+) -> Result<PathBuf> {
+    // Local filesystem - construct path directly
+    let base_path = repository
+        .local_path()
+        .ok_or_else(|| GraftError::from_source("Invalid local repository".to_owned()))?;
+
+    checked_source_path(system, base_path, pull, repo_url)
+}
+
+/// Join a pull's source onto `base_path` and check it matches the pull type.
+fn checked_source_path(
+    system: &dyn System,
+    base_path: &Path,
+    pull: &PullConfig,
+    repo_url: &str,
+) -> Result<PathBuf> {
+    let source_path = base_path.join(&pull.source);
+
+    // Verify source exists
+    if !system.exists(&source_path)? {
+        return Err(GraftError::from_source(format!(
+            "Source path '{}' not found in local repository '{}'",
+            pull.source, repo_url
+        ))
+        .into());
+    }
+
+    // Verify source matches expected type
+    let is_file = system.is_file(&source_path)?;
+    let is_dir = system.is_dir(&source_path)?;
+
+    if pull.pull_type == "file" && !is_file {
+        return Err(GraftError::from_source(format!(
+            "Source path '{}' is not a file (type specified as 'file')",
+            source_path.display()
+        ))
+        .into());
+    }
+
+    if pull.pull_type == "directory" && !is_dir {
+        return Err(GraftError::from_source(format!(
+            "Source path '{}' is not a directory (type specified as 'directory')",
+            source_path.display()
+        ))
+        .into());
+    }
+
+    Ok(source_path)
+}
+
+/// Apply a pull's replacements to file contents and, with `replaceFilenames`,
+/// to file and directory names.
+///
+/// With `pullReplacementScope: non-graft`, file contents below a directory
+/// holding a graft file are left to that graft. With `--replacements-report`,
+/// what each replacement changed is logged.
+///
+/// Returns the number of files changed plus the number of paths renamed.
+fn apply_pull_replacements(
+    system: &dyn System,
+    config: &Config,
+    pull: &PullConfig,
+    options: &RunOptions,
+) -> Result<usize> {
+    if pull.replacements.is_empty() {
+        return Ok(0);
+    }
+
+    let counts = if pull.pull_type != "file"
+        && pull.pull_replacement_scope == PullReplacementScope::NonGraft
+    {
+        apply_replacements_outside_grafts(system, config, pull)
+    } else {
+        apply_replacements_counted(
+            system,
+            Path::new(&pull.target),
+            &pull.replacements,
+            pull.text_encoding(),
+        )
+    }
+    .context("Text replacement failed")?;
+    if options.replacements_report {
+        report_replacement_counts(&pull.target, &counts);
+    }
+    options
+        .replacement_audit
+        .record(&pull.replacements, &counts);
+    let replaced = counts
+        .iter()
+        .fold(0, |total: usize, count| total.saturating_add(count.files));
+    if !pull.replace_filenames {
+        return Ok(replaced);
+    }
+
+    let renamed = apply_filename_replacements(system, &pull.target, &pull.replacements)
+        .context("Filename replacement failed")?;
+    Ok(replaced.saturating_add(renamed))
+}
+
+/// Report the replacements that changed no file in any pull of the run
+/// (`--list-replacements-unused`): as warnings, or as one error with
+/// `--strict-replacements`.
+fn audit_unused_replacements(options: &RunOptions, warnings: &WarningSink) -> Result<()> {
+    if !options.replacement_audit.is_enabled() {
+        return Ok(());
+    }
+    let unused = options.replacement_audit.unused();
+    if unused.is_empty() {
+        info!("Every replacement changed at least one file");
+        return Ok(());
+    }
+    if options.strict_replacements {
+        return Err(GraftError::configuration(format!(
+            "Replacements that changed no file in any pull (--strict-replacements): {}",
+            unused
+                .iter()
+                .map(|source| format!("'{source}'"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+        .into());
+    }
+    for source in unused {
+        warnings.push(
+            None,
+            &format!("Replacement '{source}' changed no file in any pull"),
+        );
+    }
+    Ok(())
+}
+
+/// Log the files changed and matches replaced by each of a pull's
+/// replacements (`--replacements-report`).
+fn report_replacement_counts(target: &str, counts: &[ReplacementCount]) {
+    info!("  Replacements report for {target}:");
+    for count in counts {
+        info!(
+            "    '{}': {} file(s), {} occurrence(s)",
+            count.source, count.files, count.occurrences
+        );
+    }
+}
+
+/// Apply a directory pull's replacements to the files outside every graft
+/// directory (`pullReplacementScope: non-graft`).
+///
+/// Returns what each replacement changed, summed over those files.
+fn apply_replacements_outside_grafts(
+    system: &dyn System,
+    config: &Config,
+    pull: &PullConfig,
+) -> Result<Vec<ReplacementCount>> {
+    let target_path = Path::new(&pull.target);
+    let graft_file_name = config.graft_file_name();
+    let graft_dirs: Vec<PathBuf> = discover_graft_files_named(system, target_path, graft_file_name)
+        .with_context(|| format!("Failed to discover {graft_file_name} files"))?
+        .into_iter()
+        .map(|graft| graft.directory)
+        .collect();
+    let encoding = pull.text_encoding();
+    if graft_dirs.is_empty() {
+        return apply_replacements_counted(system, target_path, &pull.replacements, encoding);
+    }
+
+    // Graft directories are canonical, so walk the canonical target
+    let root = system
+        .canonicalize(target_path)
+        .with_context(|| format!("Failed to canonicalize target: {}", target_path.display()))?;
+    let entries = system
+        .walk_dir(&root, false, false)
+        .with_context(|| format!("Failed to walk directory: {}", root.display()))?;
+
+    let mut totals: Vec<ReplacementCount> = Vec::new();
+    for entry in entries {
+        if !entry.is_file || graft_dirs.iter().any(|dir| entry.path.starts_with(dir)) {
+            continue;
+        }
+        let counts = apply_replacements_counted(system, &entry.path, &pull.replacements, encoding)?;
+        if totals.is_empty() {
+            totals = counts;
+            continue;
+        }
+        for (total, count) in totals.iter_mut().zip(counts) {
+            total.files = total.files.saturating_add(count.files);
+            total.occurrences = total.occurrences.saturating_add(count.occurrences);
+        }
+    }
+    debug!(
+        "Left {} graft directories to their own replacements",
+        graft_dirs.len()
+    );
+    Ok(totals)
+}
+
+/// Render a pull's target with its template engine and the merged context.
+///
+/// Returns the number of files rendered (0 when the engine is `none`).
+fn render_pull_templates(system: &dyn System, config: &Config, pull: &PullConfig) -> Result<usize> {
+    if pull.template_engine == "none" {
+        return Ok(0);
+    }
+
+    let context = merge_context_values(config.context.clone(), pull.context.clone());
+    render_templates(
+        system,
+        Path::new(&pull.target),
+        &pull.template_engine,
+        &context,
+        config.graft_file_name(),
+    )
+    .context("Template rendering failed")
+}
+
+/// Build the clone options for Git sources from the configuration and the
+/// pinned trust anchors (`--ca-bundle`, `--known-hosts`).
+fn build_clone_options(config: &Config, run_options: &RunOptions) -> CloneOptions {
+    let mut options = CloneOptions::default()
+        .with_fetch_tags(run_options.fetch_tags)
+        .with_verbosity(run_options.git_verbosity);
+    if let Some(clone_filter) = config.clone_filter.as_deref() {
+        options = options.with_filter(clone_filter);
+    }
+    if let Some(ca_bundle) = run_options.ca_bundle.as_deref() {
+        options = options.with_ca_bundle(ca_bundle);
+    }
+    if let Some(known_hosts) = run_options.known_hosts.as_deref() {
+        options = options.with_known_hosts(known_hosts);
+    }
+    if let Some(max_clone_size) = run_options.max_clone_size {
+        options = options.with_max_clone_size(max_clone_size);
+    }
+    options
 }
 
 /// Build context for a specific graft (with parent inheritance).
@@ -691,7 +2598,98 @@ fn build_graft_context(
     base_context.clone()
 }
 
-/// Process all .graft.yaml files in the target directory.
+/// Check that every `expectFiles` entry of `pull` exists under its target.
+fn check_expected_files(system: &dyn System, pull: &PullConfig) -> Result<()> {
+    let target = Path::new(&pull.target);
+    let mut missing = Vec::new();
+    for expected in &pull.expect_files {
+        if !system.exists(&target.join(expected))? {
+            missing.push(expected.as_str());
+        }
+    }
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(GraftError::from_source(format!(
+        "Source '{}' is missing expected file(s) in {}: {}",
+        pull.source,
+        pull.target,
+        missing.join(", ")
+    ))
+    .into())
+}
+
+/// Log the files a failed copy left in `target` (`--preserve-partial`).
+fn report_partial_copy(err: &anyhow::Error, target: &str) {
+    let Some(partial) = err.downcast_ref::<PartialCopy>() else {
+        return;
+    };
+    warn!(
+        "Copy into {target} failed after {} file(s); the partial target was left in place",
+        partial.copied.len()
+    );
+    for path in &partial.copied {
+        warn!("  copied before failure: {}", path.display());
+    }
+}
+
+/// Apply a graft's replacements, each within its scope: the graft's own
+/// directory, the whole pull target, or a path relative to the target.
+///
+/// Returns the number of files changed.
+fn apply_scoped_graft_replacements(
+    system: &dyn System,
+    graft_dir: &Path,
+    target_path: &Path,
+    replacements: &[GraftReplacement],
+    context: &ContextValues,
+    options: &RunOptions,
+) -> Result<usize> {
+    if options.no_empty_replacements {
+        check_no_empty_graft_replacements(system, replacements, context)?;
+    }
+    if options.strict_replacements {
+        check_no_identity_graft_replacements(system, replacements, context)?;
+    } else {
+        warn_identity_graft_replacements(system, replacements, context);
+    }
+
+    let mut total_replacements = 0_usize;
+    for replacement in replacements {
+        let root = match (replacement.scope, replacement.path.as_deref()) {
+            (ReplacementScope::Target, _) => target_path.to_path_buf(),
+            (ReplacementScope::Path, Some(path)) => {
+                let scoped = target_path.join(path);
+                if escapes_from_base(&scoped, target_path) {
+                    return Err(GraftError::configuration(format!(
+                        "Replacement path '{path}' escapes the pull target: {}",
+                        target_path.display()
+                    ))
+                    .into());
+                }
+                scoped
+            }
+            (ReplacementScope::Directory | ReplacementScope::Path, _) => graft_dir.to_path_buf(),
+        };
+        let root_str = root
+            .to_str()
+            .ok_or_else(|| GraftError::filesystem("Invalid directory path".to_owned()))?;
+
+        total_replacements = total_replacements.saturating_add(apply_graft_replacements(
+            system,
+            root_str,
+            slice::from_ref(replacement),
+            context,
+        )?);
+    }
+    Ok(total_replacements)
+}
+
+/// Process all graft definition files in the target directory.
+///
+/// The file name defaults to `.graft.yaml` and can be changed with
+/// `graftFileName` / `--graft-file-name`.
 #[expect(
     clippy::arithmetic_side_effects,
     reason = "Simple counter increments on usize totals that cannot realistically overflow"
@@ -700,6 +2698,8 @@ fn process_graft_files(
     system: &dyn System,
     config: &Config,
     pull: &PullConfig,
+    warnings: &WarningSink,
+    options: &RunOptions,
 ) -> Result<GraftProcessingResult> {
     let target_path = Path::new(&pull.target);
 
@@ -709,90 +2709,111 @@ fn process_graft_files(
         return Ok(GraftProcessingResult::default());
     }
 
-    // Discover all .graft.yaml files
-    let discovered_grafts = discover_graft_files(system, target_path)
-        .context("Failed to discover .graft.yaml files")?;
+    let graft_file_name = config.graft_file_name();
+
+    // Discover all graft files
+    let discovered_grafts = discover_graft_files_named(system, target_path, graft_file_name)
+        .with_context(|| format!("Failed to discover {graft_file_name} files"))?;
 
     if discovered_grafts.is_empty() {
-        // No .graft.yaml files found, nothing to do
+        // No graft files found, nothing to do
         return Ok(GraftProcessingResult::default());
     }
 
-    info!("  Found {} .graft.yaml file(s)", discovered_grafts.len());
+    info!(
+        "  Found {} {} file(s)",
+        discovered_grafts.len(),
+        graft_file_name
+    );
 
     let mut total_replacements = 0_usize;
     let mut total_commands = 0_usize;
 
     // Merge root and pull-level context
     let base_context = merge_context_values(config.context.clone(), pull.context.clone());
+    // Values captured by post-commands (`captureInto`), shared with the
+    // grafts processed after them
+    let mut captured_context = ContextValues::new();
 
-    // Process each .graft.yaml in order (root first, then children)
+    // Process each graft file in order (root first, then children)
     for discovered in &discovered_grafts {
-        debug!("Processing .graft.yaml at: {}", discovered.path.display());
+        debug!(
+            "Processing {} at: {}",
+            graft_file_name,
+            discovered.path.display()
+        );
 
-        // Load and parse .graft.yaml
+        // Load and parse the graft file
         let graft_config =
             GraftConfig::load_from_file(system, &discovered.path).with_context(|| {
                 format!(
-                    "Failed to load .graft.yaml from: {}",
+                    "Failed to load {} from: {}",
+                    graft_file_name,
                     discovered.path.display()
                 )
             })?;
 
-        // Build context for this graft (inherit from parent)
-        let graft_context = build_graft_context(discovered, &base_context);
-
-        // Validate context requirements
-        if graft_config.context.is_empty() {
-            // No context defined, apply replacements without validation
-            if !graft_config.replacements.is_empty() {
-                let replacements = apply_graft_replacements(
-                    system,
-                    discovered.directory.to_str().ok_or_else(|| {
-                        GraftError::filesystem("Invalid directory path".to_owned())
-                    })?,
-                    &graft_config.replacements,
-                    &graft_context,
-                )
-                .context("Failed to apply graft replacements")?;
+        // Build context for this graft (inherit from parent, then add what
+        // earlier grafts captured)
+        let graft_context = merge_context_values(
+            build_graft_context(discovered, &base_context),
+            captured_context.clone(),
+        );
 
-                total_replacements += replacements;
-            }
+        // Validate context requirements (a graft without definitions takes
+        // the inherited context as-is)
+        let context_values = if graft_config.context.is_empty() {
+            graft_context
         } else {
             let validated =
-                ValidatedContext::new(graft_config.context.clone(), graft_context.clone())
+                ValidatedContext::with_env(graft_config.context.clone(), graft_context, system)
                     .context("Context validation failed")?;
 
             debug!(
-                "Validated context for .graft.yaml at: {}",
+                "Validated context for {} at: {}",
+                graft_file_name,
                 discovered.directory.display()
             );
+            validated.values
+        };
 
-            // Apply graft replacements
-            if !graft_config.replacements.is_empty() {
-                let replacements = apply_graft_replacements(
-                    system,
-                    discovered.directory.to_str().ok_or_else(|| {
-                        GraftError::filesystem("Invalid directory path".to_owned())
-                    })?,
-                    &graft_config.replacements,
-                    &validated.values,
-                )
-                .context("Failed to apply graft replacements")?;
-
-                total_replacements += replacements;
-                debug!(
-                    "Applied {} replacements in {}",
-                    replacements,
-                    discovered.directory.display()
-                );
-            }
+        // Apply graft replacements
+        if !graft_config.replacements.is_empty() {
+            let graft_replacements = resolve_json_path_replacements(
+                system,
+                &discovered.directory,
+                &graft_config.replacements,
+            )?;
+            let replacements = apply_scoped_graft_replacements(
+                system,
+                &discovered.directory,
+                target_path,
+                &graft_replacements,
+                &context_values,
+                options,
+            )
+            .context("Failed to apply graft replacements")?;
+
+            total_replacements += replacements;
+            debug!(
+                "Applied {} replacements in {}",
+                replacements,
+                discovered.directory.display()
+            );
         }
 
         // Execute post-commands
         if !graft_config.post_commands.is_empty() {
-            let results = execute_post_commands(&graft_config.post_commands, &discovered.directory)
-                .context("Failed to execute post-commands")?;
+            let command_options = PostCommandOptions::new(system)
+                .with_clean_env(options.clean_env)
+                .with_output_limit(options.max_command_output)
+                .with_variables(CommandVariables::new(system, &context_values));
+            let results = execute_post_commands_with_options(
+                &graft_config.post_commands,
+                &discovered.directory,
+                &command_options,
+            )
+            .context("Failed to execute post-commands")?;
 
             total_commands += results.len();
             debug!(
@@ -803,22 +2824,30 @@ fn process_graft_files(
 
             // Log any command failures (but don't fail the operation)
             for result in results {
-                if !result.success {
-                    warn!(
-                        "Post-command failed in {}: {}",
-                        discovered.directory.display(),
-                        result.error.unwrap_or_else(|| "Unknown error".to_owned())
+                if result.success {
+                    if let Some(key) = result.capture_into {
+                        debug!("Captured post-command output into context '{key}'");
+                        captured_context
+                            .insert(key, Value::String(result.output.trim().to_owned()));
+                    }
+                } else {
+                    warnings.push(
+                        Some(&discovered.directory),
+                        &format!(
+                            "Post-command failed: {}",
+                            result.error.unwrap_or_else(|| "Unknown error".to_owned())
+                        ),
                     );
                 }
             }
         }
     }
 
-    // Cleanup: Delete all .graft.yaml files
-    let deleted =
-        cleanup_graft_files(system, target_path).context("Failed to cleanup .graft.yaml files")?;
+    // Cleanup: Delete all graft files
+    let deleted = cleanup_graft_files_named(system, target_path, graft_file_name)
+        .with_context(|| format!("Failed to cleanup {graft_file_name} files"))?;
 
-    debug!("Deleted {} .graft.yaml file(s)", deleted);
+    debug!("Deleted {} {} file(s)", deleted, graft_file_name);
 
     Ok(GraftProcessingResult {
         commands_executed: total_commands,
@@ -837,6 +2866,14 @@ fn merge_cli_args(config: &mut Config, args: &Args) -> Result<()> {
         config.tag = Some(tag.clone());
     }
 
+    if let Some(graft_file_name) = args.graft_file_name.as_ref() {
+        config.graft_file_name = Some(graft_file_name.clone());
+    }
+
+    if let Some(clone_filter) = args.clone_filter.as_ref() {
+        config.clone_filter = Some(clone_filter.clone());
+    }
+
     // Merge context from CLI arguments
     if !args.context.is_empty() || !args.context_json.is_empty() {
         let cli_context = args.parse_context()?;
@@ -861,26 +2898,22 @@ fn create_pulls_from_cli(pull_args: &PullArgs) -> Result<Vec<PullConfig>> {
         return Ok(pulls);
     }
 
-    // Verify that targets match sources count
-    if pull_args.targets.len() != count {
-        return Err(GraftError::configuration(format!(
-            "Mismatch: {} sources specified but {} targets. Each source must have a corresponding target",
-            count, pull_args.targets.len()
-        )).into());
-    }
+    check_cli_pull_counts(pull_args)?;
 
     for (source, target) in pull_args.sources.iter().zip(pull_args.targets.iter()) {
         let idx = pulls.len();
-        let pull = PullConfig {
+        let mut pull = PullConfig {
             source: source.clone(),
             target: target.clone(),
+            description: None,
             pull_type: pull_args
                 .types
                 .get(idx)
                 .cloned()
-                .unwrap_or_else(|| "directory".to_owned()),
+                .unwrap_or_else(|| "auto".to_owned()),
             repository: pull_args.repositories.get(idx).cloned(),
             tag: pull_args.tags.get(idx).cloned(),
+            tag_pattern: None,
             reset: pull_args.resets.get(idx).copied().unwrap_or(false),
             require_clean_target: pull_args
                 .require_clean_targets
@@ -888,55 +2921,172 @@ fn create_pulls_from_cli(pull_args: &PullArgs) -> Result<Vec<PullConfig>> {
                 .copied()
                 .unwrap_or(true),
             must_succeed: pull_args.must_succeeds.get(idx).copied().unwrap_or(true),
-            commands: pull_args
-                .commands
+            commands: commands_for_pull(pull_args, idx),
+            replacements: parse_replacements_for_pull(pull_args, idx)?,
+            context: parse_pull_context(
+                &values_for_pull(&pull_args.contexts, &pull_args.context_pulls, idx),
+                &values_for_pull(&pull_args.context_jsons, &pull_args.context_json_pulls, idx),
+            )
+            .with_context(|| format!("Invalid context for CLI pull #{}", idx.saturating_add(1)))?,
+            template_engine: pull_args
+                .template_engines
+                .get(idx)
+                .cloned()
+                .unwrap_or_else(|| "none".to_owned()),
+            include_hidden: pull_args.include_hiddens.get(idx).copied().unwrap_or(true),
+            respect_gitignore: pull_args
+                .respect_gitignores
                 .get(idx)
-                .map_or_else(Vec::new, |cmd_str| {
-                    cmd_str
+                .copied()
+                .unwrap_or(false),
+            replace_filenames: pull_args
+                .replace_filenames
+                .get(idx)
+                .copied()
+                .unwrap_or(false),
+            strip_components: pull_args.strip_components.get(idx).copied().unwrap_or(0),
+            expect_files: pull_args
+                .expect_files
+                .get(idx)
+                .map_or_else(Vec::new, |paths| {
+                    paths
                         .split(',')
                         .map(|segment| segment.trim().to_owned())
+                        .filter(|segment| !segment.is_empty())
                         .collect()
                 }),
-            replacements: parse_replacements_for_pull(pull_args, idx)?,
-            context: HashMap::new(),
+            use_groups: Vec::new(),
+            pull_replacement_scope: PullReplacementScope::All,
+            encoding: None,
+            sparse_patterns: Vec::new(),
+            sources: Vec::new(),
         };
 
+        for warning in pull.validate_paths()? {
+            warn!("Pull #{}: {warning}", idx.saturating_add(1));
+        }
         pulls.push(pull);
     }
 
     Ok(pulls)
 }
 
-/// Parse replacements for a specific pull index.
-/// Replacements are matched to pulls based on occurrence order.
-fn parse_replacements_for_pull(
-    pull_args: &PullArgs,
-    _pull_index: usize,
-) -> Result<Vec<ReplacementConfig>> {
-    let mut replacements = Vec::new();
+/// Check that per-pull CLI flags line up with `--pull-source`.
+///
+/// Source/target mismatches are reported with the pairing tixgraft would
+/// have used, so the missing argument is easy to spot.
+fn check_cli_pull_counts(pull_args: &PullArgs) -> Result<()> {
+    let count = pull_args.sources.len();
+
+    // Verify that targets match sources count
+    if pull_args.targets.len() != count {
+        return Err(GraftError::configuration(format!(
+            "Mismatch: {} sources specified but {} targets. Each source must have a corresponding target\n\nPairing by position:\n{}",
+            count,
+            pull_args.targets.len(),
+            format_cli_pairing(pull_args)
+        ))
+        .into());
+    }
 
-    // Strategy: Each replacement is associated with the pull at the same index
-    // If we have more replacements than pulls, extras are ignored
-    // If we have fewer replacements, later pulls get no replacements
+    let per_pull_flags = [
+        ("--pull-type", pull_args.types.len()),
+        ("--pull-repository", pull_args.repositories.len()),
+        ("--pull-tag", pull_args.tags.len()),
+    ];
+    for (flag, flag_count) in per_pull_flags {
+        if flag_count > count {
+            return Err(GraftError::configuration(format!(
+                "Mismatch: {flag} given {flag_count} times but only {count} sources specified. Per-pull flags apply by position, one per --pull-source"
+            ))
+            .into());
+        }
+    }
 
-    // For now, we'll use a simple strategy: all replacements in the Vec
-    // are distributed across pulls in the order they appear
-    // This is a limitation of the current CLI structure
+    Ok(())
+}
 
-    // Better approach: group replacements by tracking which source they follow
-    // For MVP, let's assume all replacements apply to all pulls (not ideal but simple)
-    // TODO: Implement proper grouping based on argument order
+/// Render the position-based source -> target pairing of CLI pulls.
+fn format_cli_pairing(pull_args: &PullArgs) -> String {
+    let rows = pull_args.sources.len().max(pull_args.targets.len());
+    (0..rows)
+        .map(|idx| {
+            let source = pull_args
+                .sources
+                .get(idx)
+                .map_or("(missing --pull-source)", String::as_str);
+            let target = pull_args
+                .targets
+                .get(idx)
+                .map_or("(missing --pull-target)", String::as_str);
+            format!("  #{}: {source} \u{2192} {target}", idx.saturating_add(1))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    // For now, since we can't easily track argument order in clap,
-    // we'll apply ALL replacements to ALL pulls when using CLI
-    // This is a known limitation that should be documented
+/// The CLI commands that belong to the pull at `pull_index`.
+///
+/// Each `--pull-commands` is one whole command for the `--pull-source` it
+/// follows; without that ordering information they are matched by position.
+/// `--split-pull-commands` restores the old comma-separated form.
+fn commands_for_pull(pull_args: &PullArgs, pull_index: usize) -> Vec<String> {
+    let ordered = pull_args.command_pulls.len() == pull_args.commands.len();
+    let commands = pull_args
+        .commands
+        .iter()
+        .enumerate()
+        .filter(|&(position, _)| {
+            if ordered {
+                pull_args.command_pulls.get(position) == Some(&pull_index)
+            } else {
+                position == pull_index
+            }
+        })
+        .map(|(_, command)| command);
 
-    for repl_str in &pull_args.replacements {
-        let replacement = parse_replacement_string(repl_str)?;
-        replacements.push(replacement);
+    if pull_args.split_commands {
+        commands
+            .flat_map(|command| command.split(','))
+            .map(|segment| segment.trim().to_owned())
+            .collect()
+    } else {
+        commands.cloned().collect()
     }
+}
+
+/// The CLI `values` that belong to the pull at `pull_index`, given the pull
+/// each value belongs to in `owners`.
+///
+/// Without that ordering information, every value applies to every pull.
+fn values_for_pull(values: &[String], owners: &[usize], pull_index: usize) -> Vec<String> {
+    let ordered = owners.len() == values.len();
+    values
+        .iter()
+        .enumerate()
+        .filter(|&(position, _)| !ordered || owners.get(position) == Some(&pull_index))
+        .map(|(_, value)| value.clone())
+        .collect()
+}
 
-    Ok(replacements)
+/// Parse the CLI replacements that belong to the pull at `pull_index`.
+///
+/// Each `--pull-replacement` belongs to the `--pull-source` it follows.
+/// Without that ordering information, every replacement applies to every pull.
+fn parse_replacements_for_pull(
+    pull_args: &PullArgs,
+    pull_index: usize,
+) -> Result<Vec<ReplacementConfig>> {
+    let ordered = pull_args.replacement_pulls.len() == pull_args.replacements.len();
+    pull_args
+        .replacements
+        .iter()
+        .enumerate()
+        .filter(|&(position, _)| {
+            !ordered || pull_args.replacement_pulls.get(position) == Some(&pull_index)
+        })
+        .map(|(_, repl_str)| parse_replacement_string(repl_str))
+        .collect()
 }
 
 /// Parse a single replacement string in format "SOURCE=TARGET" or "SOURCE=env:VAR".
@@ -951,14 +3101,28 @@ fn parse_replacement_string(input: &str) -> Result<ReplacementConfig> {
 
     if let Some(env_var) = target_part.strip_prefix("env:") {
         Ok(ReplacementConfig {
+            kind: ReplacementKind::Text,
+            reindent: None,
+            require_contains: None,
             source,
+            strip_prefix: None,
+            strip_suffix: None,
             target: None,
+            target_from_file: None,
+            transform: None,
             value_from_env: Some(env_var.to_owned()),
         })
     } else {
         Ok(ReplacementConfig {
+            kind: ReplacementKind::Text,
+            reindent: None,
+            require_contains: None,
             source,
+            strip_prefix: None,
+            strip_suffix: None,
             target: Some(target_part.to_owned()),
+            target_from_file: None,
+            transform: None,
             value_from_env: None,
         })
     }
@@ -981,6 +3145,13 @@ pub fn build_config_from_args(args: &Args) -> Result<Config> {
         pulls: Vec::new(),
         children: Vec::new(),
         process_children_first: false,
+        graft_file_name: args.graft_file_name.clone(),
+        post_pull: Vec::new(),
+        clone_filter: args.clone_filter.clone(),
+        channels: HashMap::new(),
+        repository_defaults: HashMap::new(),
+        replacement_groups: HashMap::new(),
+        version: None,
     };
 
     // Convert CLI pulls to config pulls
@@ -1018,6 +3189,13 @@ pub fn build_merged_config(args: &Args, system: &dyn System) -> Result<Config> {
             pulls: Vec::new(),
             children: Vec::new(),
             process_children_first: false,
+            graft_file_name: None,
+            post_pull: Vec::new(),
+            clone_filter: None,
+            channels: HashMap::new(),
+            repository_defaults: HashMap::new(),
+            replacement_groups: HashMap::new(),
+            version: None,
         }
     };
 
@@ -1033,6 +3211,147 @@ pub fn build_merged_config(args: &Args, system: &dyn System) -> Result<Config> {
     Ok(config)
 }
 
+/// Collect the resolved file-system targets of `config` and, recursively, of
+/// its children into `targets`, for `--require-clean`.
+///
+/// Children that exceed the depth limit or form a cycle are skipped here;
+/// the run itself reports them.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A target cannot be resolved
+/// - A child config cannot be loaded
+#[expect(
+    clippy::arithmetic_side_effects,
+    reason = "Depth increment cannot overflow for practical recursion depths"
+)]
+fn collect_pull_targets(
+    system: &dyn System,
+    config: &Config,
+    config_dir: &Path,
+    visited: &mut HashSet<PathBuf>,
+    depth: usize,
+    options: &RunOptions,
+    targets: &mut Vec<String>,
+) -> Result<()> {
+    let canonical = fs::canonicalize(config_dir).unwrap_or_else(|_| config_dir.to_path_buf());
+    if depth > MAX_CHILDREN_DEPTH || !visited.insert(canonical) {
+        return Ok(());
+    }
+
+    let resolved = resolve_pull_targets(config, config_dir, options)?;
+    targets.extend(
+        resolved
+            .pulls
+            .into_iter()
+            .map(|pull| pull.target)
+            .filter(|target| !is_stdout_target(target)),
+    );
+    for child_path_str in &config.children {
+        let child_config_path = config_dir.join(child_path_str);
+        let child_dir = child_config_path.parent().unwrap_or_else(|| Path::new("."));
+        let child_config = Config::load_from_file(system, &child_config_path.to_string_lossy())
+            .with_context(|| format!("Error in child '{child_path_str}': failed to load config"))?;
+        collect_pull_targets(
+            system,
+            &child_config,
+            child_dir,
+            visited,
+            depth + 1,
+            options,
+            targets,
+        )?;
+    }
+    Ok(())
+}
+
+/// Check that none of `targets` has uncommitted changes (`--require-clean`).
+///
+/// Each target is checked with `git status --porcelain` run from its nearest
+/// existing ancestor directory, so that targets in different repositories
+/// are each checked against their own working tree. Targets outside any git
+/// repository pass.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `git` cannot be run
+/// - `git status` fails for a target inside a git repository
+/// - Modified, staged or untracked files are found under a target
+#[inline]
+pub fn check_working_tree_clean(
+    system: &dyn System,
+    runner: &dyn GitRunner,
+    targets: &[String],
+) -> Result<()> {
+    let mut dirty_files = Vec::new();
+    for target in targets {
+        let target_path = Path::new(target);
+        let mut found = None;
+        for ancestor in target_path.ancestors() {
+            let dir = if ancestor.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                ancestor
+            };
+            if system.is_dir(dir)? {
+                found = Some((dir, ancestor));
+                break;
+            }
+        }
+        let Some((git_dir, ancestor)) = found else {
+            debug!("No existing directory above '{target}', skipping --require-clean");
+            continue;
+        };
+
+        // Limit the status to the target, relative to the directory git runs in
+        let pathspec = target_path
+            .strip_prefix(ancestor)
+            .ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .map_or_else(|| ".".to_owned(), |relative| relative.display().to_string());
+        let output = runner
+            .run(&GitInvocation::new(
+                ["status", "--porcelain", "--", &pathspec],
+                Some(git_dir),
+            ))
+            .context("Failed to run git status for --require-clean")?;
+        if !output.success {
+            if output.stderr.contains("not a git repository") {
+                debug!(
+                    "Target '{target}' is not inside a git repository, skipping --require-clean"
+                );
+                continue;
+            }
+            return Err(GraftError::git(format!(
+                "git status failed for '{target}' (--require-clean): {}",
+                output.stderr.trim()
+            ))
+            .into());
+        }
+
+        dirty_files.extend(
+            output
+                .stdout
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| format!("  {line}")),
+        );
+    }
+    if dirty_files.is_empty() {
+        return Ok(());
+    }
+
+    Err(GraftError::filesystem(format!(
+        "The working tree has uncommitted changes under pull targets (--require-clean). \
+         Commit or stash them before pulling.\n\
+         Dirty files:\n{}",
+        dirty_files.join("\n")
+    ))
+    .into())
+}
+
 /// Check that the target path has no uncommitted git changes.
 ///
 /// Silently passes if the target does not exist, is not inside a git
@@ -1064,12 +3383,12 @@ fn check_target_is_clean(target: &str) -> Result<()> {
             .unwrap_or_else(|| Path::new("."))
             .to_path_buf()
     };
-    let Ok(output) = Command::new("git")
-        .arg("-C")
-        .arg(&git_dir)
-        .args(["status", "--porcelain", "--", target])
-        .output()
-    else {
+    let Ok(output) = child_output(Command::new("git").arg("-C").arg(&git_dir).args([
+        "status",
+        "--porcelain",
+        "--",
+        target,
+    ])) else {
         debug!(
             "Could not run git status for target '{}', skipping clean check",
             target
@@ -4,12 +4,18 @@ use crate::cli::ReplacementConfig;
 use crate::config::context::{ContextValues, value_to_string};
 use crate::config::graft_yaml::GraftReplacement;
 use crate::error::GraftError;
+use crate::operations::structured::{apply_structured_replacement, read_key_path};
+use crate::operations::transform::{apply_transform, strip_affixes};
+use crate::utils::encoding::TextEncoding;
 use crate::utils::fs::is_binary_file;
 use anyhow::{Context as _, Result};
 use os_shim::System;
 use regex::Regex;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::io::Read as _;
 use std::path::{Path, PathBuf};
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Preview information for a replacement.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -23,8 +29,78 @@ pub struct ReplacementPreview {
     pub search_pattern: String,
 }
 
+/// What one replacement changed (`--replacements-report`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReplacementCount {
+    /// Number of files the replacement changed.
+    pub files: usize,
+    /// Number of matches replaced (lines removed for `deleteLines`, files
+    /// changed for `json`/`yaml`).
+    pub occurrences: usize,
+    /// The replacement's `source`.
+    pub source: String,
+}
+
+/// A replacement paired with its resolved value (for dry-run dumps).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResolvedReplacement {
+    /// Whether the value looks like a secret and must not be displayed.
+    pub redacted: bool,
+    /// The pattern being searched for.
+    pub source: String,
+    /// The resolved replacement value.
+    pub value: String,
+}
+
+impl ResolvedReplacement {
+    /// The value to display: the resolved value, or `***` when redacted.
+    #[must_use]
+    #[inline]
+    pub fn display_value(&self) -> &str {
+        if self.redacted { "***" } else { &self.value }
+    }
+}
+
+/// A text replacement with its value resolved, ready to apply to files.
+#[derive(Debug, Clone, Copy)]
+struct TextEdit<'value> {
+    /// Encoding files are read and written in (UTF-8 when unset).
+    encoding: Option<TextEncoding>,
+    /// The pattern being searched for.
+    pattern: &'value str,
+    /// Re-indent multi-line values (see [`replace_reindented`]).
+    reindent: Option<usize>,
+    /// Marker a file must contain to be edited (`requireContains`).
+    require_contains: Option<&'value str>,
+    /// The value that replaces the pattern.
+    value: &'value str,
+}
+
+impl<'value> TextEdit<'value> {
+    /// The edit described by a config replacement resolved to `value`.
+    fn from_config(
+        replacement: &'value ReplacementConfig,
+        value: &'value str,
+        encoding: Option<TextEncoding>,
+    ) -> Self {
+        Self {
+            encoding,
+            pattern: &replacement.source,
+            reindent: replacement.reindent,
+            require_contains: replacement.require_contains.as_deref(),
+            value,
+        }
+    }
+}
+
 /// Apply text replacements to files in the target directory.
 ///
+/// Replacements run in declaration order. A replacement whose source and
+/// resolved value match an earlier one is skipped, so a replacement declared
+/// twice (e.g. in both config and CLI) is applied once.
+///
 /// # Errors
 ///
 /// Returns an error if:
@@ -35,6 +111,28 @@ pub fn apply_replacements(
     system: &dyn System,
     target_dir: &str,
     replacements: &[ReplacementConfig],
+) -> Result<usize> {
+    apply_replacements_with_encoding(system, target_dir, replacements, None)
+}
+
+/// Apply text replacements to files in the target directory, reading and
+/// writing them in `encoding` (UTF-8 when `None`).
+///
+/// With an encoding, files are not skipped as binary; files that do not
+/// decode in it are. Only text replacements use the encoding.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The target directory does not exist
+/// - The replacements cannot be applied
+/// - A replaced file cannot be represented in `encoding`
+#[inline]
+pub fn apply_replacements_with_encoding(
+    system: &dyn System,
+    target_dir: &str,
+    replacements: &[ReplacementConfig],
+    encoding: Option<TextEncoding>,
 ) -> Result<usize> {
     if replacements.is_empty() {
         return Ok(0);
@@ -48,17 +146,175 @@ pub fn apply_replacements(
         .into());
     }
 
-    let mut total_replacements: usize = 0;
+    Ok(total_files(&apply_replacements_counted(
+        system,
+        target_path,
+        replacements,
+        encoding,
+    )?))
+}
+
+/// Apply replacements to `target_path` (a file, or every file below a
+/// directory) in `encoding`, reporting what each replacement changed.
+///
+/// Ordering and deduplication follow [`apply_replacements`]; a skipped
+/// duplicate gets no entry.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The target does not exist
+/// - The replacements cannot be applied
+/// - A replaced file cannot be represented in `encoding`
+#[inline]
+pub fn apply_replacements_counted(
+    system: &dyn System,
+    target_path: &Path,
+    replacements: &[ReplacementConfig],
+    encoding: Option<TextEncoding>,
+) -> Result<Vec<ReplacementCount>> {
+    if !system.exists(target_path)? {
+        return Err(GraftError::filesystem(format!(
+            "Target does not exist: {}",
+            target_path.display()
+        ))
+        .into());
+    }
+
+    let mut counts = Vec::with_capacity(replacements.len());
+    let mut applied = HashSet::new();
 
     // Process each replacement
     for replacement in replacements {
         let replacement_value = get_replacement_value(system, replacement)?;
-        let files_processed =
-            apply_single_replacement(system, target_path, &replacement.source, &replacement_value)?;
-        total_replacements = total_replacements.saturating_add(files_processed);
+        if !applied.insert((replacement.source.clone(), replacement_value.clone())) {
+            debug!("Skipping duplicate replacement: {}", replacement.source);
+            continue;
+        }
+        let (files, occurrences) = if replacement.kind.is_text() {
+            apply_text_edit(
+                system,
+                target_path,
+                &TextEdit::from_config(replacement, &replacement_value, encoding),
+            )?
+        } else if replacement.kind.is_delete_lines() {
+            delete_lines_below(system, target_path, &replacement.source)?
+        } else {
+            let files = apply_structured_replacement(
+                system,
+                target_path,
+                replacement.kind,
+                &replacement.source,
+                &replacement_value,
+            )?;
+            (files, files)
+        };
+        counts.push(ReplacementCount {
+            files,
+            occurrences,
+            source: replacement.source.clone(),
+        });
     }
 
-    Ok(total_replacements)
+    Ok(counts)
+}
+
+/// Apply text replacements to a single target file (file pulls).
+///
+/// Unlike [`apply_replacements`], this never looks beyond `target_file`, so
+/// sibling files in the same directory are left untouched. Ordering and
+/// deduplication follow [`apply_replacements`].
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The target file does not exist or is not a file
+/// - The replacements cannot be applied
+#[inline]
+pub fn apply_replacements_to_file(
+    system: &dyn System,
+    target_file: &Path,
+    replacements: &[ReplacementConfig],
+) -> Result<usize> {
+    apply_replacements_to_file_with_encoding(system, target_file, replacements, None)
+}
+
+/// Apply text replacements to a single target file, reading and writing it
+/// in `encoding` (UTF-8 when `None`); see [`apply_replacements_with_encoding`].
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The target file does not exist or is not a file
+/// - The replacements cannot be applied
+/// - The replaced file cannot be represented in `encoding`
+#[inline]
+pub fn apply_replacements_to_file_with_encoding(
+    system: &dyn System,
+    target_file: &Path,
+    replacements: &[ReplacementConfig],
+    encoding: Option<TextEncoding>,
+) -> Result<usize> {
+    if replacements.is_empty() {
+        return Ok(0);
+    }
+
+    if !system.is_file(target_file)? {
+        return Err(GraftError::filesystem(format!(
+            "Target file does not exist: {}",
+            target_file.display()
+        ))
+        .into());
+    }
+
+    Ok(total_files(&apply_replacements_counted(
+        system,
+        target_file,
+        replacements,
+        encoding,
+    )?))
+}
+
+/// Remove every line containing `pattern` from `target_path` (a file, or
+/// every text file below a directory).
+///
+/// Returns the number of lines removed.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A directory cannot be read
+/// - A file cannot be read or written
+#[inline]
+pub fn apply_delete_lines(system: &dyn System, target_path: &Path, pattern: &str) -> Result<usize> {
+    Ok(delete_lines_below(system, target_path, pattern)?.1)
+}
+
+/// Remove every line of `content` that contains `pattern`.
+///
+/// Returns the new content and the number of lines removed. Whether the
+/// content ends with a newline is preserved, also when its last line is
+/// removed.
+#[must_use]
+#[inline]
+pub fn delete_matching_lines(content: &str, pattern: &str) -> (String, usize) {
+    let mut kept = String::with_capacity(content.len());
+    let mut removed: usize = 0;
+    for line in content.split_inclusive('\n') {
+        if line.contains(pattern) {
+            removed = removed.saturating_add(1);
+        } else {
+            kept.push_str(line);
+        }
+    }
+
+    if !content.ends_with('\n') && kept.ends_with('\n') {
+        kept.pop();
+        if kept.ends_with('\r') {
+            kept.pop();
+        }
+    }
+    (kept, removed)
 }
 
 /// Apply graft replacements (supports context) to files in the target directory.
@@ -149,67 +405,322 @@ pub fn apply_single_replacement(
     search_pattern: &str,
     replacement_value: &str,
 ) -> Result<usize> {
-    let mut files_processed: usize = 0;
+    apply_single_replacement_with_reindent(
+        system,
+        target_path,
+        search_pattern,
+        replacement_value,
+        None,
+    )
+}
+
+/// Apply a single replacement to all text files in the target directory,
+/// optionally re-indenting multi-line values (see [`replace_reindented`]).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The replacements cannot be applied
+#[inline]
+pub fn apply_single_replacement_with_reindent(
+    system: &dyn System,
+    target_path: &Path,
+    search_pattern: &str,
+    replacement_value: &str,
+    reindent: Option<usize>,
+) -> Result<usize> {
+    let (files, _) = apply_text_edit(
+        system,
+        target_path,
+        &TextEdit {
+            encoding: None,
+            pattern: search_pattern,
+            reindent,
+            require_contains: None,
+            value: replacement_value,
+        },
+    )?;
+    Ok(files)
+}
 
+/// Apply a text edit to `target_path` (a file, or every text file below a
+/// directory), returning the number of files changed and matches replaced.
+fn apply_text_edit(
+    system: &dyn System,
+    target_path: &Path,
+    edit: &TextEdit<'_>,
+) -> Result<(usize, usize)> {
     if system.is_file(target_path)? {
         // Single file case
-        if apply_replacement_to_file(system, target_path, search_pattern, replacement_value)? {
-            files_processed = files_processed.saturating_add(1);
-        }
-    } else if system.is_dir(target_path)? {
+        let occurrences = apply_replacement_to_file(system, target_path, edit)?;
+        return Ok((usize::from(occurrences > 0), occurrences));
+    }
+    if system.is_dir(target_path)? {
         // Directory case - recursively walk all files using System trait
-        files_processed = files_processed.saturating_add(walk_and_apply(
-            system,
-            target_path,
-            search_pattern,
-            replacement_value,
-        )?);
-    } else {
-        debug!("Skipping file: {}", target_path.display());
+        return walk_and_apply(system, target_path, edit);
     }
-    Ok(files_processed)
+    debug!("Skipping file: {}", target_path.display());
+    Ok((0, 0))
+}
+
+/// Fail if any replacement resolves to an empty value
+/// (`--no-empty-replacements`), naming the replacement's source.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A replacement value cannot be resolved
+/// - A replacement value is empty
+#[inline]
+pub fn check_no_empty_replacements(
+    system: &dyn System,
+    replacements: &[ReplacementConfig],
+) -> Result<()> {
+    for replacement in replacements {
+        if replacement.kind.is_delete_lines() {
+            continue;
+        }
+        let value = get_replacement_value(system, replacement)?;
+        ensure_not_empty(&replacement.source, &value)?;
+    }
+    Ok(())
+}
+
+/// Fail if any graft replacement resolves to an empty value
+/// (`--no-empty-replacements`), naming the replacement's source.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A replacement value cannot be resolved
+/// - A replacement value is empty
+#[inline]
+pub fn check_no_empty_graft_replacements(
+    system: &dyn System,
+    replacements: &[GraftReplacement],
+    context: &ContextValues,
+) -> Result<()> {
+    for replacement in replacements {
+        let value = get_graft_replacement_value(system, replacement, context)?;
+        ensure_not_empty(&replacement.source, &value)?;
+    }
+    Ok(())
+}
+
+/// Fail if any replacement resolves to its own source (`--strict-replacements`),
+/// naming the replacement's source.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A replacement value cannot be resolved
+/// - A replacement value equals its source
+#[inline]
+pub fn check_no_identity_replacements(
+    system: &dyn System,
+    replacements: &[ReplacementConfig],
+) -> Result<()> {
+    for replacement in replacements {
+        if replacement.kind.is_delete_lines() {
+            continue;
+        }
+        let value = raw_replacement_value(system, replacement)
+            .map(|value| finish_replacement_value(value, replacement))?;
+        ensure_not_identity(&replacement.source, &value)?;
+    }
+    Ok(())
+}
+
+/// Fail if any graft replacement resolves to its own source
+/// (`--strict-replacements`), naming the replacement's source.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A replacement value cannot be resolved
+/// - A replacement value equals its source
+#[inline]
+pub fn check_no_identity_graft_replacements(
+    system: &dyn System,
+    replacements: &[GraftReplacement],
+    context: &ContextValues,
+) -> Result<()> {
+    for replacement in replacements {
+        let value = raw_graft_replacement_value(system, replacement, context)
+            .map(|value| finish_graft_replacement_value(value, replacement))?;
+        ensure_not_identity(&replacement.source, &value)?;
+    }
+    Ok(())
+}
+
+/// Warn once about each replacement that resolves to its own source, the
+/// non-strict counterpart of [`check_no_identity_replacements`].
+///
+/// Replacements whose value cannot be resolved are skipped; applying them
+/// reports the error.
+#[inline]
+pub fn warn_identity_replacements(system: &dyn System, replacements: &[ReplacementConfig]) {
+    for replacement in replacements {
+        if replacement.kind.is_delete_lines() {
+            continue;
+        }
+        if let Ok(value) = raw_replacement_value(system, replacement)
+            .map(|value| finish_replacement_value(value, replacement))
+        {
+            warn_if_identity(&replacement.source, &value);
+        }
+    }
+}
+
+/// Warn once about each graft replacement that resolves to its own source,
+/// the non-strict counterpart of [`check_no_identity_graft_replacements`].
+///
+/// Replacements whose value cannot be resolved are skipped; applying them
+/// reports the error.
+#[inline]
+pub fn warn_identity_graft_replacements(
+    system: &dyn System,
+    replacements: &[GraftReplacement],
+    context: &ContextValues,
+) {
+    for replacement in replacements {
+        if let Ok(value) = raw_graft_replacement_value(system, replacement, context)
+            .map(|value| finish_graft_replacement_value(value, replacement))
+        {
+            warn_if_identity(&replacement.source, &value);
+        }
+    }
+}
+
+/// Error when the resolved `value` of the replacement for `source` is
+/// `source` itself.
+fn ensure_not_identity(source: &str, value: &str) -> Result<()> {
+    if value == source {
+        return Err(GraftError::configuration(format!(
+            "Replacement '{source}' resolves to its own source, so it changes nothing (--strict-replacements)"
+        ))
+        .into());
+    }
+    Ok(())
 }
 
-/// Get the replacement value from either target or environment variable.
+/// Error when the resolved `value` of the replacement for `source` is empty.
+fn ensure_not_empty(source: &str, value: &str) -> Result<()> {
+    if value.is_empty() {
+        return Err(GraftError::configuration(format!(
+            "Replacement '{source}' resolved to an empty value (--no-empty-replacements)"
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Get the replacement value from a target, a file, or an environment
+/// variable, with the replacement's `transform` applied.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - The replacement does not specify exactly one of target, valueFromEnv
+/// - The replacement does not specify exactly one of target, targetFromFile, valueFromEnv
+/// - The `targetFromFile` file cannot be read
 #[inline]
 pub fn get_replacement_value(
     system: &dyn System,
     replacement: &ReplacementConfig,
 ) -> Result<String> {
+    raw_replacement_value(system, replacement)
+        .map(|value| finish_replacement_value(value, replacement))
+}
+
+/// Apply a replacement's `stripPrefix`/`stripSuffix` and then its
+/// `transform` to its raw `value`.
+fn finish_replacement_value(value: String, replacement: &ReplacementConfig) -> String {
+    let stripped = strip_affixes(
+        &value,
+        replacement.strip_prefix.as_deref(),
+        replacement.strip_suffix.as_deref(),
+    );
+    apply_transform(stripped, replacement.transform)
+}
+
+/// Resolve the raw (untransformed) value of a replacement.
+fn raw_replacement_value(system: &dyn System, replacement: &ReplacementConfig) -> Result<String> {
+    // Deleting lines needs no value
+    if replacement.kind.is_delete_lines() {
+        return Ok(String::new());
+    }
+
     match (
         replacement.target.as_ref(),
+        replacement.target_from_file.as_ref(),
         replacement.value_from_env.as_ref(),
     ) {
-        (Some(target), None) => Ok(target.clone()),
-        (None, Some(env_var)) => system.env_var(env_var).map_err(|err| {
+        (Some(target), None, None) => Ok(target.clone()),
+        (None, Some(path), None) => system.read_to_string(Path::new(path)).map_err(|err| {
+            GraftError::configuration(format!(
+                "Failed to read targetFromFile '{path}' for replacement of '{}': {err}",
+                replacement.source
+            ))
+            .into()
+        }),
+        (None, None, Some(env_var)) => system.env_var(env_var).map_err(|err| {
             GraftError::configuration(format!(
                 "Environment variable '{env_var}' is not set. Error: {err}"
             ))
             .into()
         }),
         _ => Err(GraftError::configuration(
-            "Replacement must specify exactly one of 'target' or 'valueFromEnv'".to_owned(),
+            "Replacement must specify exactly one of 'target', 'targetFromFile' or 'valueFromEnv'"
+                .to_owned(),
         )
         .into()),
     }
 }
 
-/// Get the replacement value from a `GraftReplacement` (supports context, env, or static).
+/// Get the replacement value from a `GraftReplacement` (supports context, env, or static),
+/// with the replacement's `transform` applied.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - The replacement does not specify exactly one of target, valueFromEnv, or valueFromContext
+/// - The replacement does not specify exactly one of target, valueFromEnv, valueFromContext, or valueFromJsonPath
+/// - The replacement uses valueFromJsonPath (see [`resolve_json_path_replacements`])
 #[inline]
 pub fn get_graft_replacement_value(
     system: &dyn System,
     replacement: &GraftReplacement,
     context: &ContextValues,
+) -> Result<String> {
+    raw_graft_replacement_value(system, replacement, context)
+        .map(|value| finish_graft_replacement_value(value, replacement))
+}
+
+/// Warn when the resolved `value` of the replacement for `source` is
+/// `source` itself, which usually means the value was never filled in.
+fn warn_if_identity(source: &str, value: &str) {
+    if value == source {
+        warn!(
+            "Replacement '{source}' resolves to its own source and changes nothing; did you forget to set its value?"
+        );
+    }
+}
+
+/// Apply a graft replacement's `stripPrefix`/`stripSuffix` and then its
+/// `transform` to its raw `value`.
+fn finish_graft_replacement_value(value: String, replacement: &GraftReplacement) -> String {
+    let stripped = strip_affixes(
+        &value,
+        replacement.strip_prefix.as_deref(),
+        replacement.strip_suffix.as_deref(),
+    );
+    apply_transform(stripped, replacement.transform)
+}
+
+/// Resolve the raw (untransformed) value of a `GraftReplacement`.
+fn raw_graft_replacement_value(
+    system: &dyn System,
+    replacement: &GraftReplacement,
+    context: &ContextValues,
 ) -> Result<String> {
     let mut sources = 0_i32;
     if replacement.target.is_some() {
@@ -221,10 +732,13 @@ pub fn get_graft_replacement_value(
     if replacement.value_from_context.is_some() {
         sources = sources.saturating_add(1_i32);
     }
+    if replacement.value_from_json_path.is_some() {
+        sources = sources.saturating_add(1_i32);
+    }
 
     if sources != 1_i32 {
         return Err(GraftError::configuration(format!(
-            "Replacement for '{}' must specify exactly one of: target, valueFromEnv, or valueFromContext",
+            "Replacement for '{}' must specify exactly one of: target, valueFromEnv, valueFromContext, or valueFromJsonPath",
             replacement.source
         ))
         .into());
@@ -259,6 +773,14 @@ pub fn get_graft_replacement_value(
         });
     }
 
+    if let Some(json_path) = replacement.value_from_json_path.as_ref() {
+        return Err(GraftError::configuration(format!(
+            "valueFromJsonPath ({}: {}) for '{}' is read relative to its graft's directory and cannot be resolved here",
+            json_path.file, json_path.path, replacement.source
+        ))
+        .into());
+    }
+
     Err(GraftError::configuration(format!(
         "No replacement value specified for '{}'",
         replacement.source
@@ -266,6 +788,45 @@ pub fn get_graft_replacement_value(
     .into())
 }
 
+/// Resolve every `valueFromJsonPath` replacement by reading its file,
+/// relative to `graft_dir`, and turn it into a static `target`.
+///
+/// Replacements that set another value source as well are left untouched,
+/// so they still fail the exactly-one-source check.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A file does not exist, cannot be read or does not parse
+/// - A key path does not exist in its file
+#[inline]
+pub fn resolve_json_path_replacements(
+    system: &dyn System,
+    graft_dir: &Path,
+    replacements: &[GraftReplacement],
+) -> Result<Vec<GraftReplacement>> {
+    replacements
+        .iter()
+        .map(|replacement| {
+            let mut resolved = replacement.clone();
+            if replacement.target.is_none()
+                && let Some(json_path) = resolved.value_from_json_path.take()
+            {
+                let value =
+                    read_key_path(system, &graft_dir.join(&json_path.file), &json_path.path)
+                        .with_context(|| {
+                            format!(
+                                "Failed to resolve valueFromJsonPath for '{}'",
+                                replacement.source
+                            )
+                        })?;
+                resolved.target = Some(value);
+            }
+            Ok(resolved)
+        })
+        .collect()
+}
+
 /// Preview what replacements would be applied (for dry run).
 ///
 /// # Errors
@@ -295,49 +856,318 @@ pub fn preview_replacements(
     Ok(previews)
 }
 
-/// Apply replacement to a single file.
-fn apply_replacement_to_file(
+/// Resolve a replacement's final value without applying it.
+///
+/// Values read from environment variables whose name looks like a secret
+/// (`TOKEN`, `SECRET`, `PASSWORD`, ...) are marked as redacted.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The replacement value cannot be resolved
+#[inline]
+pub fn resolve_replacement(
     system: &dyn System,
-    file_path: &Path,
-    search_pattern: &str,
-    replacement_value: &str,
-) -> Result<bool> {
-    // Skip binary files
+    replacement: &ReplacementConfig,
+) -> Result<ResolvedReplacement> {
+    Ok(ResolvedReplacement {
+        redacted: replacement
+            .value_from_env
+            .as_deref()
+            .is_some_and(is_secret_env_name),
+        source: replacement.source.clone(),
+        value: get_replacement_value(system, replacement)?,
+    })
+}
+
+/// Resolve a graft replacement's final value without applying it.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The replacement value cannot be resolved
+#[inline]
+pub fn resolve_graft_replacement(
+    system: &dyn System,
+    replacement: &GraftReplacement,
+    context: &ContextValues,
+) -> Result<ResolvedReplacement> {
+    Ok(ResolvedReplacement {
+        redacted: replacement
+            .value_from_env
+            .as_deref()
+            .is_some_and(is_secret_env_name),
+        source: replacement.source.clone(),
+        value: get_graft_replacement_value(system, replacement, context)?,
+    })
+}
+
+/// Rename files and directories under `target_dir` whose names contain a
+/// replacement `source` token, substituting the resolved value.
+///
+/// Deepest paths are renamed first so children move before their parents.
+/// All renames are planned up front: if two paths would resolve to the same
+/// name, or a new name already exists, nothing is renamed. A file target
+/// (as opposed to a directory) is never renamed.
+///
+/// Returns the number of paths renamed.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A replacement value cannot be resolved
+/// - Two paths resolve to the same name, or a new name already exists
+/// - A path cannot be moved
+#[inline]
+pub fn apply_filename_replacements(
+    system: &dyn System,
+    target_dir: &str,
+    replacements: &[ReplacementConfig],
+) -> Result<usize> {
+    let target_path = Path::new(target_dir);
+    if replacements.is_empty() || !system.is_dir(target_path)? {
+        return Ok(0);
+    }
+
+    let resolved = replacements
+        .iter()
+        .map(|replacement| {
+            get_replacement_value(system, replacement)
+                .map(|value| (replacement.source.as_str(), value))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut paths = Vec::new();
+    collect_paths_recursive(system, target_path, &mut paths)?;
+    paths.sort_by(|left, right| {
+        right
+            .components()
+            .count()
+            .cmp(&left.components().count())
+            .then_with(|| left.cmp(right))
+    });
+
+    let mut renames = Vec::new();
+    let mut new_paths = HashSet::new();
+    for path in paths {
+        let Some(name) = path.file_name().and_then(OsStr::to_str) else {
+            continue;
+        };
+        let new_name = resolved
+            .iter()
+            .fold(name.to_owned(), |acc, pair| acc.replace(pair.0, &pair.1));
+        if new_name == name {
+            continue;
+        }
+
+        let new_path = path.with_file_name(&new_name);
+        if !new_paths.insert(new_path.clone()) || system.exists(&new_path)? {
+            return Err(GraftError::filesystem(format!(
+                "Cannot rename {} to {}: another path already has that name",
+                path.display(),
+                new_path.display()
+            ))
+            .into());
+        }
+        renames.push((path, new_path));
+    }
+
+    for rename in &renames {
+        debug!("Renaming {} to {}", rename.0.display(), rename.1.display());
+        move_path(system, &rename.0, &rename.1)?;
+    }
+
+    Ok(renames.len())
+}
+
+/// Recursively collect every file and directory path under `dir_path`.
+fn collect_paths_recursive(
+    system: &dyn System,
+    dir_path: &Path,
+    paths: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let entries = system
+        .read_dir(dir_path)
+        .with_context(|| format!("Failed to read directory: {}", dir_path.display()))?;
+
+    for entry_path in entries {
+        if system.is_dir(&entry_path)? {
+            collect_paths_recursive(system, &entry_path, paths)?;
+        }
+        paths.push(entry_path);
+    }
+
+    Ok(())
+}
+
+/// Move a file or directory. `System` has no rename, so entries are copied
+/// to the new location and then removed.
+fn move_path(system: &dyn System, from: &Path, to: &Path) -> Result<()> {
+    if !system.is_dir(from)? {
+        system
+            .copy(from, to)
+            .with_context(|| format!("Failed to copy {} to {}", from.display(), to.display()))?;
+        return system
+            .remove_file(from)
+            .with_context(|| format!("Failed to remove {}", from.display()));
+    }
+
+    system
+        .create_dir_all(to)
+        .with_context(|| format!("Failed to create directory: {}", to.display()))?;
+    let children = system
+        .read_dir(from)
+        .with_context(|| format!("Failed to read directory: {}", from.display()))?;
+    for child in children {
+        if let Some(name) = child.file_name() {
+            move_path(system, &child, &to.join(name))?;
+        }
+    }
+    system
+        .remove_dir_all(from)
+        .with_context(|| format!("Failed to remove directory: {}", from.display()))
+}
+
+/// Check whether an environment variable name looks like it holds a secret.
+fn is_secret_env_name(name: &str) -> bool {
+    let upper = name.to_uppercase();
+    [
+        "TOKEN",
+        "SECRET",
+        "PASSWORD",
+        "PASSWD",
+        "CREDENTIAL",
+        "PRIVATE",
+        "API_KEY",
+    ]
+    .iter()
+    .any(|marker| upper.contains(marker))
+}
+
+/// Remove every line containing `pattern` below `target_path`, returning
+/// the number of files changed and lines removed.
+fn delete_lines_below(
+    system: &dyn System,
+    target_path: &Path,
+    pattern: &str,
+) -> Result<(usize, usize)> {
+    if system.is_file(target_path)? {
+        let removed = delete_lines_in_file(system, target_path, pattern)?;
+        return Ok((usize::from(removed > 0), removed));
+    }
+
+    let (mut files_changed, mut lines_removed) = (0_usize, 0_usize);
+    if system.is_dir(target_path)? {
+        let entries = system
+            .read_dir(target_path)
+            .with_context(|| format!("Failed to read directory: {}", target_path.display()))?;
+        for entry_path in entries {
+            let (files, lines) = delete_lines_below(system, &entry_path, pattern)?;
+            files_changed = files_changed.saturating_add(files);
+            lines_removed = lines_removed.saturating_add(lines);
+        }
+    }
+    Ok((files_changed, lines_removed))
+}
+
+/// Remove the lines containing `pattern` from one file, returning how many.
+fn delete_lines_in_file(system: &dyn System, file_path: &Path, pattern: &str) -> Result<usize> {
     if is_binary_file(system, file_path)? {
-        return Ok(false);
+        return Ok(0);
     }
 
-    // Read file content
     let content = system.read_to_string(file_path).with_context(|| {
         format!(
-            "Failed to read file for text replacement: {}",
+            "Failed to read file for line deletion: {}",
             file_path.display()
         )
     })?;
+    let (new_content, removed) = delete_matching_lines(&content, pattern);
+    if removed == 0 {
+        return Ok(0);
+    }
+
+    system
+        .write(file_path, new_content.as_bytes())
+        .with_context(|| {
+            format!(
+                "Failed to write file after line deletion: {}",
+                file_path.display()
+            )
+        })?;
+    debug!(
+        "Removed {removed} line(s) containing '{pattern}' from {}",
+        file_path.display()
+    );
+    Ok(removed)
+}
+
+/// Apply replacement to a single file, returning the number of matches
+/// replaced (0 when the file was left unchanged).
+fn apply_replacement_to_file(
+    system: &dyn System,
+    file_path: &Path,
+    edit: &TextEdit<'_>,
+) -> Result<usize> {
+    let &TextEdit {
+        encoding,
+        pattern: search_pattern,
+        reindent,
+        require_contains,
+        value: replacement_value,
+    } = edit;
+
+    // Read file content (binary files, or ones not in `encoding`, are skipped)
+    let Some((content, file_encoding)) = read_text_file(system, file_path, encoding)? else {
+        return Ok(0);
+    };
 
     // Check if the search pattern exists
     if !content.contains(search_pattern) {
-        return Ok(false);
+        return Ok(0);
+    }
+
+    // Only files carrying the required marker are edited (`requireContains`)
+    if let Some(marker) = require_contains
+        && !content.contains(marker)
+    {
+        debug!(
+            "Skipping {}: does not contain '{marker}'",
+            file_path.display()
+        );
+        return Ok(0);
     }
 
     // Apply replacement
-    let new_content = content.replace(search_pattern, replacement_value);
+    let new_content = match reindent {
+        Some(extra) => replace_reindented(&content, search_pattern, replacement_value, extra),
+        None => content.replace(search_pattern, replacement_value),
+    };
 
     // Only write if content actually changed
     if new_content != content {
-        system
-            .write(file_path, new_content.as_bytes())
-            .with_context(|| {
-                format!(
-                    "Failed to write file after text replacement: {}",
-                    file_path.display()
-                )
-            })?;
+        let bytes = match file_encoding {
+            Some(file_encoding) => file_encoding.encode(&new_content).ok_or_else(|| {
+                GraftError::filesystem(format!(
+                    "Replacement in {} produced text that cannot be written as {}",
+                    file_path.display(),
+                    file_encoding.name()
+                ))
+            })?,
+            None => new_content.into_bytes(),
+        };
+        system.write(file_path, &bytes).with_context(|| {
+            format!(
+                "Failed to write file after text replacement: {}",
+                file_path.display()
+            )
+        })?;
+        debug!("Replaced '{search_pattern}' in {}", file_path.display());
 
-        return Ok(true);
+        return Ok(content.matches(search_pattern).count());
     }
 
-    Ok(false)
+    Ok(0)
 }
 
 /// Apply regex replacement to a single file.
@@ -442,14 +1272,64 @@ fn find_files_recursive(
     Ok(())
 }
 
+/// Total number of files changed across `counts`.
+fn total_files(counts: &[ReplacementCount]) -> usize {
+    counts
+        .iter()
+        .fold(0, |total, count| total.saturating_add(count.files))
+}
+
+/// Read a file for text replacement, decoding it from `encoding` when set.
+///
+/// Returns the text and, for an encoded file, the encoding to write it back
+/// in; `None` for binary files (without an encoding) or files that do not
+/// decode.
+fn read_text_file(
+    system: &dyn System,
+    file_path: &Path,
+    encoding: Option<TextEncoding>,
+) -> Result<Option<(String, Option<TextEncoding>)>> {
+    let Some(encoding) = encoding else {
+        if is_binary_file(system, file_path)? {
+            return Ok(None);
+        }
+        let content = system.read_to_string(file_path).with_context(|| {
+            format!(
+                "Failed to read file for text replacement: {}",
+                file_path.display()
+            )
+        })?;
+        return Ok(Some((content, None)));
+    };
+
+    let mut file = system
+        .open(file_path)
+        .with_context(|| format!("Failed to open file: {}", file_path.display()))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).with_context(|| {
+        format!(
+            "Failed to read file for text replacement: {}",
+            file_path.display()
+        )
+    })?;
+    let Some((content, file_encoding)) = encoding.decode(&bytes) else {
+        debug!(
+            "Skipping {}: not valid {}",
+            file_path.display(),
+            encoding.name()
+        );
+        return Ok(None);
+    };
+    Ok(Some((content, Some(file_encoding))))
+}
+
 /// Recursively walk directory and apply replacements using System trait.
 fn walk_and_apply(
     system: &dyn System,
     dir_path: &Path,
-    search_pattern: &str,
-    replacement_value: &str,
-) -> Result<usize> {
-    let mut files_processed: usize = 0;
+    edit: &TextEdit<'_>,
+) -> Result<(usize, usize)> {
+    let (mut files_processed, mut occurrences) = (0_usize, 0_usize);
 
     let entries = system
         .read_dir(dir_path)
@@ -457,23 +1337,22 @@ fn walk_and_apply(
 
     for entry_path in entries {
         if system.is_file(&entry_path)? {
-            if apply_replacement_to_file(system, &entry_path, search_pattern, replacement_value)? {
+            let replaced = apply_replacement_to_file(system, &entry_path, edit)?;
+            if replaced > 0 {
                 files_processed = files_processed.saturating_add(1);
+                occurrences = occurrences.saturating_add(replaced);
             }
         } else if system.is_dir(&entry_path)? {
             // Recursively process subdirectories
-            files_processed = files_processed.saturating_add(walk_and_apply(
-                system,
-                &entry_path,
-                search_pattern,
-                replacement_value,
-            )?);
+            let (files, replaced) = walk_and_apply(system, &entry_path, edit)?;
+            files_processed = files_processed.saturating_add(files);
+            occurrences = occurrences.saturating_add(replaced);
         } else {
             debug!("Skipping directory: {}", entry_path.display());
         }
     }
 
-    Ok(files_processed)
+    Ok((files_processed, occurrences))
 }
 
 /// Recursively walk directory and apply regex replacements using System trait.
@@ -508,3 +1387,53 @@ fn walk_and_apply_regex(
 
     Ok(files_processed)
 }
+
+/// Replace every `search_pattern` in `content` with a multi-line `value`,
+/// indenting each inserted line after the first to the placeholder's column
+/// plus `extra` spaces.
+///
+/// When only whitespace precedes the placeholder on its line, that
+/// whitespace is reused as-is (so tab indentation is kept); otherwise the
+/// column is filled with spaces. Empty lines are left empty.
+#[must_use]
+#[inline]
+pub fn replace_reindented(
+    content: &str,
+    search_pattern: &str,
+    value: &str,
+    extra: usize,
+) -> String {
+    if search_pattern.is_empty() {
+        return content.to_owned();
+    }
+
+    let mut output = String::with_capacity(content.len());
+    let mut last_end = 0;
+    for (start, _) in content.match_indices(search_pattern) {
+        let line_start = content
+            .get(..start)
+            .and_then(|before| before.rfind('\n'))
+            .map_or(0, |newline| newline.saturating_add(1));
+        let prefix = content.get(line_start..start).unwrap_or_default();
+        let mut indent = if prefix.chars().all(char::is_whitespace) {
+            prefix.to_owned()
+        } else {
+            " ".repeat(prefix.chars().count())
+        };
+        indent.push_str(&" ".repeat(extra));
+
+        output.push_str(content.get(last_end..start).unwrap_or_default());
+        for (index, line) in value.split('\n').enumerate() {
+            if index > 0 {
+                output.push('\n');
+                if !line.is_empty() {
+                    output.push_str(&indent);
+                }
+            }
+            output.push_str(line);
+        }
+        last_end = start.saturating_add(search_pattern.len());
+    }
+    output.push_str(content.get(last_end..).unwrap_or_default());
+    output
+}
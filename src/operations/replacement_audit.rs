@@ -0,0 +1,88 @@
+//! Run-wide audit of unused replacements (`--list-replacements-unused`).
+//!
+//! Every pull's replacements are tallied by `source` over the whole run, so
+//! a replacement only counts as unused when it changed no file in any pull
+//! that configures it. Large configs collect such dead entries over time;
+//! the audit lists them once the run is over.
+
+use crate::cli::ReplacementConfig;
+use crate::operations::replace::ReplacementCount;
+use std::sync::{Arc, Mutex};
+
+/// Files changed per replacement `source`, in first-seen order.
+type SharedUsage = Arc<Mutex<Vec<(String, usize)>>>;
+
+/// Tallies the files each replacement changed across a run, or ignores
+/// everything when the audit is off.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ReplacementAudit {
+    /// Tallies so far; `None` when the audit is off.
+    usage: Option<SharedUsage>,
+}
+
+impl ReplacementAudit {
+    /// An audit that tallies replacements.
+    #[must_use]
+    #[inline]
+    pub fn enabled() -> Self {
+        Self {
+            usage: Some(Arc::new(Mutex::new(Vec::new()))),
+        }
+    }
+
+    /// Whether replacements are tallied.
+    #[must_use]
+    #[inline]
+    pub const fn is_enabled(&self) -> bool {
+        self.usage.is_some()
+    }
+
+    /// An audit that ignores everything.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add what a pull's `replacements` changed, as reported in `counts`.
+    ///
+    /// Every replacement is registered, so one missing from `counts` (e.g.
+    /// because the target had no files) is still audited.
+    #[inline]
+    pub fn record(&self, replacements: &[ReplacementConfig], counts: &[ReplacementCount]) {
+        let Some(usage) = self.usage.as_ref() else {
+            return;
+        };
+        let Ok(mut usage) = usage.lock() else {
+            return;
+        };
+        for replacement in replacements {
+            if !usage.iter().any(|entry| entry.0 == replacement.source) {
+                usage.push((replacement.source.clone(), 0));
+            }
+        }
+        for count in counts {
+            if let Some(entry) = usage.iter_mut().find(|entry| entry.0 == count.source) {
+                entry.1 = entry.1.saturating_add(count.files);
+            }
+        }
+    }
+
+    /// Sources of the replacements that changed no file, in first-seen order.
+    #[must_use]
+    #[inline]
+    pub fn unused(&self) -> Vec<String> {
+        let Some(usage) = self.usage.as_ref() else {
+            return Vec::new();
+        };
+        let Ok(usage) = usage.lock() else {
+            return Vec::new();
+        };
+        usage
+            .iter()
+            .filter(|entry| entry.1 == 0)
+            .map(|entry| entry.0.clone())
+            .collect()
+    }
+}
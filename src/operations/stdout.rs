@@ -0,0 +1,97 @@
+//! Writing a file pull to stdout (`target: "-"`).
+//!
+//! The source file is staged in a scratch directory so the regular
+//! replacement engine can run on it, then its content is written to the
+//! given writer and the scratch directory is removed. Nothing is left on
+//! the filesystem.
+
+use crate::cli::PullConfig;
+use crate::error::GraftError;
+use crate::operations::replace::apply_replacements_to_file;
+use anyhow::{Context as _, Result};
+use os_shim::System;
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process;
+use tracing::warn;
+
+/// Pull target that writes the file to stdout instead of the filesystem.
+pub const STDOUT_TARGET: &str = "-";
+
+/// Whether `target` is the stdout target (`-`).
+#[must_use]
+#[inline]
+pub fn is_stdout_target(target: &str) -> bool {
+    target == STDOUT_TARGET
+}
+
+/// Apply `pull`'s replacements to `source` and write the result to `out`.
+///
+/// Returns the number of replacements applied.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The source is not a file
+/// - The file cannot be staged or the replacements cannot be applied
+/// - Writing to `out` fails
+#[inline]
+pub fn write_file_to_stdout(
+    system: &dyn System,
+    source: &Path,
+    pull: &PullConfig,
+    out: &mut dyn io::Write,
+) -> Result<usize> {
+    if !system.is_file(source)? {
+        return Err(GraftError::from_source(format!(
+            "Target '{STDOUT_TARGET}' requires a file source, but '{}' is not a file",
+            source.display()
+        ))
+        .into());
+    }
+
+    let staging_dir = env::temp_dir().join(format!("tixgraft-stdout-{}", process::id()));
+    let staged_file = staging_dir.join(source.file_name().unwrap_or(source.as_os_str()));
+
+    let result = stage_and_replace(system, source, &staging_dir, &staged_file, pull);
+    if system.remove_dir_all(&staging_dir).is_err() {
+        warn!(
+            "Failed to remove staging directory: {}",
+            staging_dir.display()
+        );
+    }
+    let (content, replacements_applied) = result?;
+
+    out.write_all(content.as_bytes())
+        .and_then(|()| out.flush())
+        .map_err(|err| GraftError::filesystem(format!("Failed to write to stdout: {err}")))?;
+
+    Ok(replacements_applied)
+}
+
+/// Copy `source` to `staged_file`, apply replacements, and read it back.
+fn stage_and_replace(
+    system: &dyn System,
+    source: &Path,
+    staging_dir: &Path,
+    staged_file: &Path,
+    pull: &PullConfig,
+) -> Result<(String, usize)> {
+    system.create_dir_all(staging_dir).with_context(|| {
+        format!(
+            "Failed to create staging directory: {}",
+            staging_dir.display()
+        )
+    })?;
+    system
+        .copy(source, staged_file)
+        .with_context(|| format!("Failed to stage file: {}", source.display()))?;
+
+    let replacements_applied = apply_replacements_to_file(system, staged_file, &pull.replacements)?;
+    let content = system
+        .read_to_string(staged_file)
+        .with_context(|| format!("Failed to read staged file: {}", staged_file.display()))?;
+
+    Ok((content, replacements_applied))
+}
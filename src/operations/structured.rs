@@ -0,0 +1,285 @@
+//! Structural replacements in JSON and YAML files.
+//!
+//! A replacement with `kind: json` or `kind: yaml` treats its `source` as a
+//! dotted key path (`scripts.build`, `services.0.image`) instead of text.
+//! Matching files are parsed, the value at that path is set, and the
+//! document is written back. Only paths that already exist are updated;
+//! files that fail to parse are skipped.
+//!
+//! The same key paths are read by `valueFromJsonPath` graft replacements
+//! (see [`read_key_path`]).
+
+use crate::config::context::value_to_string;
+use crate::error::GraftError;
+use anyhow::{Context as _, Result};
+use os_shim::System;
+use serde::{Deserialize, Serialize};
+use serde_json::ser::{PrettyFormatter, Serializer};
+use std::path::Path;
+use tracing::debug;
+
+/// How a replacement's `source` is matched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ReplacementKind {
+    /// Every line containing `source` is removed (no value is needed).
+    #[serde(rename = "deleteLines")]
+    DeleteLines,
+    /// `source` is a key path set in `.json` files.
+    Json,
+    /// `source` is literal text replaced in every text file.
+    #[default]
+    Text,
+    /// `source` is a key path set in `.yaml`/`.yml` files.
+    Yaml,
+}
+
+impl ReplacementKind {
+    /// Whether this replacement removes matching lines.
+    #[must_use]
+    #[inline]
+    pub const fn is_delete_lines(&self) -> bool {
+        matches!(*self, Self::DeleteLines)
+    }
+
+    /// Whether this is the default text replacement.
+    #[must_use]
+    #[inline]
+    pub const fn is_text(&self) -> bool {
+        matches!(*self, Self::Text)
+    }
+
+    /// Whether `path` has an extension this kind applies to.
+    #[must_use]
+    #[inline]
+    pub fn matches_file(self, path: &Path) -> bool {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        match self {
+            Self::DeleteLines | Self::Text => true,
+            Self::Json => extension.eq_ignore_ascii_case("json"),
+            Self::Yaml => {
+                extension.eq_ignore_ascii_case("yaml") || extension.eq_ignore_ascii_case("yml")
+            }
+        }
+    }
+
+    /// Set the value at `key_path` in `content`, returning the rewritten
+    /// document, or `None` when the path does not exist, the value is
+    /// already set, or `content` does not parse.
+    ///
+    /// The value is always written as a string.
+    #[must_use]
+    #[inline]
+    pub fn set_key_path(self, content: &str, key_path: &str, value: &str) -> Option<String> {
+        match self {
+            Self::DeleteLines | Self::Text => None,
+            Self::Json => set_json_key_path(content, key_path, value),
+            Self::Yaml => set_yaml_key_path(content, key_path, value),
+        }
+    }
+}
+
+/// Apply a structural replacement to `target_path` (a file, or every
+/// matching file below a directory).
+///
+/// Returns the number of files changed.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A directory cannot be read
+/// - A file cannot be read or written
+#[inline]
+pub fn apply_structured_replacement(
+    system: &dyn System,
+    target_path: &Path,
+    kind: ReplacementKind,
+    key_path: &str,
+    value: &str,
+) -> Result<usize> {
+    if system.is_file(target_path)? {
+        return Ok(usize::from(apply_to_file(
+            system,
+            target_path,
+            kind,
+            key_path,
+            value,
+        )?));
+    }
+
+    let mut files_processed: usize = 0;
+    if system.is_dir(target_path)? {
+        let entries = system
+            .read_dir(target_path)
+            .with_context(|| format!("Failed to read directory: {}", target_path.display()))?;
+        for entry_path in entries {
+            files_processed = files_processed.saturating_add(apply_structured_replacement(
+                system,
+                &entry_path,
+                kind,
+                key_path,
+                value,
+            )?);
+        }
+    }
+    Ok(files_processed)
+}
+
+/// Apply a structural replacement to one file, returning whether it changed.
+fn apply_to_file(
+    system: &dyn System,
+    file_path: &Path,
+    kind: ReplacementKind,
+    key_path: &str,
+    value: &str,
+) -> Result<bool> {
+    if !kind.matches_file(file_path) {
+        return Ok(false);
+    }
+
+    let content = system.read_to_string(file_path).with_context(|| {
+        format!(
+            "Failed to read file for structural replacement: {}",
+            file_path.display()
+        )
+    })?;
+    let Some(new_content) = kind.set_key_path(&content, key_path, value) else {
+        return Ok(false);
+    };
+
+    system
+        .write(file_path, new_content.as_bytes())
+        .with_context(|| {
+            format!(
+                "Failed to write file after structural replacement: {}",
+                file_path.display()
+            )
+        })?;
+    debug!("Set '{key_path}' in {}", file_path.display());
+    Ok(true)
+}
+
+/// Indentation of the first indented line of `content` (JSON defaults to two spaces).
+fn detect_indent(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start_matches([' ', '\t']);
+            line.get(..line.len().saturating_sub(trimmed.len()))
+                .unwrap_or_default()
+        })
+        .find(|indent| !indent.is_empty())
+        .unwrap_or("  ")
+        .to_owned()
+}
+
+/// Read the value at `key_path` in the JSON or YAML file `file_path`.
+///
+/// Files ending in `.yaml`/`.yml` are parsed as YAML and anything else as
+/// JSON. Strings are returned as-is, numbers and booleans in their text form,
+/// and arrays and objects as compact JSON.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The file does not exist or cannot be read
+/// - The file does not parse
+/// - `key_path` does not exist in the document
+#[inline]
+pub fn read_key_path(system: &dyn System, file_path: &Path, key_path: &str) -> Result<String> {
+    if !system.exists(file_path)? {
+        return Err(GraftError::configuration(format!(
+            "File for valueFromJsonPath not found: {}",
+            file_path.display()
+        ))
+        .into());
+    }
+    let content = system
+        .read_to_string(file_path)
+        .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+    let document: serde_json::Value = if ReplacementKind::Yaml.matches_file(file_path) {
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse YAML file: {}", file_path.display()))?
+    } else {
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse JSON file: {}", file_path.display()))?
+    };
+
+    let mut slot = &document;
+    for segment in key_path.split('.') {
+        let next = if slot.is_array() {
+            segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| slot.get(index))
+        } else {
+            slot.get(segment)
+        };
+        slot = next.ok_or_else(|| {
+            GraftError::configuration(format!(
+                "Key path '{key_path}' not found in {}",
+                file_path.display()
+            ))
+        })?;
+    }
+    value_to_string(slot)
+}
+
+/// Set `key_path` in a JSON document, keeping key order and indentation.
+fn set_json_key_path(content: &str, key_path: &str, value: &str) -> Option<String> {
+    let mut document: serde_json::Value = serde_json::from_str(content).ok()?;
+    let mut slot = &mut document;
+    for segment in key_path.split('.') {
+        slot = if slot.is_array() {
+            slot.as_array_mut()?
+                .get_mut(segment.parse::<usize>().ok()?)?
+        } else {
+            slot.as_object_mut()?.get_mut(segment)?
+        };
+    }
+    if slot.as_str() == Some(value) {
+        return None;
+    }
+    *slot = serde_json::Value::String(value.to_owned());
+
+    let mut output = if content.trim().contains('\n') {
+        let indent = detect_indent(content);
+        let mut buffer = Vec::new();
+        let mut serializer = Serializer::with_formatter(
+            &mut buffer,
+            PrettyFormatter::with_indent(indent.as_bytes()),
+        );
+        document.serialize(&mut serializer).ok()?;
+        String::from_utf8(buffer).ok()?
+    } else {
+        serde_json::to_string(&document).ok()?
+    };
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    Some(output)
+}
+
+/// Set `key_path` in a YAML document, keeping key order (comments are lost).
+fn set_yaml_key_path(content: &str, key_path: &str, value: &str) -> Option<String> {
+    let mut document: serde_yaml::Value = serde_yaml::from_str(content).ok()?;
+    let mut slot = &mut document;
+    for segment in key_path.split('.') {
+        slot = if slot.is_sequence() {
+            slot.as_sequence_mut()?
+                .get_mut(segment.parse::<usize>().ok()?)?
+        } else {
+            slot.as_mapping_mut()?.get_mut(segment)?
+        };
+    }
+    if slot.as_str() == Some(value) {
+        return None;
+    }
+    *slot = serde_yaml::Value::String(value.to_owned());
+
+    serde_yaml::to_string(&document).ok()
+}
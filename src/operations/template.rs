@@ -0,0 +1,152 @@
+//! Template rendering for pulled files.
+//!
+//! An alternative to literal placeholder replacement: when a pull selects a
+//! template engine, every text file in its target is rendered against the
+//! merged (global + pull) context.
+//!
+//! - `none` (default): no rendering, only literal replacements apply.
+//! - `simple`: `{{ name }}` variables are substituted from context; unknown
+//!   names are left untouched. No conditionals or loops.
+//! - `handlebars`: full Handlebars templates (`{{#if}}`, `{{#each}}`, ...).
+//!   Requires the `handlebars` cargo feature.
+
+use crate::config::context::{ContextValues, value_to_string};
+use crate::error::GraftError;
+use crate::utils::fs::is_binary_file;
+use anyhow::{Context as _, Result};
+#[cfg(feature = "handlebars")]
+use handlebars::{Handlebars, no_escape};
+use os_shim::System;
+use regex::{Captures, Regex};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// Template engine names accepted by `templateEngine`.
+pub const TEMPLATE_ENGINES: [&str; 3] = ["none", "simple", "handlebars"];
+
+/// Check whether the given template engine is available in this build.
+#[must_use]
+#[inline]
+pub fn is_template_engine_available(engine: &str) -> bool {
+    match engine {
+        "none" | "simple" => true,
+        "handlebars" => cfg!(feature = "handlebars"),
+        _ => false,
+    }
+}
+
+/// Render every text file under `target_path` with `engine`, using `context`.
+///
+/// Binary files and files named `skip_file_name` (graft definition files,
+/// whose own placeholders are resolved later) are left untouched.
+///
+/// Returns the number of files whose content changed.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The engine is unknown or not available in this build
+/// - A file cannot be read, rendered, or written
+#[inline]
+pub fn render_templates(
+    system: &dyn System,
+    target_path: &Path,
+    engine: &str,
+    context: &ContextValues,
+    skip_file_name: &str,
+) -> Result<usize> {
+    let render: fn(&str, &ContextValues) -> Result<String> = match engine {
+        "none" => return Ok(0),
+        "simple" => render_simple,
+        "handlebars" => render_handlebars,
+        other => {
+            return Err(GraftError::configuration(format!(
+                "Unknown template engine '{other}'. Must be one of: {}",
+                TEMPLATE_ENGINES.join(", ")
+            ))
+            .into());
+        }
+    };
+
+    let files: Vec<PathBuf> = if system.is_file(target_path)? {
+        vec![target_path.to_path_buf()]
+    } else {
+        system
+            .walk_dir(target_path, false, false)
+            .with_context(|| format!("Failed to walk directory: {}", target_path.display()))?
+            .into_iter()
+            .filter(|entry| entry.is_file)
+            .map(|entry| entry.path)
+            .collect()
+    };
+
+    let mut files_rendered = 0_usize;
+
+    for file_path in files {
+        if file_path.file_name() == Some(OsStr::new(skip_file_name))
+            || is_binary_file(system, &file_path)?
+        {
+            continue;
+        }
+
+        let content = system.read_to_string(&file_path).with_context(|| {
+            format!(
+                "Failed to read file for template rendering: {}",
+                file_path.display()
+            )
+        })?;
+
+        let rendered = render(&content, context)
+            .with_context(|| format!("Failed to render template: {}", file_path.display()))?;
+
+        if rendered != content {
+            system
+                .write(&file_path, rendered.as_bytes())
+                .with_context(|| {
+                    format!(
+                        "Failed to write file after template rendering: {}",
+                        file_path.display()
+                    )
+                })?;
+            files_rendered = files_rendered.saturating_add(1);
+        }
+    }
+
+    Ok(files_rendered)
+}
+
+/// Substitute `{{ name }}` variables from context, leaving unknown names as-is.
+fn render_simple(content: &str, context: &ContextValues) -> Result<String> {
+    let placeholder = Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}")
+        .context("Failed to compile template placeholder pattern")?;
+
+    let rendered = placeholder.replace_all(content, |caps: &Captures<'_>| {
+        let whole = caps.get(0).map_or("", |found| found.as_str());
+        caps.get(1)
+            .and_then(|name| context.get(name.as_str()))
+            .and_then(|value| value_to_string(value).ok())
+            .unwrap_or_else(|| whole.to_owned())
+    });
+
+    Ok(rendered.into_owned())
+}
+
+/// Render a Handlebars template against the context (no HTML escaping).
+#[cfg(feature = "handlebars")]
+fn render_handlebars(content: &str, context: &ContextValues) -> Result<String> {
+    let mut registry = Handlebars::new();
+    registry.register_escape_fn(no_escape);
+    registry
+        .render_template(content, context)
+        .context("Failed to render Handlebars template")
+}
+
+/// Fallback when the `handlebars` feature is disabled.
+#[cfg(not(feature = "handlebars"))]
+fn render_handlebars(_content: &str, _context: &ContextValues) -> Result<String> {
+    Err(GraftError::configuration(
+        "templateEngine 'handlebars' requires tixgraft to be built with the `handlebars` feature"
+            .to_owned(),
+    )
+    .into())
+}
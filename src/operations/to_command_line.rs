@@ -4,7 +4,11 @@ use core::str::FromStr;
 
 use crate::cli::{PullConfig, ReplacementConfig};
 use crate::config::Config;
-use anyhow::Result;
+use anyhow::{Context as _, Result, anyhow};
+use std::env;
+
+/// Program name used as the first token of generated command lines.
+pub const DEFAULT_PROGRAM_NAME: &str = "tixgraft";
 
 /// Output format for command-line representation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,6 +20,18 @@ pub enum OutputFormat {
     Shell,
 }
 
+impl OutputFormat {
+    /// Format name as accepted by `--output-format`.
+    #[must_use]
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Shell => "shell",
+        }
+    }
+}
+
 impl FromStr for OutputFormat {
     type Err = String;
 
@@ -29,26 +45,213 @@ impl FromStr for OutputFormat {
     }
 }
 
+/// Shell whose quoting rules `shell` output follows (`--shell-dialect`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShellDialect {
+    /// Windows `cmd.exe` batch scripts: double quotes, `""` and `%%` escapes.
+    Cmd,
+    /// POSIX shells (sh, bash, zsh): double quotes with backslash escapes.
+    #[default]
+    Posix,
+    /// `PowerShell`: single quotes, `''` escapes.
+    PowerShell,
+}
+
+impl ShellDialect {
+    /// Escape a single argument for this shell.
+    #[must_use]
+    #[inline]
+    pub fn escape(self, input: &str) -> String {
+        match self {
+            Self::Cmd => cmd_escape(input),
+            Self::Posix => shell_escape(input),
+            Self::PowerShell => powershell_escape(input),
+        }
+    }
+
+    /// Token that continues a command on the next line.
+    #[must_use]
+    #[inline]
+    pub const fn line_continuation(self) -> &'static str {
+        match self {
+            Self::Cmd => "^",
+            Self::Posix => "\\",
+            Self::PowerShell => "`",
+        }
+    }
+
+    /// Dialect name as accepted by `--shell-dialect`.
+    #[must_use]
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Cmd => "cmd",
+            Self::Posix => "posix",
+            Self::PowerShell => "powershell",
+        }
+    }
+}
+
+impl FromStr for ShellDialect {
+    type Err = String;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cmd" => Ok(Self::Cmd),
+            "posix" => Ok(Self::Posix),
+            "powershell" => Ok(Self::PowerShell),
+            _ => Err(format!(
+                "Invalid shell dialect: {s}. Use 'posix', 'powershell' or 'cmd'"
+            )),
+        }
+    }
+}
+
+/// Options for [`generate_command_line`]: the output formats, the invoked
+/// program and the shell dialect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CommandLineOptions<'opts> {
+    /// Shell whose quoting rules `shell` output follows (default:
+    /// [`ShellDialect::Posix`]).
+    pub dialect: ShellDialect,
+    /// Formats rendered in order (default: `shell` only).
+    pub formats: &'opts [OutputFormat],
+    /// Program invoked by the generated command line (default:
+    /// [`DEFAULT_PROGRAM_NAME`]).
+    pub program: &'opts str,
+}
+
+impl<'opts> CommandLineOptions<'opts> {
+    /// Options for a single POSIX `shell` command line invoking `tixgraft`.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            dialect: ShellDialect::Posix,
+            formats: &[OutputFormat::Shell],
+            program: DEFAULT_PROGRAM_NAME,
+        }
+    }
+
+    /// Quote `shell` output for `dialect`.
+    #[must_use]
+    #[inline]
+    pub const fn with_dialect(mut self, dialect: ShellDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Render one representation per format in `formats`.
+    #[must_use]
+    #[inline]
+    pub const fn with_formats(mut self, formats: &'opts [OutputFormat]) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    /// Invoke `program` instead of `tixgraft`.
+    #[must_use]
+    #[inline]
+    pub const fn with_program(mut self, program: &'opts str) -> Self {
+        self.program = program;
+        self
+    }
+}
+
+impl Default for CommandLineOptions<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Convert configuration to command-line representation.
 ///
+/// A single format produces just its representation. Several formats are
+/// rendered in order, each preceded by a `# <format>` header line and
+/// separated by a blank line.
+///
 /// # Errors
 ///
 /// Returns an error if:
-/// - The configuration cannot be converted to command-line arguments
-/// - The command-line arguments cannot be serialized to the requested format
+/// - No format is requested
+/// - The command-line arguments cannot be serialized to a requested format
 #[inline]
-pub fn generate_command_line(config: &Config, format: OutputFormat) -> Result<String> {
-    let args = build_command_args(config);
+pub fn generate_command_line(config: &Config, options: &CommandLineOptions<'_>) -> Result<String> {
+    let args = build_command_args(config, options.program);
+    match *options.formats {
+        [] => Err(anyhow!("At least one output format is required")),
+        [format] => format_command_line(&args, format, options.dialect),
+        _ => {
+            let blocks = options
+                .formats
+                .iter()
+                .map(|&format| {
+                    format_command_line(&args, format, options.dialect)
+                        .map(|output| format!("# {}\n{output}", format.name()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(blocks.join("\n\n"))
+        }
+    }
+}
 
-    match format {
-        OutputFormat::Shell => Ok(format_as_shell(&args)),
-        OutputFormat::Json => format_as_json(&args),
+/// Parse a comma-separated `--output-format` value (e.g. `shell,json`).
+///
+/// Duplicate formats are ignored; order is preserved.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A format name is invalid
+/// - No format is given
+#[inline]
+pub fn parse_output_formats(spec: &str) -> Result<Vec<OutputFormat>, String> {
+    let mut formats = Vec::new();
+    for name in spec
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let format = name.parse::<OutputFormat>()?;
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+
+    if formats.is_empty() {
+        return Err(format!(
+            "Invalid format: {spec}. Use 'shell', 'json', or both (shell,json)"
+        ));
+    }
+    Ok(formats)
+}
+
+/// Resolve the program name for generated command lines.
+///
+/// `--absolute-program` uses the path of the running executable; otherwise
+/// `--program-name` is used, falling back to `tixgraft`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `absolute` is set and the current executable path cannot be determined
+#[inline]
+pub fn resolve_program_name(program_name: Option<&str>, absolute: bool) -> Result<String> {
+    if absolute {
+        let exe = env::current_exe().context("Failed to determine the tixgraft executable path")?;
+        return Ok(exe.display().to_string());
     }
+
+    Ok(program_name.unwrap_or(DEFAULT_PROGRAM_NAME).to_owned())
 }
 
 /// Build argument list from configuration.
-fn build_command_args(config: &Config) -> Vec<String> {
-    let mut args = vec!["tixgraft".to_owned()];
+fn build_command_args(config: &Config, program: &str) -> Vec<String> {
+    let mut args = vec![program.to_owned()];
 
     // Add global repository if specified
     if let Some(repo) = config.repository.as_ref() {
@@ -62,6 +265,18 @@ fn build_command_args(config: &Config) -> Vec<String> {
         args.push(tag.clone());
     }
 
+    // Add custom graft file name if specified
+    if let Some(graft_file_name) = config.graft_file_name.as_ref() {
+        args.push("--graft-file-name".to_owned());
+        args.push(graft_file_name.clone());
+    }
+
+    // Add custom clone filter if specified
+    if let Some(clone_filter) = config.clone_filter.as_ref() {
+        args.push("--clone-filter".to_owned());
+        args.push(clone_filter.clone());
+    }
+
     // Add each pull operation
     for pull in &config.pulls {
         add_pull_args(&mut args, pull, config);
@@ -81,7 +296,7 @@ fn add_pull_args(args: &mut Vec<String>, pull: &PullConfig, global_config: &Conf
     args.push(pull.target.clone());
 
     // Type (only if not default)
-    if pull.pull_type != "directory" {
+    if pull.pull_type != "auto" {
         args.push("--pull-type".to_owned());
         args.push(pull.pull_type.clone());
     }
@@ -120,6 +335,42 @@ fn add_pull_args(args: &mut Vec<String>, pull: &PullConfig, global_config: &Conf
         args.push("false".to_owned());
     }
 
+    // Hidden files (only emit when false, since true is the default)
+    if !pull.include_hidden {
+        args.push("--pull-include-hidden".to_owned());
+        args.push("false".to_owned());
+    }
+
+    // Respect .gitignore (only if true)
+    if pull.respect_gitignore {
+        args.push("--pull-respect-gitignore".to_owned());
+        args.push("true".to_owned());
+    }
+
+    // Replace in filenames (only if true)
+    if pull.replace_filenames {
+        args.push("--pull-replace-filenames".to_owned());
+        args.push("true".to_owned());
+    }
+
+    // Strip components (only if not zero)
+    if pull.strip_components > 0 {
+        args.push("--pull-strip-components".to_owned());
+        args.push(pull.strip_components.to_string());
+    }
+
+    // Expected files (only if any)
+    if !pull.expect_files.is_empty() {
+        args.push("--pull-expect-files".to_owned());
+        args.push(pull.expect_files.join(","));
+    }
+
+    // Template engine (only if not default)
+    if pull.template_engine != "none" {
+        args.push("--pull-template-engine".to_owned());
+        args.push(pull.template_engine.clone());
+    }
+
     // Replacements
     for replacement in &pull.replacements {
         args.push("--pull-replacement".to_owned());
@@ -149,17 +400,35 @@ fn format_replacement(repl: &ReplacementConfig) -> String {
     )
 }
 
-/// Format arguments as a shell command with proper escaping.
-fn format_as_shell(args: &[String]) -> String {
+/// Render `args` in `format`, quoting `shell` output for `dialect`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The arguments cannot be serialized to `format`
+fn format_command_line(
+    args: &[String],
+    format: OutputFormat,
+    dialect: ShellDialect,
+) -> Result<String> {
+    match format {
+        OutputFormat::Shell => Ok(format_as_shell(args, dialect)),
+        OutputFormat::Json => format_as_json(args),
+    }
+}
+
+/// Format arguments as a shell command with proper escaping for `dialect`.
+fn format_as_shell(args: &[String], dialect: ShellDialect) -> String {
+    let separator = format!(" {}\n  ", dialect.line_continuation());
     let mut output = String::new();
 
     for (i, arg) in args.iter().enumerate() {
         if i > 0 {
-            output.push_str(" \\\n  ");
+            output.push_str(&separator);
         }
 
         // Shell escape the argument
-        let escaped = shell_escape(arg);
+        let escaped = dialect.escape(arg);
         output.push_str(&escaped);
     }
 
@@ -172,13 +441,47 @@ fn format_as_json(args: &[String]) -> Result<String> {
         .map_err(|err| anyhow::anyhow!("Failed to serialize to JSON: {err}"))
 }
 
-/// Escape a string for shell execution.
+/// Whether `input` can be passed to any shell without quoting.
+fn is_shell_safe(input: &str) -> bool {
+    input.chars().all(|ch| {
+        ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == '/' || ch == '.' || ch == ':'
+    })
+}
+
+/// Escape a string for a `cmd.exe` batch script.
+/// Uses double quotes; `"` is doubled and `%` is doubled so variables are not expanded.
+fn cmd_escape(input: &str) -> String {
+    if is_shell_safe(input) {
+        return input.to_owned();
+    }
+
+    let mut result = String::from('"');
+    for ch in input.chars() {
+        match ch {
+            '"' => result.push_str("\"\""),
+            '%' => result.push_str("%%"),
+            _ => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Escape a string for `PowerShell`.
+/// Uses single quotes, in which nothing is expanded; `'` is doubled.
+fn powershell_escape(input: &str) -> String {
+    if is_shell_safe(input) {
+        return input.to_owned();
+    }
+
+    format!("'{}'", input.replace('\'', "''"))
+}
+
+/// Escape a string for POSIX shell execution.
 /// Uses double quotes for safety, escaping special characters inside.
 fn shell_escape(input: &str) -> String {
     // If string contains no special characters, return as-is
-    if input.chars().all(|ch| {
-        ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == '/' || ch == '.' || ch == ':'
-    }) {
+    if is_shell_safe(input) {
         return input.to_owned();
     }
 
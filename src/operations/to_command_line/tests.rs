@@ -4,8 +4,6 @@
     reason = "index-based assertions are acceptable in tests"
 )]
 
-use std::collections::HashMap;
-
 use super::*;
 
 #[test]
@@ -24,20 +22,68 @@ fn shell_escape_special_chars() {
     assert_eq!(shell_escape("back\\slash"), r#""back\\slash""#);
 }
 
+#[test]
+fn shell_dialect_posix_escaping() {
+    assert_eq!(
+        ShellDialect::Posix.escape(r#"say "hi" to $USER"#),
+        r#""say \"hi\" to \$USER""#
+    );
+    assert_eq!(ShellDialect::Posix.escape("simple"), "simple");
+}
+
+#[test]
+fn shell_dialect_powershell_escaping() {
+    assert_eq!(
+        ShellDialect::PowerShell.escape(r#"say "hi" to $env:USER"#),
+        r#"'say "hi" to $env:USER'"#
+    );
+    assert_eq!(ShellDialect::PowerShell.escape("it's"), "'it''s'");
+    assert_eq!(ShellDialect::PowerShell.escape("simple"), "simple");
+}
+
+#[test]
+fn shell_dialect_cmd_escaping() {
+    assert_eq!(
+        ShellDialect::Cmd.escape(r#"say "hi" to %USERNAME%"#),
+        r#""say ""hi"" to %%USERNAME%%""#
+    );
+    assert_eq!(ShellDialect::Cmd.escape("simple"), "simple");
+}
+
+#[test]
+fn shell_dialect_line_continuations() {
+    let args = vec!["tixgraft".to_owned(), "--repository".to_owned()];
+    assert_eq!(
+        format_as_shell(&args, ShellDialect::Posix),
+        "tixgraft \\\n  --repository"
+    );
+    assert_eq!(
+        format_as_shell(&args, ShellDialect::PowerShell),
+        "tixgraft `\n  --repository"
+    );
+    assert_eq!(
+        format_as_shell(&args, ShellDialect::Cmd),
+        "tixgraft ^\n  --repository"
+    );
+}
+
+#[test]
+fn shell_dialect_from_str() {
+    assert_eq!("posix".parse::<ShellDialect>(), Ok(ShellDialect::Posix));
+    assert_eq!(
+        "PowerShell".parse::<ShellDialect>(),
+        Ok(ShellDialect::PowerShell)
+    );
+    assert_eq!("cmd".parse::<ShellDialect>(), Ok(ShellDialect::Cmd));
+    assert!("fish".parse::<ShellDialect>().is_err());
+}
+
 #[test]
 fn format_replacement_tst() {
-    let repl_static = ReplacementConfig {
-        source: "{{VAR}}".to_owned(),
-        target: Some("value".to_owned()),
-        value_from_env: None,
-    };
+    let repl_static = ReplacementConfig::new("{{VAR}}".to_owned(), Some("value".to_owned()), None);
     assert_eq!(format_replacement(&repl_static), "{{VAR}}=value");
 
-    let repl_env = ReplacementConfig {
-        source: "{{VAR}}".to_owned(),
-        target: None,
-        value_from_env: Some("MY_ENV".to_owned()),
-    };
+    let repl_env = ReplacementConfig::new("{{VAR}}".to_owned(), None, Some("MY_ENV".to_owned()));
     assert_eq!(format_replacement(&repl_env), "{{VAR}}=env:MY_ENV");
 }
 
@@ -55,6 +101,20 @@ fn output_format_from_str() {
     "invalid".parse::<OutputFormat>().unwrap_err();
 }
 
+#[test]
+fn parse_output_formats_list() {
+    assert_eq!(
+        parse_output_formats("shell, json").unwrap(),
+        vec![OutputFormat::Shell, OutputFormat::Json]
+    );
+    assert_eq!(
+        parse_output_formats("json,json").unwrap(),
+        vec![OutputFormat::Json]
+    );
+    parse_output_formats("shell,xml").unwrap_err();
+    parse_output_formats(",").unwrap_err();
+}
+
 #[test]
 fn build_command_args_basic() {
     use crate::cli::PullConfig;
@@ -62,25 +122,14 @@ fn build_command_args_basic() {
     let config = Config {
         repository: Some("my_organization/repo".to_owned()),
         tag: Some("main".to_owned()),
-        context: HashMap::new(),
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
-    let args = build_command_args(&config);
+    let args = build_command_args(&config, DEFAULT_PROGRAM_NAME);
     assert_eq!(args[0], "tixgraft");
     assert!(args.contains(&"--repository".to_owned()));
     assert!(args.contains(&"my_organization/repo".to_owned()));
@@ -98,26 +147,15 @@ fn build_command_args_with_reset() {
 
     let config = Config {
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
-        context: HashMap::new(),
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
             reset: true,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
-    let args = build_command_args(&config);
+    let args = build_command_args(&config, DEFAULT_PROGRAM_NAME);
     assert!(args.contains(&"--pull-reset".to_owned()));
 }
 
@@ -127,37 +165,18 @@ fn build_command_args_with_replacements() {
 
     let config = Config {
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
-        context: HashMap::new(),
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
             replacements: vec![
-                ReplacementConfig {
-                    source: "{{VAR1}}".to_owned(),
-                    target: Some("value1".to_owned()),
-                    value_from_env: None,
-                },
-                ReplacementConfig {
-                    source: "{{VAR2}}".to_owned(),
-                    target: None,
-                    value_from_env: Some("MY_ENV".to_owned()),
-                },
+                ReplacementConfig::new("{{VAR1}}".to_owned(), Some("value1".to_owned()), None),
+                ReplacementConfig::new("{{VAR2}}".to_owned(), None, Some("MY_ENV".to_owned())),
             ],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
-    let args = build_command_args(&config);
+    let args = build_command_args(&config, DEFAULT_PROGRAM_NAME);
     assert!(args.contains(&"--pull-replacement".to_owned()));
     assert!(args.contains(&"{{VAR1}}=value1".to_owned()));
     assert!(args.contains(&"{{VAR2}}=env:MY_ENV".to_owned()));
@@ -169,26 +188,15 @@ fn multiline_command() {
 
     let config = Config {
         repository: Some("repo".to_owned()),
-        tag: None,
-        context: HashMap::new(),
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
             commands: vec!["echo 'line1'\necho 'line2'".to_owned()],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
-    let result = generate_command_line(&config, OutputFormat::Shell);
+    let result = generate_command_line(&config, &CommandLineOptions::new());
     assert!(result.is_ok());
     let output = result.unwrap();
     // Should escape the newline properly
@@ -199,11 +207,11 @@ fn multiline_command() {
 fn replacement_with_special_chars() {
     use crate::cli::ReplacementConfig;
 
-    let replacement = ReplacementConfig {
-        source: "{{VAR}}".to_owned(),
-        target: Some(r#"value with "quotes" and $vars"#.to_owned()),
-        value_from_env: None,
-    };
+    let replacement = ReplacementConfig::new(
+        "{{VAR}}".to_owned(),
+        Some(r#"value with "quotes" and $vars"#.to_owned()),
+        None,
+    );
 
     let formatted = format_replacement(&replacement);
     assert_eq!(formatted, r#"{{VAR}}=value with "quotes" and $vars"#);
@@ -218,11 +226,8 @@ fn replacement_with_special_chars() {
 fn replacement_with_newlines() {
     use crate::cli::ReplacementConfig;
 
-    let replacement = ReplacementConfig {
-        source: "{{VAR}}".to_owned(),
-        target: Some("line1\nline2".to_owned()),
-        value_from_env: None,
-    };
+    let replacement =
+        ReplacementConfig::new("{{VAR}}".to_owned(), Some("line1\nline2".to_owned()), None);
 
     let formatted = format_replacement(&replacement);
     assert_eq!(formatted, "{{VAR}}=line1\nline2");
@@ -238,13 +243,10 @@ fn empty_pulls_array() {
     let config = Config {
         repository: Some("my_organization/repo".to_owned()),
         tag: Some("main".to_owned()),
-        context: HashMap::new(),
-        pulls: vec![],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
-    let args = build_command_args(&config);
+    let args = build_command_args(&config, DEFAULT_PROGRAM_NAME);
     // Should still work, just no pull args
     assert_eq!(args[0], "tixgraft");
     assert!(args.contains(&"--repository".to_owned()));
@@ -257,26 +259,14 @@ fn path_with_spaces() {
 
     let config = Config {
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
-        context: HashMap::new(),
         pulls: vec![PullConfig {
-            source: "src with spaces".to_owned(),
-            target: "dst with spaces".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src with spaces", "dst with spaces")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
-    let result = generate_command_line(&config, OutputFormat::Shell);
+    let result = generate_command_line(&config, &CommandLineOptions::new());
     assert!(result.is_ok());
     let output = result.unwrap();
     // Paths with spaces should be quoted
@@ -290,26 +280,14 @@ fn file_type_pull() {
 
     let config = Config {
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
-        context: HashMap::new(),
         pulls: vec![PullConfig {
-            source: "file.txt".to_owned(),
-            target: "output.txt".to_owned(),
             pull_type: "file".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("file.txt", "output.txt")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
-    let args = build_command_args(&config);
+    let args = build_command_args(&config, DEFAULT_PROGRAM_NAME);
     // File type should be included since it's not the default
     assert!(args.contains(&"--pull-type".to_owned()));
     assert!(args.contains(&"file".to_owned()));
@@ -322,40 +300,23 @@ fn per_pull_overrides() {
     let config = Config {
         repository: Some("global/repo".to_owned()),
         tag: Some("v1".to_owned()),
-        context: HashMap::new(),
+        // Override
         pulls: vec![
             PullConfig {
-                source: "src1".to_owned(),
-                target: "dst1".to_owned(),
                 pull_type: "directory".to_owned(),
-                repository: None, // Uses global
-                tag: None,        // Uses global
-                reset: false,
-                require_clean_target: true,
-                must_succeed: true,
-                commands: vec![],
-                replacements: vec![],
-                context: HashMap::new(),
+                ..PullConfig::new("src1", "dst1")
             },
             PullConfig {
-                source: "src2".to_owned(),
-                target: "dst2".to_owned(),
                 pull_type: "directory".to_owned(),
-                repository: Some("per-pull/repo".to_owned()), // Override
-                tag: Some("v2".to_owned()),                   // Override
-                reset: false,
-                require_clean_target: true,
-                must_succeed: true,
-                commands: vec![],
-                replacements: vec![],
-                context: HashMap::new(),
+                repository: Some("per-pull/repo".to_owned()),
+                tag: Some("v2".to_owned()),
+                ..PullConfig::new("src2", "dst2")
             },
         ],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
-    let args = build_command_args(&config);
+    let args = build_command_args(&config, DEFAULT_PROGRAM_NAME);
     // Should have per-pull overrides for the second pull
     assert!(args.contains(&"--pull-repository".to_owned()));
     assert!(args.contains(&"per-pull/repo".to_owned()));
@@ -369,26 +330,14 @@ fn must_succeed_true_not_emitted() {
 
     let config = Config {
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
-        context: HashMap::new(),
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
-    let args = build_command_args(&config);
+    let args = build_command_args(&config, DEFAULT_PROGRAM_NAME);
     // Default must_succeed=true should NOT emit the flag
     assert!(!args.contains(&"--pull-must-succeed".to_owned()));
 }
@@ -399,27 +348,36 @@ fn must_succeed_false_emitted() {
 
     let config = Config {
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
-        context: HashMap::new(),
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
             must_succeed: false,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
-    let args = build_command_args(&config);
+    let args = build_command_args(&config, DEFAULT_PROGRAM_NAME);
     // must_succeed=false should emit the flag
     assert!(args.contains(&"--pull-must-succeed".to_owned()));
     assert!(args.contains(&"false".to_owned()));
 }
+
+#[test]
+fn command_line_options_select_formats_program_and_dialect() {
+    let config = Config {
+        repository: Some("my_organization/repo".to_owned()),
+        ..Config::default()
+    };
+
+    let options = CommandLineOptions::new()
+        .with_dialect(ShellDialect::PowerShell)
+        .with_formats(&[OutputFormat::Shell, OutputFormat::Json])
+        .with_program("graft tool");
+    let output = generate_command_line(&config, &options).unwrap();
+    let (shell, json) = output.split_once("\n\n").unwrap();
+    assert!(shell.starts_with("# shell\n'graft tool'"));
+    assert!(json.starts_with("# json\n"));
+    assert!(json.contains(r#""graft tool""#));
+
+    generate_command_line(&config, &CommandLineOptions::new().with_formats(&[])).unwrap_err();
+}
@@ -4,33 +4,19 @@
     reason = "index-based assertions are acceptable in tests"
 )]
 
-use std::collections::HashMap;
-
 use super::*;
 use crate::cli::{PullConfig, ReplacementConfig};
 
 #[test]
 fn serialize_basic_config() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
         tag: Some("main".to_owned()),
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     let yaml = serialize_config(&config).unwrap();
@@ -45,25 +31,12 @@ fn serialize_basic_config() {
 #[test]
 fn serialize_with_header() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     let yaml = serialize_config(&config).unwrap();
@@ -76,25 +49,13 @@ fn serialize_with_header() {
 #[test]
 fn roundtrip_basic() {
     let original_config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
             reset: true,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     // Serialize to YAML
@@ -123,36 +84,16 @@ fn roundtrip_basic() {
 #[test]
 fn config_with_replacements() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
             replacements: vec![
-                ReplacementConfig {
-                    source: "{{VAR1}}".to_owned(),
-                    target: Some("value1".to_owned()),
-                    value_from_env: None,
-                },
-                ReplacementConfig {
-                    source: "{{VAR2}}".to_owned(),
-                    target: None,
-                    value_from_env: Some("MY_ENV".to_owned()),
-                },
+                ReplacementConfig::new("{{VAR1}}".to_owned(), Some("value1".to_owned()), None),
+                ReplacementConfig::new("{{VAR2}}".to_owned(), None, Some("MY_ENV".to_owned())),
             ],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     let yaml = serialize_config(&config).unwrap();
@@ -166,25 +107,13 @@ fn config_with_replacements() {
 #[test]
 fn config_with_commands() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
             commands: vec!["npm install".to_owned(), "npm run build".to_owned()],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     let yaml = serialize_config(&config).unwrap();
@@ -197,40 +126,22 @@ fn config_with_commands() {
 #[test]
 fn config_per_pull_overrides() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("global/repo".to_owned()),
         tag: Some("v1".to_owned()),
+        // Override
         pulls: vec![
             PullConfig {
-                source: "src1".to_owned(),
-                target: "dst1".to_owned(),
                 pull_type: "directory".to_owned(),
-                repository: None, // Uses global
-                tag: None,        // Uses global
-                reset: false,
-                require_clean_target: true,
-                must_succeed: true,
-                commands: vec![],
-                replacements: vec![],
-                context: HashMap::new(),
+                ..PullConfig::new("src1", "dst1")
             },
             PullConfig {
-                source: "src2".to_owned(),
-                target: "dst2".to_owned(),
                 pull_type: "directory".to_owned(),
-                repository: Some("per-pull/repo".to_owned()), // Override
-                tag: Some("v2".to_owned()),                   // Override
-                reset: false,
-                require_clean_target: true,
-                must_succeed: true,
-                commands: vec![],
-                replacements: vec![],
-                context: HashMap::new(),
+                repository: Some("per-pull/repo".to_owned()),
+                tag: Some("v2".to_owned()),
+                ..PullConfig::new("src2", "dst2")
             },
         ],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     let yaml = serialize_config(&config).unwrap();
@@ -243,25 +154,12 @@ fn config_per_pull_overrides() {
 #[test]
 fn config_with_file_type() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "file.txt".to_owned(),
-            target: "output.txt".to_owned(),
             pull_type: "file".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("file.txt", "output.txt")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     let yaml = serialize_config(&config).unwrap();
@@ -272,25 +170,12 @@ fn config_with_file_type() {
 #[test]
 fn config_with_special_characters_in_paths() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "path with spaces".to_owned(),
-            target: "./target with spaces".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("path with spaces", "./target with spaces")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     let yaml = serialize_config(&config).unwrap();
@@ -303,25 +188,13 @@ fn config_with_special_characters_in_paths() {
 #[test]
 fn config_with_multiline_command() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
             commands: vec!["echo 'line1'\necho 'line2'".to_owned()],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     let yaml = serialize_config(&config).unwrap();
@@ -333,13 +206,9 @@ fn config_with_multiline_command() {
 #[test]
 fn config_empty_pulls_array_fails() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
         tag: Some("main".to_owned()),
-        pulls: vec![],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     // Serialization should work, but validation would fail
@@ -350,29 +219,17 @@ fn config_empty_pulls_array_fails() {
 #[test]
 fn replacement_with_special_chars() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![ReplacementConfig {
-                source: "{{VAR}}".to_owned(),
-                target: Some("value with $special &chars".to_owned()),
-                value_from_env: None,
-            }],
-            context: HashMap::new(),
+            replacements: vec![ReplacementConfig::new(
+                "{{VAR}}".to_owned(),
+                Some("value with $special &chars".to_owned()),
+                None,
+            )],
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     let yaml = serialize_config(&config).unwrap();
@@ -385,25 +242,13 @@ fn replacement_with_special_chars() {
 #[test]
 fn serialize_must_succeed_false() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
             must_succeed: false,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     let yaml = serialize_config(&config).unwrap();
@@ -415,25 +260,13 @@ fn serialize_must_succeed_false() {
 #[test]
 fn roundtrip_must_succeed_false() {
     let original_config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
             must_succeed: false,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     // Serialize to YAML
@@ -455,25 +288,12 @@ fn roundtrip_must_succeed_false() {
 #[test]
 fn serialize_must_succeed_true_is_default() {
     let config = Config {
-        context: HashMap::new(),
-
         repository: Some("my_organization/repo".to_owned()),
-        tag: None,
         pulls: vec![PullConfig {
-            source: "src".to_owned(),
-            target: "dst".to_owned(),
             pull_type: "directory".to_owned(),
-            repository: None,
-            tag: None,
-            reset: false,
-            require_clean_target: true,
-            must_succeed: true,
-            commands: vec![],
-            replacements: vec![],
-            context: HashMap::new(),
+            ..PullConfig::new("src", "dst")
         }],
-        children: Vec::new(),
-        process_children_first: false,
+        ..Config::default()
     };
 
     // Serialize to YAML
@@ -491,3 +311,25 @@ fn serialize_must_succeed_true_is_default() {
     // must_succeed defaults to true
     assert!(parsed_config.pulls[0].must_succeed);
 }
+
+#[test]
+fn roundtrip_preserves_pull_description() {
+    let original_config: Config = serde_yaml::from_str(
+        "repository: my_organization/repo\npulls:\n  - source: src\n    target: dst\n    description: Shared lint config\n",
+    )
+    .unwrap();
+
+    let yaml = serialize_config(&original_config).unwrap();
+    assert!(yaml.contains("description: Shared lint config"));
+
+    let yaml_without_comments: String = yaml
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<&str>>()
+        .join("\n");
+    let parsed_config: Config = serde_yaml::from_str(&yaml_without_comments).unwrap();
+    assert_eq!(
+        parsed_config.pulls[0].description.as_deref(),
+        Some("Shared lint config")
+    );
+}
@@ -0,0 +1,107 @@
+//! Value transforms applied to resolved replacement values.
+//!
+//! A replacement's `transform` runs on the value after it is resolved from
+//! `target`, `valueFromEnv` or `valueFromContext`, and before it is written
+//! into files (e.g. base64-encoding a secret read from the environment).
+//! `stripPrefix` and `stripSuffix` run first, so `stripSuffix: ".ts"` with
+//! `transform: upper` turns `Foo.ts` into `FOO`.
+
+use base64::Engine as _;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+/// Built-in transform applied to a resolved replacement value.
+///
+/// Unknown names are rejected when the config is parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Transform {
+    /// Standard base64 with padding.
+    Base64,
+    /// URL-safe base64 without padding.
+    Base64Url,
+    /// Lowercase the value.
+    Lower,
+    /// Hex-encoded SHA-256 digest of the value.
+    Sha256,
+    /// Strip leading and trailing whitespace.
+    Trim,
+    /// Uppercase the value.
+    Upper,
+    /// Percent-encode everything except RFC 3986 unreserved characters.
+    Urlencode,
+}
+
+impl Transform {
+    /// Apply the transform to `value`.
+    #[must_use]
+    #[inline]
+    pub fn apply(self, value: &str) -> String {
+        match self {
+            Self::Base64 => STANDARD.encode(value),
+            Self::Base64Url => URL_SAFE_NO_PAD.encode(value),
+            Self::Lower => value.to_lowercase(),
+            Self::Sha256 => Sha256::digest(value.as_bytes())
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect(),
+            Self::Trim => value.trim().to_owned(),
+            Self::Upper => value.to_uppercase(),
+            Self::Urlencode => url_encode(value),
+        }
+    }
+}
+
+/// Apply `transform` to `value` when one is configured.
+#[must_use]
+#[inline]
+pub fn apply_transform(value: String, transform: Option<Transform>) -> String {
+    match transform {
+        Some(transform) => transform.apply(&value),
+        None => value,
+    }
+}
+
+/// Remove `prefix` from the start and then `suffix` from the end of `value`,
+/// where it has them; a value without them is left as it is.
+#[must_use]
+#[inline]
+pub fn strip_affixes(value: &str, prefix: Option<&str>, suffix: Option<&str>) -> String {
+    let value = prefix
+        .and_then(|prefix| value.strip_prefix(prefix))
+        .unwrap_or(value);
+    suffix
+        .and_then(|suffix| value.strip_suffix(suffix))
+        .unwrap_or(value)
+        .to_owned()
+}
+
+/// Remove `prefix` from the start and then `suffix` from the end of `value`,
+/// where it has them; a value without them is left as it is.
+#[must_use]
+#[inline]
+pub fn strip_affixes(value: &str, prefix: Option<&str>, suffix: Option<&str>) -> String {
+    let value = prefix
+        .and_then(|prefix| value.strip_prefix(prefix))
+        .unwrap_or(value);
+    suffix
+        .and_then(|suffix| value.strip_suffix(suffix))
+        .unwrap_or(value)
+        .to_owned()
+}
+
+/// Percent-encode `value`, keeping only unreserved characters (`A-Z a-z 0-9 - _ . ~`).
+fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
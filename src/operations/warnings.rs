@@ -0,0 +1,92 @@
+//! Run-scoped warning collection.
+//!
+//! Non-fatal problems (failed graft post-commands, failed `postPull`
+//! commands, optional pulls that failed) are logged as they happen and also
+//! collected in a [`WarningSink`], so they can be summarized once at the end
+//! of the run instead of getting lost in the log.
+
+use core::cell::RefCell;
+use core::fmt;
+use serde::Serialize;
+use std::path::Path;
+use tracing::warn;
+
+/// A non-fatal problem raised during a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct RunWarning {
+    /// Directory or target the warning relates to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl fmt::Display for RunWarning {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location.as_ref() {
+            Some(location) => write!(formatter, "{location}: {}", self.message),
+            None => write!(formatter, "{}", self.message),
+        }
+    }
+}
+
+/// Collects [`RunWarning`]s for the end-of-run summary.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct WarningSink {
+    /// Warnings collected so far, in order.
+    warnings: RefCell<Vec<RunWarning>>,
+}
+
+impl WarningSink {
+    /// Whether no warning has been collected.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.warnings.borrow().is_empty()
+    }
+
+    /// Create an empty sink.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Log a warning and collect it for the summary.
+    #[inline]
+    pub fn push(&self, location: Option<&Path>, message: &str) {
+        let warning = RunWarning {
+            location: location.map(|path| path.display().to_string()),
+            message: message.to_owned(),
+        };
+        warn!("{warning}");
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    /// The consolidated `Warnings (N):` block, or `None` when there are none.
+    #[must_use]
+    #[inline]
+    pub fn summary(&self) -> Option<String> {
+        let warnings = self.warnings.borrow();
+        if warnings.is_empty() {
+            return None;
+        }
+
+        let mut summary = format!("Warnings ({}):", warnings.len());
+        for warning in warnings.iter() {
+            summary.push_str("\n  - ");
+            summary.push_str(&warning.to_string());
+        }
+        Some(summary)
+    }
+
+    /// All warnings collected so far, in order.
+    #[must_use]
+    #[inline]
+    pub fn warnings(&self) -> Vec<RunWarning> {
+        self.warnings.borrow().clone()
+    }
+}
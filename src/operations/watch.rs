@@ -0,0 +1,112 @@
+//! Re-running local pulls when their source changes (`--watch`).
+//!
+//! Only pulls from local (`file:`) sources are watched. Change events are
+//! collected until the source has been quiet for a short debounce window,
+//! then each affected pull is executed again. Pulls re-copy their source
+//! and re-apply their replacements from scratch, so re-runs are idempotent.
+
+use anyhow::{Context as _, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+use tracing::{debug, error};
+
+/// How long the sources must stay quiet before affected pulls re-run.
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A pull whose local source is being watched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WatchedPull {
+    /// Index of the pull in its config's `pulls`.
+    pub index: usize,
+    /// Resolved path of the pull's source (a file or directory).
+    pub source: PathBuf,
+}
+
+impl WatchedPull {
+    /// Watch `source` on behalf of the pull at `index`.
+    #[must_use]
+    #[inline]
+    pub const fn new(index: usize, source: PathBuf) -> Self {
+        Self { index, source }
+    }
+
+    /// Whether a change to `path` affects this pull.
+    #[must_use]
+    #[inline]
+    pub fn is_affected_by(&self, path: &Path) -> bool {
+        path.starts_with(&self.source)
+    }
+}
+
+/// Start watching the sources of `watched`, sending each changed path.
+///
+/// The returned watcher must be kept alive for events to arrive.
+///
+/// # Errors
+///
+/// Returns an error if the watcher cannot be created or a source cannot be
+/// watched.
+#[inline]
+pub fn start_watcher(watched: &[WatchedPull]) -> Result<(RecommendedWatcher, Receiver<PathBuf>)> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let Ok(changed) = event else {
+            return;
+        };
+        if matches!(changed.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in changed.paths {
+            // The receiver is gone once watching stops
+            if sender.send(path).is_err() {
+                return;
+            }
+        }
+    })
+    .context("Failed to create filesystem watcher")?;
+
+    for pull in watched {
+        watcher
+            .watch(&pull.source, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch {}", pull.source.display()))?;
+    }
+    Ok((watcher, receiver))
+}
+
+/// Re-run the pulls affected by each debounced batch of changes from
+/// `events` until the channel closes.
+///
+/// `rerun` is called once per affected pull index per batch. A failing
+/// re-run is logged and watching continues.
+#[inline]
+pub fn run_watch_loop<F>(
+    events: &Receiver<PathBuf>,
+    watched: &[WatchedPull],
+    debounce: Duration,
+    mut rerun: F,
+) where
+    F: FnMut(usize) -> Result<()>,
+{
+    while let Ok(first) = events.recv() {
+        let mut changed = vec![first];
+        while let Ok(path) = events.recv_timeout(debounce) {
+            changed.push(path);
+        }
+        debug!("Detected {} change(s)", changed.len());
+
+        for pull in watched {
+            if !changed.iter().any(|path| pull.is_affected_by(path)) {
+                continue;
+            }
+            if let Err(err) = rerun(pull.index) {
+                error!(
+                    "Re-running pull #{} failed: {err:#}",
+                    pull.index.saturating_add(1)
+                );
+            }
+        }
+    }
+}
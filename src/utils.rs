@@ -1,4 +1,6 @@
 //! Utility functions and helpers.
 
+pub mod encoding;
 pub mod fs;
 pub mod path;
+pub mod process;
@@ -0,0 +1,89 @@
+//! Non-UTF-8 text encodings for a pull's files (`encoding`).
+//!
+//! Files are decoded before text replacements run and re-encoded in the
+//! same encoding (keeping any byte order mark) when written back. Labels are
+//! the WHATWG ones `encoding_rs` understands (`utf-16`, `latin1`,
+//! `shift_jis`, ...); note that `latin1` is decoded as windows-1252.
+
+use encoding_rs::{Encoding, UTF_8, UTF_16BE, UTF_16LE};
+
+/// A text encoding, with whether files carry a byte order mark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TextEncoding {
+    /// Whether the byte order mark is written before the text.
+    bom: bool,
+    /// The underlying encoding.
+    encoding: &'static Encoding,
+}
+
+impl TextEncoding {
+    /// Decode `bytes`, returning the text and the encoding to write it back
+    /// in, or `None` when the bytes are malformed.
+    ///
+    /// A byte order mark overrides this encoding (a `utf-16` file may be
+    /// big-endian) and is kept for [`encode`](Self::encode).
+    #[must_use]
+    #[inline]
+    pub fn decode(self, bytes: &[u8]) -> Option<(String, Self)> {
+        let (encoding, bom_length) = Encoding::for_bom(bytes).unwrap_or((self.encoding, 0));
+        let body = bytes.get(bom_length..).unwrap_or_default();
+        let text = encoding.decode_without_bom_handling_and_without_replacement(body)?;
+        Some((
+            text.into_owned(),
+            Self {
+                bom: bom_length > 0,
+                encoding,
+            },
+        ))
+    }
+
+    /// Encode `text`, or `None` when it holds characters this encoding
+    /// cannot represent.
+    #[must_use]
+    #[inline]
+    pub fn encode(self, text: &str) -> Option<Vec<u8>> {
+        // encoding_rs only decodes UTF-16, so it is encoded by hand
+        let mut bytes = Vec::new();
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            let little_endian = self.encoding == UTF_16LE;
+            let bom = self.bom.then_some(0xFEFF_u16);
+            for unit in bom.into_iter().chain(text.encode_utf16()) {
+                bytes.extend(if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                });
+            }
+            return Some(bytes);
+        }
+
+        if self.bom && self.encoding == UTF_8 {
+            bytes.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        let (encoded, _, unmappable) = self.encoding.encode(text);
+        if unmappable {
+            return None;
+        }
+        bytes.extend_from_slice(&encoded);
+        Some(bytes)
+    }
+
+    /// The encoding named by `label` (e.g. `utf-16`, `latin1`), or `None`
+    /// when the label is unknown.
+    #[must_use]
+    #[inline]
+    pub fn for_label(label: &str) -> Option<Self> {
+        Encoding::for_label(label.trim().as_bytes()).map(|encoding| Self {
+            bom: false,
+            encoding,
+        })
+    }
+
+    /// Canonical name of the encoding (e.g. `UTF-16LE`).
+    #[must_use]
+    #[inline]
+    pub fn name(self) -> &'static str {
+        self.encoding.name()
+    }
+}
@@ -1,5 +1,6 @@
 //! File system utilities.
 
+use crate::error::GraftError;
 use anyhow::{Context as _, Result};
 use os_shim::System;
 use std::io::{self, Read as _, Write as _};
@@ -91,11 +92,52 @@ pub fn create_parent_directories(system: &dyn System, file_path: &Path) -> Resul
     Ok(())
 }
 
-/// Check if a file is binary by examining its extension and content.
-///
-/// # Returns
+/// Why a file was classified as text or binary (see [`classify_file`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BinaryClassification {
+    /// The file is empty (text).
+    Empty,
+    /// The content is not valid UTF-8 (binary).
+    InvalidUtf8,
+    /// The path is not a regular file (not binary).
+    NotAFile,
+    /// The content contains a NUL byte (binary).
+    NullByte,
+    /// The extension is a known text extension (text, content not read).
+    TextExtension(String),
+    /// The content is valid UTF-8 without NUL bytes (text).
+    ValidUtf8,
+}
+
+impl BinaryClassification {
+    /// Whether this classification means the file is treated as binary.
+    #[must_use]
+    #[inline]
+    pub const fn is_binary(&self) -> bool {
+        matches!(*self, Self::InvalidUtf8 | Self::NullByte)
+    }
+
+    /// Human-readable reason for the classification.
+    #[must_use]
+    #[inline]
+    pub fn reason(&self) -> String {
+        match self {
+            Self::Empty => "empty file".to_owned(),
+            Self::InvalidUtf8 => "content is not valid UTF-8".to_owned(),
+            Self::NotAFile => "not a regular file".to_owned(),
+            Self::NullByte => "content contains a NUL byte".to_owned(),
+            Self::TextExtension(extension) => format!("known text extension '.{extension}'"),
+            Self::ValidUtf8 => "content is valid UTF-8 without NUL bytes".to_owned(),
+        }
+    }
+}
+
+/// Classify a file as text or binary, recording why.
 ///
-/// Returns true if the file is binary, false otherwise.
+/// A known text extension (see [`text_extensions`]) decides without reading
+/// the file; otherwise the first 8 KiB are inspected: a NUL byte or invalid
+/// UTF-8 means binary.
 ///
 /// # Errors
 ///
@@ -103,17 +145,17 @@ pub fn create_parent_directories(system: &dyn System, file_path: &Path) -> Resul
 /// - The file cannot be opened
 /// - The file cannot be read
 #[inline]
-pub fn is_binary_file(system: &dyn System, file_path: &Path) -> Result<bool> {
+pub fn classify_file(system: &dyn System, file_path: &Path) -> Result<BinaryClassification> {
     // If it's a directory, it's not a binary file
     if !system.is_file(file_path)? {
-        return Ok(false);
+        return Ok(BinaryClassification::NotAFile);
     }
 
     // Check if it has a known text file extension
     if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
         let ext = extension.to_lowercase();
         if TEXT_EXTENSIONS.contains(&ext.as_str()) {
-            return Ok(false); // Known text file extension
+            return Ok(BinaryClassification::TextExtension(ext));
         }
     }
 
@@ -128,24 +170,45 @@ pub fn is_binary_file(system: &dyn System, file_path: &Path) -> Result<bool> {
         .with_context(|| format!("Failed to read from file: {}", file_path.display()))?;
 
     if bytes_read == 0 {
-        return Ok(false); // Empty file is text
+        return Ok(BinaryClassification::Empty);
     }
 
     // Check for null bytes - text files don't have them
     let read_slice = buffer.get(..bytes_read).unwrap_or(&buffer);
-    for &byte in read_slice {
-        if byte == 0 {
-            return Ok(true); // Has null byte = binary
-        }
+    if read_slice.contains(&0) {
+        return Ok(BinaryClassification::NullByte);
     }
 
     // Check if it's valid UTF-8
     if str::from_utf8(read_slice).is_ok() {
-        return Ok(false); // Valid UTF-8 = text
+        return Ok(BinaryClassification::ValidUtf8);
     }
 
     // Not valid UTF-8 and no null bytes = assume binary
-    Ok(true)
+    Ok(BinaryClassification::InvalidUtf8)
+}
+
+/// Check if a file is binary by examining its extension and content.
+///
+/// # Returns
+///
+/// Returns true if the file is binary, false otherwise.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The file cannot be opened
+/// - The file cannot be read
+#[inline]
+pub fn is_binary_file(system: &dyn System, file_path: &Path) -> Result<bool> {
+    Ok(classify_file(system, file_path)?.is_binary())
+}
+
+/// Extensions that are always treated as text, without reading the file.
+#[must_use]
+#[inline]
+pub const fn text_extensions() -> &'static [&'static str] {
+    TEXT_EXTENSIONS
 }
 
 /// Get file size in bytes.
@@ -325,6 +388,35 @@ pub fn create_temp_dir(prefix: &str) -> Result<tempfile::TempDir> {
         .context("Failed to create temporary directory")
 }
 
+/// Check that `root` can hold temporary directories (`--temp-dir`).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `root` does not exist or is not a directory
+/// - A file cannot be created in `root`
+#[inline]
+pub fn validate_temp_root(system: &dyn System, root: &Path) -> Result<()> {
+    if !system.is_dir(root).unwrap_or(false) {
+        return Err(GraftError::configuration(format!(
+            "Temporary directory root does not exist or is not a directory: {}",
+            root.display()
+        ))
+        .into());
+    }
+
+    let probe = root.join(".tixgraft-write-probe");
+    system.write(&probe, b"").map_err(|err| {
+        GraftError::configuration(format!(
+            "Temporary directory root is not writable: {}: {err}",
+            root.display()
+        ))
+    })?;
+    system
+        .remove_file(&probe)
+        .with_context(|| format!("Failed to remove write probe: {}", probe.display()))
+}
+
 /// Ensure a directory exists, creating it if necessary.
 ///
 /// # Errors
@@ -1,9 +1,39 @@
 //! Path manipulation and validation utilities.
 
 use crate::error::GraftError;
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
+use os_shim::System;
 use std::path::{Component, Path, PathBuf};
 
+/// Normalize paths against a [`System`], whether or not they exist yet.
+///
+/// `System::canonicalize` fails on paths that have not been created (the real
+/// system) or keeps their `..` components (the mock), so targets are
+/// resolved with [`NormalizePath::normalize_path`] instead.
+pub trait NormalizePath {
+    /// Make `path` absolute (relative to the current directory) and resolve
+    /// its `.` and `..` components logically, without requiring it to exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - `path` is relative and the current directory cannot be determined
+    fn normalize_path(&self, path: &Path) -> Result<PathBuf>;
+}
+
+impl<S: System + ?Sized> NormalizePath for S {
+    #[inline]
+    fn normalize_path(&self, path: &Path) -> Result<PathBuf> {
+        if path.is_absolute() {
+            return Ok(normalize(path));
+        }
+        let current_dir = self
+            .canonicalize(Path::new("."))
+            .context("Failed to determine the current directory")?;
+        Ok(normalize(&current_dir.join(path)))
+    }
+}
+
 /// Normalize a path by resolving `.` and `..` components.
 #[must_use]
 #[inline]
@@ -0,0 +1,256 @@
+//! Child process groups, so that a timed-out run can stop what it started.
+//!
+//! With `--timeout`, every child process (git, commands, post-commands) is
+//! spawned through [`spawn_child`], which starts it as the leader of its own
+//! process group and records its PID while it runs. Signalling those groups
+//! also reaches the processes the children spawned themselves, such as the
+//! stages of a shell pipeline or git's transport helpers.
+
+#[cfg(not(unix))]
+use core::time::Duration;
+use std::io::{self, Read};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt as _;
+use std::process::{Child, ChildStderr, ChildStdout, Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use tracing::debug;
+
+/// How often a waiter checks whether its child is to be killed (non-Unix
+/// only).
+#[cfg(not(unix))]
+const TERMINATION_POLL: Duration = Duration::from_millis(20);
+
+/// Whether child processes are started in their own process group.
+static OWN_PROCESS_GROUPS: AtomicBool = AtomicBool::new(false);
+
+/// PIDs of the running children started while process groups are enabled.
+static RUNNING_CHILDREN: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Set by [`terminate_child_process_groups`]: no further children are
+/// started, and on non-Unix platforms waiters kill their child.
+static TERMINATING: AtomicBool = AtomicBool::new(false);
+
+/// A child process started by [`spawn_child`], recorded as running until it
+/// is waited for or dropped.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct TrackedChild {
+    /// The spawned process.
+    child: Child,
+    /// Whether the PID was recorded in [`RUNNING_CHILDREN`].
+    tracked: bool,
+}
+
+impl TrackedChild {
+    /// The process ID of the child.
+    #[must_use]
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Take the child's captured standard error, if piped.
+    #[inline]
+    pub const fn take_stderr(&mut self) -> Option<ChildStderr> {
+        self.child.stderr.take()
+    }
+
+    /// Take the child's captured standard output, if piped.
+    #[inline]
+    pub const fn take_stdout(&mut self) -> Option<ChildStdout> {
+        self.child.stdout.take()
+    }
+
+    /// Wait for the child to exit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Waiting for the process fails
+    #[inline]
+    pub fn wait(mut self) -> io::Result<ExitStatus> {
+        wait_for(&mut self.child)
+    }
+
+    /// Wait for the child to exit, collecting its piped output like
+    /// [`Child::wait_with_output`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Reading the output or waiting for the process fails
+    #[inline]
+    pub fn wait_with_output(mut self) -> io::Result<Output> {
+        let stdout_pipe = self.take_stdout();
+        let stderr_pipe = self.take_stderr();
+        let stdout_reader = thread::spawn(move || read_pipe(stdout_pipe));
+        let stderr_reader = thread::spawn(move || read_pipe(stderr_pipe));
+        let status = wait_for(&mut self.child)?;
+        Ok(Output {
+            status,
+            stdout: stdout_reader
+                .join()
+                .map_err(|_panic| io::Error::other("stdout reader thread panicked"))??,
+            stderr: stderr_reader
+                .join()
+                .map_err(|_panic| io::Error::other("stderr reader thread panicked"))??,
+        })
+    }
+}
+
+impl Drop for TrackedChild {
+    #[inline]
+    fn drop(&mut self) {
+        if !self.tracked {
+            return;
+        }
+        let pid = self.child.id();
+        let mut running = RUNNING_CHILDREN
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(index) = running.iter().position(|&running_pid| running_pid == pid) {
+            running.swap_remove(index);
+        }
+    }
+}
+
+/// Run `command` to completion through [`spawn_child`], capturing its output
+/// like [`Command::output`]: standard input is closed and standard output
+/// and error are piped.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The process cannot be spawned or waited for
+#[inline]
+pub fn child_output(command: &mut Command) -> io::Result<Output> {
+    spawn_child(
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped()),
+    )?
+    .wait_with_output()
+}
+
+/// Run `command` to completion through [`spawn_child`], like
+/// [`Command::status`].
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The process cannot be spawned or waited for
+#[inline]
+pub fn child_status(command: &mut Command) -> io::Result<ExitStatus> {
+    spawn_child(command)?.wait()
+}
+
+/// Start every child process spawned from now on in its own process group.
+///
+/// Children in their own group no longer receive the terminal's signals
+/// (such as Ctrl-C) directly, so this is only enabled for runs with a
+/// deadline.
+#[inline]
+pub fn enable_process_groups() {
+    OWN_PROCESS_GROUPS.store(true, Ordering::Relaxed);
+}
+
+/// Read all of `pipe`, if any.
+fn read_pipe<R: Read>(pipe: Option<R>) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    if let Some(mut pipe) = pipe {
+        pipe.read_to_end(&mut bytes)?;
+    }
+    Ok(bytes)
+}
+
+/// Spawn `command`. Once [`enable_process_groups`] was called, the child
+/// leads a new process group (Unix) and is recorded for
+/// [`terminate_child_process_groups`] until it is waited for.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The child processes are being terminated
+/// - The process cannot be spawned
+#[inline]
+pub fn spawn_child(command: &mut Command) -> io::Result<TrackedChild> {
+    let tracked = OWN_PROCESS_GROUPS.load(Ordering::Relaxed);
+    #[cfg(unix)]
+    if tracked {
+        command.process_group(0);
+    }
+
+    // Spawn under the lock, so termination cannot miss a starting child
+    let mut running = RUNNING_CHILDREN
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if TERMINATING.load(Ordering::Relaxed) {
+        return Err(io::Error::other("child processes are being terminated"));
+    }
+    let child = command.spawn()?;
+    if tracked {
+        running.push(child.id());
+    }
+    Ok(TrackedChild { child, tracked })
+}
+
+/// Best-effort `SIGTERM` to the process group of every running child
+/// started by [`spawn_child`], which reaches their descendants too. No
+/// further children are started afterwards.
+///
+/// On non-Unix platforms each child is killed with [`Child::kill`] by the
+/// thread waiting for it.
+#[inline]
+pub fn terminate_child_process_groups() {
+    let running = RUNNING_CHILDREN
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    TERMINATING.store(true, Ordering::Relaxed);
+
+    // A child leads its own group, whose ID is the child's PID
+    #[cfg(unix)]
+    for pid in running.iter().copied() {
+        let Ok(group) = libc::pid_t::try_from(pid) else {
+            continue;
+        };
+        // SAFETY: killpg only sends a signal and takes no pointers
+        if unsafe { libc::killpg(group, libc::SIGTERM) } != 0_i32 {
+            debug!(
+                "Failed to terminate process group {pid}: {}",
+                io::Error::last_os_error()
+            );
+        }
+    }
+    #[cfg(not(unix))]
+    debug!("Killing {} running child process(es)", running.len());
+}
+
+/// Wait for `child` to exit.
+#[cfg(unix)]
+fn wait_for(child: &mut Child) -> io::Result<ExitStatus> {
+    child.wait()
+}
+
+/// Wait for `child` to exit, killing it once
+/// [`terminate_child_process_groups`] was called.
+#[cfg(not(unix))]
+fn wait_for(child: &mut Child) -> io::Result<ExitStatus> {
+    if !OWN_PROCESS_GROUPS.load(Ordering::Relaxed) {
+        return child.wait();
+    }
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if TERMINATING.load(Ordering::Relaxed) {
+            if let Err(err) = child.kill() {
+                debug!("Failed to kill child process {}: {err}", child.id());
+            }
+            return child.wait();
+        }
+        thread::sleep(TERMINATION_POLL);
+    }
+}
@@ -61,6 +61,34 @@ pulls:
         .stdout(predicate::str::contains("Dry run preview"));
 }
 
+#[test]
+fn dry_run_skips_git_check() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("test.yaml");
+    let empty_path = temp_dir.path().join("no-git");
+    fs::create_dir_all(&empty_path).unwrap();
+
+    let config_content = r#"
+repository: "example/test"
+pulls:
+  - source: "test/dir"
+    target: "./output"
+    type: "directory"
+"#;
+
+    fs::write(&config_path, config_content).unwrap();
+
+    // With no git on PATH, the version check would fail the run
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.env("PATH", &empty_path)
+        .arg("--config")
+        .arg(config_path.to_str().unwrap())
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Dry run preview"));
+}
+
 #[test]
 fn invalid_yaml_config() {
     let temp_dir = TempDir::new().unwrap();
@@ -139,3 +167,294 @@ fn cli_args_mismatch_sources_targets() {
         .code(1_i32) // Configuration error
         .stdout(predicate::str::contains("Mismatch"));
 }
+
+#[test]
+fn missing_ca_bundle_rejected_before_cloning() {
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.arg("--repository")
+        .arg("test/repo")
+        .arg("--pull-source")
+        .arg("src")
+        .arg("--pull-target")
+        .arg("./target")
+        .arg("--ca-bundle")
+        .arg("/nonexistent/ca.pem")
+        .assert()
+        .failure()
+        .code(1_i32)
+        .stdout(predicate::str::contains(
+            "--ca-bundle file not found: /nonexistent/ca.pem",
+        ));
+}
+
+#[test]
+fn cli_args_mismatch_shows_pairing() {
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.arg("--repository")
+        .arg("test/repo")
+        .arg("--pull-source")
+        .arg("src/dir1")
+        .arg("--pull-source")
+        .arg("src/dir2")
+        .arg("--pull-target")
+        .arg("./target1")
+        .assert()
+        .failure()
+        .code(1_i32)
+        .stdout(predicate::str::contains("#1: src/dir1 \u{2192} ./target1"))
+        .stdout(predicate::str::contains(
+            "#2: src/dir2 \u{2192} (missing --pull-target)",
+        ));
+}
+
+#[test]
+fn cli_args_extra_pull_types_rejected() {
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.arg("--repository")
+        .arg("test/repo")
+        .arg("--pull-source")
+        .arg("src/dir1")
+        .arg("--pull-target")
+        .arg("./target1")
+        .arg("--pull-type")
+        .arg("directory")
+        .arg("--pull-type")
+        .arg("file")
+        .assert()
+        .failure()
+        .code(1_i32)
+        .stdout(predicate::str::contains(
+            "--pull-type given 2 times but only 1 sources specified",
+        ));
+}
+
+#[test]
+fn invalid_clone_filter_rejected_before_cloning() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg("example/test")
+        .arg("--clone-filter")
+        .arg("blob:everything")
+        .arg("--pull-source")
+        .arg("src")
+        .arg("--pull-target")
+        .arg("./out")
+        .assert()
+        .failure()
+        .code(1_i32)
+        .stdout(predicate::str::contains("Invalid clone filter"));
+}
+
+#[test]
+fn diff_config_reports_differences_and_exit_code() {
+    let temp_dir = TempDir::new().unwrap();
+    let left = temp_dir.path().join("left.yaml");
+    let same = temp_dir.path().join("same.yaml");
+    let right = temp_dir.path().join("right.yaml");
+    fs::write(
+        &left,
+        "repository: my_org/repo\npulls:\n  - source: src\n    target: dst\n",
+    )
+    .unwrap();
+    fs::write(
+        &same,
+        "pulls:\n  - target: dst\n    source: src\n    reset: false\nrepository: my_org/repo\n",
+    )
+    .unwrap();
+    fs::write(
+        &right,
+        "repository: my_org/repo\npulls:\n  - source: src\n    target: other\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .arg("--diff-config")
+        .arg(&left)
+        .arg(&same)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Configs are equivalent"));
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .arg("--diff-config")
+        .arg(&left)
+        .arg(&right)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            r#"pulls[0].target: "dst" -> "other""#,
+        ));
+}
+
+#[test]
+fn migrate_config_upgrades_file_in_place() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = temp_dir.path().join("tixgraft.yaml");
+    fs::write(
+        &config,
+        "repository: my_org/repo\npulls:\n  - source: src\n    target: dst\n    must_succeed: false\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .arg("--config")
+        .arg(&config)
+        .arg("--migrate-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "pulls[0].must_succeed -> mustSucceed",
+        ));
+
+    let migrated = fs::read_to_string(&config).unwrap();
+    assert!(migrated.starts_with("version: 1\n"));
+    assert!(migrated.contains("mustSucceed: false"));
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .arg("--config")
+        .arg(&config)
+        .arg("--migrate-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already at config version 1"));
+}
+
+#[test]
+fn unversioned_config_warns_on_load() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = temp_dir.path().join("tixgraft.yaml");
+    fs::write(
+        &config,
+        "repository: my_org/repo\npulls:\n  - source: src\n    target: dst\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--config")
+        .arg(&config)
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Config has no 'version'"));
+}
+
+#[test]
+fn list_grafts_prints_context_and_replacements() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("template/plugins/auth")).unwrap();
+    fs::write(
+        temp_dir.path().join("template/.graft.yaml"),
+        r#"
+context:
+  - name: serviceName
+    description: "Name of the service"
+    dataType: string
+  - name: port
+    description: "Service port"
+    dataType: number
+    defaultValue: 8080
+replacements:
+  - source: "{{SERVICE_NAME}}"
+    valueFromContext: serviceName
+"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("template/plugins/auth/.graft.yaml"),
+        "replacements:\n  - source: \"{{AUTH}}\"\n    target: \"jwt\"\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .arg("--list-grafts")
+        .arg(temp_dir.path().join("template"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "serviceName (string, required): Name of the service",
+        ))
+        .stdout(predicate::str::contains("port (number, default 8080)"))
+        .stdout(predicate::str::contains("plugins/auth/.graft.yaml"))
+        .stdout(predicate::str::contains("- {{AUTH}}"));
+
+    // Nothing is processed: the graft files are left in place
+    assert!(temp_dir.path().join("template/.graft.yaml").exists());
+}
+
+#[test]
+fn list_text_extensions_and_test_binary() {
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.arg("--list-text-extensions")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".json\n"))
+        .stdout(predicate::str::contains(".yaml\n"));
+
+    let temp_dir = TempDir::new().unwrap();
+    let binary = temp_dir.path().join("blob.dat");
+    fs::write(&binary, [0_u8, 1, 2]).unwrap();
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.arg("--test-binary")
+        .arg(&binary)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("binary"))
+        .stdout(predicate::str::contains("NUL byte"));
+
+    let text = temp_dir.path().join("notes.md");
+    fs::write(&text, "# Notes\n").unwrap();
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.arg("--test-binary")
+        .arg(&text)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("text: known text extension '.md'"));
+}
+
+#[test]
+fn config_path_defaults_to_env_var() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("configs")).unwrap();
+    fs::write(
+        temp_dir.path().join("configs/from-env.yaml"),
+        "repository: my_org/from-env\npulls:\n  - source: src\n    target: dst\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("configs/explicit.yaml"),
+        "repository: my_org/explicit\npulls:\n  - source: src\n    target: dst\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .env("TIXGRAFT_CONFIG", "configs/from-env.yaml")
+        .arg("--to-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("my_org/from-env"));
+
+    // An explicit --config wins over the environment
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .env("TIXGRAFT_CONFIG", "configs/from-env.yaml")
+        .arg("--config")
+        .arg("configs/explicit.yaml")
+        .arg("--to-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("my_org/explicit"))
+        .stdout(predicate::str::contains("my_org/from-env").not());
+}
@@ -0,0 +1,4 @@
+//! Unit tests for semantic config comparison.
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,39 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use tixgraft::config::Config;
+
+/// Parse a config from YAML.
+fn parse(yaml: &str) -> Config {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[test]
+fn explicit_defaults_are_semantically_equal_to_omitted() {
+    let omitted =
+        parse("repository: my_org/repo\ntag: v1.0.0\npulls:\n  - source: src\n    target: dst\n");
+    let explicit = parse(
+        "tag: v1.0.0\nrepository: my_org/repo\ngraftFileName: .graft.yaml\ncloneFilter: blob:none\nprocessChildrenFirst: false\npulls:\n  - target: dst\n    source: src\n    type: auto\n    reset: false\n    repository: my_org/repo\n    tag: v1.0.0\n",
+    );
+
+    assert!(omitted.semantic_eq(&explicit));
+    assert!(explicit.semantic_eq(&omitted));
+    assert!(omitted.diff(&explicit).is_empty());
+}
+
+#[test]
+fn real_differences_are_reported_by_path() {
+    let left = parse("repository: my_org/repo\npulls:\n  - source: src\n    target: dst\n");
+    let right = parse(
+        "repository: my_org/repo\npulls:\n  - source: src\n    target: other\n    reset: true\n  - source: docs\n    target: docs\n",
+    );
+
+    assert!(!left.semantic_eq(&right));
+    let differences = left.diff(&right);
+    assert!(differences.contains(&r#"pulls[0].target: "dst" -> "other""#.to_owned()));
+    assert!(differences.contains(&"pulls[0].reset: false -> true".to_owned()));
+    assert!(
+        differences
+            .iter()
+            .any(|difference| difference.starts_with("pulls[1]: added"))
+    );
+}
@@ -0,0 +1,4 @@
+//! Unit tests for config version migration.
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,77 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use tixgraft::config::Config;
+use tixgraft::config::migrate::migrate_config;
+use tixgraft::config::schema::{CURRENT_CONFIG_VERSION, check_config_version};
+
+#[test]
+fn unversioned_config_warns() {
+    let warning = check_config_version(None).unwrap().unwrap();
+    assert!(warning.contains("no 'version'"));
+    assert!(warning.contains("--migrate-config"));
+
+    assert_eq!(
+        check_config_version(Some(CURRENT_CONFIG_VERSION)).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn newer_config_version_is_rejected() {
+    let err = check_config_version(Some(CURRENT_CONFIG_VERSION.saturating_add(1))).unwrap_err();
+    assert!(err.to_string().contains("upgrade tixgraft"));
+
+    migrate_config("version: 99\npulls: []\n").unwrap_err();
+}
+
+#[test]
+fn migrate_config_renames_legacy_keys() {
+    let legacy = "repository: my_org/repo\nprocess_children_first: true\npulls:\n  - source: src\n    target: dst\n    must_succeed: false\n    replacements:\n      - source: \"{{NAME}}\"\n        value_from_env: NAME\n";
+
+    // Before migrating, the legacy keys are silently ignored
+    let before: Config = serde_yaml::from_str(legacy).unwrap();
+    assert!(!before.process_children_first);
+
+    let migration = migrate_config(legacy).unwrap();
+    assert_eq!(migration.from_version, 0);
+    assert_eq!(
+        migration.changes,
+        [
+            "process_children_first -> processChildrenFirst",
+            "pulls[0].must_succeed -> mustSucceed",
+            "pulls[0].replacements[0].value_from_env -> valueFromEnv",
+            "version: 0 -> 1",
+        ]
+    );
+    assert!(migration.content.starts_with("version: 1\n"));
+
+    let after: Config = serde_yaml::from_str(&migration.content).unwrap();
+    assert_eq!(after.version, Some(CURRENT_CONFIG_VERSION));
+    assert!(after.process_children_first);
+    let pull = after.pulls.first().unwrap();
+    assert!(!pull.must_succeed);
+    assert_eq!(
+        pull.replacements.first().unwrap().value_from_env.as_deref(),
+        Some("NAME")
+    );
+}
+
+#[test]
+fn migrate_config_keeps_current_spelling_on_conflict() {
+    let migration = migrate_config(
+        "pulls:\n  - source: src\n    target: dst\n    mustSucceed: true\n    must_succeed: false\n",
+    )
+    .unwrap();
+
+    assert!(migration.changes.contains(
+        &"pulls[0].must_succeed: removed (pulls[0].mustSucceed is already set)".to_owned()
+    ));
+    let after: Config = serde_yaml::from_str(&migration.content).unwrap();
+    assert!(after.pulls.first().unwrap().must_succeed);
+}
+
+#[test]
+fn current_config_needs_no_migration() {
+    let migration = migrate_config("version: 1\npulls: []\n").unwrap();
+    assert!(migration.changes.is_empty());
+}
@@ -9,6 +9,7 @@
 )]
 
 use os_shim::mock::MockSystem;
+use tixgraft::cli::PullConfig;
 use tixgraft::config::Config;
 
 #[test]
@@ -161,3 +162,64 @@ pulls:
     let error = config.unwrap_err();
     assert!(error.to_string().contains("validation failed"));
 }
+
+#[test]
+fn config_normalizes_backslash_separators_in_pull_paths() {
+    let config_content = r#"
+repository: "my_organization/templates"
+pulls:
+  - source: 'docker\nodejs'
+    target: '.\services\docker'
+"#;
+
+    let system = MockSystem::new()
+        .with_file("/test/windows.yaml", config_content.as_bytes())
+        .unwrap();
+
+    let config = Config::load_from_file(&system, "/test/windows.yaml").unwrap();
+    assert_eq!(config.pulls[0].source, "docker/nodejs");
+    assert_eq!(config.pulls[0].target, "./services/docker");
+}
+
+#[test]
+fn validate_paths_rejects_parent_directory_target() {
+    let mut pull: PullConfig =
+        serde_yaml::from_str("source: app\ntarget: '..\\outside'\n").unwrap();
+
+    let error = pull.validate_paths().unwrap_err();
+    assert!(error.to_string().contains("Invalid target '../outside'"));
+}
+
+#[test]
+fn validate_paths_warns_on_trailing_slash_file_source() {
+    let mut pull: PullConfig =
+        serde_yaml::from_str("source: config.yaml/\ntarget: ./config.yaml\ntype: file\n").unwrap();
+
+    let warnings = pull.validate_paths().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("ends with '/'"));
+}
+
+#[test]
+fn config_rejects_unknown_replacement_group() {
+    let config_content = r#"
+repository: "my_organization/templates"
+replacementGroups:
+  org:
+    - source: "{{ORG}}"
+      target: "acme"
+pulls:
+  - source: "app"
+    target: "./app"
+    useGroups: ["org", "branding"]
+"#;
+
+    let system = MockSystem::new()
+        .with_file("/test/groups.yaml", config_content.as_bytes())
+        .unwrap();
+
+    let error = Config::load_from_file(&system, "/test/groups.yaml").unwrap_err();
+    assert!(
+        format!("{error:#}").contains("unknown replacement group 'branding' (defined groups: org)")
+    );
+}
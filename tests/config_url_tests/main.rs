@@ -0,0 +1,4 @@
+//! Tests for loading configuration from a repository (`--config-url`).
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,169 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use anyhow::Result;
+use os_shim::System as _;
+use os_shim::mock::MockSystem;
+use os_shim::real::RealSystem;
+use std::fs;
+use std::path::Path;
+use tixgraft::config::Config;
+use tixgraft::config::remote::ConfigUrl;
+use tixgraft::git::{GitInvocation, GitOutput, GitRunner, MockGitRunner};
+use tixgraft::operations::PullOperation;
+
+const REMOTE_CONFIG: &str = r#"
+repository: "my_org/templates"
+pulls:
+  - source: "docker/nodejs"
+    target: "./docker"
+  - source: "k8s/base"
+    target: "./k8s"
+"#;
+
+const TEMPLATE_CONFIG: &str = r#"
+pulls:
+  - source: "skeleton"
+    target: "./app"
+    requireCleanTarget: false
+"#;
+
+/// Mock runner that materializes the remote config file on `git checkout`.
+struct CheckoutWritesConfig {
+    /// Records invocations and answers them.
+    inner: MockGitRunner,
+}
+
+impl GitRunner for CheckoutWritesConfig {
+    fn run(&self, invocation: &GitInvocation) -> Result<GitOutput> {
+        if invocation.subcommand() == Some("checkout")
+            && let Some(repo_path) = invocation.cwd.as_ref()
+        {
+            fs::create_dir_all(repo_path.join("configs")).unwrap();
+            fs::write(repo_path.join("configs/web.yaml"), REMOTE_CONFIG).unwrap();
+            fs::write(repo_path.join("tixgraft.yaml"), TEMPLATE_CONFIG).unwrap();
+        }
+        self.inner.run(invocation)
+    }
+}
+
+#[test]
+fn parse_config_url() {
+    let parsed = ConfigUrl::parse("org/templates#main:configs/web.yaml").unwrap();
+    assert_eq!(parsed.repository, "org/templates");
+    assert_eq!(parsed.reference.as_deref(), Some("main"));
+    assert_eq!(parsed.path, "configs/web.yaml");
+
+    let ssh = ConfigUrl::parse("git@github.com:org/templates.git:web.yaml").unwrap();
+    assert_eq!(ssh.repository, "git@github.com:org/templates.git");
+    assert_eq!(ssh.reference, None);
+    assert_eq!(ssh.path, "web.yaml");
+}
+
+#[test]
+fn parse_config_url_invalid() {
+    ConfigUrl::parse("org/templates").unwrap_err();
+    ConfigUrl::parse("org/templates:").unwrap_err();
+    ConfigUrl::parse("org/templates#:web.yaml").unwrap_err();
+    ConfigUrl::parse("https://github.com/org/templates").unwrap_err();
+}
+
+#[test]
+fn load_from_url_checks_out_config_and_parses_pulls() {
+    let system = RealSystem::new();
+    let runner = CheckoutWritesConfig {
+        inner: MockGitRunner::new(),
+    };
+
+    let config =
+        Config::load_from_url(&system, &runner, "org/templates#v2:configs/web.yaml").unwrap();
+
+    assert_eq!(config.pulls.len(), 2);
+    assert_eq!(config.pulls.first().unwrap().source, "docker/nodejs");
+    assert_eq!(config.repository.as_deref(), Some("my_org/templates"));
+
+    let invocations = runner.inner.invocations();
+    let sparse_set = invocations
+        .iter()
+        .find(|invocation| {
+            invocation
+                .args
+                .starts_with(&["sparse-checkout".to_owned(), "set".to_owned()])
+        })
+        .unwrap();
+    assert!(sparse_set.args.contains(&"configs/web.yaml".to_owned()));
+    let checkout = invocations
+        .iter()
+        .find(|invocation| invocation.subcommand() == Some("checkout"))
+        .unwrap();
+    assert!(checkout.args.contains(&"v2".to_owned()));
+}
+
+#[test]
+fn parse_template_spec() {
+    let parsed = ConfigUrl::template("org/template-repo#v2").unwrap();
+    assert_eq!(parsed.repository, "org/template-repo");
+    assert_eq!(parsed.reference.as_deref(), Some("v2"));
+    assert_eq!(parsed.path, "tixgraft.yaml");
+
+    ConfigUrl::template("").unwrap_err();
+    ConfigUrl::template("org/template-repo#").unwrap_err();
+}
+
+#[test]
+fn load_template_fetches_root_config_and_defaults_repository() {
+    let system = RealSystem::new();
+    let runner = CheckoutWritesConfig {
+        inner: MockGitRunner::new(),
+    };
+
+    let config = Config::load_template(&system, &runner, "org/template-repo#v2").unwrap();
+
+    assert_eq!(config.pulls.len(), 1);
+    assert_eq!(config.repository.as_deref(), Some("org/template-repo"));
+    assert_eq!(config.tag.as_deref(), Some("v2"));
+
+    let invocations = runner.inner.invocations();
+    let sparse_set = invocations
+        .iter()
+        .find(|invocation| {
+            invocation
+                .args
+                .starts_with(&["sparse-checkout".to_owned(), "set".to_owned()])
+        })
+        .unwrap();
+    assert!(sparse_set.args.contains(&"tixgraft.yaml".to_owned()));
+}
+
+#[test]
+fn template_pulls_execute_into_the_target() {
+    let system = MockSystem::new()
+        .with_dir("/templates/web")
+        .unwrap()
+        .with_file("/templates/web/tixgraft.yaml", TEMPLATE_CONFIG.as_bytes())
+        .unwrap()
+        .with_dir("/templates/web/skeleton")
+        .unwrap()
+        .with_file("/templates/web/skeleton/README.md", b"# New app\n")
+        .unwrap()
+        .with_dir("/work")
+        .unwrap();
+    let runner = MockGitRunner::new();
+
+    let config = Config::load_template(&system, &runner, "file:///templates/web").unwrap();
+    PullOperation::builder()
+        .config(config)
+        .config_dir(Path::new("/work"))
+        .system(&system)
+        .build()
+        .unwrap()
+        .execute()
+        .unwrap();
+
+    assert!(runner.invocations().is_empty());
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/work/app/README.md"))
+            .unwrap(),
+        "# New app\n"
+    );
+}
@@ -462,6 +462,17 @@ fn value_to_string_tst() {
     assert!(obj_str.contains("key"));
 }
 
+#[test]
+fn value_to_string_keeps_object_keys_in_document_order() {
+    // serde_json's `preserve_order` feature: keys are not sorted
+    let value: serde_json::Value =
+        serde_json::from_str(r#"{"zeta": 1, "alpha": {"b": 2, "a": 3}}"#).unwrap();
+    assert_eq!(
+        value_to_string(&value).unwrap(),
+        r#"{"zeta":1,"alpha":{"b":2,"a":3}}"#
+    );
+}
+
 #[test]
 fn validated_context_get_and_get_as_string() {
     let def_yaml = "- name: name\n  description: Name\n  dataType: string\n";
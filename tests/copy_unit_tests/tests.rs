@@ -2,10 +2,15 @@
 
 use os_shim::System as _;
 use os_shim::mock::MockSystem;
+use os_shim::real::RealSystem;
+use std::io;
 use std::path::Path;
+use std::sync::Mutex;
 use tixgraft::operations::copy::{
-    calculate_copy_size, copy_directory, copy_file, copy_files, count_files_to_copy,
+    CopyOptions, PartialCopy, calculate_copy_size, copy_directory, copy_directory_with_options,
+    copy_file, copy_files, count_files_to_copy, verify_copy,
 };
+use tracing::Level;
 
 #[test]
 fn copy_file_tst() {
@@ -109,6 +114,75 @@ fn copy_files_file_type() {
     assert!(system.exists(Path::new("/target/out.txt")).unwrap());
 }
 
+#[test]
+fn copy_files_file_type_into_existing_directory_keeps_name() {
+    let system = MockSystem::new()
+        .with_dir("/test")
+        .unwrap()
+        .with_file("/test/source.txt", b"hello")
+        .unwrap()
+        .with_dir("/target")
+        .unwrap();
+
+    let copied = copy_files(
+        &system,
+        Path::new("/test/source.txt"),
+        "/target",
+        "file",
+        false,
+    )
+    .unwrap();
+    assert_eq!(copied, 1);
+    assert!(system.is_dir(Path::new("/target")).unwrap());
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/target/source.txt"))
+            .unwrap(),
+        "hello"
+    );
+}
+
+#[test]
+fn copy_files_file_type_into_trailing_slash_target_keeps_name() {
+    let system = MockSystem::new()
+        .with_dir("/test")
+        .unwrap()
+        .with_file("/test/source.txt", b"hello")
+        .unwrap();
+
+    copy_files(
+        &system,
+        Path::new("/test/source.txt"),
+        "/target/",
+        "file",
+        false,
+    )
+    .unwrap();
+    assert!(system.is_file(Path::new("/target/source.txt")).unwrap());
+}
+
+#[test]
+fn copy_files_file_type_renames_to_target_file_name() {
+    let system = MockSystem::new()
+        .with_dir("/test")
+        .unwrap()
+        .with_file("/test/source.txt", b"hello")
+        .unwrap()
+        .with_dir("/target")
+        .unwrap();
+
+    copy_files(
+        &system,
+        Path::new("/test/source.txt"),
+        "/target/renamed.txt",
+        "file",
+        false,
+    )
+    .unwrap();
+    assert!(system.is_file(Path::new("/target/renamed.txt")).unwrap());
+    assert!(!system.exists(Path::new("/target/source.txt")).unwrap());
+}
+
 #[test]
 fn copy_files_directory_with_reset() {
     let system = MockSystem::new()
@@ -220,3 +294,263 @@ fn count_files_to_copy_invalid_type() {
         0
     );
 }
+
+#[test]
+fn copy_directory_excludes_hidden_when_disabled() {
+    let system = MockSystem::new()
+        .with_dir("/test/source/.config")
+        .unwrap()
+        .with_file("/test/source/visible.txt", b"visible")
+        .unwrap()
+        .with_file("/test/source/.env", b"hidden")
+        .unwrap()
+        .with_file("/test/source/.config/settings.txt", b"hidden dir")
+        .unwrap();
+
+    let source_dir = Path::new("/test/source");
+    let target_dir = Path::new("/test/target");
+
+    let copied = copy_directory_with_options(
+        &system,
+        source_dir,
+        target_dir,
+        CopyOptions::new(false, false),
+    )
+    .unwrap();
+
+    assert_eq!(copied, 1);
+    assert!(system.exists(&target_dir.join("visible.txt")).unwrap());
+    assert!(!system.exists(&target_dir.join(".env")).unwrap());
+    assert!(
+        !system
+            .exists(&target_dir.join(".config/settings.txt"))
+            .unwrap()
+    );
+}
+
+#[test]
+fn copy_directory_includes_hidden_by_default() {
+    let system = MockSystem::new()
+        .with_dir("/test/source")
+        .unwrap()
+        .with_file("/test/source/visible.txt", b"visible")
+        .unwrap()
+        .with_file("/test/source/.env", b"hidden")
+        .unwrap();
+
+    let target_dir = Path::new("/test/target");
+    let copied = copy_directory(&system, Path::new("/test/source"), target_dir).unwrap();
+
+    assert_eq!(copied, 2);
+    assert!(system.exists(&target_dir.join(".env")).unwrap());
+}
+
+/// A source tree with files at depths one, two and three.
+fn nested_source() -> MockSystem {
+    MockSystem::new()
+        .with_dir("/test/source/src/lib")
+        .unwrap()
+        .with_file("/test/source/README.md", b"readme")
+        .unwrap()
+        .with_file("/test/source/src/main.rs", b"main")
+        .unwrap()
+        .with_file("/test/source/src/lib/util.rs", b"util")
+        .unwrap()
+}
+
+#[test]
+fn copy_directory_strips_one_component() {
+    let system = nested_source();
+    let target_dir = Path::new("/test/target");
+
+    let copied = copy_directory_with_options(
+        &system,
+        Path::new("/test/source"),
+        target_dir,
+        CopyOptions::default().with_strip_components(1),
+    )
+    .unwrap();
+
+    assert_eq!(copied, 2);
+    assert!(system.exists(&target_dir.join("main.rs")).unwrap());
+    assert!(system.exists(&target_dir.join("lib/util.rs")).unwrap());
+    assert!(!system.exists(&target_dir.join("src")).unwrap());
+}
+
+#[test]
+fn copy_directory_strips_two_components() {
+    let system = nested_source();
+    let target_dir = Path::new("/test/target");
+
+    let copied = copy_directory_with_options(
+        &system,
+        Path::new("/test/source"),
+        target_dir,
+        CopyOptions::default().with_strip_components(2),
+    )
+    .unwrap();
+
+    assert_eq!(copied, 1);
+    assert_eq!(
+        system.read_to_string(&target_dir.join("util.rs")).unwrap(),
+        "util"
+    );
+}
+
+#[test]
+fn copy_directory_skips_files_with_too_few_components() {
+    let system = nested_source();
+    let target_dir = Path::new("/test/target");
+
+    copy_directory_with_options(
+        &system,
+        Path::new("/test/source"),
+        target_dir,
+        CopyOptions::default().with_strip_components(1),
+    )
+    .unwrap();
+
+    // README.md sits directly in the source root, so stripping one
+    // component leaves nothing of its path.
+    assert!(!system.exists(&target_dir.join("README.md")).unwrap());
+}
+
+#[test]
+fn copy_directory_reports_files_copied_before_a_failure() {
+    let system = RealSystem::new();
+    let temp_dir = system.create_temp_dir().unwrap();
+    let source = temp_dir.path().join("source");
+    let target = temp_dir.path().join("target");
+    system.create_dir_all(&source.join("nested")).unwrap();
+    system.write(&source.join("a.txt"), b"a").unwrap();
+    system.write(&source.join("b.txt"), b"b").unwrap();
+    system.write(&source.join("nested/c.txt"), b"c").unwrap();
+    // A file where the copy needs a directory makes nested/c.txt fail.
+    system.create_dir_all(&target).unwrap();
+    system.write(&target.join("nested"), b"in the way").unwrap();
+
+    let err = copy_directory(&system, &source, &target).unwrap_err();
+    let partial = err.downcast_ref::<PartialCopy>().unwrap();
+
+    // Whatever was copied before the failure is reported, and nothing else.
+    let expected: Vec<_> = ["a.txt", "b.txt"]
+        .iter()
+        .map(|name| target.join(name))
+        .filter(|path| system.exists(path).unwrap())
+        .collect();
+    let mut copied = partial.copied.clone();
+    copied.sort();
+    assert_eq!(copied, expected);
+    assert!(format!("{err:#}").contains("Failed to copy file"));
+}
+
+/// Log output captured by [`CapturedLogs`].
+static CAPTURED_LOGS: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+/// Log writer that appends everything to [`CAPTURED_LOGS`].
+struct CapturedLogs;
+
+#[expect(
+    clippy::missing_trait_methods,
+    reason = "a test log writer only needs write and flush"
+)]
+impl io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        CAPTURED_LOGS.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn copy_directory_logs_each_copied_file_at_debug() {
+    let system = MockSystem::new()
+        .with_dir("/test/source/nested")
+        .unwrap()
+        .with_file("/test/source/nested/app.txt", b"app")
+        .unwrap();
+
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(|| CapturedLogs)
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || {
+        copy_directory(
+            &system,
+            Path::new("/test/source"),
+            Path::new("/test/target"),
+        )
+        .unwrap();
+    });
+
+    let logs = String::from_utf8_lossy(&CAPTURED_LOGS.lock().unwrap()).into_owned();
+    assert!(logs.contains("Copied /test/source/nested/app.txt -> /test/target/nested/app.txt"));
+}
+
+#[test]
+fn verify_copy_accepts_a_faithful_copy() {
+    let system = MockSystem::new()
+        .with_dir("/test/source/nested")
+        .unwrap()
+        .with_file("/test/source/a.txt", b"alpha")
+        .unwrap()
+        .with_file("/test/source/nested/b.txt", b"beta")
+        .unwrap();
+    copy_directory(
+        &system,
+        Path::new("/test/source"),
+        Path::new("/test/target"),
+    )
+    .unwrap();
+
+    verify_copy(
+        &system,
+        Path::new("/test/source"),
+        Path::new("/test/target"),
+        "directory",
+        CopyOptions::default(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn verify_copy_detects_missing_and_truncated_files() {
+    // The target as an interrupted copy leaves it: b.txt never written and
+    // a.txt cut short.
+    let system = MockSystem::new()
+        .with_dir("/test/source/nested")
+        .unwrap()
+        .with_file("/test/source/a.txt", b"alpha")
+        .unwrap()
+        .with_file("/test/source/nested/b.txt", b"beta")
+        .unwrap()
+        .with_dir("/test/target")
+        .unwrap()
+        .with_file("/test/target/a.txt", b"al")
+        .unwrap();
+
+    let err = verify_copy(
+        &system,
+        Path::new("/test/source"),
+        Path::new("/test/target"),
+        "directory",
+        CopyOptions::default(),
+    )
+    .unwrap_err()
+    .to_string();
+
+    assert!(
+        err.contains("Copy verification failed for /test/target"),
+        "{err}"
+    );
+    assert!(err.contains("missing /test/target/nested/b.txt"), "{err}");
+    assert!(
+        err.contains("/test/target/a.txt is 2 bytes, expected 5"),
+        "{err}"
+    );
+}
@@ -6,7 +6,10 @@
 
 use os_shim::{System as _, mock::MockSystem};
 use std::path::Path;
-use tixgraft::operations::discovery::{cleanup_graft_files, discover_graft_files};
+use tixgraft::operations::discovery::{
+    cleanup_graft_files, cleanup_graft_files_named, discover_graft_files,
+    discover_graft_files_named,
+};
 
 #[test]
 fn discover_single_graft() {
@@ -140,3 +143,39 @@ fn cleanup_empty_directory() {
     let deleted = cleanup_graft_files(&system, Path::new("/test")).unwrap();
     assert_eq!(deleted, 0);
 }
+
+#[test]
+fn discover_custom_graft_file_name() {
+    let system = MockSystem::new()
+        .with_dir("/test")
+        .unwrap()
+        .with_dir("/test/nested")
+        .unwrap()
+        .with_file("/test/graft.yml", b"# Custom graft\n")
+        .unwrap()
+        .with_file("/test/nested/graft.yml", b"# Nested custom graft\n")
+        .unwrap()
+        .with_file("/test/.graft.yaml", b"# Default graft, not ours\n")
+        .unwrap();
+
+    let grafts = discover_graft_files_named(&system, Path::new("/test"), "graft.yml").unwrap();
+    assert_eq!(grafts.len(), 2);
+    assert_eq!(grafts[0].path, Path::new("/test/graft.yml"));
+    assert_eq!(grafts[1].depth, 1);
+}
+
+#[test]
+fn cleanup_custom_graft_file_name_leaves_default() {
+    let system = MockSystem::new()
+        .with_dir("/test")
+        .unwrap()
+        .with_file("/test/graft.yml", b"# Custom graft\n")
+        .unwrap()
+        .with_file("/test/.graft.yaml", b"# Unrelated file\n")
+        .unwrap();
+
+    let deleted = cleanup_graft_files_named(&system, Path::new("/test"), "graft.yml").unwrap();
+    assert_eq!(deleted, 1);
+    assert!(!system.exists(Path::new("/test/graft.yml")).unwrap());
+    assert!(system.exists(Path::new("/test/.graft.yaml")).unwrap());
+}
@@ -0,0 +1,4 @@
+//! Unit tests for the `--doctor` environment diagnostics.
+
+#[cfg(test)]
+mod tests;
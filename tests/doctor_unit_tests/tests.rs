@@ -0,0 +1,99 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use os_shim::mock::MockSystem;
+use std::env;
+use tixgraft::git::{GitOutput, MockGitRunner};
+use tixgraft::operations::doctor::{CheckStatus, diagnose};
+
+const CONFIG: &str =
+    "repository: \"myorg/templates\"\npulls:\n  - source: \"a\"\n    target: \"./a\"\n";
+
+fn system_with_config() -> MockSystem {
+    MockSystem::new()
+        .with_dir(env::temp_dir())
+        .unwrap()
+        .with_file("/work/tixgraft.yaml", CONFIG.as_bytes())
+        .unwrap()
+}
+
+#[test]
+fn missing_git_is_a_failure() {
+    let system = system_with_config();
+    let runner = MockGitRunner::new().without_git();
+
+    let report = diagnose(&system, &runner, "/work/tixgraft.yaml");
+
+    let git = report.check("git").unwrap();
+    assert_eq!(git.status, CheckStatus::Fail);
+    assert!(git.hint.is_some());
+    assert!(report.has_failures());
+    assert!(report.to_string().contains("[fail] git: git not found"));
+}
+
+#[test]
+fn healthy_environment_passes() {
+    let system = system_with_config();
+    let runner = MockGitRunner::new()
+        .with_response("--version", GitOutput::success("git version 2.43.0\n"))
+        .with_response("lfs", GitOutput::success("git-lfs/3.4.1\n"));
+
+    let report = diagnose(&system, &runner, "/work/tixgraft.yaml");
+
+    assert!(!report.has_failures(), "{report}");
+    assert_eq!(report.check("git").unwrap().status, CheckStatus::Ok);
+    assert_eq!(report.check("config").unwrap().status, CheckStatus::Ok);
+    assert_eq!(report.check("repository").unwrap().status, CheckStatus::Ok);
+
+    let ls_remote = runner
+        .invocations()
+        .into_iter()
+        .find(|invocation| invocation.subcommand() == Some("ls-remote"))
+        .unwrap();
+    assert!(
+        ls_remote
+            .args
+            .contains(&"https://github.com/myorg/templates.git".to_owned())
+    );
+}
+
+#[test]
+fn old_git_and_missing_lfs() {
+    let system = system_with_config();
+    let runner = MockGitRunner::new()
+        .with_response("--version", GitOutput::success("git version 2.20.1\n"))
+        .with_response("lfs", GitOutput::failure("git: 'lfs' is not a git command"));
+
+    let report = diagnose(&system, &runner, "/work/tixgraft.yaml");
+
+    assert_eq!(report.check("git").unwrap().status, CheckStatus::Fail);
+    assert_eq!(report.check("git-lfs").unwrap().status, CheckStatus::Warn);
+}
+
+#[test]
+fn missing_config_is_a_warning() {
+    let system = MockSystem::new().with_dir(env::temp_dir()).unwrap();
+    let runner =
+        MockGitRunner::new().with_response("--version", GitOutput::success("git version 2.43.0\n"));
+
+    let report = diagnose(&system, &runner, "/work/tixgraft.yaml");
+
+    assert_eq!(report.check("config").unwrap().status, CheckStatus::Warn);
+    assert!(report.check("repository").is_none());
+}
+
+#[test]
+fn unreachable_repository_is_a_failure() {
+    let system = system_with_config();
+    let runner = MockGitRunner::new()
+        .with_response("--version", GitOutput::success("git version 2.43.0\n"))
+        .with_response(
+            "ls-remote",
+            GitOutput::failure("fatal: could not read Username"),
+        );
+
+    let report = diagnose(&system, &runner, "/work/tixgraft.yaml");
+
+    let repository = report.check("repository").unwrap();
+    assert_eq!(repository.status, CheckStatus::Fail);
+    assert!(repository.message.contains("could not read Username"));
+}
@@ -1,4 +1,4 @@
-use tixgraft::error::GraftError;
+use tixgraft::error::{GraftError, PullOutcome};
 
 #[test]
 fn constructors_produce_correct_variants() {
@@ -25,6 +25,10 @@ fn constructors_produce_correct_variants() {
     let skill = GraftError::skill("install fail");
     assert!(matches!(skill, GraftError::Skill { .. }));
     assert_eq!(skill.to_string(), "Skill error: install fail");
+
+    let timeout = GraftError::timeout("too slow");
+    assert!(matches!(timeout, GraftError::Timeout { .. }));
+    assert_eq!(timeout.to_string(), "Timeout error: too slow");
 }
 
 #[test]
@@ -35,6 +39,8 @@ fn exit_codes() {
     assert_eq!(GraftError::git("x").exit_code(), 4_i32);
     assert_eq!(GraftError::filesystem("x").exit_code(), 5_i32);
     assert_eq!(GraftError::skill("x").exit_code(), 6_i32);
+    assert_eq!(GraftError::timeout("x").exit_code(), 7_i32);
+    assert_eq!(GraftError::partial_failure(Vec::new()).exit_code(), 10_i32);
 }
 
 #[test]
@@ -46,3 +52,16 @@ fn accepts_string_and_str() {
     // format!
     drop(GraftError::command(format!("formatted {}", 42_i32)));
 }
+
+#[test]
+fn partial_failure_lists_failed_pulls() {
+    let err = GraftError::partial_failure(vec![
+        PullOutcome::succeeded(1, "docs", "out/docs"),
+        PullOutcome::failed(2, "missing", "out/missing", "source not found"),
+    ]);
+    assert!(matches!(err, GraftError::PartialFailure { .. }));
+    assert_eq!(
+        err.to_string(),
+        "Partial failure: 1 of 2 pulls failed: pull #2 (missing -> out/missing): source not found"
+    );
+}
@@ -3,8 +3,9 @@
 use os_shim::{System as _, mock::MockSystem};
 use std::path::Path;
 use tixgraft::utils::fs::{
-    copy_file_with_progress, create_parent_directories, ensure_dir_exists, format_file_size,
-    get_file_size, is_binary_file, is_directory_empty, remove_dir_safe,
+    BinaryClassification, classify_file, copy_file_with_progress, create_parent_directories,
+    ensure_dir_exists, format_file_size, get_file_size, is_binary_file, is_directory_empty,
+    remove_dir_safe, validate_temp_root,
 };
 
 #[test]
@@ -27,6 +28,47 @@ fn is_binary_file_tst() {
     assert!(is_binary_file(&system, binary_file).unwrap());
 }
 
+#[test]
+fn classify_file_reports_extension_and_content_reasons() {
+    let system = MockSystem::new()
+        .with_file("/test/data.JSON", &[0, 1, 2])
+        .unwrap()
+        .with_file("/test/App.vue", b"<template></template>\n")
+        .unwrap()
+        .with_file("/test/image.png", &[0x89, b'P', b'N', b'G', 0])
+        .unwrap()
+        .with_file("/test/latin1.dat", &[b'c', b'a', b'f', 0xE9])
+        .unwrap()
+        .with_file("/test/empty.bin", b"")
+        .unwrap();
+
+    let json = classify_file(&system, Path::new("/test/data.JSON")).unwrap();
+    assert_eq!(json, BinaryClassification::TextExtension("json".to_owned()));
+    assert!(!json.is_binary());
+
+    let vue = classify_file(&system, Path::new("/test/App.vue")).unwrap();
+    assert_eq!(vue, BinaryClassification::ValidUtf8);
+    assert!(!vue.is_binary());
+
+    let png = classify_file(&system, Path::new("/test/image.png")).unwrap();
+    assert_eq!(png, BinaryClassification::NullByte);
+    assert!(png.is_binary());
+    assert_eq!(png.reason(), "content contains a NUL byte");
+
+    let latin1 = classify_file(&system, Path::new("/test/latin1.dat")).unwrap();
+    assert_eq!(latin1, BinaryClassification::InvalidUtf8);
+    assert!(latin1.is_binary());
+
+    assert_eq!(
+        classify_file(&system, Path::new("/test/empty.bin")).unwrap(),
+        BinaryClassification::Empty
+    );
+    assert_eq!(
+        classify_file(&system, Path::new("/test")).unwrap(),
+        BinaryClassification::NotAFile
+    );
+}
+
 #[test]
 fn format_file_size_tst() {
     assert_eq!(format_file_size(0), "0 B");
@@ -173,3 +215,23 @@ fn create_parent_directories_already_exists() {
     create_parent_directories(&system, Path::new("/test/existing/file.txt")).unwrap();
     assert!(system.exists(Path::new("/test/existing")).unwrap());
 }
+
+#[test]
+fn validate_temp_root_requires_existing_directory() {
+    let system = MockSystem::new()
+        .with_dir("/scratch")
+        .unwrap()
+        .with_file("/scratch/file.txt", b"not a dir")
+        .unwrap();
+
+    validate_temp_root(&system, Path::new("/scratch")).unwrap();
+    assert!(
+        !system
+            .exists(Path::new("/scratch/.tixgraft-write-probe"))
+            .unwrap()
+    );
+
+    let missing = validate_temp_root(&system, Path::new("/missing")).unwrap_err();
+    assert!(missing.to_string().contains("/missing"));
+    validate_temp_root(&system, Path::new("/scratch/file.txt")).unwrap_err();
+}
@@ -5,9 +5,12 @@
 )]
 
 use os_shim::mock::MockSystem;
+use serde_json::json;
+use std::collections::HashMap;
 use std::path::Path;
+use tixgraft::config::context::ValidatedContext;
 use tixgraft::config::graft_yaml::{
-    ChoiceOption, GraftConfig, GraftReplacement, PostCommand, TestCommand,
+    ChoiceOption, GraftConfig, GraftReplacement, PostCommand, ReplacementScope, TestCommand,
 };
 
 #[test]
@@ -262,3 +265,119 @@ fn choice_option_new_tst() {
     assert_eq!(option.expected_output, "expected");
     assert_eq!(option.test.command, "test");
 }
+
+#[test]
+fn replacement_scope_path_must_stay_inside_target() {
+    let graft = GraftConfig::load_from_string(
+        r##"
+replacements:
+  - source: "# ROUTES"
+    target: "- auth"
+    scope: path
+    path: "config/routes.yaml"
+"##,
+    )
+    .unwrap();
+    let replacement = graft.replacements.first().unwrap();
+    assert_eq!(replacement.scope, ReplacementScope::Path);
+    assert_eq!(replacement.path.as_deref(), Some("config/routes.yaml"));
+
+    let escaping = GraftConfig::load_from_string(
+        r##"
+replacements:
+  - source: "# ROUTES"
+    target: "- auth"
+    scope: path
+    path: "../outside.yaml"
+"##,
+    );
+    assert!(format!("{:#}", escaping.unwrap_err()).contains("unsafe directory traversal"));
+
+    let missing_path = GraftConfig::load_from_string(
+        r##"
+replacements:
+  - source: "# ROUTES"
+    target: "- auth"
+    scope: path
+"##,
+    );
+    assert!(missing_path.unwrap_err().to_string().contains("no 'path'"));
+}
+
+#[test]
+fn context_default_value_and_default_from_env_are_exclusive() {
+    let graft = GraftConfig::load_from_string(
+        r#"
+context:
+  - name: owner
+    description: "Service owner"
+    dataType: string
+    defaultFromEnv: USER
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        graft.context.first().unwrap().default_from_env.as_deref(),
+        Some("USER")
+    );
+
+    let both = GraftConfig::load_from_string(
+        r#"
+context:
+  - name: owner
+    description: "Service owner"
+    dataType: string
+    defaultValue: "alice"
+    defaultFromEnv: USER
+"#,
+    );
+    assert!(format!("{:#}", both.unwrap_err()).contains("cannot set both"));
+}
+
+#[test]
+fn context_from_merges_shared_definitions() {
+    let shared = r#"
+context:
+  - name: teamName
+    description: Owning team
+    dataType: string
+  - name: port
+    description: Service port
+    dataType: number
+    defaultValue: 80
+"#;
+    let graft = r#"
+contextFrom: ../shared/context.yaml
+context:
+  - name: port
+    description: Service port
+    dataType: number
+    defaultValue: 8080
+replacements:
+  - source: "{{TEAM}}"
+    valueFromContext: teamName
+"#;
+    let system = MockSystem::new()
+        .with_dir("/tpl/shared")
+        .unwrap()
+        .with_dir("/tpl/app")
+        .unwrap()
+        .with_file("/tpl/shared/context.yaml", shared.as_bytes())
+        .unwrap()
+        .with_file("/tpl/app/.graft.yaml", graft.as_bytes())
+        .unwrap();
+
+    let config = GraftConfig::load_from_file(&system, Path::new("/tpl/app/.graft.yaml")).unwrap();
+    let names: Vec<&str> = config.context.iter().map(|def| def.name.as_str()).collect();
+    assert_eq!(names, vec!["teamName", "port"]);
+
+    // The shared property is required during validation
+    let err = ValidatedContext::new(config.context.clone(), HashMap::new()).unwrap_err();
+    assert!(err.to_string().contains("teamName"));
+
+    // The local definition overrides the shared default
+    let mut values = HashMap::new();
+    values.insert("teamName".to_owned(), json!("platform"));
+    let validated = ValidatedContext::new(config.context, values).unwrap();
+    assert_eq!(validated.values.get("port"), Some(&json!(8080)));
+}
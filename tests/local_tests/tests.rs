@@ -1,10 +1,31 @@
 #![expect(clippy::unwrap_used, reason = "This is a test module")]
 
 use assert_cmd::Command;
+use flate2::Compression;
+use flate2::write::GzEncoder;
 use predicates::prelude::*;
 use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Command as ProcessCommand;
 use tempfile::TempDir;
 
+/// Create a git repository whose `.gitignore` excludes `ignored.txt`, with
+/// both files under `templates/`, and return its canonical path.
+fn gitignored_source(root: &Path) -> PathBuf {
+    let source = root.join("source");
+    fs::create_dir_all(source.join("templates")).unwrap();
+    ProcessCommand::new("git")
+        .args(["init", "--quiet"])
+        .current_dir(&source)
+        .output()
+        .unwrap();
+    fs::write(source.join(".gitignore"), "ignored.txt\n").unwrap();
+    fs::write(source.join("templates/kept.txt"), "kept").unwrap();
+    fs::write(source.join("templates/ignored.txt"), "ignored").unwrap();
+    source.canonicalize().unwrap()
+}
+
 #[test]
 fn local_file_source_with_file_prefix() {
     let temp_dir = TempDir::new().unwrap();
@@ -542,3 +563,1671 @@ pulls:
         "From source 2"
     );
 }
+
+#[test]
+fn custom_graft_file_name_is_applied_and_removed() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source/app")).unwrap();
+    fs::write(temp_dir.path().join("source/app/README.md"), "# {{NAME}}\n").unwrap();
+    fs::write(
+        temp_dir.path().join("source/app/graft.yml"),
+        r#"
+replacements:
+  - source: "{{NAME}}"
+    target: "demo"
+"#,
+    )
+    .unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+graftFileName: graft.yml
+pulls:
+  - source: "app"
+    target: "./target"
+    type: "directory"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("target/README.md")).unwrap(),
+        "# demo\n"
+    );
+    assert!(!temp_dir.path().join("target/graft.yml").exists());
+}
+
+#[test]
+fn post_pull_targets_include_child_config_targets() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/a.txt"), "A").unwrap();
+    fs::create_dir_all(temp_dir.path().join("child")).unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let child_config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "a.txt"
+    target: "./child_out/a.txt"
+    type: "file"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("child/tixgraft.yaml"), child_config).unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "a.txt"
+    target: "./out_a/a.txt"
+    type: "file"
+children:
+  - "child/tixgraft.yaml"
+postPull:
+  - command: "sh"
+    args: ["-c", "printf '%s\n' \"$TIXGRAFT_TARGETS\" > post_pull.log"]
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let log = fs::read_to_string(temp_dir.path().join("post_pull.log")).unwrap();
+    assert!(log.contains("out_a/a.txt"));
+    assert!(log.contains("child_out/a.txt"));
+}
+
+#[test]
+fn post_pull_runs_once_with_all_targets() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/a.txt"), "A").unwrap();
+    fs::write(temp_dir.path().join("source/b.txt"), "B").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "a.txt"
+    target: "./out_a/a.txt"
+    type: "file"
+  - source: "b.txt"
+    target: "./out_b/b.txt"
+    type: "file"
+postPull:
+  - command: "sh"
+    args: ["-c", "printf '%s\n--\n' \"$TIXGRAFT_TARGETS\" >> post_pull.log"]
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .assert()
+        .success();
+
+    let log = fs::read_to_string(temp_dir.path().join("post_pull.log")).unwrap();
+    assert_eq!(log.matches("--").count(), 1, "postPull should run once");
+    assert!(log.contains("out_a/a.txt"));
+    assert!(log.contains("out_b/b.txt"));
+}
+
+#[test]
+fn dry_run_dumps_resolved_replacements() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/app.txt"), "{{NAME}} {{KEY}}").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "app.txt"
+    target: "./out/app.txt"
+    type: "file"
+    replacements:
+      - source: "{{{{NAME}}}}"
+        valueFromEnv: "TIXGRAFT_DUMP_NAME"
+      - source: "{{{{KEY}}}}"
+        valueFromEnv: "TIXGRAFT_DUMP_SECRET"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env("TIXGRAFT_DUMP_NAME", "resolved-name")
+        .env("TIXGRAFT_DUMP_SECRET", "hunter2")
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .arg("--dry-run")
+        .arg("--dump-resolved-replacements")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("{{NAME}} -> resolved-name"))
+        .stdout(predicate::str::contains("{{KEY}} -> ***"))
+        .stdout(predicate::str::contains("hunter2").not());
+
+    // Nothing is written during a dry run
+    assert!(!temp_dir.path().join("out").exists());
+}
+
+#[test]
+fn dry_run_dumps_graft_replacements_and_reports_unresolved() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/service")).unwrap();
+    fs::write(
+        temp_dir.path().join("source/service/app.txt"),
+        "{{SERVICE}} {{TOKEN}}",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("source/service/.graft.yaml"),
+        r#"
+context:
+  - name: serviceName
+    description: Name of the service
+    dataType: string
+replacements:
+  - source: "{{SERVICE}}"
+    valueFromContext: serviceName
+"#,
+    )
+    .unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+context:
+  serviceName: billing
+pulls:
+  - source: "service"
+    target: "./out"
+    replacements:
+      - source: "{{{{TOKEN}}}}"
+        valueFromEnv: "TIXGRAFT_DUMP_UNSET_TOKEN"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env_remove("TIXGRAFT_DUMP_UNSET_TOKEN")
+        .arg("--dry-run")
+        .arg("--dump-resolved-replacements")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(".graft.yaml:"))
+        .stdout(predicate::str::contains("{{SERVICE}} -> billing"))
+        .stdout(predicate::str::contains("{{TOKEN}} -> (unresolved)"))
+        .stdout(predicate::str::contains(
+            "Dry run found 1 problem(s) that would fail the pull",
+        ))
+        .stdout(predicate::str::contains(
+            "Pull #1: replacement '{{TOKEN}}': ",
+        ));
+
+    assert!(!temp_dir.path().join("out").exists());
+}
+
+#[test]
+fn cli_pull_context_satisfies_graft_context() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/service")).unwrap();
+    fs::write(
+        temp_dir.path().join("source/service/app.txt"),
+        "service: {{SERVICE}}",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("source/service/.graft.yaml"),
+        r#"
+context:
+  - name: serviceName
+    description: Name of the service
+    dataType: string
+replacements:
+  - source: "{{SERVICE}}"
+    valueFromContext: serviceName
+"#,
+    )
+    .unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg(format!("file://{}", source_abs.display()))
+        .arg("--pull-source")
+        .arg("service")
+        .arg("--pull-target")
+        .arg("./out")
+        .arg("--pull-context")
+        .arg("serviceName=billing")
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/app.txt")).unwrap(),
+        "service: billing"
+    );
+}
+
+#[test]
+fn respect_gitignore_controls_ignored_files() {
+    for (respect_gitignore, expect_ignored) in [(false, true), (true, false)] {
+        let temp_dir = TempDir::new().unwrap();
+        let source_abs = gitignored_source(temp_dir.path());
+        let config = format!(
+            r#"
+repository: "file://{}"
+pulls:
+  - source: "templates"
+    target: "./out"
+    respectGitignore: {respect_gitignore}
+"#,
+            source_abs.display()
+        );
+        fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+        let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+        cmd.current_dir(temp_dir.path())
+            .arg("--config")
+            .arg("tixgraft.yaml")
+            .assert()
+            .success();
+
+        assert!(temp_dir.path().join("out/kept.txt").exists());
+        assert_eq!(
+            temp_dir.path().join("out/ignored.txt").exists(),
+            expect_ignored,
+            "respectGitignore: {respect_gitignore}"
+        );
+    }
+}
+
+#[test]
+fn replace_filenames_renames_and_replaces_content() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/templates")).unwrap();
+    fs::write(
+        temp_dir.path().join("source/templates/{{NAME}}.txt"),
+        "name: {{NAME}}",
+    )
+    .unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "templates"
+    target: "./out"
+    replaceFilenames: true
+    replacements:
+      - source: "{{{{NAME}}}}"
+        target: "service"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .assert()
+        .success();
+
+    assert!(!temp_dir.path().join("out/{{NAME}}.txt").exists());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/service.txt")).unwrap(),
+        "name: service"
+    );
+}
+
+#[test]
+fn failed_post_pull_is_summarized_at_end_of_run() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/a.txt"), "A").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "a.txt"
+    target: "./out/a.txt"
+    type: "file"
+postPull:
+  - command: "sh"
+    args: ["-c", "exit 3"]
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Warnings (1):"))
+        .stdout(predicate::str::contains("postPull command failed"));
+
+    assert!(temp_dir.path().join("out/a.txt").exists());
+}
+
+#[test]
+fn file_pull_replacements_leave_sibling_files_alone() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/app.txt"), "name: {{NAME}}").unwrap();
+    fs::create_dir_all(temp_dir.path().join("out")).unwrap();
+    fs::write(temp_dir.path().join("out/notes.txt"), "name: {{NAME}}").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "app.txt"
+    target: "./out/app.txt"
+    type: "file"
+    replacements:
+      - source: "{{{{NAME}}}}"
+        target: "web"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/app.txt")).unwrap(),
+        "name: web"
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/notes.txt")).unwrap(),
+        "name: {{NAME}}"
+    );
+}
+
+#[test]
+fn trusted_source_config_contributes_replacements() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/web")).unwrap();
+    fs::write(
+        temp_dir.path().join("source/web/app.txt"),
+        "name: {{NAME}}, port: {{PORT}}",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("source/web/tixgraft.pull.yaml"),
+        r#"
+replacements:
+  - source: "{{PORT}}"
+    target: "8080"
+"#,
+    )
+    .unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "web"
+    target: "./out"
+    replacements:
+      - source: "{{{{NAME}}}}"
+        target: "web"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .arg("--trust-source-config")
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/app.txt")).unwrap(),
+        "name: web, port: 8080"
+    );
+    assert!(!temp_dir.path().join("out/tixgraft.pull.yaml").exists());
+}
+
+#[test]
+fn source_config_ignored_without_trust() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/web")).unwrap();
+    fs::write(temp_dir.path().join("source/web/app.txt"), "port: {{PORT}}").unwrap();
+    fs::write(
+        temp_dir.path().join("source/web/tixgraft.pull.yaml"),
+        "replacements:\n  - source: \"{{PORT}}\"\n    target: \"8080\"\n",
+    )
+    .unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg(format!("file://{}", source_abs.display()))
+        .arg("--pull-source")
+        .arg("web")
+        .arg("--pull-target")
+        .arg("./out")
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/app.txt")).unwrap(),
+        "port: {{PORT}}"
+    );
+}
+
+#[test]
+fn auto_pull_type_detects_file_source() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/Dockerfile"), "FROM scratch").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg(format!("file://{}", source_abs.display()))
+        .arg("--pull-source")
+        .arg("Dockerfile")
+        .arg("--pull-target")
+        .arg("./out/Dockerfile")
+        .arg("--pull-type")
+        .arg("auto")
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/Dockerfile")).unwrap(),
+        "FROM scratch"
+    );
+}
+
+#[test]
+fn auto_pull_type_detects_directory_source() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/web/src")).unwrap();
+    fs::write(
+        temp_dir.path().join("source/web/src/main.rs"),
+        "fn main() {}",
+    )
+    .unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    // No `type`: auto is the default
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "web"
+    target: "./out"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .assert()
+        .success();
+
+    assert!(temp_dir.path().join("out/src/main.rs").is_file());
+}
+
+#[test]
+fn stdout_target_prints_replaced_file_only() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/app.txt"), "name: {{NAME}}\n").unwrap();
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg(format!("file://{}", source_abs.display()))
+        .arg("--pull-source")
+        .arg("app.txt")
+        .arg("--pull-target")
+        .arg("-")
+        .arg("--pull-type")
+        .arg("file")
+        .arg("--pull-replacement")
+        .arg("{{NAME}}=web")
+        .assert()
+        .success()
+        .stdout("name: web\n");
+
+    assert!(!temp_dir.path().join("-").exists());
+    assert!(!temp_dir.path().join("app.txt").exists());
+}
+
+#[test]
+fn archive_source_is_extracted_and_copied() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Release-style tarball with a single top-level directory
+    let archive_path = temp_dir.path().join("templates-1.0.tar.gz");
+    let encoder = GzEncoder::new(
+        fs::File::create(&archive_path).unwrap(),
+        Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    let contents = b"name: {{NAME}}";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(u64::try_from(contents.len()).unwrap());
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(
+            &mut header,
+            "templates-1.0/web/app.txt",
+            contents.as_slice(),
+        )
+        .unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "web"
+    target: "./out"
+    type: "directory"
+    replacements:
+      - source: "{{{{NAME}}}}"
+        target: "web"
+"#,
+        archive_path.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/app.txt")).unwrap(),
+        "name: web"
+    );
+}
+
+#[test]
+fn archive_source_is_downloaded_over_http() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let contents = b"served";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(u64::try_from(contents.len()).unwrap());
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(
+            &mut header,
+            "templates-1.0/web/app.txt",
+            contents.as_slice(),
+        )
+        .unwrap();
+    let archive = builder.into_inner().unwrap().finish().unwrap();
+
+    // Serve the archive to a single request
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0_u8; 1024];
+        while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+            let read = std::io::Read::read(&mut stream, &mut buffer).unwrap();
+            request.extend_from_slice(buffer.get(..read).unwrap());
+        }
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            archive.len()
+        );
+        std::io::Write::write_all(&mut stream, head.as_bytes()).unwrap();
+        std::io::Write::write_all(&mut stream, &archive).unwrap();
+    });
+
+    let config = format!(
+        r#"
+repository: "http://127.0.0.1:{port}/templates-1.0.tar.gz"
+pulls:
+  - source: "web"
+    target: "./out"
+    type: "directory"
+"#
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["--config", "tixgraft.yaml"])
+        .assert()
+        .success();
+    server.join().unwrap();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/app.txt")).unwrap(),
+        "served"
+    );
+}
+
+#[test]
+fn dry_run_reports_estimated_source_size() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/web/nested")).unwrap();
+    fs::write(temp_dir.path().join("source/web/a.txt"), vec![b'a'; 1024]).unwrap();
+    fs::write(
+        temp_dir.path().join("source/web/nested/b.txt"),
+        vec![b'b'; 512],
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("source/web/nested/c.txt"),
+        vec![b'c'; 512],
+    )
+    .unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "web"
+    target: "./out"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Estimated size: 3 file(s), 2.0 KB",
+        ));
+
+    assert!(!temp_dir.path().join("out").exists());
+}
+
+#[test]
+fn graft_replacement_with_target_scope_edits_target_root() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/app/plugins/auth")).unwrap();
+    fs::write(
+        temp_dir.path().join("source/app/index.yaml"),
+        "name: {{NAME}}\nplugins:\n  # PLUGINS\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("source/app/plugins/auth/plugin.yaml"),
+        "name: {{NAME}}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("source/app/plugins/auth/.graft.yaml"),
+        r##"
+replacements:
+  - source: "# PLUGINS"
+    target: "- auth\n  # PLUGINS"
+    scope: target
+  - source: "{{NAME}}"
+    target: "auth"
+"##,
+    )
+    .unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg(format!("file://{}", source_abs.display()))
+        .arg("--pull-source")
+        .arg("app")
+        .arg("--pull-target")
+        .arg("./out")
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/index.yaml")).unwrap(),
+        "name: {{NAME}}\nplugins:\n  - auth\n  # PLUGINS\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/plugins/auth/plugin.yaml")).unwrap(),
+        "name: auth\n"
+    );
+}
+
+#[test]
+fn expect_files_fails_pull_when_file_is_missing() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/app")).unwrap();
+    fs::write(temp_dir.path().join("source/app/package.json"), "{}\n").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg(format!("file://{}", source_abs.display()))
+        .arg("--pull-source")
+        .arg("app")
+        .arg("--pull-target")
+        .arg("./missing")
+        .arg("--pull-expect-files")
+        .arg("package.json,README.md")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("README.md"));
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg(format!("file://{}", source_abs.display()))
+        .arg("--pull-source")
+        .arg("app")
+        .arg("--pull-target")
+        .arg("./present")
+        .arg("--pull-expect-files")
+        .arg("package.json")
+        .assert()
+        .success();
+
+    assert!(temp_dir.path().join("present/package.json").exists());
+}
+
+#[test]
+fn dry_run_fails_on_unset_replacement_env_var() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/file.txt"), "{{TOKEN}}").unwrap();
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "file.txt"
+    target: "./out.txt"
+    type: "file"
+    replacements:
+      - source: "{{{{TOKEN}}}}"
+        valueFromEnv: "TIXGRAFT_TEST_DRY_RUN_UNSET_VAR"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .env_remove("TIXGRAFT_TEST_DRY_RUN_UNSET_VAR")
+        .arg("--dry-run")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Dry run found 1 problem(s)"))
+        .stdout(predicate::str::contains(
+            "Pull #1: replacement '{{TOKEN}}': environment variable 'TIXGRAFT_TEST_DRY_RUN_UNSET_VAR' is not set",
+        ));
+
+    assert!(!temp_dir.path().join("out.txt").exists());
+}
+
+#[test]
+fn dry_run_lists_missing_local_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/file.txt"), "{{TOKEN}}").unwrap();
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "missing-one"
+    target: "./one"
+  - source: "missing-two"
+    target: "./two"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--dry-run")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Dry run found 2 problem(s)"))
+        .stdout(predicate::str::contains("source 'missing-one' not found"))
+        .stdout(predicate::str::contains("source 'missing-two' not found"));
+
+    assert!(!temp_dir.path().join("one").exists());
+}
+
+#[test]
+fn archive_target_bundles_replaced_files() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/app/sub")).unwrap();
+    fs::write(
+        temp_dir.path().join("source/app/config.txt"),
+        "name: {{NAME}}\n",
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join("source/app/sub/notes.md"), "# Notes\n").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg(format!("file://{}", source_abs.display()))
+        .arg("--pull-source")
+        .arg("app")
+        .arg("--pull-target")
+        .arg("./dist/bundle.zip")
+        .arg("--pull-type")
+        .arg("archive")
+        .arg("--pull-replacement")
+        .arg("{{NAME}}=demo")
+        .assert()
+        .success();
+
+    assert!(!temp_dir.path().join("dist/config.txt").exists());
+    let file = fs::File::open(temp_dir.path().join("dist/bundle.zip")).unwrap();
+    let mut archive = zip::ZipArchive::new(file).unwrap();
+    let mut names: Vec<String> = archive.file_names().map(str::to_owned).collect();
+    names.sort();
+    assert_eq!(names, vec!["config.txt", "sub/notes.md"]);
+
+    let mut content = String::new();
+    std::io::Read::read_to_string(&mut archive.by_name("config.txt").unwrap(), &mut content)
+        .unwrap();
+    assert_eq!(content, "name: demo\n");
+}
+
+#[test]
+fn local_directory_source_skips_git_directory_by_default() {
+    let temp_dir = TempDir::new().unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("source/.git/objects")).unwrap();
+    fs::write(
+        temp_dir.path().join("source/.git/HEAD"),
+        "ref: refs/heads/main\n",
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join("source/.gitignore"), "target/\n").unwrap();
+    fs::write(temp_dir.path().join("source/README.md"), "# Template\n").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg(format!("file://{}", source_abs.display()))
+        .arg("--pull-source")
+        .arg(".")
+        .arg("--pull-target")
+        .arg("./default")
+        .assert()
+        .success();
+
+    assert!(temp_dir.path().join("default/README.md").exists());
+    assert!(temp_dir.path().join("default/.gitignore").exists());
+    assert!(!temp_dir.path().join("default/.git").exists());
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--repository")
+        .arg(format!("file://{}", source_abs.display()))
+        .arg("--pull-source")
+        .arg(".")
+        .arg("--pull-target")
+        .arg("./with-git")
+        .arg("--include-git")
+        .assert()
+        .success();
+
+    assert!(temp_dir.path().join("with-git/.git/HEAD").exists());
+}
+
+#[test]
+fn replacement_group_is_applied_to_pulls_that_use_it() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(
+        temp_dir.path().join("source/service.txt"),
+        "{{ORG}}/{{SERVICE}}\n",
+    )
+    .unwrap();
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+
+    let config = format!(
+        r#"
+repository: "file://{}"
+replacementGroups:
+  org:
+    - source: "{{{{ORG}}}}"
+      target: "acme"
+    - source: "{{{{SERVICE}}}}"
+      target: "default"
+pulls:
+  - source: "service.txt"
+    target: "./billing.txt"
+    type: "file"
+    useGroups: ["org"]
+    replacements:
+      - source: "{{{{SERVICE}}}}"
+        target: "billing"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(temp_dir.path().join("billing.txt")).unwrap();
+    assert_eq!(content, "acme/billing\n");
+}
+
+#[test]
+fn output_dir_places_relative_targets_under_base() {
+    let temp_dir = TempDir::new().unwrap();
+    let sandbox = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/app.txt"), "app\n").unwrap();
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "app.txt"
+    target: "./x"
+    type: "file"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--output-dir")
+        .arg(sandbox.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(sandbox.path().join("x")).unwrap(),
+        "app\n"
+    );
+    assert!(!temp_dir.path().join("x").exists());
+}
+
+#[test]
+fn non_graft_replacement_scope_leaves_graft_dirs_to_the_graft() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source");
+    fs::create_dir_all(source.join("svc")).unwrap();
+    fs::write(source.join("top.txt"), "name: {{NAME}}\n").unwrap();
+    fs::write(source.join("svc/app.txt"), "name: {{NAME}}\n").unwrap();
+    fs::write(
+        source.join("svc/.graft.yaml"),
+        "replacements:\n  - source: \"{{NAME}}\"\n    target: \"from-graft\"\n",
+    )
+    .unwrap();
+    let source_abs = source.canonicalize().unwrap();
+
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "."
+    target: "./out"
+    type: "directory"
+    pullReplacementScope: "non-graft"
+    replacements:
+      - source: "{{{{NAME}}}}"
+        target: "from-pull"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/top.txt")).unwrap(),
+        "name: from-pull\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("out/svc/app.txt")).unwrap(),
+        "name: from-graft\n"
+    );
+}
+
+#[test]
+fn progress_json_reports_pull_done_with_file_count() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("a.txt"), "a\n").unwrap();
+    fs::write(source.join("b.txt"), "b\n").unwrap();
+    let source_abs = source.canonicalize().unwrap();
+
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "."
+    target: "./out"
+    type: "directory"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let output = Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--progress-json")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let events: Vec<serde_json::Value> = String::from_utf8(output.stderr)
+        .unwrap()
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let names: Vec<&str> = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "pull_start",
+            "checkout_done",
+            "files_copied",
+            "replacements_done",
+            "pull_done"
+        ]
+    );
+    let done = events.last().unwrap();
+    assert_eq!(done["files"], 2);
+}
+
+#[test]
+fn pull_description_appears_in_summary_and_progress_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("a.txt"), "a\n").unwrap();
+    let source_abs = source.canonicalize().unwrap();
+
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "."
+    target: "./out"
+    type: "directory"
+    description: "Shared lint config"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let output = Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--progress-json")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Pull operation #1 \u{2014} Shared lint config"));
+    assert!(stdout.contains("(directory, 1 files) \u{2014} Shared lint config"));
+
+    let start: serde_json::Value = String::from_utf8(output.stderr)
+        .unwrap()
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .find(|event| event["event"] == "pull_start")
+        .unwrap();
+    assert_eq!(start["description"], "Shared lint config");
+}
+
+#[cfg(unix)]
+#[test]
+fn local_directory_source_with_symlink_cycle_is_skipped() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let templates = temp_dir.path().join("source/templates");
+    fs::create_dir_all(&templates).unwrap();
+    fs::write(templates.join("file.txt"), "File").unwrap();
+    std::os::unix::fs::symlink(".", templates.join("loop")).unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "templates"
+    target: "./target"
+    type: "directory"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .timeout(std::time::Duration::from_secs(30))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("links back to"));
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("target/file.txt")).unwrap(),
+        "File"
+    );
+    assert!(!temp_dir.path().join("target/loop").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn timeout_terminates_grandchild_processes() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/file.txt"), "Original").unwrap();
+    let pid_file = temp_dir.path().join("grandchild.pid");
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "file.txt"
+    target: "./target/file.txt"
+    type: "file"
+    commands:
+      - "sleep 60 & echo $! > '{}'; wait"
+"#,
+        source_abs.display(),
+        pid_file.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["--timeout", "2"])
+        .timeout(std::time::Duration::from_secs(30))
+        .assert()
+        .code(7_i32);
+
+    // The sleep is a grandchild: the shell running the command started it
+    let pid = fs::read_to_string(&pid_file).unwrap().trim().to_owned();
+    let mut alive = true;
+    for _ in 0..50 {
+        alive = ProcessCommand::new("kill")
+            .args(["-0", &pid])
+            .status()
+            .unwrap()
+            .success();
+        if !alive {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert!(!alive, "grandchild process {pid} survived the timeout");
+}
+
+#[cfg(unix)]
+#[test]
+fn local_directory_source_copies_symlink_to_sibling() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let templates = temp_dir.path().join("source/templates");
+    fs::create_dir_all(templates.join("b")).unwrap();
+    fs::write(templates.join("b/file.txt"), "File").unwrap();
+    std::os::unix::fs::symlink("b", templates.join("a")).unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "templates"
+    target: "./target"
+    type: "directory"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--config")
+        .arg("tixgraft.yaml")
+        .timeout(std::time::Duration::from_secs(30))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("links back to").not());
+
+    // The sibling is not an ancestor of the link, so both copies are made
+    for dir in ["a", "b"] {
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("target").join(dir).join("file.txt")).unwrap(),
+            "File"
+        );
+    }
+}
+
+#[test]
+fn identity_replacement_warns_and_fails_under_strict_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source")).unwrap();
+    fs::write(temp_dir.path().join("source/app.txt"), "name: {{NAME}}\n").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "app.txt"
+    target: "./out/app.txt"
+    type: "file"
+    replacements:
+      - source: "{{{{NAME}}}}"
+        target: "{{{{NAME}}}}"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--strict-replacements")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Replacement '{{NAME}}' resolves to its own source, so it changes nothing",
+        ));
+    assert!(!temp_dir.path().join("out/app.txt").exists());
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Replacement '{{NAME}}' resolves to its own source and changes nothing",
+        ));
+    assert!(temp_dir.path().join("out/app.txt").exists());
+}
+
+#[test]
+fn identity_replacement_warns_once_per_pull() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source/app")).unwrap();
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        fs::write(
+            temp_dir.path().join("source/app").join(name),
+            "name: {{NAME}}\n",
+        )
+        .unwrap();
+    }
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "app"
+    target: "./out"
+    type: "directory"
+    replacements:
+      - source: "{{{{NAME}}}}"
+        target: "{{{{NAME}}}}"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    let output = Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout
+            .matches("Replacement '{{NAME}}' resolves to its own source and changes nothing")
+            .count(),
+        1,
+        "{stdout}"
+    );
+}
+
+#[test]
+fn graft_post_command_capture_feeds_later_grafts() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source");
+    fs::create_dir_all(source.join("a")).unwrap();
+    fs::create_dir_all(source.join("b/c")).unwrap();
+    fs::write(
+        source.join("a/.graft.yaml"),
+        "postCommands:\n  - command: echo\n    args: [\"blue\"]\n    captureInto: color\n",
+    )
+    .unwrap();
+    fs::write(
+        source.join("b/c/.graft.yaml"),
+        "replacements:\n  - source: \"{{COLOR}}\"\n    valueFromContext: color\n",
+    )
+    .unwrap();
+    fs::write(source.join("b/c/theme.txt"), "color: {{COLOR}}\n").unwrap();
+
+    let source_abs = source.canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "."
+    target: "./out"
+    type: "directory"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let theme = fs::read_to_string(temp_dir.path().join("out/b/c/theme.txt")).unwrap();
+    assert_eq!(theme, "color: blue\n");
+}
+
+#[test]
+fn no_fail_fast_exits_with_partial_failure_code() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source/present")).unwrap();
+    fs::write(temp_dir.path().join("source/present/file.txt"), "kept").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "present"
+    target: "./target/present"
+  - source: "missing"
+    target: "./target/missing"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--no-fail-fast")
+        .assert()
+        .code(10_i32)
+        .stdout(predicate::str::contains("1 of 2 pulls failed"));
+
+    // The passing pull still ran
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("target/present/file.txt")).unwrap(),
+        "kept"
+    );
+}
+
+#[test]
+fn no_fail_fast_partial_failure_still_runs_children_and_post_pull() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source/present")).unwrap();
+    fs::write(temp_dir.path().join("source/present/file.txt"), "kept").unwrap();
+    fs::create_dir_all(temp_dir.path().join("child")).unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let child_config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "present"
+    target: "./child_target"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("child/tixgraft.yaml"), child_config).unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "present"
+    target: "./target/present"
+  - source: "missing"
+    target: "./target/missing"
+children:
+  - "child/tixgraft.yaml"
+postPull:
+  - command: "sh"
+    args: ["-c", "touch post_pull.done"]
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--no-fail-fast")
+        .assert()
+        .code(10_i32)
+        .stdout(predicate::str::contains("1 of 2 pulls failed"));
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("child/child_target/file.txt")).unwrap(),
+        "kept"
+    );
+    assert!(temp_dir.path().join("post_pull.done").exists());
+}
+
+#[test]
+fn list_replacements_unused_reports_only_unmatched_replacements() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source/app")).unwrap();
+    fs::write(temp_dir.path().join("source/app/README.md"), "# {{NAME}}\n").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "app"
+    target: "./target"
+    replacements:
+      - source: "{{{{NAME}}}}"
+        target: "demo"
+      - source: "{{{{NEVER_USED}}}}"
+        target: "dead"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--list-replacements-unused")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Replacement '{{NEVER_USED}}' changed no file in any pull",
+        ))
+        .stdout(predicate::str::contains("Replacement '{{NAME}}' changed no file").not());
+
+    // The same audit fails the run under --strict-replacements
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--list-replacements-unused")
+        .arg("--strict-replacements")
+        .assert()
+        .code(1_i32)
+        .stdout(predicate::str::contains("'{{NEVER_USED}}'"));
+}
+
+#[test]
+fn offline_rejects_remote_repository_before_cloning() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("tixgraft.yaml"),
+        "repository: \"my_organization/repo\"\npulls:\n  - source: \"src\"\n    target: \"./out\"\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--offline")
+        .assert()
+        .code(1_i32)
+        .stdout(predicate::str::contains("Pull #1 cannot run offline"))
+        .stdout(predicate::str::contains("needs network access"))
+        .stdout(predicate::str::contains("Starting tixgraft pull operation").not());
+    assert!(!temp_dir.path().join("out").exists());
+}
+
+#[test]
+fn offline_allows_file_repository() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("source/app")).unwrap();
+    fs::write(temp_dir.path().join("source/app/README.md"), "# App\n").unwrap();
+
+    let source_abs = temp_dir.path().join("source").canonicalize().unwrap();
+    let config = format!(
+        r#"
+repository: "file://{}"
+pulls:
+  - source: "app"
+    target: "./target"
+"#,
+        source_abs.display()
+    );
+    fs::write(temp_dir.path().join("tixgraft.yaml"), config).unwrap();
+
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("--offline")
+        .assert()
+        .success();
+    assert!(temp_dir.path().join("target/README.md").exists());
+}
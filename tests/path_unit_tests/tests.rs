@@ -4,11 +4,14 @@
     reason = "test functions reuse variable names for clarity"
 )]
 
+use os_shim::mock::MockSystem;
+use os_shim::real::RealSystem;
+use std::env;
 use std::path::{Path, PathBuf};
 use tixgraft::utils::path::{
-    common_path_prefix, depth, escapes_from_base, get_file_extension, has_extension,
-    is_path_allowed, join_path_safe, normalize, normalize_separators, to_unix, to_windows,
-    validate_path_safety,
+    NormalizePath as _, common_path_prefix, depth, escapes_from_base, get_file_extension,
+    has_extension, is_path_allowed, join_path_safe, normalize, normalize_separators, to_unix,
+    to_windows, validate_path_safety,
 };
 #[test]
 fn normalize_path() {
@@ -19,6 +22,28 @@ fn normalize_path() {
     assert_eq!(normalize(Path::new("a/b/../..")), PathBuf::from(""));
 }
 
+#[test]
+fn normalize_path_resolves_missing_paths_on_both_systems() {
+    let missing = Path::new("/tixgraft-missing/out/../dist/./app");
+    let expected = PathBuf::from("/tixgraft-missing/dist/app");
+
+    assert_eq!(RealSystem::new().normalize_path(missing).unwrap(), expected);
+    assert_eq!(MockSystem::new().normalize_path(missing).unwrap(), expected);
+
+    // Relative paths are resolved against the current directory
+    let relative = RealSystem::new()
+        .normalize_path(Path::new("tixgraft-missing/../dist"))
+        .unwrap();
+    assert_eq!(
+        relative,
+        env::current_dir()
+            .unwrap()
+            .canonicalize()
+            .unwrap()
+            .join("dist")
+    );
+}
+
 #[test]
 fn validate_path_safety_tst() {
     // Safe paths
@@ -0,0 +1,4 @@
+//! Unit tests for the `--print-plan-graph` DOT output.
+
+#[cfg(test)]
+mod tests;
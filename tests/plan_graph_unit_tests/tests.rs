@@ -0,0 +1,57 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use tixgraft::config::Config;
+use tixgraft::operations::plan_graph::{overlapping_targets, plan_graph};
+
+const CONFIG: &str = r#"
+repository: "myorg/templates"
+tag: "v1"
+pulls:
+  - source: "api"
+    target: "./services/api"
+  - source: "web"
+    target: "./services/web"
+  - source: "shared"
+    target: "./services"
+    repository: "myorg/shared"
+"#;
+
+#[test]
+fn graph_has_a_node_per_pull_and_a_cluster_per_repository() {
+    let config: Config = serde_yaml::from_str(CONFIG).unwrap();
+    let graph = plan_graph(&config).unwrap();
+
+    assert!(graph.starts_with("digraph plan {"));
+    assert!(graph.contains("pull0 [label=\"#1 api -> ./services/api (auto)\"];"));
+    assert!(graph.contains("pull1 [label=\"#2 web -> ./services/web (auto)\"];"));
+    assert!(graph.contains("pull2 [label=\"#3 shared -> ./services (auto)\"];"));
+
+    // The two pulls sharing myorg/templates@v1 land in the same cluster
+    let shared_cluster = graph
+        .split("subgraph ")
+        .find(|cluster| cluster.contains("label=\"myorg/templates@v1\";"))
+        .unwrap();
+    assert!(shared_cluster.contains("pull0 ["));
+    assert!(shared_cluster.contains("pull1 ["));
+    assert!(!shared_cluster.contains("pull2 ["));
+    assert!(graph.contains("label=\"myorg/shared@v1\";"));
+
+    // Execution order
+    assert!(graph.contains("pull0 -> pull1;"));
+    assert!(graph.contains("pull1 -> pull2;"));
+}
+
+#[test]
+fn overlapping_targets_are_flagged() {
+    let config: Config = serde_yaml::from_str(CONFIG).unwrap();
+
+    assert_eq!(overlapping_targets(&config), vec![(0, 2), (1, 2)]);
+    let graph = plan_graph(&config).unwrap();
+    assert!(graph.contains(
+        "// warning: targets of pull #1 (./services/api) and pull #3 (./services) overlap"
+    ));
+    assert!(
+        graph.contains("pull0 -> pull2 [color=red, style=dashed, dir=none, label=\"overlap\"];")
+    );
+    assert!(!graph.contains("pull0 -> pull1 [color=red"));
+}
@@ -4,11 +4,15 @@
     reason = "test code uses indexing after length assertions"
 )]
 
+use os_shim::mock::MockSystem;
 use std::fs;
 use tempfile::TempDir;
+use tixgraft::config::context::ContextValues;
 use tixgraft::config::graft_yaml::{ChoiceOption, PostCommand, TestCommand};
 use tixgraft::operations::post_commands::{
-    execute_post_command, execute_post_commands, resolve_working_directory,
+    CommandVariables, DEFAULT_OUTPUT_LIMIT, PostCommandOptions, execute_post_command,
+    execute_post_commands, execute_post_commands_with_options, resolve_working_directory,
+    truncate_output,
 };
 
 #[test]
@@ -19,6 +23,8 @@ fn execute_simple_command() {
         command: "echo".to_owned(),
         args: vec!["Hello, World!".to_owned()],
         cwd: None,
+        capture_into: None,
+        clean_env: false,
     };
 
     let result = execute_post_command(&command, temp_dir.path()).unwrap();
@@ -403,3 +409,198 @@ fn error_message_formatting() {
         "Error should mention command name, got: {error}"
     );
 }
+
+#[test]
+fn execute_commands_with_env() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let commands = vec![PostCommand::new(
+        "sh".to_owned(),
+        vec![
+            "-c".to_owned(),
+            "echo \"targets=$TIXGRAFT_TARGETS\"".to_owned(),
+        ],
+        None,
+    )];
+    let envs = [("TIXGRAFT_TARGETS".to_owned(), "./a\n./b".to_owned())];
+
+    let system = MockSystem::new();
+    let options = PostCommandOptions::new(&system).with_envs(&envs);
+    let results = execute_post_commands_with_options(&commands, temp_dir.path(), &options).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].success);
+    assert!(results[0].output.contains("targets=./a\n./b"));
+}
+
+#[test]
+fn clean_env_command_only_sees_whitelisted_variables() {
+    let temp_dir = TempDir::new().unwrap();
+    // The passthrough variables come from the System, not the process
+    let system = MockSystem::new()
+        .with_env("PATH", &std::env::var("PATH").unwrap())
+        .unwrap()
+        .with_env("HOME", "/home/tixgraft-mock")
+        .unwrap();
+    let context = ContextValues::new();
+    // Cargo sets this for every test process; it is not whitelisted
+    assert!(std::env::var_os("CARGO_MANIFEST_DIR").is_some());
+
+    let commands = vec![PostCommand::Command {
+        command: "env".to_owned(),
+        args: vec![],
+        cwd: None,
+        capture_into: None,
+        clean_env: true,
+    }];
+    let envs = [("TIXGRAFT_TARGETS".to_owned(), "./a".to_owned())];
+
+    let options = PostCommandOptions::new(&system)
+        .with_envs(&envs)
+        .with_variables(CommandVariables::new(&system, &context));
+    let results = execute_post_commands_with_options(&commands, temp_dir.path(), &options).unwrap();
+    assert!(results[0].success);
+    assert!(results[0].output.contains("PATH="));
+    assert!(results[0].output.contains("HOME=/home/tixgraft-mock\n"));
+    assert!(results[0].output.contains("TIXGRAFT_TARGETS=./a"));
+    assert!(!results[0].output.contains("CARGO_MANIFEST_DIR="));
+}
+
+#[test]
+fn large_command_output_is_truncated_with_marker() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let commands = vec![PostCommand::Command {
+        command: "sh".to_owned(),
+        args: vec!["-c".to_owned(), "yes tixgraft | head -c 10000".to_owned()],
+        cwd: None,
+        capture_into: None,
+        clean_env: false,
+    }];
+
+    let system = MockSystem::new();
+    let options = PostCommandOptions::new(&system).with_output_limit(1024);
+    let results = execute_post_commands_with_options(&commands, temp_dir.path(), &options).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].success);
+    assert!(results[0].output.starts_with("tixgraft\ntixgraft\n"));
+    assert!(
+        results[0]
+            .output
+            .ends_with("\n... [output truncated: 8976 of 10000 bytes omitted]")
+    );
+}
+
+#[test]
+fn huge_command_output_is_drained_past_the_limit() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Far more output than the limit; only the first bytes are kept
+    let commands = vec![PostCommand::Command {
+        command: "sh".to_owned(),
+        args: vec![
+            "-c".to_owned(),
+            "printf '\\303\\251\\303\\251'; head -c 50000000 /dev/zero".to_owned(),
+        ],
+        cwd: None,
+        capture_into: None,
+        clean_env: false,
+    }];
+
+    let system = MockSystem::new();
+    let options = PostCommandOptions::new(&system).with_output_limit(3);
+    let results = execute_post_commands_with_options(&commands, temp_dir.path(), &options).unwrap();
+    assert!(results[0].success);
+    // "é" is two bytes; the limit of 3 splits the second one
+    assert_eq!(
+        results[0].output,
+        "é\n... [output truncated: 50000002 of 50000004 bytes omitted]"
+    );
+}
+
+#[test]
+fn default_output_limit_keeps_short_output_intact() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let command = PostCommand::Command {
+        command: "echo".to_owned(),
+        args: vec!["short".to_owned()],
+        cwd: None,
+        capture_into: None,
+        clean_env: false,
+    };
+
+    let result = execute_post_command(&command, temp_dir.path()).unwrap();
+    assert_eq!(result.output, "short\n");
+    assert_eq!(DEFAULT_OUTPUT_LIMIT, 64 * 1024);
+}
+
+#[test]
+fn truncate_output_cuts_at_char_boundary() {
+    // "é" is two bytes; a limit of 3 would split the second one
+    let truncated = truncate_output("ééé".as_bytes(), 3);
+    assert_eq!(truncated, "é\n... [output truncated: 4 of 6 bytes omitted]");
+}
+
+#[test]
+fn variable_references_in_args_expand_from_environment() {
+    let temp_dir = TempDir::new().unwrap();
+    let system = MockSystem::new().with_env("FOO", "from-env").unwrap();
+    let context = ContextValues::new();
+
+    let commands = vec![PostCommand::Command {
+        command: "echo".to_owned(),
+        args: vec![
+            "${FOO}".to_owned(),
+            "${MISSING:-fallback}".to_owned(),
+            "$FOO".to_owned(),
+        ],
+        cwd: None,
+        capture_into: None,
+        clean_env: false,
+    }];
+
+    let options =
+        PostCommandOptions::new(&system).with_variables(CommandVariables::new(&system, &context));
+    let results = execute_post_commands_with_options(&commands, temp_dir.path(), &options).unwrap();
+    assert!(results[0].success);
+    assert_eq!(results[0].output, "from-env fallback $FOO\n");
+}
+
+#[test]
+fn undefined_variable_reference_fails_the_command() {
+    let temp_dir = TempDir::new().unwrap();
+    let system = MockSystem::new();
+    let context = ContextValues::new();
+
+    let commands = vec![PostCommand::Command {
+        command: "echo".to_owned(),
+        args: vec!["${UNDEFINED_VARIABLE}".to_owned()],
+        cwd: None,
+        capture_into: None,
+        clean_env: false,
+    }];
+
+    let options =
+        PostCommandOptions::new(&system).with_variables(CommandVariables::new(&system, &context));
+    let results = execute_post_commands_with_options(&commands, temp_dir.path(), &options).unwrap();
+    assert!(!results[0].success);
+    assert!(
+        results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("Undefined variable '${UNDEFINED_VARIABLE}'")
+    );
+}
+
+#[test]
+fn context_values_take_precedence_over_environment() {
+    let system = MockSystem::new().with_env("PORT", "1111").unwrap();
+    let mut context = ContextValues::new();
+    context.insert("PORT".to_owned(), serde_json::json!(8080));
+
+    let expanded = CommandVariables::new(&system, &context)
+        .expand("--port=${PORT}")
+        .unwrap();
+    assert_eq!(expanded, "--port=8080");
+}
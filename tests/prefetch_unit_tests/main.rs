@@ -0,0 +1,4 @@
+//! Unit tests for `--prefetch` up-front cloning.
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,78 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use os_shim::mock::MockSystem;
+use tixgraft::config::Config;
+use tixgraft::git::{CloneOptions, GitOutput, MockGitRunner};
+use tixgraft::operations::prefetch::prefetch_sources;
+
+const CONFIG: &str = r#"
+repository: "myorg/templates"
+tag: "v1"
+pulls:
+  - source: "api"
+    target: "./api"
+  - source: "web"
+    target: "./web"
+  - source: "ci"
+    target: "./ci"
+    repository: "myorg/pipelines"
+  - source: "api"
+    target: "./api-copy"
+"#;
+
+#[test]
+fn each_repository_reference_is_cloned_once_up_front() {
+    let config: Config = serde_yaml::from_str(CONFIG).unwrap();
+    let runner = MockGitRunner::new();
+
+    let prefetched = prefetch_sources(
+        &MockSystem::new(),
+        &config,
+        &runner,
+        &CloneOptions::default(),
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(prefetched.len(), 2);
+    assert!(prefetched.get("myorg/templates", "v1").is_some());
+    assert!(prefetched.get("myorg/pipelines", "v1").is_some());
+
+    let invocations = runner.invocations();
+    let clones: Vec<_> = invocations
+        .iter()
+        .filter(|invocation| invocation.subcommand() == Some("clone"))
+        .collect();
+    assert_eq!(clones.len(), 2);
+
+    // Pulls sharing a repository share one sparse checkout of all their sources
+    let sparse_sets: Vec<Vec<String>> = invocations
+        .iter()
+        .filter(|invocation| invocation.args.get(1).map(String::as_str) == Some("set"))
+        .map(|invocation| invocation.args.iter().skip(2).cloned().collect())
+        .collect();
+    assert_eq!(sparse_sets, vec![vec!["api", "web"], vec!["ci"]]);
+}
+
+#[test]
+fn unreachable_repository_fails_before_any_pull() {
+    let config: Config = serde_yaml::from_str(CONFIG).unwrap();
+    let runner = MockGitRunner::new()
+        .with_response("clone", GitOutput::failure("fatal: repository not found"));
+
+    let err = prefetch_sources(
+        &MockSystem::new(),
+        &config,
+        &runner,
+        &CloneOptions::default(),
+        None,
+    )
+    .unwrap_err();
+
+    assert!(
+        format!("{err:#}")
+            .contains("Prefetch failed for repository 'myorg/templates' at reference 'v1'")
+    );
+    // The first failure stops the run; no other repository is tried
+    assert_eq!(runner.invocations().len(), 1);
+}
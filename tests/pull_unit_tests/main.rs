@@ -0,0 +1,4 @@
+//! Unit tests for pull operation helpers.
+
+#[cfg(test)]
+mod tests;
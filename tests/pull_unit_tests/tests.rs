@@ -0,0 +1,432 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+#![expect(
+    clippy::indexing_slicing,
+    reason = "Index-based assertions are acceptable in tests"
+)]
+
+use os_shim::System as _;
+use os_shim::mock::MockSystem;
+use std::path::Path;
+use tixgraft::cli::{Args, PullConfig, ReplacementConfig};
+use tixgraft::config::{Config, RefType};
+use tixgraft::git::{GitOutput, MockGitRunner};
+use tixgraft::operations::confirm::{ScriptedPrompter, destructive_actions};
+use tixgraft::operations::{PullOperation, build_config_from_args, check_working_tree_clean};
+
+/// Parse a config with one tagged and one untagged pull.
+fn config_with_tag(tag: Option<&str>) -> Config {
+    let tag_line = tag.map_or_else(String::new, |value| format!("tag: {value}\n"));
+    let yaml = format!(
+        "repository: my_org/repo\n{tag_line}pulls:\n  - source: a\n    target: ./a\n    tag: v1.0.0\n  - source: b\n    target: ./b\n"
+    );
+    serde_yaml::from_str(&yaml).unwrap()
+}
+
+#[test]
+fn resolve_reference_prefers_pull_tag() {
+    let config = config_with_tag(Some("develop"));
+    let resolved = PullOperation::resolve_reference(&config, &config.pulls[0]).unwrap();
+    assert_eq!(resolved.name, "v1.0.0");
+    assert_eq!(resolved.sha, None);
+}
+
+#[test]
+fn resolve_reference_falls_back_to_config_tag() {
+    let config = config_with_tag(Some("develop"));
+    let resolved = PullOperation::resolve_reference(&config, &config.pulls[1]).unwrap();
+    assert_eq!(resolved.name, "develop");
+}
+
+#[test]
+fn resolve_reference_defaults_to_main() {
+    let config = config_with_tag(None);
+    let resolved = PullOperation::resolve_reference(&config, &config.pulls[1]).unwrap();
+    assert_eq!(resolved.name, "main");
+}
+
+/// Parse a config defining `stable` and `beta` channels, tagged `tag`.
+fn config_with_channels(tag: &str) -> Config {
+    let yaml = format!(
+        "repository: my_org/repo\ntag: {tag}\nchannels:\n  stable: v2.3.1\n  beta: v2.4.0-rc.1\npulls:\n  - source: a\n    target: ./a\n"
+    );
+    serde_yaml::from_str(&yaml).unwrap()
+}
+
+#[test]
+fn resolve_reference_maps_channel_to_its_reference() {
+    let config = config_with_channels("stable");
+    let resolved = PullOperation::resolve_reference(&config, &config.pulls[0]).unwrap();
+    assert_eq!(resolved.name, "v2.3.1");
+}
+
+#[test]
+fn resolve_reference_rejects_unknown_channel() {
+    let config = config_with_channels("nightly");
+    let err = PullOperation::resolve_reference(&config, &config.pulls[0]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Configuration error: Unknown channel 'nightly'; available channels: beta, stable"
+    );
+}
+
+#[test]
+fn resolve_reference_uses_repository_default_before_config_tag() {
+    let yaml = "repository: my_org/repo\ntag: develop\nrepositoryDefaults:\n  my_org/legacy:\n    tag: master\n    refType: branch\npulls:\n  - source: a\n    target: ./a\n    repository: my_org/legacy\n  - source: b\n    target: ./b\n    repository: my_org/legacy\n    tag: v1.0.0\n  - source: c\n    target: ./c\n";
+    let config: Config = serde_yaml::from_str(yaml).unwrap();
+
+    let resolved = PullOperation::resolve_reference(&config, &config.pulls[0]).unwrap();
+    assert_eq!(resolved.name, "master");
+    assert_eq!(resolved.ref_type, Some(RefType::Branch));
+
+    let resolved = PullOperation::resolve_reference(&config, &config.pulls[1]).unwrap();
+    assert_eq!(resolved.name, "v1.0.0");
+
+    let resolved = PullOperation::resolve_reference(&config, &config.pulls[2]).unwrap();
+    assert_eq!(resolved.name, "develop");
+    assert_eq!(resolved.ref_type, None);
+}
+
+#[test]
+fn cli_replacements_apply_to_the_preceding_pull_source() {
+    let args = Args::try_parse_ordered_from([
+        "tixgraft",
+        "--repository",
+        "my_org/repo",
+        "--pull-source",
+        "a",
+        "--pull-target",
+        "./a",
+        "--pull-replacement",
+        "{{A}}=first",
+        "--pull-source",
+        "b",
+        "--pull-replacement",
+        "{{B}}=second",
+        "--pull-target",
+        "./b",
+    ])
+    .unwrap();
+    let config = build_config_from_args(&args).unwrap();
+
+    assert_eq!(config.pulls.len(), 2);
+    assert_eq!(config.pulls[0].replacements.len(), 1);
+    assert_eq!(config.pulls[0].replacements[0].source, "{{A}}");
+    assert_eq!(config.pulls[1].replacements.len(), 1);
+    assert_eq!(config.pulls[1].replacements[0].source, "{{B}}");
+}
+
+#[test]
+fn config_env_var_replaces_only_the_default_config_path() {
+    let system = MockSystem::new()
+        .with_env("TIXGRAFT_CONFIG", "configs/from-env.yaml")
+        .unwrap();
+
+    let mut args = Args::try_parse_ordered_from(["tixgraft"]).unwrap();
+    args.apply_config_env(&system);
+    assert_eq!(args.config, "configs/from-env.yaml");
+
+    let mut args = Args::try_parse_ordered_from(["tixgraft", "--config", "explicit.yaml"]).unwrap();
+    args.apply_config_env(&system);
+    assert_eq!(args.config, "explicit.yaml");
+}
+
+#[test]
+fn temp_dir_env_var_applies_only_without_temp_dir_flag() {
+    let system = MockSystem::new()
+        .with_env("TIXGRAFT_TMPDIR", "/scratch/from-env")
+        .unwrap();
+
+    let mut args = Args::try_parse_ordered_from(["tixgraft"]).unwrap();
+    args.apply_config_env(&system);
+    assert_eq!(args.temp_dir.as_deref(), Some("/scratch/from-env"));
+
+    let mut args = Args::try_parse_ordered_from(["tixgraft", "--temp-dir", "/explicit"]).unwrap();
+    args.apply_config_env(&system);
+    assert_eq!(args.temp_dir.as_deref(), Some("/explicit"));
+
+    let mut args = Args::try_parse_ordered_from(["tixgraft"]).unwrap();
+    args.apply_config_env(&MockSystem::new());
+    assert_eq!(args.temp_dir, None);
+}
+
+#[test]
+fn cli_pull_context_applies_to_the_preceding_pull_source() {
+    let args = Args::try_parse_ordered_from([
+        "tixgraft",
+        "--repository",
+        "my_org/repo",
+        "--pull-source",
+        "a",
+        "--pull-target",
+        "./a",
+        "--pull-source",
+        "b",
+        "--pull-target",
+        "./b",
+        "--pull-context",
+        "greeting=hello, world",
+        "--pull-context",
+        "name=billing",
+        "--pull-context-json",
+        "port=8080",
+        "--pull-context-json",
+        r#"tags=["x","y"]"#,
+    ])
+    .unwrap();
+    let config = build_config_from_args(&args).unwrap();
+
+    assert_eq!(config.pulls.len(), 2);
+    assert!(config.pulls[0].context.is_empty());
+    let context = &config.pulls[1].context;
+    assert_eq!(context["greeting"], serde_json::json!("hello, world"));
+    assert_eq!(context["name"], serde_json::json!("billing"));
+    assert_eq!(context["port"], serde_json::json!(8080));
+    assert_eq!(context["tags"], serde_json::json!(["x", "y"]));
+}
+
+#[test]
+fn builder_executes_pull_against_mock_system() {
+    let system = MockSystem::new()
+        .with_file("/repo/src/greeting.txt", b"Hello, {{NAME}}!\n")
+        .unwrap()
+        .with_dir("/work")
+        .unwrap();
+
+    let mut pull = PullConfig::new("src", "out");
+    pull.require_clean_target = false;
+    pull.replacements.push(ReplacementConfig::new(
+        "{{NAME}}".to_owned(),
+        Some("tixgraft".to_owned()),
+        None,
+    ));
+
+    PullOperation::builder()
+        .repository("file:///repo")
+        .add_pull(pull)
+        .config_dir(Path::new("/work"))
+        .system(&system)
+        .build()
+        .unwrap()
+        .execute()
+        .unwrap();
+
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/work/out/greeting.txt"))
+            .unwrap(),
+        "Hello, tixgraft!\n"
+    );
+}
+
+#[test]
+fn overlay_pull_lets_later_sources_override_files() {
+    let system = MockSystem::new()
+        .with_dir("/repo/base")
+        .unwrap()
+        .with_file("/repo/base/app.conf", b"mode=default\n")
+        .unwrap()
+        .with_file("/repo/base/README.md", b"base readme\n")
+        .unwrap()
+        .with_dir("/repo/override")
+        .unwrap()
+        .with_file("/repo/override/app.conf", b"mode=custom\n")
+        .unwrap()
+        .with_dir("/work")
+        .unwrap();
+
+    let mut pull = PullConfig::new("base", "out");
+    pull.pull_type = "overlay".to_owned();
+    pull.sources = vec!["override".to_owned()];
+    pull.require_clean_target = false;
+
+    PullOperation::builder()
+        .repository("file:///repo")
+        .add_pull(pull)
+        .config_dir(Path::new("/work"))
+        .system(&system)
+        .build()
+        .unwrap()
+        .execute()
+        .unwrap();
+
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/work/out/app.conf"))
+            .unwrap(),
+        "mode=custom\n"
+    );
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/work/out/README.md"))
+            .unwrap(),
+        "base readme\n"
+    );
+}
+
+#[test]
+fn overlay_pull_reports_missing_later_source() {
+    let system = MockSystem::new()
+        .with_dir("/repo/base")
+        .unwrap()
+        .with_file("/repo/base/app.conf", b"mode=default\n")
+        .unwrap()
+        .with_dir("/work")
+        .unwrap();
+
+    let mut pull = PullConfig::new("base", "out");
+    pull.pull_type = "overlay".to_owned();
+    pull.sources = vec!["missing".to_owned()];
+    pull.require_clean_target = false;
+
+    let err = PullOperation::builder()
+        .repository("file:///repo")
+        .add_pull(pull)
+        .config_dir(Path::new("/work"))
+        .system(&system)
+        .build()
+        .unwrap()
+        .execute()
+        .unwrap_err();
+
+    assert!(
+        format!("{err:#}").contains("Overlay source 'missing' is not a directory"),
+        "{err:#}"
+    );
+}
+
+/// Run a `reset: true` pull over a stale target, answering `answer` when asked to confirm.
+fn run_reset_pull_with_answer(answer: bool) -> (MockSystem, ScriptedPrompter, bool) {
+    let system = MockSystem::new()
+        .with_file("/repo/src/fresh.txt", b"fresh\n")
+        .unwrap()
+        .with_dir("/work/out")
+        .unwrap()
+        .with_file("/work/out/stale.txt", b"stale\n")
+        .unwrap();
+    let prompter = ScriptedPrompter::new(&[answer]);
+
+    let mut pull = PullConfig::new("src", "out");
+    pull.require_clean_target = false;
+    pull.reset = true;
+
+    let succeeded = PullOperation::builder()
+        .repository("file:///repo")
+        .add_pull(pull)
+        .config_dir(Path::new("/work"))
+        .prompter(&prompter)
+        .system(&system)
+        .build()
+        .unwrap()
+        .execute()
+        .is_ok();
+    (system, prompter, succeeded)
+}
+
+#[test]
+fn declined_confirmation_aborts_reset_pull() {
+    let (system, prompter, succeeded) = run_reset_pull_with_answer(false);
+
+    assert!(!succeeded);
+    assert_eq!(prompter.questions().len(), 1);
+    assert!(system.exists(Path::new("/work/out/stale.txt")).unwrap());
+    assert!(!system.exists(Path::new("/work/out/fresh.txt")).unwrap());
+}
+
+#[test]
+fn accepted_confirmation_runs_reset_pull() {
+    let (system, prompter, succeeded) = run_reset_pull_with_answer(true);
+
+    assert!(succeeded);
+    assert_eq!(prompter.questions().len(), 1);
+    assert!(!system.exists(Path::new("/work/out/stale.txt")).unwrap());
+    assert!(system.exists(Path::new("/work/out/fresh.txt")).unwrap());
+}
+
+#[test]
+fn destructive_actions_list_resets_and_flagged_commands() {
+    let config: Config = serde_yaml::from_str(
+        "repository: my_org/repo\npulls:\n  - source: a\n    target: ./a\n    reset: true\n  - source: b\n    target: ./b\n    commands: [\"npm install\", \"rm -rf build\"]\n",
+    )
+    .unwrap();
+
+    let actions = destructive_actions(&config);
+    assert_eq!(actions.len(), 2);
+    assert!(actions[0].starts_with("Pull #1: wipe target directory ./a"));
+    assert!(actions[1].starts_with("Pull #2: run 'rm -rf build'"));
+}
+
+#[test]
+fn builder_requires_a_system() {
+    let result = PullOperation::builder()
+        .repository("file:///repo")
+        .add_pull(PullConfig::new("src", "out"))
+        .build();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn require_clean_aborts_on_dirty_target() {
+    let system = MockSystem::new().with_dir("/work/out").unwrap();
+    let runner = MockGitRunner::new().with_response("status", GitOutput::success(" M a.txt\n"));
+    let targets = vec!["/work/out/gen".to_owned()];
+
+    let err = check_working_tree_clean(&system, &runner, &targets).unwrap_err();
+    assert!(err.to_string().contains("a.txt"));
+
+    let invocations = runner.invocations();
+    assert_eq!(invocations.len(), 1);
+    assert_eq!(invocations[0].args, ["status", "--porcelain", "--", "gen"]);
+    assert_eq!(invocations[0].cwd.as_deref(), Some(Path::new("/work/out")));
+}
+
+#[test]
+fn require_clean_proceeds_on_clean_status() {
+    let system = MockSystem::new().with_dir("/work/out").unwrap();
+    let runner = MockGitRunner::new().with_response("status", GitOutput::success(""));
+
+    check_working_tree_clean(&system, &runner, &["/work/out".to_owned()]).unwrap();
+    assert_eq!(
+        runner.invocations()[0].args,
+        ["status", "--porcelain", "--", "."]
+    );
+}
+
+#[test]
+fn require_clean_checks_each_target_in_its_own_repository() {
+    let system = MockSystem::new()
+        .with_dir("/scratch/out")
+        .unwrap()
+        .with_dir("/repo/vendor")
+        .unwrap();
+    let runner = MockGitRunner::new()
+        .with_response_in(
+            Path::new("/scratch/out"),
+            "status",
+            GitOutput::failure(
+                "fatal: not a git repository (or any of the parent directories): .git",
+            ),
+        )
+        .with_response_in(
+            Path::new("/repo/vendor"),
+            "status",
+            GitOutput::success("?? vendor/new.rs\n"),
+        );
+    let targets = vec!["/scratch/out".to_owned(), "/repo/vendor".to_owned()];
+
+    let err = check_working_tree_clean(&system, &runner, &targets).unwrap_err();
+    assert!(err.to_string().contains("vendor/new.rs"));
+    assert_eq!(runner.invocations().len(), 2);
+}
+
+#[test]
+fn require_clean_fails_when_git_status_fails_inside_a_repository() {
+    let system = MockSystem::new().with_dir("/repo").unwrap();
+    let runner = MockGitRunner::new().with_response(
+        "status",
+        GitOutput::failure("fatal: /repo/out: '/repo/out' is outside repository"),
+    );
+
+    let err = check_working_tree_clean(&system, &runner, &["/repo/out".to_owned()]).unwrap_err();
+    assert!(err.to_string().contains("outside repository"));
+}
@@ -8,14 +8,31 @@ use os_shim::System as _;
 use os_shim::mock::MockSystem;
 use serde_json::json;
 use std::collections::HashMap;
+use std::io::Read as _;
 use std::path::Path;
 use tixgraft::cli::ReplacementConfig;
-use tixgraft::config::graft_yaml::GraftReplacement;
+use tixgraft::config::graft_yaml::{GraftReplacement, JsonPathSource};
 use tixgraft::operations::replace::{
-    apply_graft_replacements, apply_regex_replacement, apply_replacements,
-    apply_single_replacement, get_graft_replacement_value, get_replacement_value,
-    preview_replacements,
+    apply_filename_replacements, apply_graft_replacements, apply_regex_replacement,
+    apply_replacements, apply_replacements_counted, apply_replacements_to_file,
+    apply_replacements_to_file_with_encoding, apply_single_replacement,
+    check_no_empty_graft_replacements, check_no_empty_replacements,
+    check_no_identity_graft_replacements, check_no_identity_replacements, delete_matching_lines,
+    get_graft_replacement_value, get_replacement_value, preview_replacements, replace_reindented,
+    resolve_graft_replacement, resolve_json_path_replacements, resolve_replacement,
 };
+use tixgraft::operations::structured::ReplacementKind;
+use tixgraft::operations::transform::Transform;
+use tixgraft::utils::encoding::TextEncoding;
+
+/// `text` as UTF-16LE with a byte order mark.
+fn utf16le_with_bom(text: &str) -> Vec<u8> {
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in text.encode_utf16() {
+        bytes.extend(unit.to_le_bytes());
+    }
+    bytes
+}
 
 #[test]
 fn apply_simple_replacement() {
@@ -168,6 +185,68 @@ fn apply_replacements_multiple() {
     assert_eq!(content, "Hello Alice from Earth");
 }
 
+#[test]
+fn apply_replacements_runs_duplicates_once() {
+    let system = MockSystem::new()
+        .with_dir("/test")
+        .unwrap()
+        .with_file("/test/file.txt", b"app: {{APP}}")
+        .unwrap();
+
+    // The value contains the pattern, so a second run would grow the text.
+    let replacement =
+        ReplacementConfig::new("{{APP}}".to_owned(), Some("{{APP}}-web".to_owned()), None);
+    let replacements = vec![replacement.clone(), replacement];
+
+    let count = apply_replacements(&system, "/test", &replacements).unwrap();
+    assert_eq!(count, 1);
+
+    let content = system.read_to_string(Path::new("/test/file.txt")).unwrap();
+    assert_eq!(content, "app: {{APP}}-web");
+}
+
+#[test]
+fn apply_replacements_to_file_leaves_siblings_alone() {
+    let system = MockSystem::new()
+        .with_dir("/test")
+        .unwrap()
+        .with_file("/test/app.txt", b"name: {{NAME}}")
+        .unwrap()
+        .with_file("/test/other.txt", b"name: {{NAME}}")
+        .unwrap();
+
+    let replacements = vec![ReplacementConfig::new(
+        "{{NAME}}".to_owned(),
+        Some("web".to_owned()),
+        None,
+    )];
+
+    let count =
+        apply_replacements_to_file(&system, Path::new("/test/app.txt"), &replacements).unwrap();
+    assert_eq!(count, 1);
+
+    assert_eq!(
+        system.read_to_string(Path::new("/test/app.txt")).unwrap(),
+        "name: web"
+    );
+    assert_eq!(
+        system.read_to_string(Path::new("/test/other.txt")).unwrap(),
+        "name: {{NAME}}"
+    );
+}
+
+#[test]
+fn apply_replacements_to_file_requires_a_file() {
+    let system = MockSystem::new().with_dir("/test").unwrap();
+    let replacements = vec![ReplacementConfig::new(
+        "{{NAME}}".to_owned(),
+        Some("web".to_owned()),
+        None,
+    )];
+
+    apply_replacements_to_file(&system, Path::new("/test"), &replacements).unwrap_err();
+}
+
 #[test]
 fn apply_regex_replacement_on_file() {
     let system = MockSystem::new()
@@ -356,3 +435,574 @@ fn apply_replacements_in_subdirectories() {
             .contains("REPLACED")
     );
 }
+
+#[test]
+fn resolve_replacement_from_env() {
+    let system = MockSystem::new()
+        .with_env("SERVICE_NAME", "billing")
+        .unwrap();
+    let replacement = ReplacementConfig::new(
+        "{{SERVICE}}".to_owned(),
+        None,
+        Some("SERVICE_NAME".to_owned()),
+    );
+
+    let resolved = resolve_replacement(&system, &replacement).unwrap();
+    assert_eq!(resolved.source, "{{SERVICE}}");
+    assert_eq!(resolved.display_value(), "billing");
+    assert!(!resolved.redacted);
+}
+
+#[test]
+fn resolve_replacement_redacts_secrets() {
+    let system = MockSystem::new()
+        .with_env("DEPLOY_TOKEN", "s3cr3t")
+        .unwrap();
+    let replacement = ReplacementConfig::new(
+        "{{TOKEN}}".to_owned(),
+        None,
+        Some("DEPLOY_TOKEN".to_owned()),
+    );
+
+    let resolved = resolve_replacement(&system, &replacement).unwrap();
+    assert_eq!(resolved.value, "s3cr3t");
+    assert_eq!(resolved.display_value(), "***");
+}
+
+#[test]
+fn resolve_replacement_missing_env() {
+    let system = MockSystem::new();
+    let replacement = ReplacementConfig::new(
+        "{{SERVICE}}".to_owned(),
+        None,
+        Some("UNSET_SERVICE_NAME".to_owned()),
+    );
+
+    let result = resolve_replacement(&system, &replacement);
+    assert!(result.is_err());
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("UNSET_SERVICE_NAME")
+    );
+}
+
+#[test]
+fn resolve_graft_replacement_from_context() {
+    let system = MockSystem::new();
+    let replacement =
+        GraftReplacement::new("{{PORT}}".to_owned(), None, None, Some("port".to_owned()));
+    let mut context = HashMap::new();
+    context.insert("port".to_owned(), json!(8080));
+
+    let resolved = resolve_graft_replacement(&system, &replacement, &context).unwrap();
+    assert_eq!(resolved.display_value(), "8080");
+
+    let missing = resolve_graft_replacement(&system, &replacement, &HashMap::new());
+    assert!(missing.is_err());
+}
+
+#[test]
+fn apply_filename_replacements_renames_deepest_first() {
+    let system = MockSystem::new()
+        .with_dir("/target/{{NAME}}")
+        .unwrap()
+        .with_file("/target/{{NAME}}/{{NAME}}Controller.ts", b"controller")
+        .unwrap()
+        .with_file("/target/README.md", b"readme")
+        .unwrap();
+
+    let replacements = vec![ReplacementConfig::new(
+        "{{NAME}}".to_owned(),
+        Some("billing".to_owned()),
+        None,
+    )];
+
+    let renamed = apply_filename_replacements(&system, "/target", &replacements).unwrap();
+
+    assert_eq!(renamed, 2);
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/target/billing/billingController.ts"))
+            .unwrap(),
+        "controller"
+    );
+    assert!(!system.exists(Path::new("/target/{{NAME}}")).unwrap());
+    assert!(system.exists(Path::new("/target/README.md")).unwrap());
+}
+
+#[test]
+fn apply_filename_replacements_collision_renames_nothing() {
+    let system = MockSystem::new()
+        .with_dir("/target")
+        .unwrap()
+        .with_file("/target/{{A}}.txt", b"a")
+        .unwrap()
+        .with_file("/target/{{B}}.txt", b"b")
+        .unwrap();
+
+    let replacements = vec![
+        ReplacementConfig::new("{{A}}".to_owned(), Some("same".to_owned()), None),
+        ReplacementConfig::new("{{B}}".to_owned(), Some("same".to_owned()), None),
+    ];
+
+    let err = apply_filename_replacements(&system, "/target", &replacements).unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("another path already has that name")
+    );
+    assert!(system.exists(Path::new("/target/{{A}}.txt")).unwrap());
+    assert!(system.exists(Path::new("/target/{{B}}.txt")).unwrap());
+}
+
+#[test]
+fn base64_transform_encodes_resolved_value() {
+    let system = MockSystem::new()
+        .with_env("API_TOKEN", "hello world")
+        .unwrap();
+    let replacement =
+        ReplacementConfig::new("{{TOKEN}}".to_owned(), None, Some("API_TOKEN".to_owned()))
+            .with_transform(Transform::Base64);
+
+    assert_eq!(
+        get_replacement_value(&system, &replacement).unwrap(),
+        "aGVsbG8gd29ybGQ="
+    );
+}
+
+#[test]
+fn upper_transform_uppercases_context_value() {
+    let system = MockSystem::new();
+    let replacement =
+        GraftReplacement::new("{{ENV}}".to_owned(), None, None, Some("env".to_owned()))
+            .with_transform(Transform::Upper);
+    let mut context = HashMap::new();
+    context.insert("env".to_owned(), json!("staging"));
+
+    assert_eq!(
+        get_graft_replacement_value(&system, &replacement, &context).unwrap(),
+        "STAGING"
+    );
+}
+
+#[test]
+fn strip_suffix_removes_extension_before_transform() {
+    let system = MockSystem::new();
+    let replacement =
+        ReplacementConfig::new("{{MODULE}}".to_owned(), Some("Foo.ts".to_owned()), None)
+            .with_strip_suffix(".ts");
+    assert_eq!(get_replacement_value(&system, &replacement).unwrap(), "Foo");
+
+    let upper = replacement.with_transform(Transform::Upper);
+    assert_eq!(get_replacement_value(&system, &upper).unwrap(), "FOO");
+}
+
+#[test]
+fn strip_prefix_trims_leading_segment_of_context_value() {
+    let system = MockSystem::new();
+    let replacement =
+        GraftReplacement::new("{{PATH}}".to_owned(), None, None, Some("path".to_owned()))
+            .with_strip_prefix("src/");
+    let mut context = HashMap::new();
+    context.insert("path".to_owned(), json!("src/lib/index.ts"));
+    assert_eq!(
+        get_graft_replacement_value(&system, &replacement, &context).unwrap(),
+        "lib/index.ts"
+    );
+
+    // A value without the prefix is left unchanged
+    context.insert("path".to_owned(), json!("lib/index.ts"));
+    assert_eq!(
+        get_graft_replacement_value(&system, &replacement, &context).unwrap(),
+        "lib/index.ts"
+    );
+}
+
+#[test]
+fn transforms_encode_hash_and_trim() {
+    assert_eq!(Transform::Base64.apply("??>"), "Pz8+");
+    assert_eq!(Transform::Base64Url.apply("??>"), "Pz8-");
+    assert_eq!(Transform::Urlencode.apply("a b/c~d"), "a%20b%2Fc~d");
+    assert_eq!(
+        Transform::Sha256.apply("abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(Transform::Lower.apply("MiXeD"), "mixed");
+    assert_eq!(Transform::Trim.apply("  padded \n"), "padded");
+}
+
+#[test]
+fn multi_line_target_is_inserted_verbatim() {
+    let system = MockSystem::new()
+        .with_file("/target/values.yaml", b"config:\n  {{BLOCK}}\nend: true\n")
+        .unwrap();
+    let replacements = vec![ReplacementConfig::new(
+        "{{BLOCK}}".to_owned(),
+        Some("first: 1\nnested:\n  second: 2".to_owned()),
+        None,
+    )];
+
+    apply_replacements(&system, "/target", &replacements).unwrap();
+
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/target/values.yaml"))
+            .unwrap(),
+        "config:\n  first: 1\nnested:\n  second: 2\nend: true\n"
+    );
+}
+
+#[test]
+fn reindent_aligns_block_with_placeholder_column() {
+    let system = MockSystem::new()
+        .with_file("/target/values.yaml", b"config:\n  {{BLOCK}}\nend: true\n")
+        .unwrap();
+    let replacements = vec![
+        ReplacementConfig::new(
+            "{{BLOCK}}".to_owned(),
+            Some("first: 1\nnested:\n  second: 2\n\nthird: 3".to_owned()),
+            None,
+        )
+        .with_reindent(0),
+    ];
+
+    apply_replacements(&system, "/target", &replacements).unwrap();
+
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/target/values.yaml"))
+            .unwrap(),
+        "config:\n  first: 1\n  nested:\n    second: 2\n\n  third: 3\nend: true\n"
+    );
+}
+
+#[test]
+fn target_from_file_inserts_reindented_python_block() {
+    let system = MockSystem::new()
+        .with_file(
+            "/blocks/body.py",
+            b"if ready:\n    start()\nelse:\n    wait()",
+        )
+        .unwrap()
+        .with_file("/target/main.py", b"def run():\n    # BODY\n")
+        .unwrap();
+    let replacements = vec![
+        ReplacementConfig::new("# BODY".to_owned(), None, None)
+            .with_target_from_file("/blocks/body.py")
+            .with_reindent(0),
+    ];
+
+    apply_replacements(&system, "/target", &replacements).unwrap();
+
+    assert_eq!(
+        system.read_to_string(Path::new("/target/main.py")).unwrap(),
+        "def run():\n    if ready:\n        start()\n    else:\n        wait()\n"
+    );
+}
+
+#[test]
+fn replace_reindented_uses_column_after_text_and_extra_spaces() {
+    assert_eq!(
+        replace_reindented("key: {{V}}\n", "{{V}}", "a\nb", 2),
+        "key: a\n       b\n"
+    );
+    assert_eq!(
+        replace_reindented("\t{{V}} {{V}}", "{{V}}", "x\ny", 0),
+        "\tx\n\ty x\n       y"
+    );
+}
+
+#[test]
+fn json_replacement_sets_nested_key_and_keeps_format() {
+    let system = MockSystem::new()
+        .with_dir("/target")
+        .unwrap()
+        .with_file(
+            "/target/package.json",
+            b"{\n    \"name\": \"app\",\n    \"scripts\": {\n        \"build\": \"tsc\",\n        \"test\": \"jest\"\n    },\n    \"version\": \"1.0.0\"\n}\n",
+        )
+        .unwrap()
+        .with_file("/target/notes.txt", b"scripts.build\n")
+        .unwrap();
+
+    let replacement = ReplacementConfig::new(
+        "scripts.build".to_owned(),
+        Some("vite build".to_owned()),
+        None,
+    )
+    .with_kind(ReplacementKind::Json);
+    let count = apply_replacements(&system, "/target", &[replacement]).unwrap();
+
+    assert_eq!(count, 1);
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/target/package.json"))
+            .unwrap(),
+        "{\n    \"name\": \"app\",\n    \"scripts\": {\n        \"build\": \"vite build\",\n        \"test\": \"jest\"\n    },\n    \"version\": \"1.0.0\"\n}\n"
+    );
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/target/notes.txt"))
+            .unwrap(),
+        "scripts.build\n"
+    );
+}
+
+#[test]
+fn yaml_replacement_only_sets_existing_keys() {
+    let system = MockSystem::new()
+        .with_file(
+            "/compose.yaml",
+            b"services:\n  web:\n    image: nginx\n    ports:\n    - '80'\n",
+        )
+        .unwrap();
+
+    let image = ReplacementConfig::new(
+        "services.web.image".to_owned(),
+        Some("caddy".to_owned()),
+        None,
+    )
+    .with_kind(ReplacementKind::Yaml);
+    let missing = ReplacementConfig::new(
+        "services.db.image".to_owned(),
+        Some("postgres".to_owned()),
+        None,
+    )
+    .with_kind(ReplacementKind::Yaml);
+
+    let count =
+        apply_replacements_to_file(&system, Path::new("/compose.yaml"), &[image, missing]).unwrap();
+
+    assert_eq!(count, 1);
+    assert_eq!(
+        system.read_to_string(Path::new("/compose.yaml")).unwrap(),
+        "services:\n  web:\n    image: caddy\n    ports:\n    - '80'\n"
+    );
+}
+
+#[test]
+fn require_contains_only_edits_files_with_the_marker() {
+    let system = MockSystem::new()
+        .with_file(
+            "/project/managed.ts",
+            b"// managed\nconst name = \"{{NAME}}\";\n",
+        )
+        .unwrap()
+        .with_file("/project/custom.ts", b"const name = \"{{NAME}}\";\n")
+        .unwrap();
+
+    let replacement = ReplacementConfig::new("{{NAME}}".to_owned(), Some("demo".to_owned()), None)
+        .with_require_contains("// managed");
+    let count = apply_replacements(&system, "/project", &[replacement]).unwrap();
+
+    assert_eq!(count, 1);
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/project/managed.ts"))
+            .unwrap(),
+        "// managed\nconst name = \"demo\";\n"
+    );
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/project/custom.ts"))
+            .unwrap(),
+        "const name = \"{{NAME}}\";\n"
+    );
+}
+
+#[test]
+fn delete_lines_removes_marker_lines_and_keeps_final_newline_state() {
+    let system = MockSystem::new()
+        .with_file("/project/a.txt", b"keep 1\nlog(); // DEV ONLY\nkeep 2\n")
+        .unwrap()
+        .with_file("/project/b.txt", b"keep 3\ndebug(); // DEV ONLY")
+        .unwrap();
+
+    let replacement = ReplacementConfig::new("// DEV ONLY".to_owned(), None, None)
+        .with_kind(ReplacementKind::DeleteLines);
+    apply_replacements(&system, "/project", &[replacement]).unwrap();
+
+    assert_eq!(
+        system.read_to_string(Path::new("/project/a.txt")).unwrap(),
+        "keep 1\nkeep 2\n"
+    );
+    assert_eq!(
+        system.read_to_string(Path::new("/project/b.txt")).unwrap(),
+        "keep 3"
+    );
+
+    assert_eq!(
+        delete_matching_lines("x\r\nmark\r\ny", "mark"),
+        ("x\r\ny".to_owned(), 1)
+    );
+    assert_eq!(
+        delete_matching_lines("mark\nmark", "mark"),
+        (String::new(), 2)
+    );
+}
+
+#[test]
+fn no_empty_replacements_rejects_empty_env_value() {
+    let system = MockSystem::new()
+        .with_env("EMPTY_VALUE", "")
+        .unwrap()
+        .with_env("SET_VALUE", "value")
+        .unwrap();
+
+    let empty =
+        ReplacementConfig::new("{{EMPTY}}".to_owned(), None, Some("EMPTY_VALUE".to_owned()));
+    let set = ReplacementConfig::new("{{SET}}".to_owned(), None, Some("SET_VALUE".to_owned()));
+
+    check_no_empty_replacements(&system, &[set.clone()]).unwrap();
+    let err = check_no_empty_replacements(&system, &[set, empty]).unwrap_err();
+    assert!(err.to_string().contains("{{EMPTY}}"));
+}
+
+#[test]
+fn no_empty_graft_replacements_rejects_empty_context_value() {
+    let system = MockSystem::new();
+    let mut context = HashMap::new();
+    context.insert("name".to_owned(), json!(""));
+
+    let replacement =
+        GraftReplacement::new("{{NAME}}".to_owned(), None, None, Some("name".to_owned()));
+    let err = check_no_empty_graft_replacements(&system, &[replacement], &context).unwrap_err();
+    assert!(err.to_string().contains("{{NAME}}"));
+
+    context.insert("name".to_owned(), json!("demo"));
+    let replacement =
+        GraftReplacement::new("{{NAME}}".to_owned(), None, None, Some("name".to_owned()));
+    check_no_empty_graft_replacements(&system, &[replacement], &context).unwrap();
+}
+
+#[test]
+fn strict_replacements_reject_identity_values() {
+    let system = MockSystem::new();
+    let identity = ReplacementConfig::new("{{NAME}}".to_owned(), Some("{{NAME}}".to_owned()), None);
+    let changed = ReplacementConfig::new("{{NAME}}".to_owned(), Some("demo".to_owned()), None);
+
+    check_no_identity_replacements(&system, &[changed]).unwrap();
+    let err = check_no_identity_replacements(&system, &[identity.clone()]).unwrap_err();
+    assert!(err.to_string().contains("{{NAME}}"));
+    // Outside strict mode the value still resolves
+    assert_eq!(
+        get_replacement_value(&system, &identity).unwrap(),
+        "{{NAME}}"
+    );
+
+    let mut context = HashMap::new();
+    context.insert("name".to_owned(), json!("{{NAME}}"));
+    let graft_identity =
+        GraftReplacement::new("{{NAME}}".to_owned(), None, None, Some("name".to_owned()));
+    check_no_identity_graft_replacements(&system, &[graft_identity], &context).unwrap_err();
+}
+
+#[test]
+fn value_from_json_path_copies_a_value_from_another_file() {
+    let system = MockSystem::new()
+        .with_dir("/target")
+        .unwrap()
+        .with_file(
+            "/target/package.json",
+            b"{\n  \"name\": \"demo\",\n  \"version\": \"2.4.1\"\n}\n",
+        )
+        .unwrap()
+        .with_file("/target/README.md", b"Install demo@{{VERSION}}\n")
+        .unwrap();
+
+    let replacements = vec![
+        GraftReplacement::new("{{VERSION}}".to_owned(), None, None, None)
+            .with_value_from_json_path(JsonPathSource::new(
+                "package.json".to_owned(),
+                "version".to_owned(),
+            )),
+    ];
+    let resolved =
+        resolve_json_path_replacements(&system, Path::new("/target"), &replacements).unwrap();
+    apply_graft_replacements(&system, "/target", &resolved, &HashMap::new()).unwrap();
+
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/target/README.md"))
+            .unwrap(),
+        "Install demo@2.4.1\n"
+    );
+
+    // A missing key path names the path and the file
+    let missing = vec![
+        GraftReplacement::new("{{X}}".to_owned(), None, None, None).with_value_from_json_path(
+            JsonPathSource::new("package.json".to_owned(), "engines.node".to_owned()),
+        ),
+    ];
+    let err = resolve_json_path_replacements(&system, Path::new("/target"), &missing).unwrap_err();
+    assert!(format!("{err:#}").contains("Key path 'engines.node' not found"));
+}
+
+#[test]
+fn apply_replacements_round_trips_utf16_files() {
+    let system = MockSystem::new()
+        .with_file(
+            "/test/legacy.txt",
+            &utf16le_with_bom("caf\u{e9}: {{NAME}}\r\n"),
+        )
+        .unwrap();
+
+    let replacements = vec![ReplacementConfig::new(
+        "{{NAME}}".to_owned(),
+        Some("cr\u{e8}me".to_owned()),
+        None,
+    )];
+
+    let count = apply_replacements_to_file_with_encoding(
+        &system,
+        Path::new("/test/legacy.txt"),
+        &replacements,
+        TextEncoding::for_label("utf-16"),
+    )
+    .unwrap();
+    assert_eq!(count, 1);
+
+    let mut written = Vec::new();
+    system
+        .open(Path::new("/test/legacy.txt"))
+        .unwrap()
+        .read_to_end(&mut written)
+        .unwrap();
+    assert_eq!(written, utf16le_with_bom("caf\u{e9}: cr\u{e8}me\r\n"));
+}
+
+#[test]
+fn apply_replacements_counted_attributes_files_to_each_pattern() {
+    let system = MockSystem::new()
+        .with_dir("/project/nested")
+        .unwrap()
+        .with_file("/project/a.txt", b"{{NAME}} {{NAME}} on {{PORT}}")
+        .unwrap()
+        .with_file("/project/nested/b.txt", b"{{NAME}}")
+        .unwrap()
+        .with_file("/project/c.txt", b"untouched")
+        .unwrap();
+
+    let replacements = vec![
+        ReplacementConfig::new("{{NAME}}".to_owned(), Some("web".to_owned()), None),
+        ReplacementConfig::new("{{PORT}}".to_owned(), Some("8080".to_owned()), None),
+        ReplacementConfig::new("{{MISSING}}".to_owned(), Some("x".to_owned()), None),
+    ];
+
+    let counts =
+        apply_replacements_counted(&system, Path::new("/project"), &replacements, None).unwrap();
+    let summary: Vec<(&str, usize, usize)> = counts
+        .iter()
+        .map(|count| (count.source.as_str(), count.files, count.occurrences))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("{{NAME}}", 2, 3),
+            ("{{PORT}}", 1, 1),
+            ("{{MISSING}}", 0, 0)
+        ]
+    );
+}
@@ -1,4 +1,4 @@
-//! Unit tests for `Repository` public API.
+//! Unit tests for `Repository` and `ArchiveCheckout` public API.
 //!
 //! Private function tests (`normalize_repository_url`) remain inline in `src/git/repository.rs`.
 
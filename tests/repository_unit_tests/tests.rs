@@ -1,7 +1,15 @@
 #![expect(clippy::unwrap_used, reason = "This is a test module")]
 
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use os_shim::System as _;
 use os_shim::mock::MockSystem;
-use tixgraft::git::Repository;
+use std::io::{Cursor, Write as _};
+use std::path::PathBuf;
+use tixgraft::git::{
+    ArchiveCheckout, ArchiveFormat, ArchiveLocation, Repository, ensure_offline_access,
+};
+use zip::write::SimpleFileOptions;
 
 #[test]
 fn detect_git_source() {
@@ -65,6 +73,37 @@ fn detect_local_source_with_relative_path() {
     assert!(repo.local_path().is_some());
 }
 
+#[cfg(windows)]
+#[test]
+fn detect_local_source_with_windows_drive_urls() {
+    let system = MockSystem::new().with_dir("C:\\repos\\app").unwrap();
+
+    for url in [
+        "file:///C:/repos/app",
+        "file://C:/repos/app",
+        "file:/C:/repos/app",
+        "file:C:\\repos\\app",
+    ] {
+        let repo = Repository::new(&system, url).unwrap();
+        assert!(repo.is_local(), "{url}");
+        assert_eq!(
+            repo.local_path().unwrap(),
+            &PathBuf::from("C:\\repos\\app"),
+            "{url}"
+        );
+    }
+}
+
+#[cfg(windows)]
+#[test]
+fn detect_local_source_with_lowercase_windows_drive() {
+    let system = MockSystem::new().with_dir("d:\\work\\repo").unwrap();
+
+    let repo = Repository::new(&system, "file:///d:/work/repo").unwrap();
+
+    assert_eq!(repo.local_path().unwrap(), &PathBuf::from("d:\\work\\repo"));
+}
+
 #[test]
 fn local_source_nonexistent_path() {
     let system = MockSystem::new();
@@ -102,3 +141,135 @@ fn repository_methods() {
         "/test/local_repo"
     );
 }
+
+#[test]
+fn detect_archive_sources() {
+    let system = MockSystem::new()
+        .with_file("/test/templates-1.0.tar.gz", b"")
+        .unwrap();
+
+    let local = Repository::new(&system, "file:///test/templates-1.0.tar.gz").unwrap();
+    assert!(local.is_archive());
+    assert!(!local.is_git());
+    assert!(!local.is_local());
+    assert_eq!(
+        local.archive(),
+        Some((
+            ArchiveFormat::TarGz,
+            &ArchiveLocation::Local(PathBuf::from("/test/templates-1.0.tar.gz"))
+        ))
+    );
+
+    let prefixed = Repository::new(&system, "zip:file:///test/templates-1.0.tar.gz").unwrap();
+    assert_eq!(
+        prefixed.archive().map(|(format, _)| format),
+        Some(ArchiveFormat::Zip)
+    );
+}
+
+#[test]
+fn detect_remote_archive_sources() {
+    let system = MockSystem::new();
+
+    let remote = Repository::new(&system, "https://example.com/templates.zip").unwrap();
+    assert!(remote.is_archive());
+    assert!(remote.is_remote());
+    assert_eq!(
+        remote.archive(),
+        Some((
+            ArchiveFormat::Zip,
+            &ArchiveLocation::Remote("https://example.com/templates.zip".to_owned())
+        ))
+    );
+
+    let prefixed = Repository::new(&system, "tar:https://example.com/download?id=42").unwrap();
+    assert_eq!(
+        prefixed.archive(),
+        Some((
+            ArchiveFormat::TarGz,
+            &ArchiveLocation::Remote("https://example.com/download?id=42".to_owned())
+        ))
+    );
+    ensure_offline_access(&prefixed).unwrap_err();
+}
+
+#[test]
+fn detect_archive_source_errors() {
+    let system = MockSystem::new();
+
+    let missing = Repository::new(&system, "file:///test/missing.tar.gz").unwrap_err();
+    assert!(missing.to_string().contains("Archive file does not exist"));
+
+    let unsupported = Repository::new(&system, "zip:s3://bucket/templates.zip").unwrap_err();
+    assert!(
+        unsupported
+            .to_string()
+            .contains("Unsupported archive location")
+    );
+}
+
+#[test]
+fn archive_checkout_extracts_through_system() {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let contents = b"name: {{NAME}}";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(u64::try_from(contents.len()).unwrap());
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(
+            &mut header,
+            "templates-1.0/web/app.txt",
+            contents.as_slice(),
+        )
+        .unwrap();
+    let archive = builder.into_inner().unwrap().finish().unwrap();
+
+    let system = MockSystem::new()
+        .with_dir("/test")
+        .unwrap()
+        .with_file("/test/templates-1.0.tar.gz", &archive)
+        .unwrap();
+
+    let checkout = ArchiveCheckout::extract(
+        &system,
+        ArchiveFormat::TarGz,
+        &ArchiveLocation::Local(PathBuf::from("/test/templates-1.0.tar.gz")),
+    )
+    .unwrap();
+
+    // The single top-level directory becomes the root
+    assert!(checkout.root().ends_with("templates-1.0"));
+    let extracted = checkout.root().join("web/app.txt");
+    assert_eq!(system.read_to_string(&extracted).unwrap(), "name: {{NAME}}");
+    // Nothing is written to the real filesystem
+    assert!(!extracted.exists());
+}
+
+#[test]
+fn archive_checkout_rejects_entries_outside_extraction_dir() {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    writer
+        .start_file("../escape.txt", SimpleFileOptions::default())
+        .unwrap();
+    writer.write_all(b"escaped").unwrap();
+    let archive = writer.finish().unwrap().into_inner();
+
+    let system = MockSystem::new()
+        .with_dir("/test")
+        .unwrap()
+        .with_file("/test/templates.zip", &archive)
+        .unwrap();
+
+    let err = ArchiveCheckout::extract(
+        &system,
+        ArchiveFormat::Zip,
+        &ArchiveLocation::Local(PathBuf::from("/test/templates.zip")),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("points outside the extraction directory"),
+        "unexpected error: {err}"
+    );
+}
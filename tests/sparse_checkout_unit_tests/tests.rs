@@ -1,6 +1,16 @@
 #![expect(clippy::unwrap_used, reason = "This is a test module")]
+#![expect(
+    clippy::indexing_slicing,
+    reason = "Index-based assertions are acceptable in tests"
+)]
 
-use tixgraft::git::sparse_checkout::parse_git_version;
+use os_shim::mock::MockSystem;
+use std::path::Path;
+use tixgraft::git::sparse_checkout::{
+    CloneOptions, SparseCheckout, is_cone_pattern, is_relative_reference, looks_like_tag,
+    parse_git_version, parse_ls_tree_sizes, validate_clone_filter,
+};
+use tixgraft::git::{GitOutput, GitVerbosity, MockGitRunner, Repository};
 
 #[test]
 fn parse_git_version_tst() {
@@ -8,3 +18,413 @@ fn parse_git_version_tst() {
     assert_eq!(parse_git_version("2.25.0").unwrap(), (2, 25, 0));
     parse_git_version("invalid").unwrap_err();
 }
+
+/// Build a sparse checkout of `src` from a Git repository, routed through `runner`.
+fn mock_checkout<'runner>(
+    runner: &'runner MockGitRunner,
+    options: CloneOptions,
+) -> SparseCheckout<'runner> {
+    let system = MockSystem::new();
+    let repository = Repository::new(&system, "my_org/repo").unwrap();
+    SparseCheckout::new(repository, "main".to_owned(), "src".to_owned())
+        .unwrap()
+        .with_options(options)
+        .with_runner(runner)
+}
+
+#[test]
+fn clone_uses_default_filter() {
+    let runner = MockGitRunner::new();
+    mock_checkout(&runner, CloneOptions::default())
+        .execute()
+        .unwrap();
+
+    let invocations = runner.invocations();
+    let clone = invocations
+        .iter()
+        .find(|invocation| invocation.subcommand() == Some("clone"))
+        .unwrap();
+    assert!(clone.args.contains(&"--filter=blob:none".to_owned()));
+}
+
+#[test]
+fn clone_uses_configured_filter() {
+    let runner = MockGitRunner::new();
+    mock_checkout(&runner, CloneOptions::default().with_filter("tree:0"))
+        .execute()
+        .unwrap();
+
+    let invocations = runner.invocations();
+    let clone = invocations
+        .iter()
+        .find(|invocation| invocation.subcommand() == Some("clone"))
+        .unwrap();
+    assert!(clone.args.contains(&"--filter=tree:0".to_owned()));
+    assert!(!clone.args.contains(&"--filter=blob:none".to_owned()));
+}
+
+#[test]
+fn oversized_source_aborts_before_checkout() {
+    let runner = MockGitRunner::new().with_response(
+        "ls-tree",
+        GitOutput::success(
+            "100644 blob 1111111 4096\tsrc/big.bin\n100644 blob 2222222 2048\tsrc/more.bin\n",
+        ),
+    );
+    let result =
+        mock_checkout(&runner, CloneOptions::default().with_max_clone_size(5000)).execute();
+
+    let err = result.unwrap_err();
+    assert!(format!("{err:#}").contains("--max-clone-size"));
+    let invocations = runner.invocations();
+    assert!(
+        !invocations
+            .iter()
+            .any(|invocation| invocation.subcommand() == Some("checkout"))
+    );
+}
+
+#[test]
+fn source_within_clone_size_limit_is_checked_out() {
+    let runner = MockGitRunner::new().with_response(
+        "ls-tree",
+        GitOutput::success("100644 blob 1111111 1024\tsrc/small.txt\n"),
+    );
+    mock_checkout(&runner, CloneOptions::default().with_max_clone_size(5000))
+        .execute()
+        .unwrap();
+
+    let invocations = runner.invocations();
+    assert!(
+        invocations
+            .iter()
+            .any(|invocation| invocation.subcommand() == Some("checkout"))
+    );
+}
+
+#[test]
+fn explicit_sparse_patterns_use_non_cone_mode() {
+    let runner = MockGitRunner::new();
+    mock_checkout(&runner, CloneOptions::default())
+        .with_sparse_patterns(vec!["/docs".to_owned(), "!/docs/drafts".to_owned()])
+        .execute()
+        .unwrap();
+
+    let invocations = runner.invocations();
+    let init = invocations
+        .iter()
+        .find(|invocation| {
+            invocation
+                .args
+                .starts_with(&["sparse-checkout".to_owned(), "init".to_owned()])
+        })
+        .unwrap();
+    assert!(init.args.contains(&"--no-cone".to_owned()));
+    let set = invocations
+        .iter()
+        .find(|invocation| {
+            invocation
+                .args
+                .starts_with(&["sparse-checkout".to_owned(), "set".to_owned()])
+        })
+        .unwrap();
+    assert_eq!(
+        set.args,
+        vec!["sparse-checkout", "set", "/docs", "!/docs/drafts"]
+    );
+}
+
+#[test]
+fn cone_patterns_are_plain_directories() {
+    assert!(is_cone_pattern("docs/guides"));
+    assert!(!is_cone_pattern("/docs"));
+    assert!(!is_cone_pattern("!docs/drafts"));
+    assert!(!is_cone_pattern("*.md"));
+}
+
+#[test]
+fn relative_reference_clones_full_history() {
+    let runner = MockGitRunner::new();
+    let system = MockSystem::new();
+    let repository = Repository::new(&system, "my_org/repo").unwrap();
+    SparseCheckout::new(repository, "HEAD~2".to_owned(), "src".to_owned())
+        .unwrap()
+        .with_runner(&runner)
+        .execute()
+        .unwrap();
+
+    let invocations = runner.invocations();
+    let clone = invocations
+        .iter()
+        .find(|invocation| invocation.subcommand() == Some("clone"))
+        .unwrap();
+    assert!(!clone.args.iter().any(|arg| arg.starts_with("--filter=")));
+
+    let checkout = invocations
+        .iter()
+        .find(|invocation| invocation.subcommand() == Some("checkout"))
+        .unwrap();
+    assert!(checkout.args.contains(&"HEAD~2".to_owned()));
+}
+
+#[test]
+fn resolve_sha_reports_checked_out_commit() {
+    let runner = MockGitRunner::new().with_response(
+        "rev-parse",
+        GitOutput::success("0123456789abcdef0123456789abcdef01234567\n"),
+    );
+    let checkout = mock_checkout(&runner, CloneOptions::default());
+    checkout.execute().unwrap();
+
+    assert_eq!(
+        checkout.resolve_sha().unwrap(),
+        "0123456789abcdef0123456789abcdef01234567"
+    );
+    let invocations = runner.invocations();
+    let rev_parse = invocations.last().unwrap();
+    assert_eq!(rev_parse.args, ["rev-parse", "HEAD"]);
+    assert_eq!(rev_parse.cwd.as_deref(), Some(checkout.temp_path()));
+}
+
+#[test]
+fn resolve_sha_failure_is_reported() {
+    let runner =
+        MockGitRunner::new().with_response("rev-parse", GitOutput::failure("fatal: bad revision"));
+    let checkout = mock_checkout(&runner, CloneOptions::default());
+
+    let err = checkout.resolve_sha().unwrap_err();
+    assert!(format!("{err:#}").contains("fatal: bad revision"));
+}
+
+#[test]
+fn is_relative_reference_tst() {
+    assert!(is_relative_reference("HEAD"));
+    assert!(is_relative_reference("@"));
+    assert!(is_relative_reference("HEAD~2"));
+    assert!(is_relative_reference("main^"));
+    assert!(is_relative_reference("@{1}"));
+
+    assert!(!is_relative_reference("main"));
+    assert!(!is_relative_reference("v1.0.0"));
+    assert!(!is_relative_reference("feature/login"));
+}
+
+#[test]
+fn pinned_trust_anchors_set_git_env() {
+    let runner = MockGitRunner::new();
+    let options = CloneOptions::default()
+        .with_ca_bundle(Path::new("/etc/pinned/ca.pem"))
+        .with_known_hosts(Path::new("/etc/pinned/known_hosts"));
+    mock_checkout(&runner, options).execute().unwrap();
+
+    let invocations = runner.invocations();
+    let clone = invocations
+        .iter()
+        .find(|invocation| invocation.subcommand() == Some("clone"))
+        .unwrap();
+    assert_eq!(clone.env_var("GIT_SSL_CAINFO"), Some("/etc/pinned/ca.pem"));
+    let ssh_command = clone.env_var("GIT_SSH_COMMAND").unwrap();
+    assert!(ssh_command.contains("UserKnownHostsFile=\"/etc/pinned/known_hosts\""));
+    assert!(ssh_command.contains("StrictHostKeyChecking=yes"));
+
+    // Later commands may fetch from the remote too
+    assert!(
+        invocations
+            .iter()
+            .all(|invocation| invocation.env_var("GIT_SSL_CAINFO").is_some())
+    );
+}
+
+#[test]
+fn clone_without_trust_anchors_sets_no_env() {
+    let runner = MockGitRunner::new();
+    mock_checkout(&runner, CloneOptions::default())
+        .execute()
+        .unwrap();
+
+    assert!(
+        runner
+            .invocations()
+            .iter()
+            .all(|invocation| invocation.env.is_empty())
+    );
+}
+
+#[test]
+fn clone_failure_is_reported() {
+    let runner =
+        MockGitRunner::new().with_response("clone", GitOutput::failure("repository not found"));
+    let result = mock_checkout(&runner, CloneOptions::default()).execute();
+
+    assert!(result.is_err());
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("repository not found")
+    );
+    // Nothing runs after a failed clone
+    assert_eq!(runner.invocations().len(), 1);
+}
+
+#[test]
+fn validate_clone_filter_tst() {
+    validate_clone_filter("blob:none").unwrap();
+    validate_clone_filter("blob:limit=1m").unwrap();
+    validate_clone_filter("blob:limit=1024").unwrap();
+    validate_clone_filter("tree:0").unwrap();
+    validate_clone_filter("object:type=blob").unwrap();
+    validate_clone_filter("sparse:oid=main:.gitfilter").unwrap();
+    validate_clone_filter("combine:blob:none+tree:3").unwrap();
+
+    assert!(validate_clone_filter("").is_err());
+    assert!(validate_clone_filter("blob").is_err());
+    assert!(validate_clone_filter("blob:limit=lots").is_err());
+    assert!(validate_clone_filter("tree:deep").is_err());
+    assert!(validate_clone_filter("object:type=file").is_err());
+    assert!(validate_clone_filter("everything").is_err());
+}
+
+#[test]
+fn looks_like_tag_tst() {
+    assert!(looks_like_tag("v1.2.0"));
+    assert!(looks_like_tag("1.4"));
+    assert!(looks_like_tag("v2.0.0-rc.1"));
+    assert!(!looks_like_tag("main"));
+    assert!(!looks_like_tag("feature/v1.2"));
+    assert!(!looks_like_tag("v2"));
+    assert!(!looks_like_tag("HEAD~1"));
+}
+
+/// Find the `clone` invocation of a sparse checkout of `reference`.
+fn clone_args_for(reference: &str, options: CloneOptions) -> Vec<String> {
+    let runner = MockGitRunner::new();
+    let system = MockSystem::new();
+    let repository = Repository::new(&system, "my_org/repo").unwrap();
+    SparseCheckout::new(repository, reference.to_owned(), "src".to_owned())
+        .unwrap()
+        .with_options(options)
+        .with_runner(&runner)
+        .execute()
+        .unwrap();
+
+    runner
+        .invocations()
+        .into_iter()
+        .find(|invocation| invocation.subcommand() == Some("clone"))
+        .unwrap()
+        .args
+}
+
+#[test]
+fn tag_reference_uses_single_tag_shallow_clone() {
+    let args = clone_args_for("v1.2.0", CloneOptions::default());
+    let branch = args.iter().position(|arg| arg == "--branch").unwrap();
+    assert_eq!(args[branch.saturating_add(1)], "v1.2.0");
+    let depth = args.iter().position(|arg| arg == "--depth").unwrap();
+    assert_eq!(args[depth.saturating_add(1)], "1");
+
+    let args = clone_args_for("main", CloneOptions::default());
+    assert!(!args.contains(&"--branch".to_owned()));
+    assert!(!args.contains(&"--depth".to_owned()));
+}
+
+#[test]
+fn fetch_tags_disables_single_tag_clone() {
+    let args = clone_args_for("v1.2.0", CloneOptions::default().with_fetch_tags(true));
+    assert!(!args.contains(&"--branch".to_owned()));
+    assert!(!args.contains(&"--depth".to_owned()));
+}
+
+#[test]
+fn parse_ls_tree_sizes_sums_blobs() {
+    let output = "100644 blob 1111111111111111111111111111111111111111     120\tsrc/a.txt\n\
+                  100755 blob 2222222222222222222222222222222222222222    2048\tsrc/bin/run.sh\n\
+                  160000 commit 3333333333333333333333333333333333333333       -\tsrc/vendor\n";
+    assert_eq!(parse_ls_tree_sizes(output), (2, 2168));
+    assert_eq!(parse_ls_tree_sizes(""), (0, 0));
+}
+
+#[test]
+fn estimate_size_lists_source_tree_at_reference() {
+    let runner = MockGitRunner::new().with_response(
+        "ls-tree",
+        GitOutput::success("100644 blob 1111111111111111111111111111111111111111 300\tsrc/a.txt\n"),
+    );
+    let checkout = mock_checkout(&runner, CloneOptions::default());
+
+    assert_eq!(checkout.estimate_size().unwrap(), (1, 300));
+    let invocations = runner.invocations();
+    let ls_tree = invocations
+        .iter()
+        .find(|invocation| invocation.subcommand() == Some("ls-tree"))
+        .unwrap();
+    assert_eq!(ls_tree.args, ["ls-tree", "-r", "-l", "main", "--", "src"]);
+    assert!(
+        !invocations
+            .iter()
+            .any(|invocation| invocation.subcommand() == Some("checkout"))
+    );
+}
+
+#[test]
+fn quiet_git_passes_quiet_flag_to_clone_and_checkout() {
+    let runner = MockGitRunner::new();
+    mock_checkout(
+        &runner,
+        CloneOptions::default().with_verbosity(GitVerbosity::Quiet),
+    )
+    .execute()
+    .unwrap();
+
+    let invocations = runner.invocations();
+    for subcommand in ["clone", "checkout"] {
+        let invocation = invocations
+            .iter()
+            .find(|invocation| invocation.subcommand() == Some(subcommand))
+            .unwrap();
+        assert!(invocation.args.contains(&"-q".to_owned()));
+        assert!(!invocation.inherit_stderr);
+    }
+
+    let args = clone_args_for("main", CloneOptions::default());
+    assert!(!args.contains(&"-q".to_owned()));
+}
+
+#[test]
+fn loud_git_inherits_stderr_for_clone_and_checkout() {
+    let runner = MockGitRunner::new();
+    mock_checkout(
+        &runner,
+        CloneOptions::default().with_verbosity(GitVerbosity::Loud),
+    )
+    .execute()
+    .unwrap();
+
+    for invocation in runner.invocations() {
+        let noisy = matches!(invocation.subcommand(), Some("clone" | "checkout"));
+        assert_eq!(invocation.inherit_stderr, noisy, "{:?}", invocation.args);
+        assert!(!invocation.args.contains(&"-q".to_owned()));
+    }
+}
+
+#[test]
+fn new_in_creates_temp_dir_under_root_and_cleans_up() {
+    let root = tempfile::TempDir::new().unwrap();
+    let system = MockSystem::new();
+    let repository = Repository::new(&system, "my_org/repo").unwrap();
+
+    let checkout = SparseCheckout::new_in(
+        repository,
+        "main".to_owned(),
+        "src".to_owned(),
+        Some(root.path()),
+    )
+    .unwrap();
+    let temp_path = checkout.temp_path().to_path_buf();
+    assert!(temp_path.starts_with(root.path()));
+    assert!(temp_path.is_dir());
+
+    drop(checkout);
+    assert!(!temp_path.exists());
+}
@@ -0,0 +1,4 @@
+//! Unit tests for writing file pulls to stdout (`target: "-"`).
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,66 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use os_shim::System as _;
+use os_shim::mock::MockSystem;
+use std::env;
+use std::path::Path;
+use std::process;
+use tixgraft::cli::PullConfig;
+use tixgraft::operations::stdout::{is_stdout_target, write_file_to_stdout};
+
+/// A `target: "-"` file pull with one replacement.
+fn stdout_pull() -> PullConfig {
+    let yaml = "source: app.txt\ntarget: \"-\"\ntype: file\nreplacements:\n  - source: \"{{NAME}}\"\n    target: Alice\n";
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[test]
+fn is_stdout_target_tst() {
+    assert!(is_stdout_target("-"));
+    assert!(!is_stdout_target("./-"));
+    assert!(!is_stdout_target("out.txt"));
+}
+
+#[test]
+fn write_file_to_stdout_applies_replacements_and_creates_no_file() {
+    let system = MockSystem::new()
+        .with_file("/source/app.txt", b"Hello {{NAME}}!")
+        .unwrap();
+    let mut stdout = Vec::new();
+
+    let replacements = write_file_to_stdout(
+        &system,
+        Path::new("/source/app.txt"),
+        &stdout_pull(),
+        &mut stdout,
+    )
+    .unwrap();
+
+    assert_eq!(replacements, 1);
+    assert_eq!(String::from_utf8(stdout).unwrap(), "Hello Alice!");
+    assert!(!system.exists(Path::new("-")).unwrap());
+    assert_eq!(
+        system.read_to_string(Path::new("/source/app.txt")).unwrap(),
+        "Hello {{NAME}}!"
+    );
+
+    let staging_dir = env::temp_dir().join(format!("tixgraft-stdout-{}", process::id()));
+    assert!(!system.exists(&staging_dir).unwrap());
+}
+
+#[test]
+fn write_file_to_stdout_rejects_directory_source() {
+    let system = MockSystem::new().with_dir("/source/app").unwrap();
+    let mut stdout = Vec::new();
+
+    let err = write_file_to_stdout(
+        &system,
+        Path::new("/source/app"),
+        &stdout_pull(),
+        &mut stdout,
+    )
+    .unwrap_err();
+
+    assert!(err.to_string().contains("requires a file source"));
+    assert!(stdout.is_empty());
+}
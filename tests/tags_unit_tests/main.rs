@@ -0,0 +1,4 @@
+//! Unit tests for resolving `tagPattern` to a remote tag.
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,82 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use tixgraft::git::{GitOutput, MockGitRunner, resolve_tag_pattern};
+
+/// `git ls-remote --tags --refs` output listing `tags`.
+fn ls_remote_output(tags: &[&str]) -> GitOutput {
+    let lines: Vec<String> = tags
+        .iter()
+        .enumerate()
+        .map(|(index, tag)| format!("{index:040x}\trefs/tags/{tag}"))
+        .collect();
+    GitOutput::success(&lines.join("\n"))
+}
+
+#[test]
+fn resolves_pattern_to_highest_matching_version() {
+    let runner = MockGitRunner::new().with_response(
+        "ls-remote",
+        ls_remote_output(&["v1.9.0", "v2.1.0", "v2.10.0", "v2.9.3", "v3.0.0", "latest"]),
+    );
+
+    let tag =
+        resolve_tag_pattern(&runner, "https://example.com/repo.git", "v2.*", Vec::new()).unwrap();
+
+    // Compared numerically, not as strings: v2.10.0 is above v2.9.3
+    assert_eq!(tag, "v2.10.0");
+    let invocation = runner.invocations().into_iter().next().unwrap();
+    assert_eq!(
+        invocation.args,
+        [
+            "ls-remote",
+            "--tags",
+            "--refs",
+            "https://example.com/repo.git"
+        ]
+    );
+}
+
+#[test]
+fn prefers_release_over_its_pre_release() {
+    let runner = MockGitRunner::new().with_response(
+        "ls-remote",
+        ls_remote_output(&["v2.1.0-rc2", "v2.1.0", "v2.0.5"]),
+    );
+
+    let tag = resolve_tag_pattern(&runner, "repo", "v2.?.*", Vec::new()).unwrap();
+
+    assert_eq!(tag, "v2.1.0");
+}
+
+#[test]
+fn orders_pre_releases_numerically() {
+    let runner = MockGitRunner::new().with_response(
+        "ls-remote",
+        ls_remote_output(&["v2.1.0-rc10", "v2.1.0-rc2", "v2.1.0-rc9"]),
+    );
+    let tag = resolve_tag_pattern(&runner, "repo", "v2.1.0-rc*", Vec::new()).unwrap();
+    assert_eq!(tag, "v2.1.0-rc10");
+
+    let runner = MockGitRunner::new().with_response(
+        "ls-remote",
+        ls_remote_output(&["v3.0.0-beta.10", "v3.0.0-beta.2", "v3.0.0-alpha.11"]),
+    );
+    let tag = resolve_tag_pattern(&runner, "repo", "v3.*", Vec::new()).unwrap();
+    assert_eq!(tag, "v3.0.0-beta.10");
+}
+
+#[test]
+fn invalid_pattern_or_no_match_errors() {
+    let runner = MockGitRunner::new().with_response("ls-remote", ls_remote_output(&["v1.0.0"]));
+
+    let invalid = resolve_tag_pattern(&runner, "repo", "v2 *", Vec::new()).unwrap_err();
+    assert!(invalid.to_string().contains("Invalid tagPattern 'v2 *'"));
+    assert!(runner.invocations().is_empty());
+
+    let unmatched = resolve_tag_pattern(&runner, "repo", "v2.*", Vec::new()).unwrap_err();
+    assert!(
+        unmatched
+            .to_string()
+            .contains("No tag of 'repo' matches tagPattern 'v2.*'")
+    );
+}
@@ -1,4 +1,4 @@
-//! Tests for System temp directory abstraction.
+//! Tests for System temp directory abstraction and temp directory cleanup.
 
 #[cfg(test)]
 mod tests;
@@ -1,7 +1,11 @@
 #![expect(clippy::unwrap_used, reason = "This is a test module")]
 
+use assert_cmd::Command;
 use os_shim::System as _;
 use os_shim::mock::MockSystem;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
 
 #[test]
 fn mock_temp_dir_creation() {
@@ -88,3 +92,29 @@ fn temp_dir_with_subdirectories() {
     assert!(!system.exists(&path_copy).unwrap());
     assert!(!system.exists(&path_copy.join("subdir")).unwrap());
 }
+
+#[test]
+fn dry_run_remote_inspection_leaves_no_temp_dirs() {
+    let work_dir = TempDir::new().unwrap();
+    let temp_root = work_dir.path().join("tmp");
+    fs::create_dir_all(&temp_root).unwrap();
+    fs::write(
+        work_dir.path().join("tixgraft.yaml"),
+        "repository: \"https://127.0.0.1:9/unreachable.git\"\npulls:\n  - source: src\n    target: dst\n",
+    )
+    .unwrap();
+
+    // The clone fails, so the estimate takes its error path
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .current_dir(work_dir.path())
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .arg("--dry-run")
+        .arg("--check-remote")
+        .arg("--temp-dir")
+        .arg(&temp_root)
+        .assert()
+        .stdout(predicate::str::contains("Could not estimate size"));
+
+    assert_eq!(fs::read_dir(&temp_root).unwrap().count(), 0);
+}
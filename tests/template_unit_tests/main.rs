@@ -0,0 +1,4 @@
+//! Unit tests for template rendering.
+
+#[cfg(test)]
+mod tests;
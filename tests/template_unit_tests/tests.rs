@@ -0,0 +1,135 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use os_shim::System as _;
+use os_shim::mock::MockSystem;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+use tixgraft::config::context::ContextValues;
+use tixgraft::operations::template::{is_template_engine_available, render_templates};
+
+fn sample_context() -> ContextValues {
+    let mut context = HashMap::new();
+    context.insert("serviceName".to_owned(), json!("billing"));
+    context.insert("enableMetrics".to_owned(), json!(true));
+    context
+}
+
+#[test]
+fn render_none_preserves_content() {
+    let system = MockSystem::new()
+        .with_dir("/target")
+        .unwrap()
+        .with_file("/target/app.txt", b"name: {{ serviceName }}\n")
+        .unwrap();
+
+    let rendered = render_templates(
+        &system,
+        Path::new("/target"),
+        "none",
+        &sample_context(),
+        ".graft.yaml",
+    )
+    .unwrap();
+
+    assert_eq!(rendered, 0);
+    let content = system.read_to_string(Path::new("/target/app.txt")).unwrap();
+    assert_eq!(content, "name: {{ serviceName }}\n");
+}
+
+#[test]
+fn render_simple_substitutes_known_variables() {
+    let system = MockSystem::new()
+        .with_dir("/target")
+        .unwrap()
+        .with_file(
+            "/target/app.txt",
+            b"name: {{ serviceName }}\nother: {{unknown}}\n",
+        )
+        .unwrap()
+        .with_file("/target/.graft.yaml", b"source: \"{{ serviceName }}\"\n")
+        .unwrap();
+
+    let rendered = render_templates(
+        &system,
+        Path::new("/target"),
+        "simple",
+        &sample_context(),
+        ".graft.yaml",
+    )
+    .unwrap();
+
+    assert!(is_template_engine_available("simple"));
+    assert_eq!(rendered, 1);
+    let content = system.read_to_string(Path::new("/target/app.txt")).unwrap();
+    assert_eq!(content, "name: billing\nother: {{unknown}}\n");
+
+    // Graft definition files are left for graft processing
+    let graft = system
+        .read_to_string(Path::new("/target/.graft.yaml"))
+        .unwrap();
+    assert_eq!(graft, "source: \"{{ serviceName }}\"\n");
+}
+
+#[test]
+fn render_unknown_engine_fails() {
+    let system = MockSystem::new().with_dir("/target").unwrap();
+
+    let result = render_templates(
+        &system,
+        Path::new("/target"),
+        "jinja",
+        &sample_context(),
+        ".graft.yaml",
+    );
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "handlebars")]
+#[test]
+fn render_handlebars_conditional_block() {
+    let system = MockSystem::new()
+        .with_dir("/target")
+        .unwrap()
+        .with_file(
+            "/target/app.yaml",
+            b"name: {{serviceName}}\n{{#if enableMetrics}}metrics: on\n{{/if}}{{#if disabled}}never: here\n{{/if}}",
+        )
+        .unwrap();
+
+    let rendered = render_templates(
+        &system,
+        Path::new("/target"),
+        "handlebars",
+        &sample_context(),
+        ".graft.yaml",
+    )
+    .unwrap();
+
+    assert_eq!(rendered, 1);
+    let content = system
+        .read_to_string(Path::new("/target/app.yaml"))
+        .unwrap();
+    assert_eq!(content, "name: billing\nmetrics: on\n");
+}
+
+#[cfg(not(feature = "handlebars"))]
+#[test]
+fn render_handlebars_requires_feature() {
+    assert!(!is_template_engine_available("handlebars"));
+
+    let system = MockSystem::new()
+        .with_dir("/target")
+        .unwrap()
+        .with_file("/target/app.txt", b"{{#if x}}y{{/if}}")
+        .unwrap();
+
+    let result = render_templates(
+        &system,
+        Path::new("/target"),
+        "handlebars",
+        &sample_context(),
+        ".graft.yaml",
+    );
+    assert!(result.unwrap_err().to_string().contains("feature"));
+}
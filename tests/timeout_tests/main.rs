@@ -0,0 +1,4 @@
+//! Tests for the run-wide `--timeout`.
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,78 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use core::time::Duration;
+#[cfg(unix)]
+use std::process::Command;
+#[cfg(unix)]
+use std::sync::mpsc;
+use std::thread;
+use tixgraft::error::GraftError;
+use tixgraft::run_with_timeout;
+#[cfg(unix)]
+use tixgraft::utils::process::{
+    enable_process_groups, spawn_child, terminate_child_process_groups,
+};
+
+#[test]
+fn timeout_returns_timeout_exit_code() {
+    let err = run_with_timeout(Duration::from_millis(20), || {
+        thread::sleep(Duration::from_secs(5));
+        Ok(())
+    })
+    .unwrap_err();
+
+    let graft_err = err.downcast_ref::<GraftError>().unwrap();
+    assert!(matches!(*graft_err, GraftError::Timeout { .. }));
+    assert_eq!(graft_err.exit_code(), 7_i32);
+}
+
+#[test]
+fn fast_failure_keeps_its_own_error() {
+    let err = run_with_timeout(Duration::from_secs(5), || {
+        Err(GraftError::git("clone failed").into())
+    })
+    .unwrap_err();
+
+    assert_eq!(err.downcast_ref::<GraftError>().unwrap().exit_code(), 4_i32);
+}
+
+#[test]
+fn completes_within_deadline() {
+    run_with_timeout(Duration::from_secs(5), || Ok(())).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn timeout_terminates_spawned_child() {
+    enable_process_groups();
+    let (sender, receiver) = mpsc::channel();
+    let err = run_with_timeout(Duration::from_millis(200), move || {
+        let child = spawn_child(Command::new("sleep").arg("60"))?;
+        sender.send(child.id())?;
+        child.wait()?;
+        Ok(())
+    })
+    .unwrap_err();
+    assert!(matches!(
+        *err.downcast_ref::<GraftError>().unwrap(),
+        GraftError::Timeout { .. }
+    ));
+
+    terminate_child_process_groups();
+
+    // The worker thread reaps the child once it is gone
+    let pid = receiver.recv().unwrap().to_string();
+    let mut alive = true;
+    for _ in 0..50 {
+        alive = Command::new("kill")
+            .args(["-0", &pid])
+            .status()
+            .unwrap()
+            .success();
+        if !alive {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert!(!alive, "child process {pid} survived the timeout");
+}
@@ -1,8 +1,10 @@
 #![expect(clippy::unwrap_used, reason = "This is a test module")]
 
 use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin;
 use predicates::prelude::*;
 use std::io::Write as _;
+use std::path::Path;
 use tempfile::NamedTempFile;
 
 #[test]
@@ -390,3 +392,126 @@ pulls:
     // Default mustSucceed (true) should NOT emit the flag
     assert!(!stdout.contains("--pull-must-succeed"));
 }
+
+#[test]
+fn to_command_line_multiple_formats() {
+    let mut config_file = NamedTempFile::new().unwrap();
+    writeln!(
+        config_file,
+        r#"
+repository: "my_organization/repo"
+pulls:
+  - source: "src"
+    target: "dst"
+"#
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    let output = cmd
+        .arg("--config")
+        .arg(config_file.path())
+        .arg("--to-command-line")
+        .arg("--output-format")
+        .arg("shell,json")
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let (shell_block, json_block) = stdout.split_once("\n\n# json\n").unwrap();
+
+    let shell = shell_block.strip_prefix("# shell\n").unwrap();
+    assert!(shell.starts_with("tixgraft"));
+    assert!(shell.contains("--repository"));
+    assert!(shell.contains("my_organization/repo"));
+
+    let json: Vec<String> = serde_json::from_str(json_block).unwrap();
+    assert_eq!(json.first().map(String::as_str), Some("tixgraft"));
+    assert!(json.contains(&"--pull-source".to_owned()));
+}
+
+#[test]
+fn to_command_line_absolute_program() {
+    let mut config_file = NamedTempFile::new().unwrap();
+    writeln!(
+        config_file,
+        r#"
+repository: "my_organization/repo"
+pulls:
+  - source: "src"
+    target: "dst"
+"#
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    let output = cmd
+        .arg("--config")
+        .arg(config_file.path())
+        .arg("--to-command-line")
+        .arg("--output-format")
+        .arg("json")
+        .arg("--absolute-program")
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let json: Vec<String> = serde_json::from_str(&stdout).unwrap();
+    let program = Path::new(json.first().unwrap());
+    assert!(program.is_absolute());
+    assert_eq!(
+        program.canonicalize().unwrap(),
+        cargo_bin("tixgraft").canonicalize().unwrap()
+    );
+}
+
+#[test]
+fn to_command_line_program_name() {
+    let mut config_file = NamedTempFile::new().unwrap();
+    writeln!(
+        config_file,
+        r#"
+repository: "my_organization/repo"
+pulls:
+  - source: "src"
+    target: "dst"
+"#
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.arg("--config")
+        .arg(config_file.path())
+        .arg("--to-command-line")
+        .arg("--program-name")
+        .arg("/opt/tools/graft")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("/opt/tools/graft --repository"));
+}
+
+#[test]
+fn to_command_line_powershell_dialect() {
+    let mut config_file = NamedTempFile::new().unwrap();
+    writeln!(
+        config_file,
+        r#"
+repository: "my_organization/repo"
+pulls:
+  - source: "src"
+    target: "my dst"
+"#
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.arg("--config")
+        .arg(config_file.path())
+        .arg("--to-command-line")
+        .arg("--shell-dialect")
+        .arg("powershell")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("tixgraft `\n"))
+        .stdout(predicate::str::contains("'my dst'"));
+}
@@ -5,10 +5,13 @@
 )]
 
 use assert_cmd::Command;
+use os_shim::real::RealSystem;
 use predicates::prelude::*;
+use std::fs;
 use std::io::Write as _;
 use std::process::Command as StdCommand;
-use tempfile::NamedTempFile;
+use tempfile::{NamedTempFile, TempDir};
+use tixgraft::config::Config;
 
 #[test]
 fn to_config_basic() {
@@ -293,6 +296,14 @@ pulls:
         original["pulls"][0]["reset"],
         roundtrip["pulls"][0]["reset"]
     );
+
+    // The whole config survives the roundtrip, up to cosmetic differences
+    let original_parsed: Config = serde_yaml::from_str(original_config).unwrap();
+    let roundtrip_parsed: Config = serde_yaml::from_str(&roundtrip_without_comments).unwrap();
+    assert_eq!(
+        original_parsed.diff(&roundtrip_parsed),
+        Vec::<String>::new()
+    );
 }
 
 #[test]
@@ -374,6 +385,42 @@ fn to_config_with_commands() {
         .stdout(predicate::str::contains("commands:"));
 }
 
+#[test]
+fn to_config_keeps_command_with_comma_intact() {
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.arg("--repository")
+        .arg("my_organization/repo")
+        .arg("--pull-source")
+        .arg("src")
+        .arg("--pull-target")
+        .arg("dst")
+        .arg("--pull-commands")
+        .arg("echo one,echo two")
+        .arg("--pull-commands")
+        .arg("npm test")
+        .arg("--to-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- echo one,echo two"))
+        .stdout(predicate::str::contains("- npm test"));
+
+    let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+    cmd.arg("--repository")
+        .arg("my_organization/repo")
+        .arg("--pull-source")
+        .arg("src")
+        .arg("--pull-target")
+        .arg("dst")
+        .arg("--pull-commands")
+        .arg("echo one,echo two")
+        .arg("--split-pull-commands")
+        .arg("--to-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- echo one\n"))
+        .stdout(predicate::str::contains("- echo two\n"));
+}
+
 #[test]
 fn to_config_header_comment() {
     let mut cmd = Command::cargo_bin("tixgraft").unwrap();
@@ -457,3 +504,55 @@ pulls:
     // Step 4: Verify mustSucceed: false is preserved through roundtrip
     assert!(roundtrip_config.contains("mustSucceed: false"));
 }
+
+#[test]
+fn save_config_writes_loadable_file_and_refuses_overwrite() {
+    let temp_dir = TempDir::new().unwrap();
+    let saved = temp_dir.path().join("tixgraft.yaml");
+    let save = |force: bool| {
+        let mut cmd = Command::cargo_bin("tixgraft").unwrap();
+        cmd.arg("--repository")
+            .arg("my_organization/repo")
+            .arg("--tag")
+            .arg("v1.0.0")
+            .arg("--pull-source")
+            .arg("src")
+            .arg("--pull-target")
+            .arg("dst")
+            .arg("--save-config")
+            .arg(&saved);
+        if force {
+            cmd.arg("--force");
+        }
+        cmd.assert()
+    };
+
+    save(false)
+        .success()
+        .stdout(predicate::str::contains("Saved configuration to"))
+        .stdout(predicate::str::contains("repository:").not());
+
+    let config = Config::load_from_file(&RealSystem::new(), saved.to_str().unwrap()).unwrap();
+    assert_eq!(config.repository.as_deref(), Some("my_organization/repo"));
+    assert_eq!(config.tag.as_deref(), Some("v1.0.0"));
+    assert_eq!(config.pulls.len(), 1);
+    assert_eq!(config.pulls[0].source, "src");
+    assert_eq!(config.pulls[0].target, "dst");
+
+    // The saved file round-trips to the same config
+    Command::cargo_bin("tixgraft")
+        .unwrap()
+        .arg("--config")
+        .arg(&saved)
+        .arg("--to-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            fs::read_to_string(&saved).unwrap().trim_end(),
+        ));
+
+    save(false)
+        .failure()
+        .stdout(predicate::str::contains("--force"));
+    save(true).success();
+}
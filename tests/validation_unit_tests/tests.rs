@@ -264,3 +264,84 @@ fn validate_config_invalid_pull_repo() {
     let result = validate_config(&system, &config);
     assert!(result.is_err());
 }
+
+#[test]
+fn validate_config_graft_file_name() {
+    let system = MockSystem::new();
+    let mut config = make_config(vec![make_pull("src", "./target")], vec![]);
+
+    config.graft_file_name = Some("graft.yml".to_owned());
+    validate_config(&system, &config).unwrap();
+
+    config.graft_file_name = Some("nested/graft.yml".to_owned());
+    let result = validate_config(&system, &config);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("path separators"));
+
+    config.graft_file_name = Some("  ".to_owned());
+    assert!(validate_config(&system, &config).is_err());
+}
+
+#[test]
+fn validate_config_empty_source_message() {
+    let system = MockSystem::new();
+    let config = make_config(vec![make_pull("\"\"", "./target")], vec![]);
+
+    let err = validate_config(&system, &config).unwrap_err();
+    assert!(err.to_string().contains("Source path cannot be empty"));
+    assert!(err.to_string().contains("--pull-source"));
+}
+
+#[test]
+fn validate_empty_repository_url_message() {
+    let err = validate_repository_url("").unwrap_err();
+    assert!(err.to_string().contains("Repository URL is empty"));
+}
+
+#[test]
+fn validate_config_stdout_target_requires_single_file_pull() {
+    let system = MockSystem::new();
+    let file_pull = |source: &str| -> PullConfig {
+        serde_yaml::from_str(&format!("source: {source}\ntarget: \"-\"\ntype: file")).unwrap()
+    };
+
+    validate_config(&system, &make_config(vec![file_pull("a.txt")], vec![])).unwrap();
+
+    let err =
+        validate_config(&system, &make_config(vec![make_pull("src", "-")], vec![])).unwrap_err();
+    assert!(err.to_string().contains("only valid for file pulls"));
+
+    let err = validate_config(
+        &system,
+        &make_config(
+            vec![file_pull("a.txt"), make_pull("src", "./target")],
+            vec![],
+        ),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("single pull"));
+
+    let mut with_commands = file_pull("a.txt");
+    with_commands.commands = vec!["echo hi".to_owned()];
+    let err = validate_config(&system, &make_config(vec![with_commands], vec![])).unwrap_err();
+    assert!(err.to_string().contains("Commands cannot be used"));
+}
+
+#[test]
+fn validate_replacement_target_from_file() {
+    let system = MockSystem::new();
+    let mut pull = make_pull("src", "./target");
+    pull.replacements = vec![
+        ReplacementConfig::new("{{X}}".to_owned(), None, None).with_target_from_file("block.txt"),
+    ];
+    let config = make_config(vec![pull.clone()], vec![]);
+    validate_config(&system, &config).unwrap();
+
+    pull.replacements = vec![
+        ReplacementConfig::new("{{X}}".to_owned(), None, Some("ENV".to_owned()))
+            .with_target_from_file("block.txt"),
+    ];
+    let conflicting = make_config(vec![pull], vec![]);
+    let err = validate_config(&system, &conflicting).unwrap_err();
+    assert!(err.to_string().contains("Cannot specify both"));
+}
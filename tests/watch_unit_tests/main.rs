@@ -0,0 +1,4 @@
+//! Unit tests for re-running local pulls on source changes (`--watch`).
+
+#[cfg(test)]
+mod tests;
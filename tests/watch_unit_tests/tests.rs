@@ -0,0 +1,81 @@
+#![expect(clippy::unwrap_used, reason = "This is a test module")]
+
+use anyhow::anyhow;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+use tixgraft::operations::watch::{WatchedPull, run_watch_loop};
+
+/// Two watched pulls with sources under `/templates`.
+fn watched_pulls() -> Vec<WatchedPull> {
+    vec![
+        WatchedPull::new(0, PathBuf::from("/templates/app")),
+        WatchedPull::new(1, PathBuf::from("/templates/config.yaml")),
+    ]
+}
+
+#[test]
+fn change_reruns_only_the_affected_pull() {
+    let (sender, events) = mpsc::channel();
+    sender
+        .send(PathBuf::from("/templates/app/src/main.rs"))
+        .unwrap();
+    drop(sender);
+
+    let mut reruns = Vec::new();
+    run_watch_loop(&events, &watched_pulls(), Duration::ZERO, |index| {
+        reruns.push(index);
+        Ok(())
+    });
+
+    assert_eq!(reruns, vec![0]);
+}
+
+#[test]
+fn burst_of_changes_reruns_each_pull_once() {
+    let (sender, events) = mpsc::channel();
+    for path in [
+        "/templates/app/a.txt",
+        "/templates/app/b.txt",
+        "/templates/config.yaml",
+        "/elsewhere/c.txt",
+    ] {
+        sender.send(PathBuf::from(path)).unwrap();
+    }
+    drop(sender);
+
+    let mut reruns = Vec::new();
+    run_watch_loop(
+        &events,
+        &watched_pulls(),
+        Duration::from_millis(50),
+        |index| {
+            reruns.push(index);
+            Ok(())
+        },
+    );
+
+    assert_eq!(reruns, vec![0, 1]);
+}
+
+#[test]
+fn failed_rerun_keeps_watching() {
+    let (sender, events) = mpsc::channel();
+    let watched = watched_pulls();
+
+    let mut attempts: usize = 0;
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            sender.send(PathBuf::from("/templates/app/a.txt")).unwrap();
+            std::thread::sleep(Duration::from_millis(100));
+            sender.send(PathBuf::from("/templates/app/b.txt")).unwrap();
+            drop(sender);
+        });
+        run_watch_loop(&events, &watched, Duration::from_millis(20), |_| {
+            attempts = attempts.saturating_add(1);
+            Err(anyhow!("template is broken"))
+        });
+    });
+
+    assert_eq!(attempts, 2);
+}
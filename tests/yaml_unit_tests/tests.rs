@@ -1,7 +1,10 @@
 #![expect(clippy::unwrap_used, reason = "This is a test module")]
 
+use os_shim::System as _;
 use os_shim::mock::MockSystem;
+use std::path::Path;
 use tixgraft::config::yaml::load_config;
+use tixgraft::operations::apply_replacements;
 
 #[test]
 fn load_valid_config() {
@@ -34,3 +37,60 @@ fn load_nonexistent_file() {
             .contains("Configuration file not found")
     );
 }
+
+#[test]
+fn load_config_rejects_unknown_transform() {
+    let config_content = r#"
+repository: "my_organization/scaffolds"
+pulls:
+  - source: "kubernetes/mongodb"
+    target: "./k8s/mongodb"
+    replacements:
+      - source: "{{TOKEN}}"
+        valueFromEnv: "TOKEN"
+        transform: "rot13"
+"#;
+
+    let system = MockSystem::new()
+        .with_file("/test/config.yaml", config_content.as_bytes())
+        .unwrap();
+
+    load_config(&system, "/test/config.yaml").unwrap_err();
+}
+
+#[test]
+fn load_config_accepts_strip_suffix_and_applies_it() {
+    let config_content = r#"
+repository: "my_organization/scaffolds"
+pulls:
+  - source: "app"
+    target: "./app"
+    replacements:
+      - source: "{{MODULE}}"
+        valueFromEnv: "ENTRY_FILE"
+        stripSuffix: ".ts"
+      - source: "{{LIB}}"
+        target: "lib/util.ts"
+        stripPrefix: "lib/"
+        stripSuffix: ".ts"
+"#;
+
+    let system = MockSystem::new()
+        .with_file("/test/config.yaml", config_content.as_bytes())
+        .unwrap()
+        .with_env("ENTRY_FILE", "index.ts")
+        .unwrap()
+        .with_file("/work/app/main.txt", b"import {{MODULE}} from {{LIB}}\n")
+        .unwrap();
+
+    let config = load_config(&system, "/test/config.yaml").unwrap();
+    let pull = config.pulls.first().unwrap();
+    apply_replacements(&system, "/work/app", &pull.replacements).unwrap();
+
+    assert_eq!(
+        system
+            .read_to_string(Path::new("/work/app/main.txt"))
+            .unwrap(),
+        "import index from util\n"
+    );
+}